@@ -1,10 +1,21 @@
 use crate::cli::parser::Commands;
+use crate::config::Config;
 use crate::core::add::AddLogic;
+use crate::core::calculator::expected::AUTO_LUNCH_KEY;
+use crate::core::compliance::{
+    OVERRIDE_REASON_KEY, break_violations, mandatory_lunch_after, max_daily_work,
+};
+use crate::core::logic::Core;
+use crate::core::position_rules::{self, LocationHint};
+use crate::db::metadata::record_app_version;
 use crate::db::pool::DbPool;
+use crate::db::queries::load_events_by_date;
 use crate::errors::{AppError, AppResult};
 use crate::models::location::Location;
+use crate::ui::messages::{Level, capture, info, replay, success, warning};
 use crate::utils::date;
-use crate::utils::time::parse_optional_time;
+use crate::utils::formatting::{format_surplus, mins2readable};
+use crate::utils::meta::{merge_meta, parse_meta_pairs};
 use chrono::NaiveDate;
 
 fn validate_sickleave_args(
@@ -36,11 +47,40 @@ fn validate_sickleave_args(
     }
 }
 
+/// Position from `position_rules` (Office when none matches).
+fn default_position(
+    cfg: &Config,
+    wifi_ssid: &Option<String>,
+    hostname: &Option<String>,
+) -> AppResult<Location> {
+    if cfg.position_rules.is_empty() {
+        if wifi_ssid.is_some() || hostname.is_some() {
+            warning("⚠️  --wifi-ssid / --hostname ignored: no position_rules in the config.");
+        }
+        return Ok(Location::Office);
+    }
+
+    let hint = LocationHint::detect(&cfg.position_rules, wifi_ssid.clone(), hostname.clone());
+    Ok(match position_rules::resolve(&cfg.position_rules, &hint)? {
+        Some(m) => {
+            info(format!(
+                "📍 Position {} from position_rules #{}",
+                m.position.label(),
+                m.rule
+            ));
+            m.position
+        }
+        None => Location::Office,
+    })
+}
+
 /// Add or update a work session.
-pub fn handle(cmd: &Commands, cfg: &crate::config::Config) -> AppResult<()> {
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
     if let Commands::Add {
         date,
         pos,
+        wifi_ssid,
+        hostname,
         start,
         lunch,
         work_gap,
@@ -49,20 +89,32 @@ pub fn handle(cmd: &Commands, cfg: &crate::config::Config) -> AppResult<()> {
         edit_pair,
         edit,
         notes,
+        meta,
+        no_auto_lunch,
+        override_reason,
         to,
+        move_to,
     } = cmd
     {
+        if let Some(target) = move_to {
+            let d = date::parse_date(date).map_err(|_| AppError::InvalidDate(date.to_string()))?;
+            let mut pool = DbPool::new(&cfg.database)?;
+            let (old_pair, new_pair) =
+                AddLogic::move_pair(&mut pool, d, *edit_pair, *target, cfg.overlap_mode)?;
+            success(format!(
+                "📦 Pair {} of {} moved to {} (pair {} there).",
+                old_pair, d, target, new_pair
+            ));
+            record_app_version(&pool.conn)?;
+            return Ok(());
+        }
+
         //
-        // 1. Parse position (default = Office)
+        // 1. Parse position (default = position_rules, else Office)
         //
         let pos_final = match pos {
-            Some(code) => Location::from_code(code).ok_or_else(|| {
-                AppError::InvalidPosition(format!(
-                    "Invalid location code '{}'. Use a valid code such as 'office', 'remote', 'customer', ...",
-                    code
-                ))
-            })?,
-            None => Location::Office,
+            Some(p) => *p,
+            None => default_position(cfg, wifi_ssid, hostname)?,
         };
 
         //
@@ -72,19 +124,38 @@ pub fn handle(cmd: &Commands, cfg: &crate::config::Config) -> AppResult<()> {
         let d = date::parse_date(date).map_err(|_| AppError::InvalidDate(date.to_string()))?;
 
         //
-        // 3. Parse times (optional input)
-        //
-        let start_parsed = parse_optional_time(start.as_ref())?;
-
+        // 3. Times and lunch are already typed by clap
         //
-        // 4. Parse OUT time (optional)
-        //
-        let end_parsed = parse_optional_time(end.as_ref())?;
+        let start_parsed = *start;
+        let end_parsed = *end;
+        let lunch_opt = lunch.map(i32::from);
+        if let Some(minutes) = lunch_opt
+            && minutes > cfg.max_duration_lunch_break
+        {
+            return Err(AppError::LunchTooLong {
+                minutes,
+                max: cfg.max_duration_lunch_break,
+            });
+        }
 
         //
-        // 4. Lunch break (optional)
+        // 4b. Metadata (--meta key=value, stored as JSON)
         //
-        let lunch_opt = *lunch;
+        let mut meta_json = parse_meta_pairs(meta)?;
+        if *no_auto_lunch {
+            let opt_out = serde_json::json!({ AUTO_LUNCH_KEY: false }).to_string();
+            meta_json = Some(merge_meta(meta_json.as_deref(), &opt_out));
+        }
+        let override_reason = override_reason.as_deref().map(str::trim);
+        if let Some(reason) = override_reason {
+            if reason.is_empty() {
+                return Err(AppError::InvalidArgs(
+                    "--override needs a reason, e.g. --override \"release night\".".into(),
+                ));
+            }
+            let entry = serde_json::json!({ OVERRIDE_REASON_KEY: reason }).to_string();
+            meta_json = Some(merge_meta(meta_json.as_deref(), &entry));
+        }
 
         //
         // 5. Open DB
@@ -107,18 +178,22 @@ pub fn handle(cmd: &Commands, cfg: &crate::config::Config) -> AppResult<()> {
         //
         let sick_range = validate_sickleave_args(pos_final, Some(d), *to)?;
 
-        match sick_range {
-            Some((_from_date, to_date)) => {
-                // (opzionale ma consigliato) vieta start/end nel range malattia
-                if start_parsed.is_some() || end_parsed.is_some() {
-                    return Err(AppError::InvalidArgs(
-                        "--in/--out cannot be used with --pos s (use only --to)".into(),
-                    ));
-                }
+        // (opzionale ma consigliato) vieta start/end nel range malattia
+        if sick_range.is_some() && (start_parsed.is_some() || end_parsed.is_some()) {
+            return Err(AppError::InvalidArgs(
+                "--in/--out cannot be used with --pos s (use only --to)".into(),
+            ));
+        }
 
-                AddLogic::apply(
+        //
+        // 8. Every write of the command in one transaction: an error half-way
+        //    leaves the day as it was; the recap reads the same snapshot
+        //
+        commit_then_report(&mut pool, |pool| {
+            match sick_range {
+                Some((_from_date, to_date)) => AddLogic::apply(
                     cfg,
-                    &mut pool,
+                    pool,
                     d,
                     pos_final,
                     None,
@@ -128,29 +203,202 @@ pub fn handle(cmd: &Commands, cfg: &crate::config::Config) -> AppResult<()> {
                     *edit,
                     *edit_pair,
                     Some(to_date),
-                    pos.clone(),
+                    *pos,
                     notes.clone(),
-                )?;
+                    meta_json.clone(),
+                )?,
+                None => {
+                    let worked_before = day_worked(pool, cfg, d)?;
+                    AddLogic::apply(
+                        cfg,
+                        pool,
+                        d,
+                        pos_final,
+                        start_parsed,
+                        lunch_opt,
+                        work_gap,
+                        end_parsed,
+                        *edit,
+                        *edit_pair,
+                        None,
+                        *pos,
+                        notes.clone(),
+                        meta_json.clone(),
+                    )?;
+                    check_daily_cap(pool, cfg, d, worked_before, override_reason)?;
+                    warn_missing_break(pool, cfg, d)?;
+                    print_recap(pool, cfg, d)?;
+                }
             }
-            None => {
+
+            record_app_version(&pool.conn)?;
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Run `f` in one transaction and show its messages only after the commit:
+/// when it fails (e.g. `max_daily_work` refuses the add) nothing is stored,
+/// so only its warnings are shown, never an "Added ..." line.
+fn commit_then_report(
+    pool: &mut DbPool,
+    f: impl FnOnce(&mut DbPool) -> AppResult<()>,
+) -> AppResult<()> {
+    let (result, msgs) = capture(|| pool.in_transaction(f));
+    replay(
+        msgs.into_iter()
+            .filter(|m| result.is_ok() || m.level == Level::Warning),
+    );
+    result
+}
+
+/// "📊 2025-06-18 · worked 04h10m · exit 17:25 · month ΔWORK +01h15m"
+/// (an info line: hidden by `--quiet`).
+fn print_recap(pool: &mut DbPool, cfg: &Config, d: NaiveDate) -> AppResult<()> {
+    let Some(r) = AddLogic::day_recap(pool, cfg, d)? else {
+        return Ok(());
+    };
+
+    let exit = r
+        .target_exit
+        .map(|t| format!(" · exit {}", t.format("%H:%M")))
+        .unwrap_or_default();
+    info(format!(
+        "📊 {} · worked {}{} · month ΔWORK {}",
+        d.format("%Y-%m-%d"),
+        mins2readable(r.worked, false, true),
+        exit,
+        format_surplus(r.month_surplus, cfg)
+    ));
+    Ok(())
+}
+
+/// Net worked minutes of a day (None while open, or on non-working days).
+fn day_worked(pool: &mut DbPool, cfg: &Config, d: NaiveDate) -> AppResult<Option<i64>> {
+    let events = load_events_by_date(pool, &d)?;
+    let summary = Core::build_daily_summary(&events, cfg);
+    if Core::is_marker_day(&summary) {
+        return Ok(None);
+    }
+    Ok(Core::day_figures(&summary, &events, cfg).and_then(|f| f.worked))
+}
+
+/// `max_daily_work`: a change that takes the day beyond the cap (or further
+/// beyond it) fails with `enforce_max_daily_work`, rolling the whole `add`
+/// back, unless `--override` gave a reason; otherwise it is a warning.
+fn check_daily_cap(
+    pool: &mut DbPool,
+    cfg: &Config,
+    d: NaiveDate,
+    before: Option<i64>,
+    override_reason: Option<&str>,
+) -> AppResult<()> {
+    let Some(limit) = max_daily_work(cfg) else {
+        return Ok(());
+    };
+    let Some(worked) = day_worked(pool, cfg, d)? else {
+        return Ok(());
+    };
+    if worked <= limit || before.is_some_and(|b| worked <= b) {
+        return Ok(());
+    }
+
+    match override_reason {
+        None if cfg.enforce_max_daily_work => Err(AppError::MaxDailyWorkExceeded {
+            date: d,
+            worked: mins2readable(worked, false, true),
+            max: mins2readable(limit, false, true),
+        }),
+        Some(reason) => {
+            warning(format!(
+                "⚠️  {} {} of work, above max_daily_work ({}): recorded with override \"{}\".",
+                d.format("%Y-%m-%d"),
+                mins2readable(worked, false, true),
+                mins2readable(limit, false, true),
+                reason
+            ));
+            Ok(())
+        }
+        None => {
+            warning(format!(
+                "⚠️  {} {} of work, above max_daily_work ({}).",
+                d.format("%Y-%m-%d"),
+                mins2readable(worked, false, true),
+                mins2readable(limit, false, true)
+            ));
+            Ok(())
+        }
+    }
+}
+
+/// `mandatory_lunch_after`: warn about blocks of work without a break.
+fn warn_missing_break(pool: &mut DbPool, cfg: &Config, d: NaiveDate) -> AppResult<()> {
+    let Some(limit) = mandatory_lunch_after(cfg) else {
+        return Ok(());
+    };
+
+    let events = load_events_by_date(pool, &d)?;
+    let summary = Core::build_daily_summary(&events, cfg);
+    for v in break_violations(&summary, limit) {
+        warning(format!(
+            "⚠️  {} {}–{}: {} of work without a break (limit {}).",
+            d.format("%Y-%m-%d"),
+            v.start.format("%H:%M"),
+            v.end.format("%H:%M"),
+            mins2readable(v.minutes, false, true),
+            mins2readable(limit, false, true)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    #[test]
+    fn add_refused_by_the_cap_is_neither_stored_nor_reported() {
+        let cfg = Config {
+            max_daily_work: Some("8h".into()),
+            enforce_max_daily_work: true,
+            ..Config::default()
+        };
+        let mut pool = DbPool::new(":memory:").unwrap();
+        let d = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+        let hm = |s| NaiveTime::parse_from_str(s, "%H:%M").ok();
+
+        let (result, msgs) = capture(|| {
+            commit_then_report(&mut pool, |pool| {
+                let before = day_worked(pool, &cfg, d)?;
                 AddLogic::apply(
-                    cfg,
-                    &mut pool,
+                    &cfg,
+                    pool,
                     d,
-                    pos_final,
-                    start_parsed,
-                    lunch_opt,
-                    work_gap,
-                    end_parsed,
-                    *edit,
-                    *edit_pair,
+                    Location::Office,
+                    hm("07:00"),
+                    None,
+                    None,
+                    hm("19:00"),
+                    false,
+                    None,
+                    None,
+                    None,
+                    None,
                     None,
-                    pos.clone(),
-                    notes.clone(),
                 )?;
-            }
-        }
-    }
+                check_daily_cap(pool, &cfg, d, before, None)
+            })
+        });
 
-    Ok(())
+        assert!(matches!(result, Err(AppError::MaxDailyWorkExceeded { .. })));
+        assert!(msgs.iter().all(|m| m.level != Level::Success), "{msgs:?}");
+        let stored: i64 = pool
+            .conn
+            .query_row("SELECT COUNT(*) FROM events", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(stored, 0);
+    }
 }
@@ -0,0 +1,33 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::archive::ArchiveLogic;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{info, success, warning};
+use crate::utils::date;
+
+/// Handle the `archive` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Archive { before, file } = cmd {
+        let before_date =
+            date::parse_date(before).map_err(|_| AppError::InvalidDate(before.to_string()))?;
+        let file = cfg.resolve_database(file);
+
+        let mut pool = DbPool::new(&cfg.database)?;
+        let rep = ArchiveLogic::apply(&mut pool, before_date, &file)?;
+        record_app_version(&pool.conn)?;
+
+        if rep.events == 0 {
+            warning(format!("No events before {} to archive.", before_date));
+        } else {
+            success(format!(
+                "{} event(s) and {} log row(s) before {} moved to {}",
+                rep.events, rep.logs, before_date, rep.path
+            ));
+            info("Use `list --archive` to include them, `db --vacuum` to shrink the working DB.");
+        }
+    }
+
+    Ok(())
+}
@@ -1,13 +1,25 @@
 use crate::cli::parser::Commands;
 use crate::config::Config;
-use crate::core::backup::BackupLogic;
+use crate::core::backup::{BackupCompression, BackupLogic};
 use crate::db::pool::DbPool;
 use crate::errors::AppResult;
 
 pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
-    if let Commands::Backup { file, compress } = cmd {
+    if let Commands::Backup {
+        file,
+        compress,
+        compression,
+        encrypt,
+    } = cmd
+    {
+        let compression = match (compression, compress) {
+            (Some(c), _) => *c,
+            (None, true) => BackupCompression::Zip,
+            (None, false) => BackupCompression::None,
+        };
+
         let mut pool = DbPool::new(&cfg.database)?;
-        BackupLogic::backup(&mut pool, cfg, file, *compress)?;
+        BackupLogic::backup(&mut pool, cfg, file, compression, *encrypt)?;
     }
 
     Ok(())
@@ -0,0 +1,39 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::closeout::CloseoutLogic;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::errors::AppResult;
+use crate::ui::messages::{info, success, warning};
+
+/// Handle the `closeout` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Closeout { month, force } = cmd {
+        if cfg.on_closeout.is_empty() {
+            warning("No `on_closeout` reports configured: the month will only be locked.");
+        }
+
+        let mut pool = DbPool::new(&cfg.database)?;
+        let rep = CloseoutLogic::apply(&mut pool, cfg, month, *force)?;
+        record_app_version(&pool.conn)?;
+
+        for (path, sha) in &rep.files {
+            info(format!("{}  sha256={}", path.display(), sha));
+        }
+        if rep.skipped > 0 {
+            warning(format!(
+                "{} report(s) skipped: no events in {}.",
+                rep.skipped, month
+            ));
+        }
+
+        success(format!(
+            "Month {} closed: {} day(s) locked, {} report(s) written.",
+            month,
+            rep.locked,
+            rep.files.len()
+        ));
+    }
+
+    Ok(())
+}
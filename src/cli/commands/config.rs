@@ -1,8 +1,17 @@
 use crate::cli::parser::Commands;
+use crate::config::format::ConfigFormat;
+use crate::config::provenance::{
+    config_dir_override, inconsistencies, key_sources, known_keys, unused_keys,
+};
+use crate::config::write::{self, backup_file, write_config};
 use crate::config::{Config, migrate};
+use crate::db::pool::is_memory_db;
 use crate::errors::{AppError, AppResult};
-use crate::ui::messages::{error, info, success, warning};
+use crate::ui::messages::{data, error, info, is_ascii, success, warning};
 
+use serde_yaml::Value;
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
 /// Handle the `config` subcommand
@@ -11,6 +20,10 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         print_config,
         check,
         migrate,
+        set,
+        migrate_format,
+        repair_db_path: repair,
+        rollback,
         edit_config,
         editor,
     } = cmd
@@ -21,8 +34,7 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         // PRINT CONFIG
         // ------------------------------------------------------------
         if *print_config {
-            info("Current configuration:");
-            println!("{}", serde_yaml::to_string(&cfg).unwrap());
+            print_config_sources(cfg, &path);
         }
 
         // ------------------------------------------------------------
@@ -31,15 +43,23 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         if *check {
             info("🔧 Checking configuration…");
 
-            let cfg = Config::load();
+            let cfg = Config::load_for(cfg.test_mode);
 
             info(format!("Config file: {:?}", Config::config_file()));
             info(format!("Database   : {:?}", cfg.database));
 
             let db_exists = std::path::Path::new(&cfg.database).exists();
 
-            if !db_exists {
+            if is_memory_db(&cfg.database) {
+                info("In-memory database: nothing to check on disk.");
+            } else if !db_exists {
                 warning("⚠ Database file is missing.");
+                if let Some(found) = cfg.misplaced_database() {
+                    info(format!(
+                        "A database exists at {}: run `rtimelogger config --repair-db-path` to use it.",
+                        found.display()
+                    ));
+                }
             } else {
                 success("✔ Database file exists.");
             }
@@ -53,6 +73,11 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         // MIGRATE CONFIG
         // ------------------------------------------------------------
         if *migrate {
+            if cfg.test_mode {
+                warning("Test mode: configuration migration skipped.");
+                return Ok(());
+            }
+
             info("🔧 Running configuration migration…");
 
             match migrate::run_fs_migration() {
@@ -63,6 +88,45 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
             return Ok(());
         }
 
+        // ------------------------------------------------------------
+        // MIGRATE FORMAT (YAML → TOML)
+        // ------------------------------------------------------------
+        if *migrate_format {
+            if cfg.test_mode {
+                warning("Test mode: configuration format migration skipped.");
+                return Ok(());
+            }
+
+            let new_path = Config::migrate_format()?;
+            success(format!(
+                "✔ Configuration converted to {}",
+                new_path.display()
+            ));
+            info("The previous YAML file was kept as rtimelogger.conf.bak.");
+            return Ok(());
+        }
+
+        // ------------------------------------------------------------
+        // REPAIR DATABASE PATH
+        // ------------------------------------------------------------
+        if *repair {
+            return repair_db_path(cfg);
+        }
+
+        // ------------------------------------------------------------
+        // ROLLBACK
+        // ------------------------------------------------------------
+        if *rollback {
+            return rollback_config(cfg, &path);
+        }
+
+        // ------------------------------------------------------------
+        // SET VALUE
+        // ------------------------------------------------------------
+        if let Some(assignment) = set {
+            return set_value(cfg, assignment);
+        }
+
         // ------------------------------------------------------------
         // EDIT CONFIG
         // ------------------------------------------------------------
@@ -131,3 +195,192 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
 
     Ok(())
 }
+
+/// `config --set key=value`: update one top-level field in place.
+/// The value is parsed as YAML (so `Short`, `true`, `0.25` need no quoting)
+/// and the result must still be a valid config.
+fn set_value(cfg: &Config, assignment: &str) -> AppResult<()> {
+    if cfg.test_mode {
+        warning("Test mode: configuration file not modified.");
+        return Ok(());
+    }
+
+    let (key, raw) = assignment.split_once('=').ok_or_else(|| {
+        AppError::InvalidArgs(format!(
+            "Invalid assignment '{}': expected KEY=VALUE, e.g. show_weekday=Short",
+            assignment
+        ))
+    })?;
+    let (key, raw) = (key.trim(), raw.trim());
+
+    let path = Config::config_file();
+    let content = fs::read_to_string(&path)?;
+    let updated = assign(ConfigFormat::of(&path), &content, key, raw)?;
+
+    write_config(&path, &updated)?;
+    success(format!("{} = {} ({})", key, raw, path.display()));
+
+    Ok(())
+}
+
+/// `content` with the top-level `key` set to `raw`, keeping comments and
+/// layout. The key must be one read by [`Config`] and the result must parse.
+fn assign(format: ConfigFormat, content: &str, key: &str, raw: &str) -> AppResult<String> {
+    let known = known_keys();
+    if !known.iter().any(|k| k == key) {
+        return Err(AppError::InvalidArgs(format!(
+            "Unknown config key '{}'. Known keys: {}",
+            key,
+            known.join(", ")
+        )));
+    }
+
+    let value: Value = serde_yaml::from_str(raw).map_err(|e| {
+        AppError::InvalidArgs(format!("Invalid value '{}' for '{}': {}", raw, key, e))
+    })?;
+
+    let invalid = |e: String| {
+        AppError::Config(format!(
+            "'{}' is not a valid value for '{}': {}",
+            raw, key, e
+        ))
+    };
+    let updated = format
+        .set_top_level(content, key, &value)
+        .map_err(invalid)?;
+    format.parse::<Config>(&updated).map_err(invalid)?;
+    Ok(updated)
+}
+
+/// `config --rollback`: put back the version saved before the last write.
+fn rollback_config(cfg: &Config, path: &Path) -> AppResult<()> {
+    if cfg.test_mode {
+        warning("Test mode: configuration file not modified.");
+        return Ok(());
+    }
+
+    let backup = backup_file(path);
+    if !backup.exists() {
+        return Err(AppError::Config(format!(
+            "No previous version to restore: {} does not exist.",
+            backup.display()
+        )));
+    }
+
+    // The backup must still be a valid config
+    let content = fs::read_to_string(&backup)?;
+    ConfigFormat::of(path)
+        .parse::<Config>(&content)
+        .map_err(|e| {
+            AppError::Config(format!(
+                "{} is not a valid config ({}): nothing restored.",
+                backup.display(),
+                e
+            ))
+        })?;
+
+    write::rollback(path)?;
+    success(format!(
+        "✔ Restored the previous version of {}",
+        path.display()
+    ));
+    info(format!(
+        "The replaced version is now {}: run `config --rollback` again to undo.",
+        backup.display()
+    ));
+    Ok(())
+}
+
+/// `config --repair-db-path`: point `database` to the database found at the
+/// default location when the configured file is missing.
+fn repair_db_path(cfg: &Config) -> AppResult<()> {
+    if cfg.test_mode {
+        warning("Test mode: configuration file not modified.");
+        return Ok(());
+    }
+
+    let Some(found) = cfg.misplaced_database() else {
+        if is_memory_db(&cfg.database) || Path::new(&cfg.database).exists() {
+            info(format!(
+                "Database {} exists: nothing to repair.",
+                cfg.database
+            ));
+        } else {
+            warning(format!(
+                "⚠️  Database {} is missing and no database was found in {}.",
+                cfg.database,
+                Config::config_dir().display()
+            ));
+        }
+        return Ok(());
+    };
+
+    let found = found.to_string_lossy().to_string();
+    write_key("database", &Value::String(found.clone()), &found)
+}
+
+/// `config --print`: effective value of every key with its origin, then the
+/// keys of the file that are not read and the contradictory settings.
+fn print_config_sources(cfg: &Config, path: &Path) {
+    let raw = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| ConfigFormat::of(path).parse_value(&content).ok());
+
+    match config_dir_override() {
+        Some(dir) => info(format!(
+            "Config file: {} (from RTIMELOGGER_CONFIG_DIR={})",
+            path.display(),
+            dir
+        )),
+        None => info(format!("Config file: {}", path.display())),
+    }
+    if raw.is_none() {
+        warning("Config file missing or unreadable: every value is a default.");
+    }
+
+    let sources = key_sources(cfg, raw.as_ref(), is_ascii());
+    let width = sources.iter().map(|s| s.key.len()).max().unwrap_or(0);
+    for s in &sources {
+        data(format!(
+            "{:<width$} = {}  # {}",
+            s.key,
+            s.value.as_deref().unwrap_or("(unset)"),
+            s.source,
+            width = width
+        ));
+    }
+
+    if let Some(raw) = &raw {
+        for key in unused_keys(raw) {
+            warning(format!(
+                "'{}' is not a known key (removed, renamed or misspelled): ignored.",
+                key
+            ));
+        }
+    }
+    for issue in inconsistencies(cfg) {
+        warning(issue);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_omitted_when_unset_can_be_set() {
+        let yaml = "# mine\ndatabase: /tmp/rtl.sqlite\nshow_weekday: None\n";
+        let updated = assign(ConfigFormat::Yaml, yaml, "max_daily_work", "10h").unwrap();
+        assert!(updated.starts_with("# mine\n"));
+        let cfg: Config = ConfigFormat::Yaml.parse(&updated).unwrap();
+        assert_eq!(cfg.max_daily_work.as_deref(), Some("10h"));
+
+        let toml = "database = \"/tmp/rtl.sqlite\"\n";
+        let updated = assign(ConfigFormat::Toml, toml, "max_daily_work", "10h").unwrap();
+        let cfg: Config = ConfigFormat::Toml.parse(&updated).unwrap();
+        assert_eq!(cfg.max_daily_work.as_deref(), Some("10h"));
+
+        let err = assign(ConfigFormat::Yaml, yaml, "max_daily_wrok", "10h").unwrap_err();
+        assert!(err.to_string().contains("mandatory_lunch_after"));
+    }
+}
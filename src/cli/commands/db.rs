@@ -1,10 +1,14 @@
 use crate::cli::parser::Commands;
 use crate::config::Config;
+use crate::core::recompute::on_dates_changed;
+use crate::db::log::ttlog;
 use crate::db::migrate::run_pending_migrations;
 use crate::db::pool::DbPool;
+use crate::db::queries::pairs::dates_with_events;
 use crate::db::stats;
 use crate::errors::AppResult;
 use crate::ui::messages::{error, info, success};
+use crate::utils::period::Period;
 
 pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
     if let Commands::Db {
@@ -12,6 +16,8 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         check,
         vacuum,
         info: show_info,
+        rebuild,
+        period,
     } = cmd
     {
         // Unica istanza condivisa
@@ -74,8 +80,32 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
 
             info("Running VACUUM…");
             pool.conn.execute_batch("VACUUM;")?;
+            pool.checkpoint()?;
             success("VACUUM completed successfully.\n");
         }
+
+        // ------------------------------------------------------------
+        // 5) REBUILD PAIRS (whole database or one period)
+        // ------------------------------------------------------------
+        if *rebuild {
+            let bounds = Period::parse_opt(period.as_deref())?.bounds();
+            let label = period.as_deref().unwrap_or("all dates");
+            let pool = get_pool(&mut pool, &cfg.database)?;
+
+            info(format!("Rebuilding pairs ({})…", label));
+
+            // One transaction: the days outside the period are never touched
+            let dates = dates_with_events(&pool.conn, bounds)?;
+            on_dates_changed(&pool.conn, &dates, cfg.overlap_mode)?;
+            ttlog(
+                &pool.conn,
+                "rebuild",
+                label,
+                &format!("Pairs rebuilt for {} day(s)", dates.len()),
+            )?;
+
+            success(format!("Rebuilt pairs for {} day(s).\n", dates.len()));
+        }
     }
 
     Ok(())
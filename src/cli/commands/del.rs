@@ -1,15 +1,20 @@
 use crate::cli::parser::Commands;
 use crate::config::Config;
 use crate::core::del::DeleteLogic;
+use crate::db::metadata::record_app_version;
 use crate::db::pool::DbPool;
+use crate::db::queries::locks::ensure_unlocked;
 use crate::errors::{AppError, AppResult};
+use crate::models::event_type::EventType;
 use crate::ui::messages::{info, success, warning};
+use crate::utils::colors::print;
 use crate::utils::date;
+use crate::utils::period::Period;
 
 use std::io::{self, Write};
 
 /// Ask a yes/no confirmation from the user
-fn ask_confirmation(prompt: &str) -> bool {
+pub(crate) fn ask_confirmation(prompt: &str) -> bool {
     warning(prompt);
     print!("Confirm [y/N]: ");
     let _ = io::stdout().flush();
@@ -25,19 +30,40 @@ fn ask_confirmation(prompt: &str) -> bool {
 pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
     if let Commands::Del {
         pair,
+        period,
+        last,
+        kind,
         date: date_str,
     } = cmd
     {
+        if let Some(p) = period {
+            return delete_period(cfg, p);
+        }
+        if *last {
+            return delete_last(cfg, kind.as_deref());
+        }
+        let date_str = date_str.as_deref().unwrap_or_default();
         let d =
             date::parse_date(date_str).map_err(|_| AppError::InvalidDate(date_str.to_string()))?;
 
+        let mut pool = DbPool::new(&cfg.database)?;
+
+        // Refuse before asking for confirmation
+        ensure_unlocked(&pool.conn, &d)?;
+
         //
         // Confirmation prompt
         //
         let prompt = if let Some(p) = pair {
-            format!("Delete pair #{} for {}? This action is irreversible.", p, d)
+            format!(
+                "Delete pair #{} for {}? It can be restored from the trash.",
+                p, d
+            )
         } else {
-            format!("Delete ALL events for {}? This action is irreversible.", d)
+            format!(
+                "Delete ALL events for {}? They can be restored from the trash.",
+                d
+            )
         };
 
         if !ask_confirmation(&prompt) {
@@ -48,15 +74,16 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         //
         // Execute deletion
         //
-        let mut pool = DbPool::new(&cfg.database)?;
 
-        match DeleteLogic::apply(&mut pool, d, *pair) {
-            Ok(_) => {
+        match DeleteLogic::apply(&mut pool, d, *pair, cfg.overlap_mode) {
+            Ok(batch) => {
+                record_app_version(&pool.conn)?;
                 if let Some(p) = pair {
                     success(format!("Pair #{} for {} has been deleted.", p, d));
                 } else {
                     success(format!("All events for {} have been deleted.", d));
                 }
+                info(format!("Undo with: rtimelogger trash restore {}", batch));
             }
             Err(e) => {
                 return Err(e);
@@ -66,3 +93,80 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
 
     Ok(())
 }
+
+/// `del --last`: the most recently recorded event of today (optionally IN or
+/// OUT only), after a one-line confirmation.
+fn delete_last(cfg: &Config, kind: Option<&str>) -> AppResult<()> {
+    let today = date::today();
+    let kind = kind.and_then(EventType::et_from_str);
+
+    let mut pool = DbPool::new(&cfg.database)?;
+    ensure_unlocked(&pool.conn, &today)?;
+
+    let Some(ev) = DeleteLogic::last_event(&mut pool, today, kind)? else {
+        info(format!("No events to delete for {}.", today));
+        return Ok(());
+    };
+
+    let what = format!(
+        "{} {} ({}) of {}",
+        ev.kind.et_as_str().to_uppercase(),
+        ev.time_str(),
+        ev.location.label(),
+        today
+    );
+    if !ask_confirmation(&format!("Delete {}?", what)) {
+        info("Operation cancelled.");
+        return Ok(());
+    }
+
+    let batch = DeleteLogic::apply_event(&mut pool, &ev, cfg.overlap_mode)?;
+    record_app_version(&pool.conn)?;
+
+    success(format!("Deleted {}.", what));
+    info(format!("Undo with: rtimelogger trash restore {}", batch));
+    Ok(())
+}
+
+/// `del --period`: every event of the period, after a confirmation showing
+/// how many events and days are involved. One transaction, one trash batch.
+fn delete_period(cfg: &Config, period: &str) -> AppResult<()> {
+    let parsed = Period::parse(period)?;
+    if parsed.bounds().is_none() {
+        return Err(AppError::InvalidArgs(
+            "del --period needs a year, a month, a day or a START:END range (not 'all').".into(),
+        ));
+    }
+
+    let mut pool = DbPool::new(&cfg.database)?;
+    let days = DeleteLogic::period_events(&mut pool, &parsed.iter_dates())?;
+    if days.is_empty() {
+        info(format!("No events found for {}.", period));
+        return Ok(());
+    }
+
+    let events: usize = days.iter().map(|(_, ev)| ev.len()).sum();
+    let (first, last) = (days[0].0, days[days.len() - 1].0);
+    let prompt = format!(
+        "Delete {} event(s) on {} day(s) ({} → {})? They can be restored from the trash.",
+        events,
+        days.len(),
+        first,
+        last
+    );
+    if !ask_confirmation(&prompt) {
+        info("Operation cancelled.");
+        return Ok(());
+    }
+
+    let batch = DeleteLogic::apply_period(&mut pool, &days)?;
+    record_app_version(&pool.conn)?;
+
+    success(format!(
+        "{} event(s) on {} day(s) have been deleted.",
+        events,
+        days.len()
+    ));
+    info(format!("Undo with: rtimelogger trash restore {}", batch));
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::cli::parser::{Commands, DevCommands};
+use crate::config::Config;
+use crate::core::seed::SeedLogic;
+use crate::db::initialize::init_db;
+use crate::db::pool::DbPool;
+use crate::errors::AppResult;
+use crate::ui::messages::{info, success};
+
+/// Handle the hidden `dev` subcommand
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Dev { action } = cmd {
+        match action {
+            DevCommands::Seed {
+                days,
+                profile,
+                seed,
+            } => {
+                info(format!(
+                    "Seeding {} days ({:?} profile) into {}",
+                    days, profile, cfg.database
+                ));
+
+                let mut pool = DbPool::new(&cfg.database)?;
+                // A brand-new test DB has no schema yet
+                init_db(&pool.conn)?;
+                let rep = SeedLogic::seed(&mut pool, *days, *profile, *seed)?;
+
+                success(format!(
+                    "Seed completed: work days={}, holidays={}, skipped (weekend={}, existing_events={}).",
+                    rep.work_days, rep.holidays, rep.skipped_weekend, rep.skipped_existing
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
 use crate::cli::parser::Commands;
 use crate::config::Config;
+use crate::core::mark::expand_period;
+use crate::core::shift::ShiftBands;
 use crate::db::pool::DbPool;
-use crate::errors::AppResult;
-use crate::export::ExportLogic;
+use crate::errors::{AppError, AppResult};
+use crate::export::logic::default_output_file;
+use crate::export::{ExportColumns, ExportFormat, ExportLayout, ExportLogic};
+use crate::utils::formatting::decimal_separator;
 
 pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
     if let Commands::Export {
@@ -11,10 +15,96 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         range,
         events,
         force,
+        encrypt,
+        meta_keys,
+        preset,
+        decimal,
+        iso_datetime,
+        split_by,
+        snapshot,
+        layout,
     } = cmd
     {
+        if preset.is_some() && !matches!(format, ExportFormat::Csv | ExportFormat::Pdf) {
+            return Err(AppError::InvalidArgs(
+                "--preset produces CSV or PDF: use --format csv (default) or --format pdf.".into(),
+            ));
+        }
+
+        if *layout == ExportLayout::WeeklyMatrix && !matches!(format, ExportFormat::Xlsx) {
+            return Err(AppError::InvalidArgs(
+                "--layout weekly-matrix is only available with --format xlsx.".into(),
+            ));
+        }
+
+        let range = &range
+            .as_deref()
+            .map(|r| expand_period(cfg, r))
+            .transpose()?;
+
+        let file = &match file {
+            Some(f) => f.clone(),
+            None => default_output_file(cfg, format, range, split_by.is_some())?,
+        };
+
         let mut pool = DbPool::new(&cfg.database)?;
-        ExportLogic::export(&mut pool, format.clone(), file, range, *events, *force)?;
+
+        if let Some(name) = preset {
+            return ExportLogic::export_preset(
+                &mut pool,
+                cfg,
+                name,
+                file,
+                range,
+                *force,
+                *encrypt,
+                matches!(format, ExportFormat::Pdf),
+            );
+        }
+
+        if *layout == ExportLayout::WeeklyMatrix {
+            return ExportLogic::export_weekly_matrix(
+                &mut pool, cfg, file, range, *force, *encrypt,
+            );
+        }
+
+        let columns = ExportColumns {
+            meta_keys: meta_keys.clone(),
+            decimal_step: decimal.then_some(cfg.decimal_step),
+            // Only the `--decimal` figures use it (and so the `;` CSV delimiter)
+            decimal_separator: decimal
+                .then(|| decimal_separator(cfg))
+                .filter(|c| *c != '.'),
+            iso_datetime: *iso_datetime,
+            snapshot: *snapshot,
+            shift_bands: ShiftBands::from_config(cfg),
+        };
+
+        if let Some(split) = split_by {
+            return ExportLogic::export_split(
+                &mut pool,
+                cfg,
+                format.clone(),
+                file,
+                range,
+                *split,
+                *force,
+                *encrypt,
+                &columns,
+            );
+        }
+
+        ExportLogic::export(
+            &mut pool,
+            cfg,
+            format.clone(),
+            file,
+            range,
+            *events,
+            *force,
+            *encrypt,
+            &columns,
+        )?;
     }
     Ok(())
 }
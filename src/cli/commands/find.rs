@@ -0,0 +1,83 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::find::{FindFilter, FindLogic};
+use crate::core::mark::expand_period;
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{data, info, warning};
+use crate::utils::formatting::{format_surplus, mins2readable};
+use crate::utils::period::Period;
+
+/// Longest note shown in the result table
+const NOTE_WIDTH: usize = 40;
+
+/// Handle the `find` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Find {
+        period,
+        min_surplus,
+        max_surplus,
+        min_worked,
+        max_worked,
+        pos,
+        has_note,
+    } = cmd
+    {
+        let filter = FindFilter {
+            bounds: match period {
+                Some(p) => Period::parse(&expand_period(cfg, p)?)?.bounds(),
+                None => None,
+            },
+            min_surplus: *min_surplus,
+            max_surplus: *max_surplus,
+            min_worked: *min_worked,
+            max_worked: *max_worked,
+            position: *pos,
+            has_note: *has_note,
+        };
+
+        if min_surplus
+            .zip(*max_surplus)
+            .is_some_and(|(lo, hi)| lo > hi)
+            || min_worked.zip(*max_worked).is_some_and(|(lo, hi)| lo > hi)
+        {
+            return Err(AppError::InvalidArgs(
+                "A --min-* value is above the matching --max-* value: no day can match.".into(),
+            ));
+        }
+
+        let mut pool = DbPool::new(&cfg.database)?;
+        let days = FindLogic::search(&mut pool, cfg, &filter)?;
+
+        if days.is_empty() {
+            warning("No days match.");
+            return Ok(());
+        }
+
+        data(format!(
+            "{:<14} {:<3} {:>8} {:>8}  NOTES",
+            "DATE", "POS", "WORKED", "ΔWORK"
+        ));
+        for d in &days {
+            let mut notes = d.notes.join(" / ");
+            if notes.chars().count() > NOTE_WIDTH {
+                notes = notes.chars().take(NOTE_WIDTH - 1).collect::<String>() + "…";
+            }
+            data(format!(
+                "{:<14} {:<3} {:>8} {:>8}  {}",
+                d.date.format("%Y-%m-%d %a"),
+                d.position.code(),
+                d.worked
+                    .map(|w| mins2readable(w, false, true))
+                    .unwrap_or_else(|| "-".into()),
+                d.delta
+                    .map(|v| format_surplus(v, cfg))
+                    .unwrap_or_else(|| "-".into()),
+                notes
+            ));
+        }
+        info(format!("{} day(s) found.", days.len()));
+    }
+
+    Ok(())
+}
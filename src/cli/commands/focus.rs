@@ -0,0 +1,85 @@
+use crate::cli::parser::{Commands, FocusCommands};
+use crate::config::Config;
+use crate::db::log::ttlog;
+use crate::db::pool::DbPool;
+use crate::db::queries::focus::{insert_focus_event, open_focus};
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::success;
+use crate::utils::formatting::mins2readable;
+use chrono::{Local, NaiveTime, Timelike};
+
+/// Handle the `focus start` / `focus stop` commands
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Focus { action } = cmd {
+        let pool = DbPool::new(&cfg.database)?;
+        let now = Local::now().naive_local();
+        let now_time = NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or(now.time());
+
+        match action {
+            FocusCommands::Start { label, at } => {
+                if let Some(open) = open_focus(&pool.conn)? {
+                    return Err(AppError::InvalidArgs(format!(
+                        "A focus session is already running since {} {}: stop it first.",
+                        open.date,
+                        open.start.format("%H:%M")
+                    )));
+                }
+
+                let label = label.as_deref().map(str::trim).filter(|l| !l.is_empty());
+                let time = at.unwrap_or(now_time);
+                insert_focus_event(&pool.conn, &now.date(), &time, true, label)?;
+                ttlog(
+                    &pool.conn,
+                    "focus_start",
+                    &now.date().to_string(),
+                    label.unwrap_or("-"),
+                )?;
+
+                success(format!(
+                    "🎯 Focus started at {}{}",
+                    time.format("%H:%M"),
+                    label.map(|l| format!(" ({})", l)).unwrap_or_default()
+                ));
+            }
+
+            FocusCommands::Stop { at } => {
+                let open = open_focus(&pool.conn)?
+                    .ok_or_else(|| AppError::InvalidArgs("No focus session is running.".into()))?;
+
+                let time = match at {
+                    Some(t) => *t,
+                    None if open.date == now.date() => now_time,
+                    None => {
+                        return Err(AppError::InvalidArgs(format!(
+                            "The focus session started on {} is still open: stop it with --at HH:MM.",
+                            open.date
+                        )));
+                    }
+                };
+                if time < open.start {
+                    return Err(AppError::InvalidArgs(format!(
+                        "Stop time {} is before the start ({}).",
+                        time.format("%H:%M"),
+                        open.start.format("%H:%M")
+                    )));
+                }
+
+                insert_focus_event(&pool.conn, &open.date, &time, false, None)?;
+                ttlog(
+                    &pool.conn,
+                    "focus_stop",
+                    &open.date.to_string(),
+                    open.label.as_deref().unwrap_or("-"),
+                )?;
+
+                success(format!(
+                    "🎯 Focus stopped at {} after {}",
+                    time.format("%H:%M"),
+                    mins2readable((time - open.start).num_minutes(), false, true)
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,10 +1,14 @@
 use std::fs;
+use std::io::{self, IsTerminal, Read};
 
 use crate::cli::parser::Commands;
 use crate::config::Config;
 use crate::errors::{AppError, AppResult};
-use crate::import::{ImportInputFormat, import_days_from_str};
-use crate::ui::messages::{info, success, warning};
+use crate::import::{
+    ImportAction, ImportInputFormat, ImportReport, ImportRow, import_days_from_str,
+    import_toggl_from_str,
+};
+use crate::ui::messages::{data, info, success, warning};
 
 use crate::utils::formatting::build_import_source;
 use serde::{Deserialize, Serialize};
@@ -45,11 +49,38 @@ fn normalize_json_to_days(content: &str) -> AppResult<String> {
         .map_err(|e| AppError::Other(format!("Internal error while normalizing JSON: {}", e)))
 }
 
+/// Name shown in the summary for `--stdin`.
+const STDIN_LABEL: &str = "<stdin>";
+
+/// `--stdin`: the whole piped input (nothing to read from a terminal).
+fn read_stdin() -> AppResult<String> {
+    let mut stdin = io::stdin();
+    if stdin.is_terminal() {
+        return Err(AppError::InvalidArgs(
+            "--stdin expects piped data, e.g. `cat day.csv | rtimelogger import --stdin --format csv`."
+                .into(),
+        ));
+    }
+
+    let mut content = String::new();
+    stdin.read_to_string(&mut content)?;
+    if content.trim().is_empty() {
+        return Err(AppError::InvalidArgs(
+            "Nothing to import: stdin is empty.".into(),
+        ));
+    }
+    Ok(content)
+}
+
 pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
     let Commands::Import {
         file,
+        stdin,
         format,
+        from,
         dry_run,
+        diff,
+        json,
         replace,
         source,
     } = cmd
@@ -57,7 +88,16 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         return Ok(());
     };
 
-    let mut content = fs::read_to_string(file)?;
+    let (mut content, file) = match file {
+        Some(path) if !*stdin => (fs::read_to_string(path)?, path.as_str()),
+        _ => (read_stdin()?, STDIN_LABEL),
+    };
+
+    if let Some(tool) = from {
+        let imp_source = build_import_source(source, tool);
+        let report = import_toggl_from_str(cfg, &content, *dry_run, *replace, &imp_source)?;
+        return print_report(&report, file, tool, source, *dry_run, *replace);
+    }
 
     let input_format = match format.to_ascii_lowercase().as_str() {
         "json" => ImportInputFormat::Json,
@@ -85,26 +125,105 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         imp_source.as_str(),
     )?;
 
+    if *json {
+        return print_diff_json(&report, file, format);
+    }
+    if *diff {
+        print_diff(&report, format);
+    }
+
+    print_report(&report, file, format, source, *dry_run, *replace)
+}
+
+/// `--diff`: one line per date (what would be inserted or why it is skipped),
+/// then the rows that failed validation.
+fn print_diff(report: &ImportReport, format: &str) {
+    let place = if format.eq_ignore_ascii_case("csv") {
+        "line"
+    } else {
+        "item"
+    };
+
+    let mut dated: Vec<&ImportRow> = report.rows.iter().filter(|r| r.date.is_some()).collect();
+    dated.sort_by_key(|r| (r.date, r.line));
+
+    if dated.is_empty() {
+        info("Nothing to import.");
+    }
+    for r in dated {
+        let sign = match r.action {
+            ImportAction::Insert => "+",
+            ImportAction::Replace => "~",
+            ImportAction::Invalid => "!",
+            _ => "=",
+        };
+        data(format!(
+            "{} {}  {:<22} {:<24} ({} {})",
+            sign,
+            r.date.map(|d| d.to_string()).unwrap_or_default(),
+            r.action.label(),
+            r.detail,
+            place,
+            r.line
+        ));
+    }
+
+    for r in report.rows.iter().filter(|r| r.date.is_none()) {
+        data(format!("! {} {}: {}", place, r.line, r.detail));
+    }
+}
+
+/// `--diff --json`: the same view for scripts, with the totals.
+fn print_diff_json(report: &ImportReport, file: &str, format: &str) -> AppResult<()> {
+    let out = serde_json::json!({
+        "file": file,
+        "format": format,
+        "dry_run": true,
+        "summary": {
+            "total": report.total,
+            "imported": report.imported,
+            "skipped_existing": report.skipped_existing,
+            "locked": report.locked,
+            "conflicts": report.conflicts,
+            "invalid": report.invalid,
+        },
+        "rows": report.rows,
+    });
+    let text = serde_json::to_string_pretty(&out)
+        .map_err(|e| AppError::Other(format!("Failed to serialize the diff: {}", e)))?;
+    data(text);
+    Ok(())
+}
+
+fn print_report(
+    report: &ImportReport,
+    file: &str,
+    format: &str,
+    source: &str,
+    dry_run: bool,
+    replace: bool,
+) -> AppResult<()> {
     info(format!(
-        "Import summary{}:\n- File: {}\n- Format: {}\n- Source: {}\n- Total rows: {}\n- Imported: {}\n- Skipped (already present): {}\n- Conflicts: {}\n- Invalid rows: {}",
-        if *dry_run { " (dry-run)" } else { "" },
+        "Import summary{}:\n- File: {}\n- Format: {}\n- Source: {}\n- Total rows: {}\n- Imported: {}\n- Skipped (already present): {}\n- Skipped (locked): {}\n- Conflicts: {}\n- Invalid rows: {}",
+        if dry_run { " (dry-run)" } else { "" },
         file,
         format,
         source,
         report.total,
         report.imported,
         report.skipped_existing,
+        report.locked,
         report.conflicts,
         report.invalid
     ));
 
-    if report.conflicts > 0 && !*replace {
+    if report.conflicts > 0 && !replace {
         warning(
             "Some dates were skipped due to existing work events. Use --replace to override (dangerous).",
         );
     }
 
-    if *dry_run {
+    if dry_run {
         success("Dry-run completed. No changes were applied.");
     } else {
         success("Import completed.");
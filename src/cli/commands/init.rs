@@ -1,33 +1,110 @@
 use crate::config::Config;
+use crate::config::presets::{ContractPreset, PRESETS};
 use crate::db::log;
-use crate::errors::AppResult;
+use crate::errors::{AppError, AppResult};
 
-use crate::cli::parser::Cli;
+use crate::cli::parser::{Cli, Commands};
 use crate::db::initialize::init_db;
-use crate::ui::messages::{info, success, warning};
+use crate::db::metadata::{ensure_rtimelogger_db, is_rtimelogger_db};
+use crate::db::pool::{MEMORY_DB, is_memory_db};
+use crate::models::location::Location;
+use crate::ui::messages::{data, info, success, warning};
+use crate::utils::time::{parse_duration_arg, parse_lunch_window};
 
 use rusqlite::Connection;
+use std::io::{self, IsTerminal, Write};
+
+/// Accepted values of `show_weekday`
+const WEEKDAY_MODES: [&str; 4] = ["None", "Short", "Medium", "Long"];
 
 /// Handle the `init` command
 ///
 /// Responsibilities:
 ///  - Create config directory (if missing)
-///  - Create config file (if missing)
+///  - Create config file (if missing), from `--preset` or the defaults,
+///    asking the main settings first unless `--defaults` is given or stdin
+///    is not a terminal
 ///  - Initialize SQLite database
 ///  - Run migrations
 pub fn handle(cli: &Cli) -> AppResult<()> {
+    let Some(Commands::Init {
+        force_init,
+        defaults,
+        preset,
+        list_presets,
+    }) = &cli.command
+    else {
+        return Ok(());
+    };
+
+    if *list_presets {
+        print_presets();
+        return Ok(());
+    }
+
+    let preset = preset
+        .as_deref()
+        .map(|name| {
+            ContractPreset::find(name).ok_or_else(|| {
+                AppError::InvalidArgs(format!(
+                    "Unknown preset '{}'. Available: {} (see `init --list-presets`).",
+                    name,
+                    PRESETS
+                        .iter()
+                        .map(|p| p.name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })
+        })
+        .transpose()?;
+
     //
     // 1️⃣ INITIALIZE CONFIGURATION
     //
-    if let Some(custom) = &cli.db {
-        Config::init_all(Some(custom.clone()), cli.test)?;
-    } else {
-        Config::init_all(None, cli.test)?;
-    }
+    // `--db <name>` listed under `databases`: initialize that DB and keep the
+    // existing config file (rewriting it would drop the named databases)
+    let named_db = cli
+        .db()
+        .filter(|_| Config::config_file().exists())
+        .and_then(|name| {
+            let existing = Config::load_for(cli.test);
+            existing
+                .databases
+                .contains_key(name)
+                .then(|| existing.resolve_database(name))
+        });
+
+    let in_memory = cli.db().is_some_and(|d| is_memory_db(d));
 
+    let keeps_config = named_db.is_some() || in_memory;
+    let db_path = match named_db {
+        Some(path) => path,
+        // Nothing to write on disk: neither the config nor a DB file
+        None if in_memory => MEMORY_DB.to_string(),
+        None => {
+            let base = preset
+                .map(|p| p.apply(Config::default()))
+                .unwrap_or_default();
+            let settings = if *defaults || cli.test || !io::stdin().is_terminal() {
+                base
+            } else {
+                setup_wizard(base)
+            };
+            Config::init_all(cli.db().cloned(), cli.test, settings)?
+                .to_string_lossy()
+                .to_string()
+        }
+    };
+    if let Some(p) = preset
+        && keeps_config
+    {
+        warning(format!(
+            "--preset {} ignored: the existing config file is kept.",
+            p.name
+        ));
+    }
     let config_path = Config::config_file();
-    let cfg = Config::load();
-    let db_path = cfg.database.clone();
 
     info("Initializing rTimelogger…");
     info(format!("Config file : {}", config_path.display()));
@@ -38,6 +115,12 @@ pub fn handle(cli: &Cli) -> AppResult<()> {
     //
     let conn = Connection::open(&db_path)?;
 
+    if !*force_init {
+        ensure_rtimelogger_db(&conn, &db_path)?;
+    } else if !is_rtimelogger_db(&conn)? {
+        warning("--force-init: adding rTimelogger tables to an existing SQLite file.");
+    }
+
     //
     // 3️⃣ INITIALIZE DB STRUCTURE + RUN MIGRATIONS
     //
@@ -59,3 +142,127 @@ pub fn handle(cli: &Cli) -> AppResult<()> {
     success("rTimelogger initialization completed!");
     Ok(())
 }
+
+/// `init --list-presets`
+fn print_presets() {
+    data(format!(
+        "{:<14} {:>6} {:>7} {:<12} {:>9}  DESCRIPTION",
+        "PRESET", "WEEK", "DAY", "LUNCH", "MIN-MAX"
+    ));
+    for p in PRESETS {
+        let lunch = format!(
+            "{}-{}",
+            p.min_duration_lunch_break, p.max_duration_lunch_break
+        );
+        data(format!(
+            "{:<14} {:>5}h {:>7} {:<12} {:>9}  {}",
+            p.name, p.weekly_hours, p.min_work_duration, p.lunch_window, lunch, p.description
+        ));
+    }
+    info(
+        "Use one with `rtimelogger init --preset <name>`: the setup questions start from its values.",
+    );
+}
+
+/// Ask the settings the surplus depends on; Enter keeps the proposed value.
+fn setup_wizard(base: Config) -> Config {
+    info(
+        "Setup — press Enter to keep the value in brackets (rtimelogger init --defaults skips it).",
+    );
+
+    let work = ask("Contract hours per day", &base.min_work_duration, |s| {
+        parse_duration_arg(s)
+            .ok()
+            .filter(|m| *m > 0)
+            .map(format_hours)
+    });
+    let lunch_window = ask("Lunch window (HH:MM-HH:MM)", &base.lunch_window, |s| {
+        parse_lunch_window(s)
+            .filter(|(a, b)| a < b)
+            .map(|_| s.to_string())
+    });
+    let min_lunch = ask(
+        "Minimum lunch break (minutes, added automatically when the day crosses the window)",
+        &base.min_duration_lunch_break.to_string(),
+        |s| {
+            s.parse::<i32>()
+                .ok()
+                .filter(|m| *m >= 0)
+                .map(|m| m.to_string())
+        },
+    );
+    let max_lunch = ask(
+        "Maximum lunch break (minutes)",
+        &base.max_duration_lunch_break.to_string(),
+        |s| {
+            s.parse::<i32>()
+                .ok()
+                .filter(|m| *m >= min_lunch.parse().unwrap_or(0))
+                .map(|m| m.to_string())
+        },
+    );
+    let position = ask(
+        "Default position (O = Office, R = Remote, C = Client)",
+        &base.default_position,
+        |s| {
+            Location::from_code(s)
+                .filter(|l| !l.is_marker())
+                .map(|l| l.code().to_string())
+        },
+    );
+    let weekday = ask(
+        "Weekday in list (None, Short, Medium, Long)",
+        &base.show_weekday,
+        |s| {
+            WEEKDAY_MODES
+                .iter()
+                .find(|m| m.eq_ignore_ascii_case(s))
+                .map(|m| m.to_string())
+        },
+    );
+
+    info(format!(
+        "{} a day, lunch {} ({}-{} min), position {}, weekday {}",
+        work, lunch_window, min_lunch, max_lunch, position, weekday
+    ));
+
+    Config {
+        min_work_duration: work,
+        lunch_window,
+        min_duration_lunch_break: min_lunch.parse().unwrap_or(base.min_duration_lunch_break),
+        max_duration_lunch_break: max_lunch.parse().unwrap_or(base.max_duration_lunch_break),
+        default_position: position,
+        show_weekday: weekday,
+        ..base
+    }
+}
+
+/// Prompt until `valid` accepts the answer (normalized); an empty answer
+/// or the end of input keeps `default`.
+fn ask(prompt: &str, default: &str, valid: impl Fn(&str) -> Option<String>) -> String {
+    loop {
+        print!("{} [{}]: ", prompt, default);
+        let _ = io::stdout().flush();
+
+        let mut s = String::new();
+        if io::stdin().read_line(&mut s).unwrap_or(0) == 0 {
+            return default.to_string();
+        }
+        let s = s.trim();
+        if s.is_empty() {
+            return default.to_string();
+        }
+        match valid(s) {
+            Some(v) => return v,
+            None => warning(format!("'{}' is not valid here, try again.", s)),
+        }
+    }
+}
+
+/// 456 → "7h36m", 480 → "8h" (the format of `min_work_duration`)
+fn format_hours(minutes: i64) -> String {
+    match minutes % 60 {
+        0 => format!("{}h", minutes / 60),
+        m => format!("{}h{}m", minutes / 60, m),
+    }
+}
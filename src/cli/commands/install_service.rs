@@ -0,0 +1,95 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::service::{Schedule, ServiceLogic, ServiceSpec, ServiceTask, schedule_label};
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{data, info, success, warning};
+
+/// Handle the `install-service` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::InstallService {
+        task,
+        schedule,
+        user,
+        backup_file,
+        windows,
+        print,
+        force,
+    } = cmd
+    {
+        if cfg.test_mode && !*print {
+            warning("Test mode: service files not written (use --print to see them).");
+            return Ok(());
+        }
+
+        let schedule = match (task, schedule) {
+            (ServiceTask::Serve, Some(_)) => {
+                return Err(AppError::InvalidArgs(
+                    "serve runs continuously: --schedule is not accepted.".into(),
+                ));
+            }
+            (_, Some(s)) => Some(Schedule::parse(s)?),
+            (_, None) => None,
+        };
+
+        let spec = ServiceSpec {
+            task: *task,
+            schedule,
+            user: *user,
+            backup_file: backup_file
+                .clone()
+                .unwrap_or_else(ServiceLogic::default_backup_file),
+            exe: std::env::current_exe()?,
+        };
+
+        let windows = *windows || cfg!(target_os = "windows");
+        let files = if windows {
+            vec![ServiceLogic::windows_task(&spec, &Config::config_dir())]
+        } else {
+            ServiceLogic::systemd_files(&spec, &ServiceLogic::systemd_dir(*user))
+        };
+
+        if *print {
+            for f in &files {
+                info(format!("# {}", f.path.display()));
+                data(&f.content);
+            }
+            return Ok(());
+        }
+
+        ServiceLogic::write(&files, *force)?;
+        for f in &files {
+            success(format!("Written {}", f.path.display()));
+        }
+
+        let name = task.unit_name();
+        if let Some(s) = &spec.schedule {
+            info(format!("Runs {}: {}", task.name(), schedule_label(s)));
+        }
+
+        // Enabling is left to the user: print the commands
+        if windows {
+            info("Register it with:");
+            data(format!(
+                "  schtasks /Create /TN {} /XML \"{}\"",
+                name,
+                files[0].path.display()
+            ));
+        } else {
+            let ctl = if *user {
+                "systemctl --user"
+            } else {
+                "sudo systemctl"
+            };
+            let unit = if spec.schedule.is_some() {
+                format!("{}.timer", name)
+            } else {
+                format!("{}.service", name)
+            };
+            info("Enable it with:");
+            data(format!("  {} daemon-reload", ctl));
+            data(format!("  {} enable --now {}", ctl, unit));
+        }
+    }
+
+    Ok(())
+}
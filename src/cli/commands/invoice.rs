@@ -0,0 +1,98 @@
+use crate::cli::commands::list::resolve_period;
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::invoice::InvoiceLogic;
+use crate::db::log::ttlog;
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::export::fs_utils::ensure_writable;
+use crate::export::invoice::{InvoiceHeader, write_invoice};
+use crate::ui::messages::warning;
+use crate::utils::date;
+use crate::utils::path::expand_tilde;
+use crate::utils::period::Period;
+use std::path::PathBuf;
+
+/// Handle the `invoice` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Invoice {
+        period,
+        rate,
+        client,
+        format,
+        file,
+        tax,
+        number,
+        currency,
+        force,
+    } = cmd
+    {
+        if *rate <= 0.0 {
+            return Err(AppError::InvalidArgs("--rate must be positive.".into()));
+        }
+
+        let mut pool = DbPool::new(&cfg.database)?;
+        let dates = resolve_period(&pool, &Some(Period::parse(period)?))?;
+        let invoice = InvoiceLogic::build(&mut pool, cfg, &dates, client, *rate, *tax)?;
+
+        if invoice.lines.is_empty() {
+            warning(format!(
+                "⚠️  No closed working days in {}: nothing to invoice.",
+                period
+            ));
+            return Ok(());
+        }
+
+        let path = match file {
+            Some(f) => expand_tilde(f),
+            None => PathBuf::from(format!(
+                "invoice-{}-{}.{}",
+                slug(client),
+                slug(period),
+                format.extension()
+            )),
+        };
+        ensure_writable(&path, *force)?;
+
+        let header = InvoiceHeader {
+            number: number.clone(),
+            issue_date: date::today().format("%Y-%m-%d").to_string(),
+            period: period.clone(),
+            currency: currency.clone(),
+        };
+        write_invoice(&invoice, &header, *format, &path)?;
+
+        ttlog(
+            &pool.conn,
+            "invoice",
+            &path.display().to_string(),
+            &format!(
+                "{} {}: {:.2} h, {:.2} {}",
+                client,
+                period,
+                invoice.hours(),
+                invoice.subtotal(),
+                currency
+            ),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// File-name friendly form of a client or period ("ACME Srl" → "acme-srl").
+fn slug(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -1,17 +1,29 @@
 use crate::cli::parser::Commands;
 use crate::config::Config;
+use crate::core::calculator::expected::auto_lunch;
+use crate::core::calculator::timeline::unmatched_events;
 use crate::core::logic::Core;
+use crate::core::mark::expand_period;
+use crate::db::metadata::get_meta;
 use crate::db::pool::DbPool;
+use crate::db::queries::archive::{KEY_ARCHIVE_DB, attach_archive, create_events_union_view};
+use crate::db::queries::events::date_bounds;
 use crate::db::queries::load_events_by_date;
+use crate::db::queries::marks::load_marks;
+use crate::db::queries::toil::{ToilKind, load_toil};
 use crate::errors::{AppError, AppResult};
 use crate::models::day_summary::DaySummary;
 use crate::models::event::Event;
 use crate::models::location::Location;
-use crate::ui::messages::{info, warning};
+use crate::ui::messages::{data, info, warning};
 use crate::utils::date::get_day_position;
+use crate::utils::formatting::DurationStyle;
+use crate::utils::meta::format_meta;
+use crate::utils::period::Period;
 use crate::utils::table::EVENTS_TABLE_WIDTH;
-use crate::utils::{colors, date, formatting, mins2readable};
-use chrono::{Datelike, NaiveDate};
+use crate::utils::{colors, date, formatting};
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::{HashMap, HashSet};
 use textwrap::{Options, fill};
 
 //
@@ -75,17 +87,177 @@ fn date_col_width(mode: WeekdayMode) -> usize {
 
 // Column widths (daily standard table)
 const POS_W: usize = 16;
+const HOST_W: usize = 12;
+const SUMMARY_TABLE_WIDTH: usize = 73;
 const TIME_W: usize = 5; // IN / LNCH / OUT / TGT
-const DWORK_W: usize = 7;
+const DWORK_W: usize = 7; // WORKED / EXP / ΔWORK
+const CUM_W: usize = 8; // ΣΔWORK (month-to-date, may exceed 99h)
 
-/// Daily table total width, computed from column widths.
+/// Selectable columns of the daily standard table (`--columns`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DailyColumn {
+    Date,
+    Position,
+    In,
+    Lunch,
+    Out,
+    Target,
+    Worked,
+    Expected,
+    Delta,
+    /// Running month-to-date ΔWORK (`--cumulative`)
+    Cumulative,
+}
+
+/// Default layout: every column, in this order.
+const DEFAULT_COLUMNS: [DailyColumn; 9] = [
+    DailyColumn::Date,
+    DailyColumn::Position,
+    DailyColumn::In,
+    DailyColumn::Lunch,
+    DailyColumn::Out,
+    DailyColumn::Target,
+    DailyColumn::Worked,
+    DailyColumn::Expected,
+    DailyColumn::Delta,
+];
+
+impl DailyColumn {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "date" => Some(Self::Date),
+            "pos" | "position" => Some(Self::Position),
+            "in" => Some(Self::In),
+            "lunch" | "lnch" => Some(Self::Lunch),
+            "out" => Some(Self::Out),
+            "tgt" | "target" => Some(Self::Target),
+            "worked" => Some(Self::Worked),
+            "exp" | "expected" => Some(Self::Expected),
+            "delta" | "surplus" => Some(Self::Delta),
+            "cum" | "cumulative" => Some(Self::Cumulative),
+            _ => None,
+        }
+    }
+
+    fn header(&self) -> &'static str {
+        match self {
+            Self::Date => "DATE",
+            Self::Position => "POSITION",
+            Self::In => "IN",
+            Self::Lunch => "LNCH",
+            Self::Out => "OUT",
+            Self::Target => "TGT",
+            Self::Worked => "WORKED",
+            Self::Expected => "EXP",
+            Self::Delta => "ΔWORK",
+            Self::Cumulative => "ΣΔWORK",
+        }
+    }
+
+    fn width(&self, mode: WeekdayMode) -> usize {
+        match self {
+            Self::Date => date_col_width(mode),
+            Self::Position => POS_W,
+            Self::In | Self::Lunch | Self::Out | Self::Target => TIME_W,
+            Self::Worked | Self::Expected | Self::Delta => DWORK_W,
+            Self::Cumulative => CUM_W,
+        }
+    }
+}
+
+/// Parse `--columns date,pos,in,...` (None → default layout).
+fn parse_columns(spec: &Option<String>) -> AppResult<Vec<DailyColumn>> {
+    let Some(spec) = spec else {
+        return Ok(DEFAULT_COLUMNS.to_vec());
+    };
+
+    let mut cols = Vec::new();
+    for name in spec.split(',').filter(|s| !s.trim().is_empty()) {
+        let col = DailyColumn::parse(name).ok_or_else(|| {
+            AppError::InvalidArgs(format!(
+                "Unknown column '{}'. Valid columns: date, pos, in, lunch, out, tgt, worked, expected, delta, cum.",
+                name.trim()
+            ))
+        })?;
+        if !cols.contains(&col) {
+            cols.push(col);
+        }
+    }
+
+    if cols.is_empty() {
+        return Err(AppError::InvalidArgs(
+            "--columns requires at least one column.".into(),
+        ));
+    }
+
+    Ok(cols)
+}
+
+/// Daily table total width, computed from the selected column widths.
 /// Format used:
-/// " {DATE} | {POSITION} | {IN} | {LNCH} | {OUT} | {TGT} | {ΔWORK}"
-fn daily_table_width(mode: WeekdayMode) -> usize {
-    let dw = date_col_width(mode);
-    // 1 leading space + cols + separators (" | " = 3 chars) between 7 columns
-    // Total = 1 + date + 3 + pos + 3 + in + 3 + lnch + 3 + out + 3 + tgt + 3 + dwork
-    1 + dw + 3 + POS_W + 3 + TIME_W + 3 + TIME_W + 3 + TIME_W + 3 + TIME_W + 3 + DWORK_W + 1
+/// " {COL} | {COL} | ... | {COL}"
+fn daily_table_width(mode: WeekdayMode, cols: &[DailyColumn]) -> usize {
+    let widths: usize = cols.iter().map(|c| c.width(mode)).sum();
+    // 1 leading space + cols + separators (" | " = 3 chars) + 1 trailing
+    1 + widths + 3 * cols.len().saturating_sub(1) + 1
+}
+
+/// Pad a (possibly ANSI-colored) cell to `width` visible chars.
+fn pad_cell(s: &str, width: usize, col: DailyColumn) -> String {
+    let pad = width.saturating_sub(formatting::visible_len(s));
+    match col {
+        DailyColumn::Date | DailyColumn::Position => format!("{}{}", s, " ".repeat(pad)),
+        DailyColumn::Worked
+        | DailyColumn::Expected
+        | DailyColumn::Delta
+        | DailyColumn::Cumulative => {
+            format!("{}{}", " ".repeat(pad), s)
+        }
+        _ => {
+            let left = pad / 2;
+            format!("{}{}{}", " ".repeat(left), s, " ".repeat(pad - left))
+        }
+    }
+}
+
+/// Period totals printed in the footer (closed working days only).
+#[derive(Debug, Default)]
+struct PeriodTotals {
+    worked: i64,
+    expected: i64,
+    days: i64,
+}
+
+impl PeriodTotals {
+    fn add_day(&mut self, summary: &DaySummary, events: &[Event], cfg: &Config) {
+        if Core::is_marker_day(summary) {
+            return;
+        }
+
+        if let Some(fig) = Core::day_figures(summary, events, cfg)
+            && let Some(worked) = fig.worked
+        {
+            self.worked += worked;
+            self.expected += fig.expected;
+            self.days += 1;
+        }
+    }
+
+    fn average(&self) -> i64 {
+        if self.days == 0 {
+            0
+        } else {
+            self.worked / self.days
+        }
+    }
+}
+
+/// "08h12m" (or "8.20" with --decimal) style duration, grey "-" when missing.
+fn format_duration_cell(minutes: Option<i64>, style: DurationStyle) -> String {
+    match minutes {
+        Some(v) => style.format(v, false),
+        None => format!("{}-{}", colors::GREY, colors::RESET),
+    }
 }
 
 // Compact table widths
@@ -113,6 +285,20 @@ fn format_date_with_weekday(date: &NaiveDate, mode: WeekdayMode) -> String {
     }
 }
 
+fn is_weekend(date: &NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Weekend and national-holiday rows get a dimmed date, so that the working
+/// days stand out in a month view.
+fn dim_date(date_str: String, date: &NaiveDate, position: Location) -> String {
+    if is_weekend(date) || position == Location::NationalHoliday {
+        format!("{}{}{}", colors::GREY, date_str, colors::RESET)
+    } else {
+        date_str
+    }
+}
+
 fn get_meta_string(events: &[Event], max_chars: usize) -> String {
     if max_chars == 0 {
         return String::new();
@@ -120,8 +306,8 @@ fn get_meta_string(events: &[Event], max_chars: usize) -> String {
 
     let joined = events
         .iter()
-        .filter_map(|e| e.meta.as_deref())
-        .filter(|s| !s.trim().is_empty())
+        .map(|e| format_meta(e.meta.as_deref()))
+        .filter(|s| !s.is_empty())
         .collect::<Vec<_>>()
         .join(", ");
 
@@ -143,15 +329,34 @@ fn remaining_width(total_width: usize, plain_prefix: &str) -> usize {
     total_width.saturating_sub(plain_prefix.len())
 }
 
-/// Returns the total duration, in minutes, of gaps that are not marked as work gaps.
-fn total_non_work_gap_minutes(summary: &DaySummary) -> i64 {
-    summary
-        .timeline
-        .gaps
-        .iter()
-        .filter(|g| !g.is_work_gap)
-        .map(|g| g.duration_minutes)
-        .sum()
+/// `--pairs N` filters by the per-day pair number, so it only makes sense
+/// for `--events` on a single day (`--today` or `--period YYYY-MM-DD`).
+fn validate_pairs_filter(
+    pair: usize,
+    events_only: bool,
+    now: bool,
+    period: &Option<Period>,
+) -> AppResult<()> {
+    if !events_only {
+        return Err(AppError::InvalidArgs(
+            "--pairs can only be used together with --events.".into(),
+        ));
+    }
+
+    if pair == 0 {
+        return Err(AppError::InvalidArgs(
+            "--pairs expects a pair number starting from 1.".into(),
+        ));
+    }
+
+    let single_day = now || matches!(period, Some(Period::Range { from, to }) if from == to);
+    if !single_day {
+        return Err(AppError::InvalidArgs(
+            "--pairs requires a single day: use --today or --period YYYY-MM-DD (e.g. --pairs 2 --period 2025-06-18).".into(),
+        ));
+    }
+
+    Ok(())
 }
 
 //
@@ -166,62 +371,193 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
         period,
         now,
         details,
-        events: events_only,
+        events,
+        pairs,
+        unmatched,
+        meta,
+        summary,
+        only_unmatched,
+        host,
+        columns,
+        decimal,
+        weekday,
+        only_workdays,
+        archive,
+        cumulative,
+        porcelain,
         ..
     } = cmd
     {
+        let events_only = *events || *unmatched || *porcelain;
+        let style = DurationStyle::new(*decimal, cfg.decimal_step);
+
+        if *meta && !events_only {
+            return Err(AppError::InvalidArgs(
+                "--meta can only be used together with --events.".into(),
+            ));
+        }
+
+        if *summary && !events_only {
+            return Err(AppError::InvalidArgs(
+                "--summary can only be used together with --events.".into(),
+            ));
+        }
+
+        if host.is_some() && !events_only {
+            return Err(AppError::InvalidArgs(
+                "--host can only be used together with --events.".into(),
+            ));
+        }
+        let show = EventColumns {
+            meta: *meta,
+            host: host.is_some(),
+        };
+
         if *compact && *details {
             return Err(AppError::InvalidArgs(
                 "--compact cannot be used together with --details.".into(),
             ));
         }
 
+        if *compact && columns.is_some() {
+            return Err(AppError::InvalidArgs(
+                "--columns cannot be used together with --compact.".into(),
+            ));
+        }
+        if *compact && *cumulative {
+            return Err(AppError::InvalidArgs(
+                "--cumulative cannot be used together with --compact.".into(),
+            ));
+        }
+        let mut cols = parse_columns(columns)?;
+        if *cumulative && !cols.contains(&DailyColumn::Cumulative) {
+            cols.push(DailyColumn::Cumulative);
+        }
+
+        // Bookmarks (`mark:<name>`) become dates, then --period / --weekday
+        // are parsed before touching the database
+        let period = period
+            .iter()
+            .map(|p| expand_period(cfg, p))
+            .collect::<AppResult<Vec<_>>>()?;
+        let parsed_periods = period
+            .iter()
+            .map(|p| Period::parse(p))
+            .collect::<AppResult<Vec<_>>>()?;
+        let parsed_period = match parsed_periods.as_slice() {
+            [single] => Some(single.clone()),
+            _ => None,
+        };
+        let single_period = match period.as_slice() {
+            [single] => Some(single.clone()),
+            _ => None,
+        };
+        let weekdays = weekday.as_deref().map(date::parse_weekdays).transpose()?;
+
+        if let Some(p) = pairs {
+            validate_pairs_filter(*p, events_only, *now, &parsed_period)?;
+        }
+
         let mut pool = DbPool::new(&cfg.database)?;
+        if *archive {
+            include_archive(&pool)?;
+        }
         let wd_mode_cfg = weekday_mode(cfg);
         let wd_mode = effective_weekday_mode(wd_mode_cfg, *compact);
 
-        // 1️⃣ Determine dates
-        let dates = if *now {
-            vec![date::today()]
+        // 1️⃣ Determine dates, tagged with their --period block (a date
+        //    shared by two blocks is shown only in the first one)
+        let mut dates: Vec<(usize, NaiveDate)> = if *now {
+            vec![(0, date::today())]
+        } else if parsed_periods.len() > 1 {
+            let mut seen = HashSet::new();
+            let mut out = Vec::new();
+            for (block, p) in parsed_periods.iter().enumerate() {
+                for d in resolve_period(&pool, &Some(p.clone()))? {
+                    if seen.insert(d) {
+                        out.push((block, d));
+                    }
+                }
+            }
+            out
         } else {
-            resolve_period(period)?
+            resolve_period(&pool, &parsed_period)?
+                .into_iter()
+                .map(|d| (0, d))
+                .collect()
         };
+        if let Some(wds) = &weekdays {
+            dates.retain(|(_, d)| wds.contains(&d.weekday()));
+        }
+        if *only_workdays {
+            dates.retain(|(_, d)| !is_weekend(d));
+        }
+        let multi_block = parsed_periods.len() > 1 && !*now;
+        let first_day = dates.iter().map(|(_, d)| *d).min();
+        let last_day = dates.iter().map(|(_, d)| *d).max();
 
         if dates.is_empty() {
             warning("⚠️  No recorded sessions found");
             return Ok(());
         }
 
-        // 2️⃣ Header (only if not --now)
-        if !*now {
-            if period.is_some() {
-                print_header(period);
+        // Bookmarks shown under their day
+        let mut marks: HashMap<NaiveDate, Vec<String>> = HashMap::new();
+        for m in load_marks(&pool.conn, first_day.zip(last_day))? {
+            marks.entry(m.date).or_default().push(m.label);
+        }
+
+        // 2️⃣ Header (only if not --now; one per block with several --period)
+        if !*now && !multi_block {
+            if single_period.is_some() {
+                print_header(&single_period);
             } else {
                 print_header(&Some("this_month".to_string()));
             }
         }
+        let mut last_block: Option<usize> = None;
 
         let mut total_surplus: i64 = 0;
+        // Running ΔWORK of the current month (ΣΔWORK column)
+        let mut month_surplus: i64 = 0;
+        let mut totals = PeriodTotals::default();
         let mut any_output = false;
 
         // Month separator state (only for daily summaries)
         let mut last_month: Option<(i32, u32)> = None;
         let mut printed_daily_header = false;
 
-        // EVENTS header if requested
-        if *events_only && Event::has_events_for_dates(&mut pool, &dates)? {
-            println!("EVENTS:");
-            println!();
-            println!(
-                " {:^17} | {:^4} | {:^12} | {:^16} | {:^6} | {:^4} | {:^8}",
-                "Date Time", "Type", "Lunch", "Position", "Source", "Pair", "Work Gap"
-            );
-            println!("{:-<w$}", "-", w = EVENTS_TABLE_WIDTH);
-        }
+        // EVENTS header (printed lazily, before the first event row)
+        let mut printed_events_header = false;
+
+        // --summary rows, printed after the events
+        let mut summary_rows: Vec<SummaryRow> = Vec::new();
+
+        for (block, day) in dates {
+            // Block separator (several --period values)
+            if multi_block && last_block != Some(block) {
+                if last_block.is_some()
+                    && (printed_daily_header || printed_events_header)
+                    && !*porcelain
+                {
+                    let twidth = if events_only {
+                        EVENTS_TABLE_WIDTH
+                    } else if *compact {
+                        compact_table_width(wd_mode)
+                    } else {
+                        daily_table_width(wd_mode, &cols)
+                    };
+                    data(format!("{:=<w$}", "=", w = twidth));
+                }
+                print_header(&Some(period[block].clone()));
+                last_block = Some(block);
+                last_month = None;
+                printed_daily_header = false;
+                printed_events_header = false;
+            }
 
-        for day in dates {
             // Month separator (daily summaries only)
-            if !*events_only {
+            if !events_only {
                 let current_month = (day.year(), day.month());
                 if let Some((ly, lm)) = last_month
                     && (ly, lm) != current_month
@@ -229,29 +565,70 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
                     let twidth = if *compact {
                         compact_table_width(wd_mode)
                     } else {
-                        daily_table_width(wd_mode)
+                        daily_table_width(wd_mode, &cols)
                     };
-                    println!("{:-<w$}", "-", w = twidth);
+                    data(format!("{:-<w$}", "-", w = twidth));
 
                     // reprint table header at month boundary
                     if *compact {
                         print_compact_header(wd_mode);
                     } else {
-                        print_daily_table_header(wd_mode);
+                        print_daily_table_header(wd_mode, &cols);
                     }
                     printed_daily_header = true;
                 }
+                if last_month != Some(current_month) {
+                    month_surplus = 0;
+                }
                 last_month = Some(current_month);
             }
 
             // Load events
-            let events = load_events_by_date(&mut pool, &day)?;
+            let mut events = load_events_by_date(&mut pool, &day)?;
             if events.is_empty() {
                 continue;
             }
+            if *only_workdays && Core::is_marker_day(&Core::build_daily_summary(&events, cfg)) {
+                continue;
+            }
 
-            if *events_only {
-                print_raw_events(&events);
+            if events_only {
+                if *summary {
+                    let day_summary = Core::build_daily_summary(&events, cfg);
+                    summary_rows.extend(
+                        pair_summary_rows(day, &day_summary, *only_unmatched)
+                            .into_iter()
+                            .filter(|r| pairs.is_none_or(|p| r.pair == p)),
+                    );
+                }
+                if *unmatched {
+                    events = unmatched_events(&events, cfg.overlap_mode);
+                }
+                if let Some(p) = pairs {
+                    events.retain(|ev| ev.pair == *p as i32);
+                }
+                if let Some(Some(name)) = host {
+                    events.retain(|ev| {
+                        ev.host
+                            .as_deref()
+                            .is_some_and(|h| h.eq_ignore_ascii_case(name))
+                    });
+                }
+                if *porcelain {
+                    for ev in &events {
+                        data(porcelain_event_line(ev));
+                    }
+                    any_output |= !events.is_empty();
+                    continue;
+                }
+                if !events.is_empty() {
+                    if !printed_events_header {
+                        print_events_header(*unmatched, show);
+                        printed_events_header = true;
+                    }
+                    print_raw_events(&events, show);
+                    any_output = true;
+                }
                 continue;
             }
 
@@ -267,41 +644,90 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
                 if *compact {
                     print_compact_header(wd_mode);
                 } else {
-                    print_daily_table_header(wd_mode);
+                    print_daily_table_header(wd_mode, &cols);
                 }
                 printed_daily_header = true;
             }
 
             // Print row
             let day_surplus = if *compact {
-                print_daily_row_compact(&day, &events, &day_summary, cfg, wd_mode)
+                print_daily_row_compact(&day, &events, &day_summary, cfg, wd_mode, style)
             } else {
-                print_daily_row(&day, &events, &day_summary, cfg, wd_mode)
+                print_daily_row(
+                    &day,
+                    &events,
+                    &day_summary,
+                    cfg,
+                    wd_mode,
+                    &cols,
+                    style,
+                    month_surplus,
+                )
             };
 
             if let Some(v) = day_surplus {
                 total_surplus += v;
+                month_surplus += v;
             }
+            for label in marks.get(&day).into_iter().flatten() {
+                data(format!("   {}🔖 {}{}", colors::GREY, label, colors::RESET));
+            }
+            totals.add_day(&day_summary, &events, cfg);
 
             // Optional details (not allowed in compact)
-            if *details && (*now || period.as_ref().is_some_and(|p| p.len() == 10)) {
-                print_details(&day_summary);
+            if *details && (*now || single_period.as_ref().is_some_and(|p| p.len() == 10)) {
+                print_details(&day_summary, style);
             }
 
             any_output = true;
         }
 
+        if *summary {
+            if any_output {
+                data("");
+            }
+            if summary_rows.is_empty() {
+                info(if *only_unmatched {
+                    "No incomplete pairs found in the selected period."
+                } else {
+                    "No pairs found in the selected period."
+                });
+            } else {
+                print_pair_summary(&summary_rows, *only_unmatched, style);
+            }
+        }
+
+        if events_only && !any_output {
+            if *unmatched {
+                info("No unmatched events found in the selected period.");
+            } else if pairs.is_some() {
+                info("No events found for the selected pair.");
+            } else if let Some(Some(name)) = host {
+                info(format!("No events recorded by host '{}'.", name));
+            }
+        }
+
         // Footer total
-        if any_output && !*events_only {
+        if any_output && !events_only {
             let twidth = if *compact {
                 compact_table_width(wd_mode)
             } else {
-                daily_table_width(wd_mode)
+                daily_table_width(wd_mode, &cols)
             };
-            println!("{:-<w$}", "-", w = twidth);
+            data(format!("{:-<w$}", "-", w = twidth));
+
+            let stats = format!(
+                "Worked: {} | Expected: {} | Days: {} | Avg/day: {}",
+                style.format(totals.worked, false),
+                style.format(totals.expected, false),
+                totals.days,
+                style.format(totals.average(), false),
+            );
+            let stats_prefix = formatting::right_pad_prefix(twidth.saturating_sub(1), &stats);
+            data(format!("{}{}", stats_prefix, stats));
 
             let color = colors::color_for_surplus(total_surplus);
-            let delta = format_delta_compact(total_surplus);
+            let delta = format_delta(total_surplus, style, cfg);
 
             // background (SECTION_BAR) only on label
             let footer_plain = format!("Σ Total ΔWORK: {}", delta);
@@ -311,15 +737,15 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
             );
 
             if *compact {
-                println!(
+                data(format!(
                     "{}Σ Total ΔWORK: {}{}{}",
                     prefix,
                     color,
                     delta,
                     colors::RESET
-                );
+                ));
             } else {
-                println!(
+                data(format!(
                     "{}{} Σ Total ΔWORK: {} {}{}{}",
                     prefix,
                     colors::SECTION_BAR, // background ON (label)
@@ -327,7 +753,26 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
                     color,               // value color
                     delta,               // value
                     colors::RESET        // final reset
+                ));
+            }
+
+            // TOIL of the listed days (the ledger does not change ΔWORK)
+            let entries = load_toil(&pool.conn, first_day.zip(last_day))?;
+            if !entries.is_empty() {
+                let sum = |kind: ToilKind| -> i64 {
+                    entries
+                        .iter()
+                        .filter(|e| e.kind == kind)
+                        .map(|e| e.minutes)
+                        .sum()
+                };
+                let toil = format!(
+                    "TOIL accrued: {} | used: {}",
+                    style.format(sum(ToilKind::Accrue), false),
+                    style.format(sum(ToilKind::Use), false),
                 );
+                let toil_prefix = formatting::right_pad_prefix(twidth.saturating_sub(1), &toil);
+                data(format!("{}{}", toil_prefix, toil));
             }
         }
 
@@ -343,21 +788,34 @@ pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
 // ───────────────────────────────────────────────────────────────────────────────
 //
 
-fn resolve_period(period: &Option<String>) -> AppResult<Vec<NaiveDate>> {
-    if let Some(p) = period {
-        if p == "all" {
-            return date::generate_all_dates().map_err(AppError::InvalidDate);
-        }
-        if p.contains(':') {
-            let parts: Vec<&str> = p.split(':').collect();
-            if parts.len() == 2 {
-                return date::generate_range(parts[0], parts[1]).map_err(AppError::InvalidDate);
-            }
-        }
-        return date::generate_from_period(p).map_err(AppError::InvalidDate);
+/// `--archive`: attach the archive recorded by `archive` and read both DBs.
+fn include_archive(pool: &DbPool) -> AppResult<()> {
+    let path = get_meta(&pool.conn, KEY_ARCHIVE_DB)?.ok_or_else(|| {
+        AppError::InvalidArgs(
+            "No archive recorded for this database. Create one with `rtimelogger archive --before YYYY-MM-DD --file FILE`.".into(),
+        )
+    })?;
+
+    if !std::path::Path::new(&path).exists() {
+        return Err(AppError::InvalidOperation(format!(
+            "archive database not found: {}",
+            path
+        )));
     }
 
-    date::current_month_dates().map_err(AppError::InvalidDate)
+    attach_archive(&pool.conn, &path)?;
+    create_events_union_view(&pool.conn)
+}
+
+pub(crate) fn resolve_period(pool: &DbPool, period: &Option<Period>) -> AppResult<Vec<NaiveDate>> {
+    match period {
+        None => Ok(Period::current_month().iter_dates()),
+        // "all" → every day between the first and the last recorded event
+        Some(Period::All) => Ok(date_bounds(&pool.conn)?
+            .map(|(from, to)| Period::between(from, to).iter_dates())
+            .unwrap_or_default()),
+        Some(p) => Ok(p.iter_dates()),
+    }
 }
 
 //
@@ -412,7 +870,60 @@ fn print_header(period: &Option<String>) {
 // ───────────────────────────────────────────────────────────────────────────────
 //
 
-fn print_raw_events(events: &[Event]) {
+/// Optional trailing columns of the events table.
+#[derive(Clone, Copy)]
+struct EventColumns {
+    meta: bool,
+    host: bool,
+}
+
+fn print_events_header(unmatched: bool, show: EventColumns) {
+    data(format!(
+        "{}",
+        if unmatched {
+            "UNMATCHED EVENTS:"
+        } else {
+            "EVENTS:"
+        }
+    ));
+    data("");
+    let mut line = format!(
+        " {:^17} | {:^4} | {:^12} | {:^16} | {:^6} | {:^4} | {:^8}",
+        "Date Time", "Type", "Lunch", "Position", "Source", "Pair", "Work Gap"
+    );
+    if show.host {
+        line.push_str(&format!(" | {:<HOST_W$}", "Host"));
+    }
+    if show.meta {
+        line.push_str(" | Meta");
+    }
+    data(line);
+    data(format!("{:-<w$}", "-", w = EVENTS_TABLE_WIDTH));
+}
+
+/// `list --events --porcelain`: one event per line, tab-separated, no colors
+/// or padding. Stable interface for scripts: new columns are only appended.
+///
+/// `id date time kind position lunch pair work_gap source host meta`
+fn porcelain_event_line(ev: &Event) -> String {
+    let clean = |s: &str| s.replace(['\t', '\n', '\r'], " ");
+    [
+        ev.id.to_string(),
+        ev.date_str(),
+        ev.time_str(),
+        ev.kind.to_db_str().to_string(),
+        ev.location.code().to_string(),
+        ev.lunch.unwrap_or(0).to_string(),
+        ev.pair.to_string(),
+        u8::from(ev.work_gap).to_string(),
+        clean(&ev.source),
+        clean(ev.host.as_deref().unwrap_or("")),
+        clean(ev.meta.as_deref().unwrap_or("")),
+    ]
+    .join("\t")
+}
+
+fn print_raw_events(events: &[Event], show: EventColumns) {
     let mut last_date: Option<String> = None;
 
     for ev in events {
@@ -434,7 +945,7 @@ fn print_raw_events(events: &[Event]) {
             (" ", " ".repeat(10))
         };
 
-        println!(
+        let mut line = format!(
             "{} {:^10} {} | {:>4} | lunch {} | {}{}\x1b[0m | {:^6} | {:>3}  | {:^8}",
             dash,
             date_str,
@@ -447,6 +958,16 @@ fn print_raw_events(events: &[Event]) {
             ev.pair,
             if ev.work_gap { "YES" } else { "" }
         );
+        if show.host {
+            line.push_str(&match &ev.host {
+                Some(h) => format!(" | {:<HOST_W$}", h),
+                None => format!(" | {}{:<HOST_W$}{}", colors::GREY, "-", colors::RESET),
+            });
+        }
+        if show.meta {
+            line.push_str(&format!(" | {}", format_meta(ev.meta.as_deref())));
+        }
+        data(line);
     }
 }
 
@@ -456,31 +977,31 @@ fn print_raw_events(events: &[Event]) {
 // ───────────────────────────────────────────────────────────────────────────────
 //
 
-fn print_daily_table_header(wd_mode: WeekdayMode) {
-    let dw = date_col_width(wd_mode);
-    let twidth = daily_table_width(wd_mode);
+fn print_daily_table_header(wd_mode: WeekdayMode, cols: &[DailyColumn]) {
+    let twidth = daily_table_width(wd_mode, cols);
 
-    println!(
-        " {:^dw$} | {:^16} | {:^5} | {:^5} | {:^5} | {:^5} | {:^7}",
-        "DATE",
-        "POSITION",
-        "IN",
-        "LNCH",
-        "OUT",
-        "TGT",
-        "ΔWORK",
-        dw = dw
-    );
-
-    println!("{:-<w$}", "-", w = twidth);
+    let cells: Vec<String> = cols
+        .iter()
+        .map(|c| {
+            let w = c.width(wd_mode);
+            format!("{:^w$}", c.header(), w = w)
+        })
+        .collect();
+
+    data(format!(" {}", cells.join(" | ")));
+    data(format!("{:-<w$}", "-", w = twidth));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn print_daily_row(
     date: &NaiveDate,
     events: &[Event],
     summary: &DaySummary,
-    _cfg: &Config,
+    cfg: &Config,
     wd_mode: WeekdayMode,
+    cols: &[DailyColumn],
+    style: DurationStyle,
+    month_to_date: i64,
 ) -> Option<i64> {
     let timeline = &summary.timeline;
     if timeline.pairs.is_empty() {
@@ -488,12 +1009,10 @@ fn print_daily_row(
     }
 
     let day_position = get_day_position(timeline);
-    let date_str = format_date_with_weekday(date, wd_mode);
-    let dw = date_col_width(wd_mode);
+    let date_str = dim_date(format_date_with_weekday(date, wd_mode), date, day_position);
 
     let pos_label = day_position.label();
     let pos_color = day_position.color();
-    let pos_fmt = formatting::pad_right(pos_label, POS_W);
 
     // Defaults (Holiday / N/A)
     let grey_time = format!("{}--:--{}", colors::GREY, colors::RESET);
@@ -501,6 +1020,8 @@ fn print_daily_row(
     let mut lunch_c = grey_time.clone();
     let mut end_c = grey_time.clone();
     let mut expected_exit_str = grey_time.clone();
+    let mut worked_c = format_duration_cell(None, style);
+    let mut expected_c = format_duration_cell(None, style);
 
     // Defaults for surplus
     let mut surplus_opt: Option<i64> = Some(0); // Holiday contributes 0
@@ -512,46 +1033,25 @@ fn print_daily_row(
         Location::Holiday | Location::NationalHoliday | Location::SickLeave
     );
 
-    if !is_marker_day {
-        let first_in = timeline.pairs[0].in_event.timestamp();
-        first_in_str = first_in.format("%H:%M").to_string();
-
-        let last_out_opt = timeline
-            .pairs
-            .iter()
-            .filter_map(|p| p.out_event.as_ref())
-            .map(|ev| ev.timestamp())
-            .next_back();
-
-        // Lunch total
-        let mut lunch_total: i64 = timeline.pairs.iter().map(|p| p.lunch_minutes).sum();
-        if lunch_total == 0 {
-            lunch_total = events.iter().map(|ev| ev.lunch.unwrap_or(0) as i64).sum();
-        }
-
-        // Target end
-        let non_work_gap_minutes = total_non_work_gap_minutes(summary);
-        let expected_exit = first_in
-            + chrono::Duration::minutes(summary.expected)
-            + chrono::Duration::minutes(non_work_gap_minutes);
-        expected_exit_str = expected_exit.format("%H:%M").to_string();
+    if !is_marker_day && let Some(fig) = Core::day_figures(summary, events, cfg) {
+        first_in_str = fig.first_in.format("%H:%M").to_string();
+        expected_exit_str = fig.target_exit.format("%H:%M").to_string();
 
         // Lunch
-        let lunch_str = if lunch_total > 0 {
-            crate::utils::time::format_minutes(lunch_total)
-        } else {
-            "--:--".to_string()
-        };
-        lunch_c = colors::colorize_optional(&lunch_str);
+        lunch_c = colors::colorize_optional(&lunch_cell(summary, fig.lunch_total, cfg));
 
         // End
-        let end_str = last_out_opt
+        let end_str = fig
+            .last_out
             .map(|ts| ts.format("%H:%M").to_string())
             .unwrap_or_else(|| "--:--".to_string());
         end_c = colors::colorize_optional(&end_str);
 
+        worked_c = format_duration_cell(fig.worked, style);
+        expected_c = format_duration_cell(Some(fig.expected), style);
+
         // Surplus (worked)
-        surplus_opt = last_out_opt.map(|out| (out - expected_exit).num_minutes());
+        surplus_opt = fig.delta;
 
         match surplus_opt {
             None => {
@@ -563,49 +1063,73 @@ fn print_daily_row(
                 surplus_color = colors::GREY;
             }
             Some(v) => {
-                let abs = mins2readable(v.abs(), false, false); // "02h 04m"
-                let compact = abs.replace(' ', ""); // "02h04m"
-                surplus_display = format!("{}{}", if v < 0 { "-" } else { "+" }, compact);
+                surplus_display = format_delta(v, style, cfg);
                 surplus_color = colors::color_for_surplus(v);
             }
         }
     }
 
+    let pos_c = format!("{}{}{}", pos_color, pos_label, colors::RESET);
+
     if day_position == Location::NationalHoliday {
-        let twidth = daily_table_width(wd_mode);
+        let twidth = daily_table_width(wd_mode, cols);
+
+        // Only DATE / POSITION are shown, the holiday name fills the rest of the row
+        let mut plain_prefix = " ".to_string();
+        let mut prefix = " ".to_string();
+        for c in cols {
+            let (plain, colored) = match c {
+                DailyColumn::Date => (format_date_with_weekday(date, wd_mode), date_str.clone()),
+                DailyColumn::Position => (pos_label.to_string(), pos_c.clone()),
+                _ => continue,
+            };
+            let w = c.width(wd_mode);
+            plain_prefix.push_str(&format!("{:<w$} | ", plain, w = w));
+            prefix.push_str(&pad_cell(&colored, w, *c));
+            prefix.push_str(" | ");
+        }
 
-        // prefisso “plain” (senza colori) uguale a ciò che stampi prima del meta
-        let plain_prefix = format!(" {:<dw$} | {:<16} | ", date_str, pos_label, dw = dw);
         let meta_w = remaining_width(twidth, &plain_prefix);
-
         let meta = get_meta_string(events, meta_w);
 
-        println!(
-            " {:<dw$} | {}{:<16}{}\x1b[0m | {}{:<meta_w$}{}",
-            date_str,
-            pos_color,
-            pos_label,
-            colors::RESET,
+        data(format!(
+            "{}{}{:<meta_w$}{}",
+            prefix,
             pos_color,
             meta,
             colors::RESET,
-            dw = dw,
             meta_w = meta_w,
-        );
+        ));
     } else {
-        println!(
-            " {:<dw$} | {}{}\x1b[0m | {:^5} | {:^5} | {:^5} | {:^5} | {}{:>7}\x1b[0m",
-            date_str,
-            pos_color,
-            pos_fmt,
-            first_in_str,
-            lunch_c,
-            end_c,
-            expected_exit_str,
-            surplus_color,
-            surplus_display,
-            dw = dw
+        let delta_c = format!("{}{}{}", surplus_color, surplus_display, colors::RESET);
+        let running = month_to_date + surplus_opt.unwrap_or(0);
+        let cum_c = format!(
+            "{}{}{}",
+            colors::color_for_surplus(running),
+            format_delta(running, style, cfg),
+            colors::RESET
         );
+
+        let cells: Vec<String> = cols
+            .iter()
+            .map(|c| {
+                let value = match c {
+                    DailyColumn::Date => &date_str,
+                    DailyColumn::Position => &pos_c,
+                    DailyColumn::In => &first_in_str,
+                    DailyColumn::Lunch => &lunch_c,
+                    DailyColumn::Out => &end_c,
+                    DailyColumn::Target => &expected_exit_str,
+                    DailyColumn::Worked => &worked_c,
+                    DailyColumn::Expected => &expected_c,
+                    DailyColumn::Delta => &delta_c,
+                    DailyColumn::Cumulative => &cum_c,
+                };
+                pad_cell(value, c.width(wd_mode), *c)
+            })
+            .collect();
+
+        data(format!(" {}", cells.join(" | ")));
     }
 
     surplus_opt
@@ -627,18 +1151,22 @@ fn pair_notes(pair: &crate::core::calculator::timeline::Pair) -> Option<String>
         .map(ToOwned::to_owned)
 }
 
-fn print_details(summary: &DaySummary) {
+fn print_details(summary: &DaySummary, style: DurationStyle) {
     if summary.timeline.pairs.is_empty() {
         return;
     }
 
-    println!();
-    println!("    {} DETAILS {}", colors::SECTION_BAR, colors::RESET);
-    println!(
+    data("");
+    data(format!(
+        "    {} DETAILS {}",
+        colors::SECTION_BAR,
+        colors::RESET
+    ));
+    data(format!(
         "    {:^4} | {:^5} | {:^5} | {:^6} | {:^5} | {:^16} | {:^2}",
         "PAIR", "IN", "OUT", "WORKED", "LUNCH", "POSITION", "WG"
-    );
-    println!("    {:-<72}", "-");
+    ));
+    data(format!("    {:-<72}", "-"));
 
     for (idx, p) in summary.timeline.pairs.iter().enumerate() {
         let in_t = p.in_event.timestamp().format("%H:%M").to_string();
@@ -651,8 +1179,7 @@ fn print_details(summary: &DaySummary) {
             .unwrap_or_else(|| "--:--".to_string());
         let out_c = colors::colorize_in_out(&out_t, false);
 
-        let worked_raw = mins2readable(p.duration_minutes, false, false);
-        let worked_compact = worked_raw.replace(' ', "");
+        let worked_compact = style.format(p.duration_minutes, false);
         let worked_c = colors::colorize_optional(&worked_compact);
 
         let lunch_compact = format!("{:>2}m", p.lunch_minutes);
@@ -664,7 +1191,7 @@ fn print_details(summary: &DaySummary) {
 
         let wg_str = if p.work_gap { "Y" } else { "" };
 
-        println!(
+        data(format!(
             "    {:>4} | {:^5} | {:^5} | {:^6} | {:^5} | {}{}\x1b[0m | {:^2}",
             idx + 1,
             in_c,
@@ -674,23 +1201,112 @@ fn print_details(summary: &DaySummary) {
             pos_color,
             pos_fmt,
             wg_str
-        );
+        ));
 
         if let Some(notes) = pair_notes(p) {
-            println!();
-            println!("    {} NOTES {}", colors::NOTES, colors::RESET);
-            println!("    {:-<72}", "-");
+            data("");
+            data(format!("    {} NOTES {}", colors::NOTES, colors::RESET));
+            data(format!("    {:-<72}", "-"));
 
             let options = Options::new(72)
                 .initial_indent("    ")
                 .subsequent_indent("       ");
 
             let wrapped = fill(&notes, options);
-            println!("{}", wrapped);
+            data(wrapped);
         }
     }
 
-    println!();
+    data("");
+}
+
+/// One row of the `--events --summary` table.
+struct SummaryRow {
+    date: NaiveDate,
+    pair: usize,
+    in_time: String,
+    out_time: Option<String>,
+    worked: i64,
+    lunch: i64,
+    position: Location,
+}
+
+fn pair_summary_rows(date: NaiveDate, summary: &DaySummary, only_open: bool) -> Vec<SummaryRow> {
+    summary
+        .timeline
+        .pairs
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| !only_open || p.out_event.is_none())
+        .map(|(idx, p)| SummaryRow {
+            date,
+            pair: idx + 1,
+            in_time: p.in_event.time_str(),
+            out_time: p.out_event.as_ref().map(|ev| ev.time_str()),
+            worked: p.duration_minutes,
+            lunch: p.lunch_minutes,
+            position: p.position,
+        })
+        .collect()
+}
+
+/// Totals of the summary table: (pairs, worked, lunch).
+fn pair_summary_totals(rows: &[SummaryRow]) -> (usize, i64, i64) {
+    (
+        rows.len(),
+        rows.iter().map(|r| r.worked).sum(),
+        rows.iter().map(|r| r.lunch).sum(),
+    )
+}
+
+fn print_pair_summary(rows: &[SummaryRow], only_open: bool, style: DurationStyle) {
+    data(if only_open {
+        "INCOMPLETE PAIRS:"
+    } else {
+        "PAIRS SUMMARY:"
+    });
+    data("");
+    data(format!(
+        " {:^10} | {:^4} | {:^5} | {:^5} | {:^7} | {:^7} | {:^16}",
+        "Date", "Pair", "IN", "OUT", "Worked", "Lunch", "Position"
+    ));
+    data(format!("{:-<w$}", "-", w = SUMMARY_TABLE_WIDTH));
+
+    let mut last_date: Option<NaiveDate> = None;
+    for r in rows {
+        let date_str = if last_date == Some(r.date) {
+            " ".repeat(10)
+        } else {
+            last_date = Some(r.date);
+            r.date.to_string()
+        };
+        let out_t = r.out_time.clone().unwrap_or_else(|| "--:--".to_string());
+
+        data(format!(
+            " {:^10} | {:>4} | {} | {} | {:>7} | {:>7} | {}{}\x1b[0m",
+            date_str,
+            r.pair,
+            colors::colorize_in_out(&r.in_time, true),
+            colors::colorize_in_out(&out_t, false),
+            style.format(r.worked, false),
+            style.format(r.lunch, false),
+            r.position.color(),
+            formatting::pad_right(r.position.label(), POS_W),
+        ));
+    }
+
+    // Totals row: same figures as the aggregated day view
+    let (count, worked, lunch) = pair_summary_totals(rows);
+    data(format!("{:-<w$}", "-", w = SUMMARY_TABLE_WIDTH));
+    data(format!(
+        " {:^10} | {:>4} | {:^5} | {:^5} | {:>7} | {:>7} |",
+        "TOTAL",
+        count,
+        "",
+        "",
+        style.format(worked, false),
+        style.format(lunch, false),
+    ));
 }
 
 //
@@ -703,7 +1319,7 @@ fn print_compact_header(wd_mode: WeekdayMode) {
     let dw = date_col_width(wd_mode);
     let twidth = compact_table_width(wd_mode);
 
-    println!(
+    data(format!(
         "{:^dw$} | {:^16} | {:^21} | {:^5} | {:^7}",
         "DATE",
         "POSITION",
@@ -711,22 +1327,29 @@ fn print_compact_header(wd_mode: WeekdayMode) {
         "TGT",
         "ΔWORK",
         dw = dw
-    );
+    ));
 
-    println!("{:-<w$}", "-", w = twidth);
+    data(format!("{:-<w$}", "-", w = twidth));
 }
 
-fn format_delta_compact(minutes: i64) -> String {
-    let abs = mins2readable(minutes.abs(), false, true); // già compatto
-    format!("{}{}", if minutes < 0 { "-" } else { "+" }, abs)
+/// ΔWORK: decimal hours with `--decimal`, `surplus_format` otherwise.
+fn format_delta(minutes: i64, style: DurationStyle, cfg: &Config) -> String {
+    match style {
+        DurationStyle::Decimal { .. } => {
+            let abs = style.format(minutes.abs(), false);
+            format!("{}{}", if minutes < 0 { "-" } else { "+" }, abs)
+        }
+        DurationStyle::Clock => formatting::format_surplus(minutes, cfg),
+    }
 }
 
 fn print_daily_row_compact(
     date: &NaiveDate,
     events: &[Event],
     summary: &DaySummary,
-    _cfg: &Config,
+    cfg: &Config,
     wd_mode: WeekdayMode,
+    style: DurationStyle,
 ) -> Option<i64> {
     let timeline = &summary.timeline;
     if timeline.pairs.is_empty() {
@@ -734,15 +1357,16 @@ fn print_daily_row_compact(
     }
 
     let dw = date_col_width(wd_mode);
-    let date_str = format_date_with_weekday(date, wd_mode);
+    let plain_date = format_date_with_weekday(date, wd_mode);
 
     let day_position = get_day_position(timeline);
     let pos_label = day_position.label();
     let pos_color = day_position.color();
+    let date_str = dim_date(format!("{:<dw$}", plain_date), date, day_position);
 
     if day_position == Location::Holiday {
-        println!(
-            "{:<dw$} | {}{:<16}{}\x1b[0m | {:<21} | {:^5} | {}Δ -{}\x1b[0m",
+        data(format!(
+            "{} | {}{:<16}{}\x1b[0m | {:<21} | {:^5} | {}Δ -{}\x1b[0m",
             date_str,
             pos_color,
             pos_label,
@@ -751,19 +1375,18 @@ fn print_daily_row_compact(
             format!("{}--:--{}", colors::GREY, colors::RESET),
             colors::GREY,
             colors::RESET,
-            dw = dw
-        );
+        ));
         return Some(0);
     } else if day_position == Location::NationalHoliday {
         let twidth = compact_table_width(wd_mode);
 
-        let plain_prefix = format!("{:<dw$} | {:<16} | ", date_str, pos_label, dw = dw);
+        let plain_prefix = format!("{:<dw$} | {:<16} | ", plain_date, pos_label, dw = dw);
         let meta_w = remaining_width(twidth, &plain_prefix);
 
         let meta = get_meta_string(events, meta_w);
 
-        println!(
-            "{:<dw$} | {}{:<16}{}\x1b[0m | {}{:<meta_w$}{}",
+        data(format!(
+            "{} | {}{:<16}{}\x1b[0m | {}{:<meta_w$}{}",
             date_str,
             pos_color,
             pos_label,
@@ -771,58 +1394,35 @@ fn print_daily_row_compact(
             pos_color,
             meta,
             colors::RESET,
-            dw = dw,
             meta_w = meta_w
-        );
+        ));
         return Some(0);
     }
 
-    let first_in = timeline.pairs[0].in_event.timestamp();
-    let first_in_str = first_in.format("%H:%M").to_string();
+    let fig = Core::day_figures(summary, events, cfg)?;
+    let first_in_str = fig.first_in.format("%H:%M").to_string();
 
-    let last_out_opt = timeline
-        .pairs
-        .iter()
-        .filter_map(|p| p.out_event.as_ref())
-        .map(|ev| ev.timestamp())
-        .next_back();
-
-    let end_str = last_out_opt
+    let end_str = fig
+        .last_out
         .map(|ts| ts.format("%H:%M").to_string())
         .unwrap_or_else(|| "--:--".to_string());
 
-    let mut lunch_total: i64 = timeline.pairs.iter().map(|p| p.lunch_minutes).sum();
-    if lunch_total == 0 {
-        lunch_total = events.iter().map(|ev| ev.lunch.unwrap_or(0) as i64).sum();
-    }
-    let lunch_str = if lunch_total > 0 {
-        crate::utils::time::format_minutes(lunch_total)
-    } else {
-        "--:--".to_string()
-    };
+    let lunch_str = lunch_cell(summary, fig.lunch_total, cfg);
 
-    let non_work_gap_minutes = total_non_work_gap_minutes(summary);
-    let expected_exit = first_in
-        + chrono::Duration::minutes(summary.expected)
-        + chrono::Duration::minutes(non_work_gap_minutes);
-    let target_end_str = expected_exit.format("%H:%M").to_string();
+    let target_end_str = fig.target_exit.format("%H:%M").to_string();
 
-    let surplus_opt = last_out_opt.map(|out| (out - expected_exit).num_minutes());
+    let surplus_opt = fig.delta;
 
     let (delta_str, delta_color) = match surplus_opt {
         None => ("-".to_string(), colors::GREY),
         Some(0) => ("0".to_string(), colors::GREY),
-        Some(v) => {
-            let abs = mins2readable(v.abs(), false, true);
-            let sign = if v < 0 { "-" } else { "+" };
-            (format!("{}{}", sign, abs), colors::color_for_surplus(v))
-        }
+        Some(v) => (format_delta(v, style, cfg), colors::color_for_surplus(v)),
     };
 
     let times_string = format!("{} / {} / {}", first_in_str, lunch_str, end_str);
     let delta_value = format!("Δ {}", delta_str);
-    println!(
-        "{:<dw$} | {}{:<16}{}\x1b[0m | {:<21} | {:^5} | {}{}{}\x1b[0m",
+    data(format!(
+        "{} | {}{:<16}{}\x1b[0m | {:<21} | {:^5} | {}{}{}\x1b[0m",
         date_str,
         pos_color,
         pos_label,
@@ -832,12 +1432,23 @@ fn print_daily_row_compact(
         delta_color,
         delta_value,
         colors::RESET,
-        dw = dw
-    );
+    ));
 
     surplus_opt
 }
 
+/// LNCH cell: the recorded lunch, else the automatic one marked with `*`
+/// (`0:30*`, still 5 characters), else `--:--`.
+fn lunch_cell(summary: &DaySummary, lunch_total: i64, cfg: &Config) -> String {
+    if lunch_total > 0 {
+        return crate::utils::time::format_minutes(lunch_total);
+    }
+    match auto_lunch(&summary.timeline, cfg) {
+        Some(m) => format!("{}:{:02}*", m / 60, m % 60),
+        None => "--:--".to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -900,4 +1511,38 @@ mod tests {
         let events = vec![ev(Some("Epiphany"))];
         assert_eq!(get_meta_string(&events, 10), "Epiphany");
     }
+
+    #[test]
+    fn porcelain_line_has_fixed_columns() {
+        let mut e = ev(Some("{\"project\":\"a\tb\"}"));
+        e.source = "cli".into();
+        e.lunch = Some(30);
+        let line = porcelain_event_line(&e);
+        assert_eq!(line.split('\t').count(), 11);
+        assert!(line.ends_with("{\"project\":\"a b\"}"));
+        assert!(!line.contains('\x1b'));
+    }
+
+    #[test]
+    fn summary_keeps_only_open_pairs_and_sums_them() {
+        use crate::models::event::EventExtras;
+        use crate::models::event_type::EventType::{In, Out};
+        use chrono::NaiveTime;
+
+        let day = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+        let at = |t: &str, kind| {
+            let time = NaiveTime::parse_from_str(t, "%H:%M").unwrap();
+            Event::new(0, day, time, kind, Location::Office, EventExtras::default())
+        };
+        let events = [at("08:00", In), at("12:00", Out), at("13:00", In)];
+        let summary = Core::build_daily_summary(&events, &Config::default());
+
+        let all = pair_summary_rows(day, &summary, false);
+        assert_eq!(pair_summary_totals(&all), (2, 240, 0));
+
+        let open = pair_summary_rows(day, &summary, true);
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].pair, 2);
+        assert_eq!(open[0].out_time, None);
+    }
 }
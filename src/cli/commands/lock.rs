@@ -0,0 +1,36 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::db::log::ttlog;
+use crate::db::pool::DbPool;
+use crate::db::queries::locks::{lock_day, unlock_day};
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{success, warning};
+use crate::utils::date;
+
+/// Handle the `lock` / `unlock` commands
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    let (date_str, locking) = match cmd {
+        Commands::Lock { date } => (date, true),
+        Commands::Unlock { date } => (date, false),
+        _ => return Ok(()),
+    };
+
+    let d = date::parse_date(date_str).map_err(|_| AppError::InvalidDate(date_str.to_string()))?;
+    let pool = DbPool::new(&cfg.database)?;
+
+    if locking {
+        if lock_day(&pool.conn, &d)? {
+            ttlog(&pool.conn, "lock", &d.to_string(), "Day locked")?;
+            success(format!("Day {} is now locked.", d));
+        } else {
+            warning(format!("Day {} is already locked.", d));
+        }
+    } else if unlock_day(&pool.conn, &d)? {
+        ttlog(&pool.conn, "unlock", &d.to_string(), "Day unlocked")?;
+        success(format!("Day {} has been unlocked.", d));
+    } else {
+        warning(format!("Day {} is not locked.", d));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::lunch::LunchLogic;
+use crate::db::log::ttlog;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{info, success, warning};
+use crate::utils::date;
+use crate::utils::formatting::{format_surplus, mins2readable};
+
+/// Handle the `lunch` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Lunch {
+        date: date_str,
+        minutes,
+        pair,
+    } = cmd
+    {
+        let d =
+            date::parse_date(date_str).map_err(|_| AppError::InvalidDate(date_str.to_string()))?;
+
+        let mut pool = DbPool::new(&cfg.database)?;
+        let u = LunchLogic::set(&mut pool, cfg, d, i32::from(*minutes), *pair)?;
+
+        ttlog(
+            &pool.conn,
+            "lunch",
+            &d.to_string(),
+            &format!("pair {}: {} → {} min", u.pair, u.previous, minutes),
+        )?;
+        record_app_version(&pool.conn)?;
+
+        success(format!(
+            "Lunch of pair #{} ({}-{}) on {}: {} → {} min.",
+            u.pair,
+            u.start.format("%H:%M"),
+            u.end.format("%H:%M"),
+            d,
+            u.previous,
+            minutes
+        ));
+        if !u.in_window && *minutes > 0 {
+            warning(format!(
+                "⚠️  Pair #{} does not overlap the lunch window ({}).",
+                u.pair, cfg.lunch_window
+            ));
+        }
+
+        if let Some(f) = u.figures {
+            info(format!(
+                "Day now: worked {} / {}, ΔWORK {}",
+                f.worked
+                    .map(|w| mins2readable(w, false, true))
+                    .unwrap_or_else(|| "-".into()),
+                mins2readable(f.expected, false, true),
+                f.delta
+                    .map(|v| format_surplus(v, cfg))
+                    .unwrap_or_else(|| "-".into())
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::mark::MarkLogic;
+use crate::db::log::ttlog;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{data, info, success, warning};
+use crate::utils::date;
+
+/// Handle the `mark` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Mark {
+        date: date_str,
+        label,
+        list,
+        delete,
+    } = cmd
+    {
+        let pool = DbPool::new(&cfg.database)?;
+
+        if *list {
+            let marks = MarkLogic::list(&pool, None)?;
+            if marks.is_empty() {
+                warning("No bookmarks.");
+                return Ok(());
+            }
+            for m in &marks {
+                data(format!("{}  {:<24} {}", m.date, m.name, m.label));
+            }
+            return Ok(());
+        }
+
+        if let Some(name) = delete {
+            if !MarkLogic::delete(&pool, name)? {
+                return Err(AppError::InvalidArgs(format!(
+                    "No bookmark named '{}' (see `rtimelogger mark --list`).",
+                    name
+                )));
+            }
+            ttlog(&pool.conn, "mark", name, "deleted")?;
+            record_app_version(&pool.conn)?;
+            success(format!("Bookmark '{}' removed.", name));
+            return Ok(());
+        }
+
+        let (Some(date_str), Some(label)) = (date_str, label) else {
+            return Ok(());
+        };
+        let d =
+            date::parse_date(date_str).map_err(|_| AppError::InvalidDate(date_str.to_string()))?;
+
+        let m = MarkLogic::add(&pool, d, label)?;
+        ttlog(&pool.conn, "mark", &d.to_string(), &m.label)?;
+        record_app_version(&pool.conn)?;
+
+        success(format!("🔖 {} bookmarked as '{}'.", d, m.label));
+        info(format!(
+            "Use it as a period anchor: --period mark:{}:today",
+            m.name
+        ));
+    }
+
+    Ok(())
+}
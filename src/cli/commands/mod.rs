@@ -1,10 +1,31 @@
 pub mod add;
+pub mod archive;
 pub mod backup;
+pub mod closeout;
 pub mod config;
 pub mod db;
 pub mod del;
+pub mod dev;
 pub mod export;
+pub mod find;
+pub mod focus;
 pub mod import;
 pub mod init;
+pub mod install_service;
+pub mod invoice;
 pub mod list;
+pub mod lock;
 pub mod log;
+pub mod lunch;
+pub mod mark;
+pub mod plugin;
+pub mod policy;
+pub mod predict;
+pub mod report;
+pub mod schema;
+pub mod serve;
+pub mod status;
+pub mod suggest;
+pub mod toil;
+pub mod trash;
+pub mod version;
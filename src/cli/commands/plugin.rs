@@ -0,0 +1,18 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::plugin::PluginLogic;
+use crate::errors::AppResult;
+use crate::ui::messages::is_ascii;
+
+/// Handle an external subcommand (`rtimelogger-<name>` plugin).
+/// The plugin prints its own messages: a failing plugin only sets the exit code.
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Plugin(args) = cmd {
+        let code = PluginLogic::run(cfg, args, is_ascii())?;
+        if code != 0 {
+            std::process::exit(code);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::calculator::overlap::OverlapMode;
+use crate::core::mark::expand_period;
+use crate::core::policy::{LunchRule, PolicyLogic};
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{data, info};
+use crate::utils::formatting::{SurplusFormat, mins2readable};
+use crate::utils::period::Period;
+
+/// Handle the `policy` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Policy { date: date_str } = cmd {
+        let date = match Period::parse(&expand_period(cfg, date_str)?)?.bounds() {
+            Some((from, to)) if from == to => from,
+            _ => return Err(AppError::InvalidDate(date_str.to_string())),
+        };
+
+        let mut pool = DbPool::new(&cfg.database)?;
+        let p = PolicyLogic::for_date(&mut pool, cfg, date)?;
+        let hm = |m: i64| mins2readable(m, false, true);
+        let limit = |m: Option<i64>| m.map(hm).unwrap_or_else(|| "off".into());
+
+        let mut kind = match p.marker {
+            Some(m) => m.label().to_string(),
+            None if p.weekend => "weekend".to_string(),
+            None => "working day".to_string(),
+        };
+        if p.locked {
+            kind.push_str(", locked");
+        }
+
+        data(format!("Date          : {} ({})", p.date, p.weekday));
+        data(format!("Day           : {} ({} event(s))", kind, p.events));
+        data(format!("Expected work : {}", hm(p.expected_work)));
+        data(format!(
+            "Lunch window  : {}{}",
+            p.lunch_window,
+            if p.lunch_window_valid {
+                ""
+            } else {
+                " (invalid: never applied)"
+            }
+        ));
+        data(format!(
+            "Lunch break   : {}-{} min",
+            p.min_lunch, p.max_lunch
+        ));
+        data(format!(
+            "Lunch counted : {}",
+            match p.lunch {
+                LunchRule::Pending => format!(
+                    "{} min if the first IN is before the end of the lunch window",
+                    p.min_lunch
+                ),
+                LunchRule::Recorded(m) => format!("{} min (recorded)", m),
+                LunchRule::Automatic(m) => format!("{} min (automatic)", m),
+                LunchRule::OptedOut => "none (--no-auto-lunch)".to_string(),
+                LunchRule::AfterWindow => "none (first IN after the lunch window)".to_string(),
+                LunchRule::NotApplicable => "-".to_string(),
+            }
+        ));
+        data(format!(
+            "Break after   : {}",
+            limit(p.mandatory_lunch_after)
+        ));
+        data(format!(
+            "Daily cap     : {}{}",
+            limit(p.max_daily_work),
+            if p.enforce_max_daily_work && p.max_daily_work.is_some() {
+                " (enforced)"
+            } else {
+                ""
+            }
+        ));
+        data(format!(
+            "Rounding      : {} h (--decimal), surplus as {}",
+            p.decimal_step,
+            match p.surplus_format {
+                SurplusFormat::Hm => "hm",
+                SurplusFormat::Minutes => "minutes",
+                SurplusFormat::Decimal => "decimal",
+            }
+        ));
+        data(format!(
+            "Position      : {} by default, {} position rule(s)",
+            p.default_position, p.position_rules
+        ));
+        data(format!(
+            "Shift bands   : {}",
+            if p.shift_bands.is_empty() {
+                "none".to_string()
+            } else {
+                p.shift_bands.join(", ")
+            }
+        ));
+        data(format!(
+            "Overlaps      : {}",
+            match p.overlap_mode {
+                OverlapMode::Off => "off",
+                OverlapMode::Split => "split",
+                OverlapMode::Parallel => "parallel",
+            }
+        ));
+
+        info("Past days are always computed with the current config (no dated overrides).");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::predict::{PredictLogic, Prediction};
+use crate::db::pool::DbPool;
+use crate::errors::AppResult;
+use crate::ui::messages::{data, info, warning};
+use crate::utils::colors;
+use crate::utils::date::weekday_str;
+use crate::utils::formatting::mins2readable;
+use chrono::{Datelike, Local};
+
+/// Handle the `predict` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Predict { weeks } = cmd {
+        let mut pool = DbPool::new(&cfg.database)?;
+        let p = PredictLogic::compute(&mut pool, cfg, Local::now(), *weeks)?;
+
+        if p.samples == 0 {
+            warning(format!(
+                "⚠️  No closed working days in the last {} week(s): nothing to predict from.",
+                p.weeks
+            ));
+            return Ok(());
+        }
+
+        let date_str = p.date.format("%Y-%m-%d").to_string();
+        info(format!(
+            "🔮 Prediction for {} ({}), last {} week(s)\n",
+            date_str,
+            weekday_str(&date_str, 'm'),
+            p.weeks
+        ));
+
+        let basis = format!(
+            "median {}, avg {} over {} {}",
+            mins2readable(p.median_delay.unwrap_or(0), true, true),
+            mins2readable(p.avg_delay.unwrap_or(0), true, true),
+            p.samples,
+            if p.same_weekday {
+                p.date.weekday().to_string()
+            } else {
+                "days".to_string()
+            }
+        );
+
+        match (p.exit, p.target_exit) {
+            (Some(exit), Some(tgt)) => data(format!(
+                "  Exit : {}~{}{}  (TGT {}, {})",
+                colors::CYAN,
+                exit.format("%H:%M"),
+                colors::RESET,
+                tgt.format("%H:%M"),
+                basis
+            )),
+            _ => data(format!(
+                "  Exit : {}no IN recorded today{}  ({})",
+                colors::GREY,
+                colors::RESET,
+                basis
+            )),
+        }
+
+        data(format!(
+            "  Week : {}~{}{} / {}  (so far {} / {})",
+            colors::CYAN,
+            mins2readable(p.week_predicted, false, true),
+            colors::RESET,
+            mins2readable(p.week_predicted_expected, false, true),
+            mins2readable(p.week_worked, false, true),
+            mins2readable(p.week_expected, false, true)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Compact prediction for `status`: "exit ~17:40 · week ~39h10m / 40h00m".
+/// None without history.
+pub(crate) fn prediction_line(p: &Prediction) -> Option<String> {
+    if p.samples == 0 {
+        return None;
+    }
+
+    let exit = p
+        .exit
+        .map(|t| format!("exit ~{} · ", t.format("%H:%M")))
+        .unwrap_or_default();
+
+    Some(format!(
+        "{}week ~{} / {}",
+        exit,
+        mins2readable(p.week_predicted, false, true),
+        mins2readable(p.week_predicted_expected, false, true)
+    ))
+}
@@ -0,0 +1,750 @@
+use crate::cli::commands::list::resolve_period;
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::compliance::{BreakViolation, mandatory_lunch_after};
+use crate::core::mark::expand_period;
+use crate::core::report::{
+    Anomaly, AnomalyKind, EarningsRow, FocusDay, LunchIssue, LunchIssueKind, PeriodProfile,
+    PeriodTotals, PositionRow, ReportLogic, TimeStats, WeekdayRow, WeightedDay, compare_periods,
+};
+use crate::core::shift::ShiftBands;
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{data, info, warning};
+use crate::utils::colors::{self, color_for_surplus};
+use crate::utils::formatting::{format_surplus, mins2readable};
+use crate::utils::period::Period;
+use chrono::{NaiveDate, NaiveTime, Timelike};
+use unicode_width::UnicodeWidthStr;
+
+/// Handle the `report` command.
+///
+/// `databases` are the `--db` values (resolved): with more than one, every
+/// section is produced per database, followed by the combined totals.
+pub fn handle(cmd: &Commands, cfg: &Config, databases: &[String]) -> AppResult<()> {
+    if let Commands::Report {
+        period,
+        by_weekday,
+        anomalies,
+        focus,
+        earnings,
+        violations,
+        by_position,
+        weighted,
+        lunch_audit,
+        compare,
+    } = cmd
+    {
+        let consolidated = databases.len() > 1;
+
+        if let Some(spec) = compare {
+            if consolidated {
+                return Err(AppError::InvalidArgs(
+                    "--compare works on a single database.".into(),
+                ));
+            }
+            return print_compare(cfg, spec);
+        }
+
+        let sections = Sections {
+            by_weekday: *by_weekday,
+            by_position: *by_position,
+            anomalies: *anomalies,
+            focus: *focus,
+            earnings: *earnings,
+            violations: *violations,
+            weighted: *weighted,
+            lunch_audit: *lunch_audit,
+        };
+        if !sections.any() && !consolidated {
+            return Err(AppError::InvalidArgs(
+                "Choose a report: --by-weekday, --by-position, --anomalies, --focus, --earnings, --violations, --weighted, --lunch-audit or --compare."
+                    .into(),
+            ));
+        }
+
+        let parsed_period = period
+            .as_deref()
+            .map(|p| expand_period(cfg, p).and_then(|p| Period::parse(&p)))
+            .transpose()?;
+        let label = period.as_deref().unwrap_or("this month");
+
+        if !consolidated {
+            let mut pool = DbPool::new(&cfg.database)?;
+            let dates = resolve_period(&pool, &parsed_period)?;
+            return print_sections(&mut pool, cfg, &dates, label, sections);
+        }
+
+        let mut rows = Vec::new();
+        for db in databases {
+            let mut pool = DbPool::new(db)?;
+            let dates = resolve_period(&pool, &parsed_period)?;
+
+            if sections.any() {
+                info(format!("🗄️  {}\n", db));
+                print_sections(&mut pool, cfg, &dates, label, sections)?;
+                data("");
+            }
+
+            rows.push((db.as_str(), ReportLogic::totals(&mut pool, cfg, &dates)?));
+        }
+
+        info(format!("🧮 Totals by database ({})\n", label));
+        print_totals(&rows, cfg);
+    }
+
+    Ok(())
+}
+
+/// Report sections requested on the command line.
+#[derive(Clone, Copy)]
+struct Sections {
+    by_weekday: bool,
+    by_position: bool,
+    anomalies: bool,
+    focus: bool,
+    earnings: bool,
+    violations: bool,
+    weighted: bool,
+    lunch_audit: bool,
+}
+
+impl Sections {
+    fn any(self) -> bool {
+        self.by_weekday
+            || self.by_position
+            || self.anomalies
+            || self.focus
+            || self.earnings
+            || self.violations
+            || self.weighted
+            || self.lunch_audit
+    }
+}
+
+fn print_sections(
+    pool: &mut DbPool,
+    cfg: &Config,
+    dates: &[NaiveDate],
+    label: &str,
+    sections: Sections,
+) -> AppResult<()> {
+    if sections.by_weekday {
+        let rows = ReportLogic::by_weekday(pool, cfg, dates)?;
+        if rows.is_empty() {
+            warning("⚠️  No recorded sessions found");
+        } else {
+            info(format!("📊 First IN / last OUT by weekday ({})\n", label));
+            print_by_weekday(&rows);
+        }
+    }
+
+    if sections.by_position {
+        let rows = ReportLogic::by_position(pool, cfg, dates)?;
+        if sections.by_weekday {
+            data("");
+        }
+        if rows.is_empty() {
+            warning("⚠️  No recorded sessions found");
+        } else {
+            info(format!("🏢 Worked time by position ({})\n", label));
+            print_by_position(&rows);
+        }
+    }
+
+    if sections.anomalies {
+        let found = ReportLogic::anomalies(pool, cfg, dates)?;
+        if sections.by_weekday || sections.by_position {
+            data("");
+        }
+        if found.is_empty() {
+            info(format!("🔍 No anomalies found ({})", label));
+        } else {
+            info(format!("🔍 Anomalies ({})\n", label));
+            print_anomalies(&found);
+        }
+    }
+
+    if sections.focus {
+        let days = ReportLogic::focus(pool, cfg, dates)?;
+        if sections.by_weekday || sections.by_position || sections.anomalies {
+            data("");
+        }
+        if days.is_empty() {
+            info(format!("🎯 No focus sessions found ({})", label));
+        } else {
+            info(format!("🎯 Focused time ({})\n", label));
+            print_focus(&days);
+        }
+    }
+
+    if sections.earnings {
+        let rows = ReportLogic::earnings(pool, cfg, dates)?;
+        if sections.by_weekday || sections.by_position || sections.anomalies || sections.focus {
+            data("");
+        }
+        if rows.is_empty() {
+            info(format!("💶 No closed working days ({})", label));
+        } else {
+            info(format!("💶 Earnings by month and project ({})\n", label));
+            print_earnings(&rows);
+
+            let mut unrated: Vec<&str> = rows
+                .iter()
+                .filter(|r| r.rate.is_none())
+                .map(|r| r.project.as_str())
+                .collect();
+            unrated.sort_unstable();
+            unrated.dedup();
+            if !unrated.is_empty() {
+                warning(format!(
+                    "⚠️  No rate for: {} (add them under `rates` in the config)",
+                    unrated.join(", ")
+                ));
+            }
+        }
+    }
+
+    if sections.violations {
+        if sections.by_weekday
+            || sections.by_position
+            || sections.anomalies
+            || sections.focus
+            || sections.earnings
+        {
+            data("");
+        }
+        match mandatory_lunch_after(cfg) {
+            None => warning(
+                "⚠️  No break rule: set `mandatory_lunch_after` (e.g. \"6h\") in the config.",
+            ),
+            Some(limit) => {
+                let rows = ReportLogic::break_violations(pool, cfg, dates, limit)?;
+                let limit_str = mins2readable(limit, false, true);
+                if rows.is_empty() {
+                    info(format!(
+                        "⏸️  No work blocks over {} without a break ({})",
+                        limit_str, label
+                    ));
+                } else {
+                    info(format!(
+                        "⏸️  Work blocks over {} without a break ({})\n",
+                        limit_str, label
+                    ));
+                    print_violations(&rows);
+                }
+            }
+        }
+    }
+
+    if sections.weighted {
+        if sections.by_weekday
+            || sections.by_position
+            || sections.anomalies
+            || sections.focus
+            || sections.earnings
+            || sections.violations
+        {
+            data("");
+        }
+        if ShiftBands::from_config(cfg).is_empty() {
+            warning(
+                "⚠️  No shift bands: add `shift_bands` (e.g. from 22:00 to 06:00, multiplier 1.3) to the config.",
+            );
+        } else {
+            let days = ReportLogic::weighted(pool, cfg, dates)?;
+            if days.is_empty() {
+                info(format!("⚖️  No closed working days ({})", label));
+            } else {
+                info(format!("⚖️  Weighted hours by pair ({})\n", label));
+                print_weighted(&days);
+            }
+        }
+    }
+
+    if sections.lunch_audit {
+        if sections.by_weekday
+            || sections.by_position
+            || sections.anomalies
+            || sections.focus
+            || sections.earnings
+            || sections.violations
+            || sections.weighted
+        {
+            data("");
+        }
+        let rows = ReportLogic::lunch_audit(pool, cfg, dates)?;
+        if rows.is_empty() {
+            info(format!("🍽️  No unusual lunches ({})", label));
+        } else {
+            info(format!(
+                "🍽️  Unusual lunches ({}, bounds {}-{} min)\n",
+                label, cfg.min_duration_lunch_break, cfg.max_duration_lunch_break
+            ));
+            print_lunch_audit(&rows);
+        }
+    }
+
+    Ok(())
+}
+
+/// `--compare A:B`: one column per period plus the B - A difference.
+fn print_compare(cfg: &Config, spec: &str) -> AppResult<()> {
+    let (a, b) = compare_periods(spec)?;
+    let mut pool = DbPool::new(&cfg.database)?;
+
+    let dates_a = resolve_period(&pool, &Some(a))?;
+    let dates_b = resolve_period(&pool, &Some(b))?;
+    let pa = ReportLogic::profile(&mut pool, cfg, &dates_a)?;
+    let pb = ReportLogic::profile(&mut pool, cfg, &dates_b)?;
+
+    if pa.totals.days == 0 && pb.totals.days == 0 {
+        warning("⚠️  No closed working days in either period");
+        return Ok(());
+    }
+
+    let (label_a, label_b) = match spec.split_once(',') {
+        Some((x, y)) => (x.trim(), y.trim()),
+        None => spec.split_once(':').unwrap_or((spec, spec)),
+    };
+    let col_w = label_a.len().max(label_b.len()).max(8);
+
+    info(format!("⚖️  {} vs {}\n", label_a, label_b));
+    data(format!(
+        "{:<14} | {:>col_w$} | {:>col_w$} | {:>8}",
+        "", label_a, label_b, "Δ"
+    ));
+    data(format!("{:-<w$}", "-", w = col_w * 2 + 31));
+
+    let hm = |m: i64| mins2readable(m, false, true);
+    let opt = |v: Option<String>| v.unwrap_or_else(|| "-".into());
+    // Only time deltas are colored (more worked time is green)
+    let row = |name: &str, va: String, vb: String, delta: Option<String>, color: &str| {
+        data(format!(
+            "{:<14} | {:>col_w$} | {:>col_w$} | {}{:>8}{}",
+            name,
+            va,
+            vb,
+            color,
+            opt(delta),
+            colors::RESET
+        ));
+    };
+    let count = |x: u32, y: u32| Some(format!("{:+}", y as i64 - x as i64));
+    let surplus = |x: i64, y: i64| color_for_surplus(y - x);
+
+    row(
+        "Days",
+        pa.totals.days.to_string(),
+        pb.totals.days.to_string(),
+        count(pa.totals.days, pb.totals.days),
+        "",
+    );
+    row(
+        "Worked",
+        hm(pa.totals.worked),
+        hm(pb.totals.worked),
+        Some(format_surplus(pb.totals.worked - pa.totals.worked, cfg)),
+        surplus(pa.totals.worked, pb.totals.worked),
+    );
+    row(
+        "Expected",
+        hm(pa.totals.expected),
+        hm(pb.totals.expected),
+        Some(format_surplus(pb.totals.expected - pa.totals.expected, cfg)),
+        "",
+    );
+    row(
+        "ΔWORK",
+        format_surplus(pa.totals.surplus(), cfg),
+        format_surplus(pb.totals.surplus(), cfg),
+        Some(format_surplus(
+            pb.totals.surplus() - pa.totals.surplus(),
+            cfg,
+        )),
+        surplus(pa.totals.surplus(), pb.totals.surplus()),
+    );
+    row(
+        "Worked / day",
+        opt(pa.avg_worked().map(hm)),
+        opt(pb.avg_worked().map(hm)),
+        pa.avg_worked()
+            .zip(pb.avg_worked())
+            .map(|(x, y)| format_surplus(y - x, cfg)),
+        pa.avg_worked()
+            .zip(pb.avg_worked())
+            .map_or("", |(x, y)| surplus(x, y)),
+    );
+    row(
+        "Office days",
+        pa.office.to_string(),
+        pb.office.to_string(),
+        count(pa.office, pb.office),
+        "",
+    );
+    row(
+        "Remote days",
+        pa.remote.to_string(),
+        pb.remote.to_string(),
+        count(pa.remote, pb.remote),
+        "",
+    );
+    row(
+        "Office share",
+        opt(pa.office_share().map(|s| format!("{}%", s))),
+        opt(pb.office_share().map(|s| format!("{}%", s))),
+        pa.office_share()
+            .zip(pb.office_share())
+            .map(|(x, y)| format!("{:+}pp", y - x)),
+        "",
+    );
+    row(
+        "Avg first IN",
+        opt(pa.first_in.avg().map(|t| t.format("%H:%M").to_string())),
+        opt(pb.first_in.avg().map(|t| t.format("%H:%M").to_string())),
+        avg_minutes(&pa)
+            .zip(avg_minutes(&pb))
+            .map(|(x, y)| mins2readable(y - x, true, true)),
+        "",
+    );
+
+    Ok(())
+}
+
+fn avg_minutes(p: &PeriodProfile) -> Option<i64> {
+    p.first_in
+        .avg()
+        .map(|t: NaiveTime| (t.hour() * 60 + t.minute()) as i64)
+}
+
+fn print_violations(rows: &[(NaiveDate, BreakViolation)]) {
+    data(format!(
+        "{:<10} | {:<13} | {:>7}",
+        "DATE", "BLOCK", "LENGTH"
+    ));
+    data(format!("{:-<36}", "-"));
+
+    for (date, v) in rows {
+        data(format!(
+            "{} | {} - {} | {}{:>7}{}",
+            date.format("%Y-%m-%d"),
+            v.start.format("%H:%M"),
+            v.end.format("%H:%M"),
+            colors::RED,
+            mins2readable(v.minutes, false, true),
+            colors::RESET
+        ));
+    }
+}
+
+fn print_lunch_audit(rows: &[LunchIssue]) {
+    data(format!(
+        "{:<10} | {:>4} | {:<10} | {:>6}",
+        "DATE", "PAIR", "ISSUE", "LUNCH"
+    ));
+    data(format!("{:-<40}", "-"));
+
+    for r in rows {
+        let color = match r.kind {
+            LunchIssueKind::Auto => colors::YELLOW,
+            _ => colors::RED,
+        };
+        data(format!(
+            "{} | {:>4} | {}{:<10}{} | {:>3} min",
+            r.date.format("%Y-%m-%d"),
+            r.pair
+                .map(|p| format!("#{}", p))
+                .unwrap_or_else(|| "-".into()),
+            color,
+            r.kind.label(),
+            colors::RESET,
+            r.minutes
+        ));
+    }
+}
+
+/// One row per database plus the combined TOTAL.
+fn print_totals(rows: &[(&str, PeriodTotals)], cfg: &Config) {
+    let name_w = rows
+        .iter()
+        .map(|(db, _)| UnicodeWidthStr::width(*db))
+        .max()
+        .unwrap_or(0)
+        .max(8);
+
+    data(format!(
+        "{:<name_w$} | {:>4} | {:>7} | {:>7} | {:>8}",
+        "DATABASE", "DAYS", "WORKED", "EXP", "ΔWORK"
+    ));
+    data(format!("{:-<w$}", "-", w = name_w + 40));
+
+    let mut total = PeriodTotals::default();
+    for (db, t) in rows {
+        data(totals_line(db, t, name_w, cfg));
+        total.merge(t);
+    }
+
+    data(format!("{:-<w$}", "-", w = name_w + 40));
+    data(totals_line("TOTAL", &total, name_w, cfg));
+}
+
+fn totals_line(name: &str, t: &PeriodTotals, name_w: usize, cfg: &Config) -> String {
+    format!(
+        "{:<name_w$} | {:>4} | {:>7} | {:>7} | {}{:>8}{}",
+        name,
+        t.days,
+        mins2readable(t.worked, false, true),
+        mins2readable(t.expected, false, true),
+        color_for_surplus(t.surplus()),
+        format_surplus(t.surplus(), cfg),
+        colors::RESET
+    )
+}
+
+fn print_by_weekday(rows: &[WeekdayRow]) {
+    data(format!(
+        "{:<3} | {:>4} | {:<17} | {:<17}",
+        "", "", "FIRST IN", "LAST OUT"
+    ));
+    data(format!(
+        "{:<3} | {:>4} | {:<17} | {:<17}",
+        "DAY", "DAYS", "avg   min   max", "avg   min   max"
+    ));
+    data(format!("{:-<50}", "-"));
+
+    for r in rows {
+        data(format!(
+            "{:<3} | {:>4} | {} | {}",
+            r.weekday.to_string(),
+            r.first_in.count().max(r.last_out.count()),
+            stats_cell(&r.first_in),
+            stats_cell(&r.last_out)
+        ));
+    }
+}
+
+/// "09:12 08:40 10:05" (average highlighted), grey dash without data.
+fn stats_cell(s: &TimeStats) -> String {
+    match (s.avg(), s.min(), s.max()) {
+        (Some(avg), Some(min), Some(max)) => format!(
+            "{}{}{} {} {}",
+            colors::CYAN,
+            avg.format("%H:%M"),
+            colors::RESET,
+            min.format("%H:%M"),
+            max.format("%H:%M")
+        ),
+        _ => format!("{}{:^17}{}", colors::GREY, "-", colors::RESET),
+    }
+}
+
+/// Days and hours per position; shares are of the worked hours.
+fn print_by_position(rows: &[PositionRow]) {
+    data(format!(
+        "{:<16} | {:>4} | {:>7} | {:>5}",
+        "POSITION", "DAYS", "HOURS", "SHARE"
+    ));
+    data(format!("{:-<41}", "-"));
+
+    let total: i64 = rows.iter().map(|r| r.minutes).sum();
+    for r in rows {
+        let (hours, pct) = if r.minutes > 0 {
+            (
+                mins2readable(r.minutes, false, true),
+                share(r.minutes, Some(total)),
+            )
+        } else {
+            ("-".to_string(), "-".to_string())
+        };
+        data(format!(
+            "{}{:<16}{} | {:>4} | {:>7} | {:>5}",
+            r.location.color(),
+            r.location.label(),
+            colors::RESET,
+            r.days,
+            hours,
+            pct
+        ));
+    }
+
+    data(format!("{:-<41}", "-"));
+    data(format!(
+        "{:<16} | {:>4} | {:>7} | {:>5}",
+        "TOTAL",
+        rows.iter().map(|r| r.days).sum::<u32>(),
+        mins2readable(total, false, true),
+        share(total, Some(total))
+    ));
+}
+
+fn print_anomalies(rows: &[Anomaly]) {
+    data(format!("{:<10} | {:<10} | {}", "DATE", "KIND", "DETAIL"));
+    data(format!("{:-<60}", "-"));
+
+    for a in rows {
+        let color = match a.kind {
+            AnomalyKind::Impossible => colors::RED,
+            AnomalyKind::Outlier | AnomalyKind::MissingLunch => colors::YELLOW,
+        };
+        data(format!(
+            "{} | {}{:<10}{} | {}",
+            a.date.format("%Y-%m-%d"),
+            color,
+            a.kind.label(),
+            colors::RESET,
+            a.detail
+        ));
+    }
+}
+
+fn print_focus(rows: &[FocusDay]) {
+    data(format!(
+        "{:<10} | {:>4} | {:>7} | {:>7} | {:>5} | {}",
+        "DATE", "SESS", "FOCUS", "WORKED", "SHARE", "LABELS"
+    ));
+    data(format!("{:-<70}", "-"));
+
+    let mut focused = 0;
+    let mut worked = 0;
+    for r in rows {
+        focused += r.focused;
+        worked += r.worked.unwrap_or(0);
+        data(format!(
+            "{} | {:>4} | {}{:>7}{} | {:>7} | {:>5} | {}",
+            r.date.format("%Y-%m-%d"),
+            r.sessions,
+            colors::CYAN,
+            mins2readable(r.focused, false, true),
+            colors::RESET,
+            r.worked
+                .map(|w| mins2readable(w, false, true))
+                .unwrap_or_else(|| "-".into()),
+            share(r.focused, r.worked),
+            r.labels.join(", ")
+        ));
+    }
+
+    data(format!("{:-<70}", "-"));
+    data(format!(
+        "{:<10} | {:>4} | {:>7} | {:>7} | {:>5} |",
+        "TOTAL",
+        rows.iter().map(|r| r.sessions).sum::<usize>(),
+        mins2readable(focused, false, true),
+        mins2readable(worked, false, true),
+        share(focused, Some(worked))
+    ));
+}
+
+/// Focused time as a percentage of the worked time ("62%").
+fn share(focused: i64, worked: Option<i64>) -> String {
+    match worked {
+        Some(w) if w > 0 => format!("{}%", focused * 100 / w),
+        _ => "-".into(),
+    }
+}
+
+fn print_earnings(rows: &[EarningsRow]) {
+    let name_w = rows
+        .iter()
+        .map(|r| UnicodeWidthStr::width(r.project.as_str()))
+        .max()
+        .unwrap_or(0)
+        .max(7);
+
+    data(format!(
+        "{:<7} | {:<name_w$} | {:>8} | {:>8} | {:>10}",
+        "MONTH", "PROJECT", "HOURS", "RATE", "AMOUNT"
+    ));
+    data(format!("{:-<w$}", "-", w = name_w + 47));
+
+    let mut month_total = 0.0;
+    let mut total = 0.0;
+    for (i, r) in rows.iter().enumerate() {
+        data(format!(
+            "{:<7} | {:<name_w$} | {:>8.2} | {:>8} | {}{:>10.2}{}",
+            r.month,
+            r.project,
+            r.hours,
+            r.rate
+                .map(|v| format!("{:.2}", v))
+                .unwrap_or_else(|| "-".into()),
+            colors::CYAN,
+            r.amount,
+            colors::RESET
+        ));
+        month_total += r.amount;
+        total += r.amount;
+
+        // Subtotal at the end of every month
+        if rows.get(i + 1).is_none_or(|next| next.month != r.month) {
+            data(format!(
+                "{:<7} | {:<name_w$} | {:>8} | {:>8} | {:>10.2}",
+                "", "subtotal", "", "", month_total
+            ));
+            month_total = 0.0;
+        }
+    }
+
+    data(format!("{:-<w$}", "-", w = name_w + 47));
+    data(format!(
+        "{:<7} | {:<name_w$} | {:>8.2} | {:>8} | {:>10.2}",
+        "TOTAL",
+        "",
+        rows.iter().map(|r| r.hours).sum::<f64>(),
+        "",
+        total
+    ));
+}
+
+/// One row per closed pair (the date only on the first pair of the day), a
+/// subtotal for days with several pairs and the grand total.
+fn print_weighted(days: &[WeightedDay]) {
+    let row = |date: &str, pair: &str, span: &str, worked: i64, weighted: i64| {
+        format!(
+            "{:<10} | {:>4} | {:<11} | {:>7} | {:>8} | {}{:>7}{}",
+            date,
+            pair,
+            span,
+            mins2readable(worked, false, true),
+            mins2readable(weighted, false, true),
+            colors::CYAN,
+            mins2readable(weighted - worked, true, true),
+            colors::RESET
+        )
+    };
+
+    data(format!(
+        "{:<10} | {:>4} | {:<11} | {:>7} | {:>8} | {:>7}",
+        "DATE", "PAIR", "SPAN", "WORKED", "WEIGHTED", "EXTRA"
+    ));
+    data(format!("{:-<64}", "-"));
+
+    for d in days {
+        for (i, p) in d.pairs.iter().enumerate() {
+            let date = if i == 0 {
+                d.date.to_string()
+            } else {
+                String::new()
+            };
+            data(row(
+                &date,
+                &format!("#{}", p.pair),
+                &format!("{}-{}", p.start.format("%H:%M"), p.end.format("%H:%M")),
+                p.worked,
+                p.weighted,
+            ));
+        }
+        if d.pairs.len() > 1 {
+            data(row("", "", "day", d.worked(), d.weighted()));
+        }
+    }
+
+    data(format!("{:-<64}", "-"));
+    data(row(
+        "TOTAL",
+        "",
+        "",
+        days.iter().map(|d| d.worked()).sum(),
+        days.iter().map(|d| d.weighted()).sum(),
+    ));
+}
@@ -0,0 +1,16 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::errors::AppResult;
+use crate::export::schema::{SchemaFormat, json_schema};
+use crate::ui::messages::data;
+
+/// Handle the `schema` command
+pub fn handle(cmd: &Commands, _cfg: &Config) -> AppResult<()> {
+    if let Commands::Schema { format, target } = cmd {
+        match format {
+            SchemaFormat::JsonSchema => data(json_schema(*target)),
+        }
+    }
+
+    Ok(())
+}
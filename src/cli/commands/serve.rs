@@ -0,0 +1,22 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::serve::ServeLogic;
+use crate::errors::{AppError, AppResult};
+
+/// Handle the `serve` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Serve { bind } = cmd {
+        if cfg.test_mode {
+            return Err(AppError::InvalidArgs(
+                "serve is not available in test mode.".into(),
+            ));
+        }
+
+        let bind = bind
+            .clone()
+            .unwrap_or_else(|| cfg.api.clone().unwrap_or_default().bind);
+        ServeLogic::run(cfg, &bind)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use crate::cli::commands::predict::prediction_line;
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::predict::{DEFAULT_WEEKS, PredictLogic, Prediction};
+use crate::core::status::{Balance, StatusLogic, StatusReport};
+use crate::core::toil::{ToilBalance, ToilLogic};
+use crate::db::pool::DbPool;
+use crate::errors::AppResult;
+use crate::ui::messages::data;
+use crate::utils::colors::{self, color_for_surplus};
+use crate::utils::date::weekday_str;
+use crate::utils::formatting::{format_surplus, mins2readable};
+use chrono::Local;
+
+/// Handle the `status` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Status { porcelain } = cmd {
+        let mut pool = DbPool::new(&cfg.database)?;
+        let report = StatusLogic::compute(&mut pool, cfg, Local::now())?;
+
+        if *porcelain {
+            data(porcelain_line(&report));
+        } else {
+            let prediction = PredictLogic::compute(&mut pool, cfg, Local::now(), DEFAULT_WEEKS)?;
+            // The balance walks the whole database: only when TOIL is in use
+            let toil = if ToilLogic::entries(&pool, None)?.is_empty() {
+                None
+            } else {
+                Some(ToilLogic::balance(&mut pool, cfg)?)
+            };
+            print_status(&report, &prediction, toil.as_ref(), cfg);
+        }
+    }
+
+    Ok(())
+}
+
+/// `open=1 since=08:55 today=+0:12 week=-0:40 month=-1:05`
+///
+/// Keys and their order are stable; missing values are printed as `-`.
+/// No colors, no padding: meant to be embedded in prompt segments.
+fn porcelain_line(r: &StatusReport) -> String {
+    let since = r
+        .open_since
+        .map(|(t, _)| t.format("%H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let today = r
+        .today
+        .map(|b| signed_hmm(b.surplus()))
+        .unwrap_or_else(|| "-".to_string());
+
+    format!(
+        "open={} since={} today={} week={} month={}",
+        u8::from(r.is_open()),
+        since,
+        today,
+        signed_hmm(r.week.surplus()),
+        signed_hmm(r.month.surplus())
+    )
+}
+
+/// Minutes as `+H:MM` / `-H:MM` (zero is `+0:00`).
+fn signed_hmm(mins: i64) -> String {
+    let sign = if mins < 0 { '-' } else { '+' };
+    let abs = mins.abs();
+    format!("{}{}:{:02}", sign, abs / 60, abs % 60)
+}
+
+fn print_status(r: &StatusReport, p: &Prediction, toil: Option<&ToilBalance>, cfg: &Config) {
+    let date_str = r.date.format("%Y-%m-%d").to_string();
+    data(format!("📅 {} ({})", date_str, weekday_str(&date_str, 'm')));
+
+    let now_line = if let Some(loc) = r.marker {
+        format!("{}{}{}", loc.color(), loc.label(), colors::RESET)
+    } else if let Some((since, loc)) = r.open_since {
+        format!(
+            "{}● IN{} since {} ({}){}",
+            colors::GREEN,
+            colors::RESET,
+            since.format("%H:%M"),
+            loc.label(),
+            target_suffix(r)
+        )
+    } else if r.today.is_some() {
+        format!("{}○ OUT{}{}", colors::GREY, colors::RESET, target_suffix(r))
+    } else {
+        format!("{}no events recorded today{}", colors::GREY, colors::RESET)
+    };
+    data(format!("  Now   : {}", now_line));
+
+    match &r.today {
+        Some(b) => data(format!("  Today : {}", balance_line(b, false, cfg))),
+        None => data(format!("  Today : {}-{}", colors::GREY, colors::RESET)),
+    }
+    data(format!("  Week  : {}", balance_line(&r.week, true, cfg)));
+    data(format!("  Month : {}", balance_line(&r.month, true, cfg)));
+
+    if let Some(t) = toil {
+        data(format!(
+            "  TOIL  : available {} (accrued {}, used {})  bank {}{}{}",
+            mins2readable(t.available(), false, true),
+            mins2readable(t.accrued, false, true),
+            mins2readable(t.used, false, true),
+            color_for_surplus(t.bank()),
+            format_surplus(t.bank(), cfg),
+            colors::RESET
+        ));
+    }
+
+    if let Some(line) = prediction_line(p) {
+        data(format!(
+            "  Pred  : {}{}{}",
+            colors::GREY,
+            line,
+            colors::RESET
+        ));
+    }
+}
+
+fn target_suffix(r: &StatusReport) -> String {
+    r.target_exit
+        .map(|t| format!(" · TGT {}", t.format("%H:%M")))
+        .unwrap_or_default()
+}
+
+/// "worked 30h10m / 32h00m  ΔWORK -01h50m  (4 days)"
+fn balance_line(b: &Balance, with_days: bool, cfg: &Config) -> String {
+    let surplus = b.surplus();
+    let days = if with_days {
+        format!("  ({} day{})", b.days, if b.days == 1 { "" } else { "s" })
+    } else {
+        String::new()
+    };
+
+    format!(
+        "worked {} / {}  ΔWORK {}{}{}{}",
+        mins2readable(b.worked, false, true),
+        mins2readable(b.expected, false, true),
+        color_for_surplus(surplus),
+        format_surplus(surplus, cfg),
+        colors::RESET,
+        days
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::status::Balance;
+    use chrono::{NaiveDate, NaiveTime};
+
+    #[test]
+    fn porcelain_line_is_stable() {
+        let mut r = StatusReport {
+            date: NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(),
+            marker: None,
+            open_since: None,
+            target_exit: None,
+            today: None,
+            week: Balance::default(),
+            month: Balance {
+                worked: 600,
+                expected: 665,
+                days: 1,
+            },
+        };
+        assert_eq!(
+            porcelain_line(&r),
+            "open=0 since=- today=- week=+0:00 month=-1:05"
+        );
+
+        r.open_since = Some((
+            NaiveTime::from_hms_opt(8, 55, 0).unwrap(),
+            crate::models::location::Location::Office,
+        ));
+        r.today = Some(Balance {
+            worked: 472,
+            expected: 460,
+            days: 1,
+        });
+        assert!(porcelain_line(&r).starts_with("open=1 since=08:55 today=+0:12 "));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::cli::parser::Commands;
+use crate::config::Config;
+use crate::core::add::AddLogic;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::db::queries::load_events_by_date;
+use crate::errors::{AppError, AppResult};
+use crate::import::ics::{parse_ics, suggest};
+use crate::models::location::Location;
+use crate::ui::messages::{data, info, success, warning};
+use crate::utils::colors;
+use crate::utils::path::expand_tilde;
+
+/// Handle the `suggest` command
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Suggest {
+        ics,
+        date,
+        pos,
+        apply,
+    } = cmd
+    {
+        let path = expand_tilde(ics);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| AppError::InvalidArgs(format!("cannot read {}: {}", path.display(), e)))?;
+        let meetings = parse_ics(&content)?;
+
+        let Some(s) = suggest(&meetings, *date) else {
+            warning(format!(
+                "⚠️  No timed meetings on {} in {}.",
+                date,
+                path.display()
+            ));
+            return Ok(());
+        };
+
+        info(format!("📅 {} meeting(s) on {}:\n", s.meetings.len(), date));
+        for m in &s.meetings {
+            data(format!(
+                "  {}-{}  {}",
+                m.start.format("%H:%M"),
+                m.end.format("%H:%M"),
+                m.summary
+            ));
+        }
+        data("");
+        data(format!(
+            "  Suggested: {}IN {}  OUT {}{}",
+            colors::CYAN,
+            s.start.format("%H:%M"),
+            s.end.format("%H:%M"),
+            colors::RESET
+        ));
+
+        let mut pool = DbPool::new(&cfg.database)?;
+        if !load_events_by_date(&mut pool, date)?.is_empty() {
+            warning(format!(
+                "⚠️  {} already has events: the suggestion is not applied.",
+                date
+            ));
+            return Ok(());
+        }
+
+        if !*apply {
+            info(format!(
+                "Run again with --apply, or: rtimelogger add {} --in {} --out {}",
+                date,
+                s.start.format("%H:%M"),
+                s.end.format("%H:%M")
+            ));
+            return Ok(());
+        }
+
+        AddLogic::apply(
+            cfg,
+            &mut pool,
+            *date,
+            pos.unwrap_or(Location::Office),
+            Some(s.start),
+            None,
+            None,
+            Some(s.end),
+            false,
+            None,
+            None,
+            *pos,
+            None,
+            Some(r#"{"suggested_from":"ics"}"#.to_string()),
+        )?;
+        record_app_version(&pool.conn)?;
+
+        success(format!(
+            "Added {} → {} on {} from the calendar.",
+            s.start.format("%H:%M"),
+            s.end.format("%H:%M"),
+            date
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use crate::cli::parser::{Commands, ToilCommands};
+use crate::config::Config;
+use crate::core::toil::{ToilBalance, ToilLogic};
+use crate::db::log::ttlog;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::{data, info, success};
+use crate::utils::date;
+use crate::utils::formatting::{format_surplus, mins2readable};
+use crate::utils::period::Period;
+use crate::utils::time::hhmm2minutes;
+
+/// Handle the `toil accrue|use|list` commands
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Toil { action } = cmd {
+        let mut pool = DbPool::new(&cfg.database)?;
+
+        match action {
+            ToilCommands::Accrue {
+                duration,
+                date: date_str,
+                note,
+            } => {
+                let d = match date_str {
+                    Some(s) => date::parse_date(s).map_err(|_| AppError::InvalidDate(s.clone()))?,
+                    None => date::today(),
+                };
+                let minutes = hhmm2minutes(duration);
+                let balance = ToilLogic::accrue(&mut pool, cfg, minutes, d, note.as_deref())?;
+
+                ttlog(
+                    &pool.conn,
+                    "toil_accrue",
+                    &d.to_string(),
+                    &format!("{} min", minutes),
+                )?;
+                record_app_version(&pool.conn)?;
+
+                success(format!(
+                    "Accrued {} of TOIL on {}.",
+                    mins2readable(minutes, false, true),
+                    d
+                ));
+                print_balance(&balance, cfg);
+            }
+
+            ToilCommands::Use {
+                date: date_str,
+                duration,
+                note,
+            } => {
+                let d = date::parse_date(date_str)
+                    .map_err(|_| AppError::InvalidDate(date_str.to_string()))?;
+                let minutes = duration.as_deref().map(hhmm2minutes);
+                let (minutes, balance) =
+                    ToilLogic::redeem(&mut pool, cfg, d, minutes, note.as_deref())?;
+
+                ttlog(
+                    &pool.conn,
+                    "toil_use",
+                    &d.to_string(),
+                    &format!("{} min", minutes),
+                )?;
+                record_app_version(&pool.conn)?;
+
+                success(format!(
+                    "Used {} of TOIL on {}.",
+                    mins2readable(minutes, false, true),
+                    d
+                ));
+                print_balance(&balance, cfg);
+            }
+
+            ToilCommands::List { period } => {
+                let bounds = match period {
+                    Some(p) => Period::parse(p)?.bounds(),
+                    None => None,
+                };
+                let entries = ToilLogic::entries(&pool, bounds)?;
+
+                if entries.is_empty() {
+                    info("No TOIL entries.");
+                } else {
+                    data(format!(
+                        "{:<10} | {:<6} | {:>8} | {}",
+                        "DATE", "KIND", "MINUTES", "NOTE"
+                    ));
+                    data(format!("{:-<50}", "-"));
+                    for e in &entries {
+                        data(format!(
+                            "{:<10} | {:<6} | {:>8} | {}",
+                            e.date,
+                            e.kind.as_str(),
+                            mins2readable(e.minutes, false, true),
+                            e.note.as_deref().unwrap_or("")
+                        ));
+                    }
+                }
+
+                print_balance(&ToilLogic::balance(&mut pool, cfg)?, cfg);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_balance(b: &ToilBalance, cfg: &Config) {
+    info(format!(
+        "TOIL available: {} (accrued {}, used {}) · surplus still in the bank: {}",
+        mins2readable(b.available(), false, true),
+        mins2readable(b.accrued, false, true),
+        mins2readable(b.used, false, true),
+        format_surplus(b.bank(), cfg)
+    ));
+}
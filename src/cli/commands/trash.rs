@@ -0,0 +1,84 @@
+use crate::cli::commands::del::ask_confirmation;
+use crate::cli::parser::{Commands, TrashCommands};
+use crate::config::Config;
+use crate::db::log::ttlog;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::db::queries::trash::{empty_trash, list_batches, restore_batch};
+use crate::errors::AppResult;
+use crate::ui::messages::{data, info, success};
+
+/// Handle the `trash list|restore|empty` commands
+pub fn handle(cmd: &Commands, cfg: &Config) -> AppResult<()> {
+    if let Commands::Trash { action } = cmd {
+        let mut pool = DbPool::new(&cfg.database)?;
+
+        match action {
+            TrashCommands::List => {
+                let batches = list_batches(&pool.conn)?;
+                if batches.is_empty() {
+                    info("🗑️  The trash is empty.");
+                    return Ok(());
+                }
+
+                data(format!(
+                    "{:>5} | {:<19} | {:>6} | {}",
+                    "BATCH", "DELETED AT", "EVENTS", "DAYS"
+                ));
+                data(format!("{:-<60}", "-"));
+                for b in batches {
+                    let days = if b.first == b.last {
+                        b.first.to_string()
+                    } else {
+                        format!("{} → {}", b.first, b.last)
+                    };
+                    data(format!(
+                        "{:>5} | {:<19} | {:>6} | {}",
+                        b.batch,
+                        b.deleted_at
+                            .get(..19)
+                            .unwrap_or(&b.deleted_at)
+                            .replace('T', " "),
+                        b.events,
+                        days
+                    ));
+                }
+            }
+
+            TrashCommands::Restore { batch } => {
+                let tx = pool.conn.transaction()?;
+                let n = restore_batch(&tx, *batch, cfg.overlap_mode)?;
+                ttlog(
+                    &tx,
+                    "trash_restore",
+                    &batch.to_string(),
+                    &format!("{} event(s) restored", n),
+                )?;
+                tx.commit()?;
+                record_app_version(&pool.conn)?;
+
+                success(format!("Restored {} event(s) from batch #{}.", n, batch));
+            }
+
+            TrashCommands::Empty => {
+                if !ask_confirmation(
+                    "Permanently delete everything in the trash? This action is irreversible.",
+                ) {
+                    info("Operation cancelled.");
+                    return Ok(());
+                }
+
+                let n = empty_trash(&pool.conn)?;
+                ttlog(
+                    &pool.conn,
+                    "trash_empty",
+                    "",
+                    &format!("{} event(s) removed", n),
+                )?;
+                success(format!("Trash emptied ({} event(s) removed).", n));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::config::Config;
+use crate::db::metadata::{KEY_APP_VERSION, KEY_SCHEMA_VERSION, get_meta};
+use crate::db::migrate::{SCHEMA_VERSION, applied_migrations};
+use crate::db::pool::is_memory_db;
+use crate::errors::AppResult;
+use crate::ui::messages::data;
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+
+/// Handle `--version` (`--verbose`: the details asked in bug reports)
+pub fn handle(cfg: &Config, verbose: bool) -> AppResult<()> {
+    data(format!("rtimelogger {}", env!("CARGO_PKG_VERSION")));
+    if !verbose {
+        return Ok(());
+    }
+
+    data(format!(
+        "Platform    : {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    data(format!("Config      : {}", Config::config_file().display()));
+    data(format!("Database    : {}", cfg.database));
+
+    if is_memory_db(&cfg.database) {
+        data(format!("Schema      : {} (in memory)", SCHEMA_VERSION));
+        return Ok(());
+    }
+    if !Path::new(&cfg.database).exists() {
+        data("              (missing: run `rtimelogger init`)");
+        data(format!("Schema      : - (this binary: {})", SCHEMA_VERSION));
+        return Ok(());
+    }
+
+    // Read-only: reporting must never migrate or stamp the database
+    let conn = Connection::open_with_flags(&cfg.database, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let recorded = get_meta(&conn, KEY_SCHEMA_VERSION)?;
+    data(format!(
+        "Schema      : {} (this binary: {})",
+        recorded.as_deref().unwrap_or("not recorded"),
+        SCHEMA_VERSION
+    ));
+
+    let migrations = applied_migrations(&conn)?;
+    let missing: Vec<&str> = migrations
+        .iter()
+        .filter(|(_, ok)| !ok)
+        .map(|(name, _)| *name)
+        .collect();
+    data(format!(
+        "Migrations  : {}/{} applied",
+        migrations.len() - missing.len(),
+        migrations.len()
+    ));
+    if !missing.is_empty() {
+        data(format!(
+            "              missing: {} (run `rtimelogger db --migrate`)",
+            missing.join(", ")
+        ));
+    }
+
+    if let Some(writer) = get_meta(&conn, KEY_APP_VERSION)? {
+        data(format!("Last writer : rtimelogger {}", writer));
+    }
+
+    let events = conn
+        .query_row("SELECT COUNT(*) FROM events", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map(|n| n.to_string())
+        .unwrap_or_else(|_| "-".into());
+    data(format!("Events      : {}", events));
+
+    Ok(())
+}
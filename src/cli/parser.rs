@@ -1,7 +1,19 @@
-use crate::export::ExportFormat;
+use crate::core::backup::BackupCompression;
+use crate::core::seed::SeedProfile;
+use crate::core::service::ServiceTask;
+use crate::export::invoice::InvoiceFormat;
+use crate::export::schema::{SchemaFormat, SchemaTarget};
+use crate::export::{ExportFormat, ExportLayout, SplitBy};
+use crate::models::location::{Location, parse_location};
 use crate::utils::date::parse_date;
-use chrono::NaiveDate;
+use crate::utils::time::{parse_duration_arg, parse_time_arg};
+use chrono::{NaiveDate, NaiveTime};
 use clap::{Parser, Subcommand};
+use std::ffi::OsString;
+
+/// Upper bound of `add --lunch` accepted by the parser (a whole day); the
+/// configured maximum is checked by `add` itself.
+const MAX_LUNCH_ARG: u16 = 24 * 60;
 
 /// Command-line interface definition for rTimelogger
 /// CLI application to track working hours with SQLite
@@ -10,29 +22,75 @@ use clap::{Parser, Subcommand};
     name = "rtimelogger",
     version = env!("CARGO_PKG_VERSION"),
     about = "A simple time logging CLI: track working hours and calculate surplus using SQLite",
-    long_about = None
+    long_about = None,
+    disable_version_flag = true,
+    arg_required_else_help = true
 )]
 pub struct Cli {
-    /// Override database path (useful for tests or custom DB)
-    #[arg(global = true, long = "db")]
-    pub db: Option<String>,
+    /// Print version
+    #[arg(short = 'V', long)]
+    pub version: bool,
+
+    /// With --version: schema version, migrations, database and event count
+    #[arg(long, requires = "version")]
+    pub verbose: bool,
+
+    /// Override database: a path, or a name listed under `databases` in the config.
+    /// `report` accepts it more than once for a consolidated report
+    #[arg(global = true, long = "db", action = clap::ArgAction::Append)]
+    pub db: Vec<String>,
+
+    /// Hide informational messages; warnings, errors and command output stay
+    #[arg(global = true, long, short = 'q')]
+    pub quiet: bool,
+
+    /// Plain ASCII output: OK / WARN / ERR prefixes instead of emoji
+    #[arg(global = true, long = "no-emoji")]
+    pub no_emoji: bool,
 
-    /// Run in test mode (no config file update)
+    /// Run in test mode (config file read-only, separate default DB)
     #[arg(global = true, long = "test", hide = true)]
     pub test: bool,
 
+    /// Always present unless `--version` is given
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
+}
+
+impl Cli {
+    /// The `--db` override (the first one when repeated).
+    pub fn db(&self) -> Option<&String> {
+        self.db.first()
+    }
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize the database and configuration
-    Init,
+    Init {
+        /// Initialize even if the SQLite file already contains non-rTimelogger tables
+        #[arg(long = "force-init")]
+        force_init: bool,
+
+        /// Skip the setup questions and write the default configuration
+        #[arg(long)]
+        defaults: bool,
+
+        /// Start from a contract preset (see --list-presets)
+        #[arg(long, value_name = "NAME")]
+        preset: Option<String>,
+
+        /// List the contract presets and exit
+        #[arg(long = "list-presets", conflicts_with_all = ["force_init", "defaults", "preset"])]
+        list_presets: bool,
+    },
 
     /// Manage the configuration file (view or edit)
     Config {
-        #[arg(long = "print", help = "Print the current configuration file")]
+        #[arg(
+            long = "print",
+            help = "Print the effective configuration and where each value comes from"
+        )]
         print_config: bool,
 
         #[arg(long = "check", help = "Check configuration file for missing fields")]
@@ -41,6 +99,31 @@ pub enum Commands {
         #[arg(long = "migrate", help = "Run configuration file migrations if needed")]
         migrate: bool,
 
+        #[arg(
+            long = "set",
+            value_name = "KEY=VALUE",
+            help = "Set a configuration value, keeping comments and layout of the file"
+        )]
+        set: Option<String>,
+
+        #[arg(
+            long = "migrate-format",
+            help = "Convert the legacy YAML config (rtimelogger.conf) to rtimelogger.toml"
+        )]
+        migrate_format: bool,
+
+        #[arg(
+            long = "repair-db-path",
+            help = "Point `database` to the database found in the config directory when the configured one is missing"
+        )]
+        repair_db_path: bool,
+
+        #[arg(
+            long = "rollback",
+            help = "Restore the previous version of the configuration file (kept as <file>.bak)"
+        )]
+        rollback: bool,
+
         #[arg(
             long = "edit",
             help = "Edit the configuration file (default editor: $EDITOR, or nano/vim/notepad)"
@@ -67,6 +150,140 @@ pub enum Commands {
 
         #[arg(long = "info", help = "Show database information")]
         info: bool,
+
+        /// Rebuild the pair / unmatched columns from the events
+        #[arg(long)]
+        rebuild: bool,
+
+        /// With --rebuild: only the days of this period (YYYY, YYYY-MM, YYYY-MM-DD or range)
+        #[arg(long, requires = "rebuild")]
+        period: Option<String>,
+    },
+
+    /// Show today's state and the week / month balance at a glance
+    Status {
+        /// Print a single stable `key=value` line (for shell prompts)
+        #[arg(long)]
+        porcelain: bool,
+    },
+
+    /// Predict today's exit time and the week total from recent history
+    Predict {
+        /// Weeks of history used by the model
+        #[arg(
+            long,
+            default_value_t = crate::core::predict::DEFAULT_WEEKS,
+            value_parser = clap::value_parser!(u32).range(1..=52)
+        )]
+        weeks: u32,
+    },
+
+    /// Aggregated reports over a period
+    Report {
+        #[arg(long, short, help = "Filter by year/month/day or a custom range")]
+        period: Option<String>,
+
+        /// Average / min / max first IN and last OUT per weekday
+        #[arg(long = "by-weekday")]
+        by_weekday: bool,
+
+        /// List outlier days, long days without lunch and impossible values
+        #[arg(long)]
+        anomalies: bool,
+
+        /// Focused time per day (`focus start` / `stop`)
+        #[arg(long)]
+        focus: bool,
+
+        /// Billable hours and amounts per month and project (config `rates`)
+        #[arg(long)]
+        earnings: bool,
+
+        /// Blocks of work without a break longer than `mandatory_lunch_after`
+        #[arg(long)]
+        violations: bool,
+
+        /// Worked hours and share per position, with the count of Mixed days
+        #[arg(long = "by-position")]
+        by_position: bool,
+
+        /// Worked hours per pair weighted by the `shift_bands` of the config
+        #[arg(long)]
+        weighted: bool,
+
+        /// Lunches outside the min / max lunch break and days with an automatic lunch
+        #[arg(long = "lunch-audit")]
+        lunch_audit: bool,
+
+        /// Side-by-side totals of two periods (`2025-05:2025-06`, or `A,B` with ranges)
+        #[arg(
+            long,
+            value_name = "A:B",
+            conflicts_with_all = ["period", "by_weekday", "by_position", "anomalies", "focus", "earnings", "violations", "weighted", "lunch_audit"]
+        )]
+        compare: Option<String>,
+    },
+
+    /// Print the rules applied to a day (expected hours, lunch, limits, rounding)
+    Policy {
+        /// Day to explain (YYYY-MM-DD, today or mark:<name>)
+        date: String,
+    },
+
+    /// Find past days matching every given condition
+    Find {
+        #[arg(long, short, help = "Filter by year/month/day or a custom range")]
+        period: Option<String>,
+
+        /// Minimum ΔWORK in minutes (e.g. 60, or -30)
+        #[arg(
+            long = "min-surplus",
+            value_name = "MIN",
+            allow_negative_numbers = true
+        )]
+        min_surplus: Option<i64>,
+
+        /// Maximum ΔWORK in minutes
+        #[arg(
+            long = "max-surplus",
+            value_name = "MIN",
+            allow_negative_numbers = true
+        )]
+        max_surplus: Option<i64>,
+
+        /// Minimum worked time (minutes or 7h30m)
+        #[arg(long = "min-worked", value_name = "DURATION", value_parser = parse_duration_arg)]
+        min_worked: Option<i64>,
+
+        /// Maximum worked time (minutes or 7h30m)
+        #[arg(long = "max-worked", value_name = "DURATION", value_parser = parse_duration_arg)]
+        max_worked: Option<i64>,
+
+        /// Days with a pair at this position (M: days with several positions)
+        #[arg(long, value_parser = parse_location)]
+        pos: Option<Location>,
+
+        /// Days with a note
+        #[arg(long = "has-note")]
+        has_note: bool,
+    },
+
+    /// Receive badge-gateway punches over HTTP (`POST /events`)
+    Serve {
+        /// Listening address (default: `bind` under [api], else 127.0.0.1:8787)
+        #[arg(long, value_name = "ADDR")]
+        bind: Option<String>,
+    },
+
+    /// Print the JSON Schema of exported objects or of the config file
+    Schema {
+        /// Output format
+        #[arg(long, value_enum, default_value = "json-schema")]
+        format: SchemaFormat,
+
+        /// Document to describe
+        #[arg(long, value_enum, default_value = "export")]
+        target: SchemaTarget,
     },
 
     /// Print or manage the internal log table
@@ -83,17 +300,30 @@ pub enum Commands {
         /// Position (O = Office, R = Remote, H = Holiday, N = National Holiday, C = Client, M = Mixed, S = Sick Leave)
         #[arg(
             long = "pos",
+            value_parser = parse_location,
             help = "Work position: O=Office, R=Remote, H=Holiday, N=National Holiday, C=Client, M=Mixed, S=Sick Leave"
         )]
-        pos: Option<String>,
+        pos: Option<Location>,
+
+        /// Wi-Fi SSID used by `position_rules` instead of the detected one
+        #[arg(long = "wifi-ssid", value_name = "SSID", conflicts_with = "pos")]
+        wifi_ssid: Option<String>,
+
+        /// Hostname used by `position_rules` instead of this machine's
+        #[arg(long = "hostname", value_name = "NAME", conflicts_with = "pos")]
+        hostname: Option<String>,
 
         /// Clock-in time (HH:MM)
-        #[arg(long = "in", help = "Clock-in time (HH:MM)")]
-        start: Option<String>,
+        #[arg(long = "in", value_parser = parse_time_arg, help = "Clock-in time (HH:MM)")]
+        start: Option<NaiveTime>,
 
         /// Lunch break in minutes
-        #[arg(long = "lunch", help = "Lunch break duration in minutes")]
-        lunch: Option<i32>,
+        #[arg(
+            long = "lunch",
+            value_parser = clap::value_parser!(u16).range(..=MAX_LUNCH_ARG),
+            help = "Lunch break duration in minutes"
+        )]
+        lunch: Option<u16>,
 
         /// Mark this pair as a work gap between OUT and IN events
         #[arg(
@@ -112,8 +342,8 @@ pub enum Commands {
         no_work_gap: bool,
 
         /// Clock-out time (HH:MM)
-        #[arg(long = "out", help = "Clock-out time (HH:MM)")]
-        end: Option<String>,
+        #[arg(long = "out", value_parser = parse_time_arg, help = "Clock-out time (HH:MM)")]
+        end: Option<NaiveTime>,
 
         /// Edit an existing pair instead of creating a new one
         #[arg(long = "pair", help = "Pair ID to edit (used with --edit)")]
@@ -130,9 +360,35 @@ pub enum Commands {
         #[arg(long = "notes", help = "Add or update notes for the workday/pair")]
         notes: Option<String>,
 
+        /// Structured metadata stored as JSON in events.meta (repeatable)
+        #[arg(
+            long = "meta",
+            value_name = "KEY=VALUE",
+            help = "Attach metadata to the event(s), e.g. --meta project=acme --meta ticket=42"
+        )]
+        meta: Vec<String>,
+
+        /// Never add the automatic lunch (min lunch break) to this day
+        #[arg(long = "no-auto-lunch", conflicts_with = "to")]
+        no_auto_lunch: bool,
+
+        /// Record the change even beyond `max_daily_work`; the reason is stored in meta
+        #[arg(long = "override", value_name = "REASON", conflicts_with = "to")]
+        override_reason: Option<String>,
+
         /// End date (YYYY-MM-DD). Only valid with --pos Malattia.
         #[arg(long, value_parser = parse_date)]
         to: Option<NaiveDate>,
+
+        /// Move the edited pair to another day (YYYY-MM-DD)
+        #[arg(
+            long = "move-to",
+            value_name = "DATE",
+            value_parser = parse_date,
+            requires = "edit",
+            conflicts_with_all = ["pos", "start", "end", "lunch", "work_gap", "no_work_gap", "notes", "meta", "no_auto_lunch", "override_reason", "to"]
+        )]
+        move_to: Option<NaiveDate>,
     },
 
     /// Delete a work session by ID
@@ -140,20 +396,134 @@ pub enum Commands {
         #[arg(long = "pair", help = "Pair id to delete for the given date")]
         pair: Option<usize>,
 
+        /// Delete every event of a period (YYYY, YYYY-MM or START:END)
+        #[arg(long, short, conflicts_with_all = ["date", "pair"])]
+        period: Option<String>,
+
+        /// Delete the most recently recorded event of today
+        #[arg(long, conflicts_with_all = ["date", "pair", "period"])]
+        last: bool,
+
+        /// With --last: only consider IN or OUT events
+        #[arg(long, value_parser = ["in", "out"], requires = "last")]
+        kind: Option<String>,
+
+        #[arg(required_unless_present_any = ["period", "last"])]
+        date: Option<String>,
+    },
+
+    /// Set the lunch of a specific pair after the fact
+    Lunch {
+        /// Date of the pair (YYYY-MM-DD)
+        date: String,
+
+        /// Lunch minutes (0 removes it)
+        #[arg(value_parser = clap::value_parser!(u16).range(..=MAX_LUNCH_ARG))]
+        minutes: u16,
+
+        /// Pair number (default: the pair crossing lunch_window, else the last one)
+        #[arg(long)]
+        pair: Option<usize>,
+    },
+
+    /// Track focus (pomodoro / deep work) sessions; they never affect the surplus
+    Focus {
+        #[command(subcommand)]
+        action: FocusCommands,
+    },
+
+    /// List, restore or empty the events deleted by `del`
+    Trash {
+        #[command(subcommand)]
+        action: TrashCommands,
+    },
+
+    /// Time off in lieu: convert surplus into time off and take it on a day off
+    Toil {
+        #[command(subcommand)]
+        action: ToilCommands,
+    },
+
+    /// Lock a day (e.g. once approved or invoiced): add, del and import will refuse it
+    Lock {
+        /// Date to lock (YYYY-MM-DD)
+        date: String,
+    },
+
+    /// Unlock a previously locked day
+    Unlock {
+        /// Date to unlock (YYYY-MM-DD)
         date: String,
     },
 
+    /// Bookmark a day with a label, usable as `mark:<name>` in --period
+    Mark {
+        /// Date to bookmark (YYYY-MM-DD)
+        #[arg(required_unless_present_any = ["list", "delete"])]
+        date: Option<String>,
+
+        /// Label of the bookmark (e.g. "release day")
+        #[arg(required_unless_present_any = ["list", "delete"])]
+        label: Option<String>,
+
+        /// List the bookmarks
+        #[arg(long, conflicts_with_all = ["date", "label", "delete"])]
+        list: bool,
+
+        /// Remove the bookmark with this name
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["date", "label"])]
+        delete: Option<String>,
+    },
+
+    /// Move old events (and log rows) into a separate archive database
+    Archive {
+        /// Archive everything dated before this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        before: String,
+
+        /// Archive database (path or name listed under `databases`); created if missing
+        #[arg(long, value_name = "FILE")]
+        file: String,
+    },
+
+    /// Close a month: run the `on_closeout` export pipeline and lock all its days
+    Closeout {
+        /// Month to close (YYYY-MM)
+        month: String,
+
+        /// Overwrite report files that already exist
+        #[arg(long, short = 'f')]
+        force: bool,
+    },
+
     /// List sessions
     List {
         /// Compact output (single dense line per day)
         #[arg(long, action = clap::ArgAction::SetTrue)]
         compact: bool,
 
-        #[arg(long, short, help = "Filter by year/month/day or a custom range")]
-        period: Option<String>,
+        #[arg(
+            long,
+            short,
+            value_delimiter = ',',
+            help = "Filter by year/month/day or a custom range; repeat it (or separate with commas) to list several blocks"
+        )]
+        period: Vec<String>,
+
+        #[arg(long, value_parser = parse_location)]
+        pos: Option<Location>,
 
+        /// Also read the events moved to the archive database by `archive`
         #[arg(long)]
-        pos: Option<String>,
+        archive: bool,
+
+        /// Only show these weekdays of the period, e.g. `fri` or `mon,fri`
+        #[arg(long = "weekday", visible_alias = "weekdays", value_name = "DAYS")]
+        weekday: Option<String>,
+
+        /// Drop weekends, holidays and sick-leave days
+        #[arg(long = "only-workdays")]
+        only_workdays: bool,
 
         #[arg(long = "today", help = "Show only today's record")]
         now: bool,
@@ -164,17 +534,103 @@ pub enum Commands {
         #[arg(long = "events", help = "List all events (in/out)")]
         events: bool,
 
-        #[arg(long = "pairs", help = "Filter by pair id (only with --events)")]
+        #[arg(
+            long = "pairs",
+            help = "Filter by pair id (only with --events and a single day)"
+        )]
         pairs: Option<usize>,
+
+        #[arg(
+            long = "unmatched",
+            help = "Show only dangling IN/OUT events (implies --events)"
+        )]
+        unmatched: bool,
+
+        #[arg(long = "meta", help = "Show event metadata (only with --events)")]
+        meta: bool,
+
+        /// Append a table of the pairs of the listed days, with totals (only with --events)
+        #[arg(long, conflicts_with = "porcelain")]
+        summary: bool,
+
+        /// Keep only the incomplete pairs (IN without OUT) in the --summary table
+        #[arg(long = "only-unmatched", requires = "summary")]
+        only_unmatched: bool,
+
+        /// Events as stable tab-separated columns for scripts (implies --events)
+        #[arg(long, conflicts_with_all = ["compact", "details", "columns", "cumulative"])]
+        porcelain: bool,
+
+        /// Show the host that recorded each event; with a NAME, only that host's events
+        #[arg(long = "host", value_name = "NAME", num_args = 0..=1)]
+        host: Option<Option<String>>,
+
+        #[arg(
+            long = "decimal",
+            help = "Show durations as decimal hours (7.50), rounded to config decimal_step"
+        )]
+        decimal: bool,
+
+        #[arg(
+            long = "columns",
+            value_name = "COLS",
+            help = "Comma-separated daily table columns: date,pos,in,lunch,out,tgt,worked,expected,delta,cum"
+        )]
+        columns: Option<String>,
+
+        /// Append a running month-to-date ΔWORK column (ΣΔWORK)
+        #[arg(long)]
+        cumulative: bool,
     },
 
     /// Create a backup copy of the database
     Backup {
+        /// Destination file; `{YYYY}` `{MM}` `{DD}` are replaced with today's date
         #[arg(long, value_name = "FILE")]
         file: String,
 
-        #[arg(long)]
+        /// Compress the backup (shorthand for --compression zip)
+        #[arg(long, conflicts_with = "compression")]
         compress: bool,
+
+        /// Compression format of the backup
+        #[arg(long, value_enum, value_name = "FORMAT")]
+        compression: Option<BackupCompression>,
+
+        /// Store the backup in an AES-256 encrypted ZIP (implies --compression zip)
+        #[arg(long)]
+        encrypt: bool,
+    },
+
+    /// Write a systemd unit / timer (or Windows Task Scheduler XML) running a task unattended
+    InstallService {
+        /// Task to run: a scheduled backup, or `serve` kept running
+        #[arg(long, value_enum, default_value = "backup")]
+        task: ServiceTask,
+
+        /// When to run, e.g. "Mon..Fri 18:30", "Sat,Sun 09:00" or "18:30" (every day)
+        #[arg(long, value_name = "WHEN", required_if_eq("task", "backup"))]
+        schedule: Option<String>,
+
+        /// systemd user units (~/.config/systemd/user) instead of system units
+        #[arg(long)]
+        user: bool,
+
+        /// Backup destination (default: <config dir>/backups/rtimelogger-{YYYY}-{MM}-{DD}.sqlite)
+        #[arg(long = "backup-file", value_name = "FILE")]
+        backup_file: Option<String>,
+
+        /// Write Windows Task Scheduler XML instead of systemd units (default on Windows)
+        #[arg(long)]
+        windows: bool,
+
+        /// Print the files without writing them
+        #[arg(long)]
+        print: bool,
+
+        /// Overwrite existing files
+        #[arg(long, short = 'f')]
+        force: bool,
     },
 
     /// Export work session data
@@ -182,8 +638,9 @@ pub enum Commands {
         #[arg(long, value_enum, default_value = "csv")]
         format: ExportFormat,
 
+        /// Output file; default: config export_dir / export_filename
         #[arg(long, value_name = "FILE")]
-        file: String,
+        file: Option<String>,
 
         #[arg(
             long,
@@ -197,22 +654,78 @@ pub enum Commands {
 
         #[arg(long, short = 'f')]
         force: bool,
+
+        /// Wrap the exported file in an AES-256 encrypted ZIP (<FILE>.zip)
+        #[arg(long)]
+        encrypt: bool,
+
+        /// Export the given metadata keys as extra `meta.<key>` columns
+        #[arg(long = "meta-keys", value_name = "KEYS", value_delimiter = ',')]
+        meta_keys: Vec<String>,
+
+        /// Export one row per day with a preset layout (e.g. sap-cats, client-visits),
+        /// as CSV or PDF. User presets are read from <config dir>/presets/<NAME>.yaml
+        #[arg(long, value_name = "NAME", conflicts_with = "meta_keys")]
+        preset: Option<String>,
+
+        /// Export lunch as decimal hours, rounded to config decimal_step
+        #[arg(long, conflicts_with = "preset")]
+        decimal: bool,
+
+        /// Replace the date / time columns with a combined ISO 8601 `datetime` column
+        #[arg(long = "iso-datetime", conflicts_with = "preset")]
+        iso_datetime: bool,
+
+        /// Write one file per month; --file (if given) must contain {YYYY} and {MM}
+        #[arg(long = "split-by", value_enum, conflicts_with = "preset")]
+        split_by: Option<SplitBy>,
+
+        /// Also write the config snapshot (app version, min work, lunch rules)
+        /// in CSV / JSON exports; XLSX and PDF always include it
+        #[arg(long, conflicts_with = "preset")]
+        snapshot: bool,
+
+        /// Sheet layout; weekly-matrix (XLSX only) writes one block per week
+        /// with the days as columns and Start/End/Lunch/Worked/Surplus as rows
+        #[arg(
+            long,
+            value_enum,
+            default_value = "events",
+            conflicts_with_all = ["preset", "split_by", "meta_keys", "iso_datetime"]
+        )]
+        layout: ExportLayout,
     },
 
     /// Import calendar days (e.g., national holidays) from JSON or CSV
     Import {
         /// Path to JSON/CSV file to import
-        #[arg(long, value_name = "FILE")]
-        file: String,
+        #[arg(long, value_name = "FILE", required_unless_present = "stdin")]
+        file: Option<String>,
+
+        /// Read the data from standard input instead of a file (e.g. piped over SSH)
+        #[arg(long, conflicts_with = "file")]
+        stdin: bool,
 
         /// Input format: json|csv (default: json)
         #[arg(long, default_value = "json", value_parser = ["json", "csv"])]
         format: String,
 
+        /// Import time entries exported by another tool instead of days (toggl: Detailed report CSV)
+        #[arg(long, value_parser = ["toggl"], conflicts_with = "format")]
+        from: Option<String>,
+
         /// Print actions without writing to DB
         #[arg(long = "dry-run", action = clap::ArgAction::SetTrue)]
         dry_run: bool,
 
+        /// With --dry-run: show per date what would be inserted or skipped, and the invalid rows
+        #[arg(long, requires = "dry_run", conflicts_with = "from")]
+        diff: bool,
+
+        /// Print the --diff view as JSON
+        #[arg(long, requires = "diff")]
+        json: bool,
+
         /// Replace existing data on conflicting dates (DANGEROUS)
         #[arg(long, action = clap::ArgAction::SetTrue)]
         replace: bool,
@@ -221,4 +734,221 @@ pub enum Commands {
         #[arg(long, default_value = "import")]
         source: String,
     },
+    /// Invoice of the worked hours of a period (per day and project)
+    Invoice {
+        #[arg(
+            long,
+            short,
+            help = "Invoiced period: year/month/day or a custom range"
+        )]
+        period: String,
+
+        /// Hourly rate
+        #[arg(long)]
+        rate: f64,
+
+        /// Client name printed on the invoice
+        #[arg(long)]
+        client: String,
+
+        #[arg(long, value_enum, default_value = "pdf")]
+        format: InvoiceFormat,
+
+        /// Output file (default: invoice-<client>-<period>.<ext>)
+        #[arg(long, value_name = "FILE")]
+        file: Option<String>,
+
+        /// Tax percentage (placeholders are printed without it)
+        #[arg(long, value_name = "PERCENT")]
+        tax: Option<f64>,
+
+        /// Invoice number (placeholder without it)
+        #[arg(long)]
+        number: Option<String>,
+
+        #[arg(long, default_value = "EUR")]
+        currency: String,
+
+        /// Overwrite the output file without asking
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Propose IN / OUT for a forgotten day from the meetings of an .ics calendar
+    Suggest {
+        /// Calendar file (.ics)
+        #[arg(long, value_name = "FILE")]
+        ics: String,
+
+        /// Day to reconstruct (YYYY-MM-DD)
+        #[arg(long, value_parser = parse_date)]
+        date: NaiveDate,
+
+        /// Position of the added pair with --apply (default: O)
+        #[arg(long, value_parser = parse_location)]
+        pos: Option<Location>,
+
+        /// Add the suggested pair instead of only printing it
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// Developer utilities (hidden)
+    #[command(hide = true)]
+    Dev {
+        #[command(subcommand)]
+        action: DevCommands,
+    },
+
+    /// Any other command runs the `rtimelogger-<name>` plugin found on PATH
+    #[command(external_subcommand)]
+    Plugin(Vec<OsString>),
+}
+
+impl Commands {
+    /// Commands that may write the database: a WAL checkpoint follows them.
+    pub fn writes_db(&self) -> bool {
+        matches!(
+            self,
+            Commands::Init { .. }
+                | Commands::Serve { .. }
+                | Commands::Backup { .. }
+                | Commands::Invoice { .. }
+                | Commands::Add { .. }
+                | Commands::Del { .. }
+                | Commands::Lunch { .. }
+                | Commands::Focus { .. }
+                | Commands::Trash { .. }
+                | Commands::Toil { .. }
+                | Commands::Lock { .. }
+                | Commands::Unlock { .. }
+                | Commands::Mark { .. }
+                | Commands::Archive { .. }
+                | Commands::Closeout { .. }
+                | Commands::Import { .. }
+                | Commands::Suggest { .. }
+                | Commands::Db { .. }
+                | Commands::Dev { .. }
+        )
+    }
+
+    /// Commands followed by the daily reminder of open pairs left on past
+    /// days (not the setup ones, not the long-running or script-facing ones).
+    pub fn reminds_open_pairs(&self) -> bool {
+        !self.is_machine_readable()
+            && !matches!(
+                self,
+                Commands::Init { .. }
+                    | Commands::Config { .. }
+                    | Commands::Serve { .. }
+                    | Commands::InstallService { .. }
+            )
+    }
+
+    /// Commands whose stdout is meant for scripts (`status --porcelain`,
+    /// `schema`, `import --diff --json`) or belongs to a plugin: messages
+    /// other than data are kept off stdout.
+    pub fn is_machine_readable(&self) -> bool {
+        matches!(
+            self,
+            Commands::Status { porcelain: true }
+                | Commands::List {
+                    porcelain: true,
+                    ..
+                }
+                | Commands::Schema { .. }
+                | Commands::Import { json: true, .. }
+                | Commands::Plugin(_)
+        )
+    }
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommands {
+    /// Show the deletions in the trash (one batch per `del`)
+    List,
+
+    /// Put a deleted batch back
+    Restore {
+        /// Batch number shown by `trash list`
+        batch: i64,
+    },
+
+    /// Permanently remove everything in the trash
+    Empty,
+}
+
+#[derive(Subcommand)]
+pub enum ToilCommands {
+    /// Convert part of the surplus into time off (e.g. `toil accrue 8h`)
+    Accrue {
+        /// Duration to convert (e.g. 8h, 3h30m, 90m)
+        duration: String,
+
+        /// Date the conversion is recorded on (YYYY-MM-DD, default: today)
+        #[arg(long)]
+        date: Option<String>,
+
+        #[arg(long)]
+        note: Option<String>,
+    },
+
+    /// Take time off on a day without working time (e.g. `toil use 2025-07-04`)
+    Use {
+        /// Day off (YYYY-MM-DD)
+        date: String,
+
+        /// Time off taken (default: min_work_duration)
+        #[arg(long)]
+        duration: Option<String>,
+
+        #[arg(long)]
+        note: Option<String>,
+    },
+
+    /// Show the ledger and the current balance
+    List {
+        /// Only the entries of this period (YYYY, YYYY-MM, YYYY-MM-DD or range)
+        #[arg(long)]
+        period: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FocusCommands {
+    /// Start a focus session
+    Start {
+        /// What the session is about (e.g. "deep work")
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Start time (HH:MM, default: now)
+        #[arg(long, value_parser = parse_time_arg)]
+        at: Option<NaiveTime>,
+    },
+
+    /// Stop the running focus session
+    Stop {
+        /// Stop time (HH:MM, default: now)
+        #[arg(long, value_parser = parse_time_arg)]
+        at: Option<NaiveTime>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DevCommands {
+    /// Fill the database with plausible sample punches
+    Seed {
+        /// Number of calendar days to generate, ending today
+        #[arg(long, default_value_t = 365)]
+        days: u32,
+
+        /// Data profile: realistic | uniform
+        #[arg(long, value_enum, default_value = "realistic")]
+        profile: SeedProfile,
+
+        /// Random seed (same seed → same data)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
 }
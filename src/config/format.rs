@@ -0,0 +1,73 @@
+//! On-disk formats of the config file: `rtimelogger.toml` (primary) and the
+//! legacy YAML `rtimelogger.conf`, still read and edited in place when it is
+//! the only file present.
+
+use super::{toml_lines, yaml_edit};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Format of a config file, from its extension.
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "TOML",
+            ConfigFormat::Yaml => "YAML",
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T, String> {
+        match self {
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Raw content as a generic value, to see which keys are really in the file.
+    pub fn parse_value(self, content: &str) -> Result<serde_yaml::Value, String> {
+        match self {
+            ConfigFormat::Toml => {
+                let table: toml::Table = toml::from_str(content).map_err(|e| e.to_string())?;
+                serde_yaml::to_value(table).map_err(|e| e.to_string())
+            }
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn serialize<T: Serialize>(self, value: &T) -> Result<String, String> {
+        match self {
+            ConfigFormat::Toml => toml::to_string(value).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Set a top-level key in place, keeping comments and layout of the file.
+    pub fn set_top_level(
+        self,
+        content: &str,
+        key: &str,
+        value: &serde_yaml::Value,
+    ) -> Result<String, String> {
+        match self {
+            ConfigFormat::Toml => {
+                let value = toml::Value::try_from(value)
+                    .map_err(|e| format!("value not representable in TOML: {}", e))?;
+                Ok(toml_lines::set_top_level(content, key, &value))
+            }
+            ConfigFormat::Yaml => Ok(yaml_edit::set_top_level(content, key, value)),
+        }
+    }
+}
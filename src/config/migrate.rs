@@ -1,3 +1,5 @@
+use super::write::write_config;
+use super::yaml_edit::set_top_level;
 use crate::ui::messages::{info, success};
 use rusqlite::{Connection, Error, OptionalExtension};
 use serde_yaml::Value;
@@ -63,12 +65,10 @@ fn move_or_copy(from: &Path, to: &Path) -> io::Result<()> {
 fn update_db_reference_in_conf_io(new_conf: &Path, new_dir: &Path) -> io::Result<bool> {
     let content = fs::read_to_string(new_conf)?;
 
-    if let Ok(mut yaml) = serde_yaml::from_str::<Value>(&content)
-        && let Some(map) = yaml.as_mapping_mut()
+    if let Ok(yaml) = serde_yaml::from_str::<Value>(&content)
+        && let Some(map) = yaml.as_mapping()
     {
-        let key = Value::String("database".to_string());
-
-        if let Some(val) = map.get(&key)
+        if let Some(val) = map.get("database")
             && let Some(dbstr) = val.as_str()
         {
             let db_path = PathBuf::from(dbstr);
@@ -99,16 +99,9 @@ fn update_db_reference_in_conf_io(new_conf: &Path, new_dir: &Path) -> io::Result
 
                 // Update YAML: preserve directory, change filename only
                 let new_db_str = preserve_db_filename(dbstr, "rtimelogger.sqlite");
-                map.insert(key.clone(), Value::String(new_db_str));
+                let updated = set_top_level(&content, "database", &Value::String(new_db_str));
 
-                let serialized = serde_yaml::to_string(&yaml).map_err(|e| {
-                    io::Error::other(format!(
-                        "Failed to serialize YAML for {:?}: {}",
-                        new_conf, e
-                    ))
-                })?;
-
-                fs::write(new_conf, serialized)?;
+                write_config(new_conf, &updated)?;
 
                 return Ok(true);
             }
@@ -260,12 +253,10 @@ pub fn run_fs_migration_with(new_dir: PathBuf, old_dir: PathBuf) -> io::Result<(
     // Update DB reference inside config (if present)
     if new_conf.exists() {
         let content = fs::read_to_string(&new_conf)?;
-        if let Ok(mut yaml) = serde_yaml::from_str::<Value>(&content)
-            && let Some(map) = yaml.as_mapping_mut()
+        if let Ok(yaml) = serde_yaml::from_str::<Value>(&content)
+            && let Some(map) = yaml.as_mapping()
         {
-            let key = Value::String("database".to_string());
-
-            if let Some(val) = map.get(&key)
+            if let Some(val) = map.get("database")
                 && let Some(dbstr) = val.as_str()
             {
                 let db_path = PathBuf::from(dbstr);
@@ -289,12 +280,9 @@ pub fn run_fs_migration_with(new_dir: PathBuf, old_dir: PathBuf) -> io::Result<(
                         .to_string_lossy()
                         .to_string();
 
-                    map.insert(key.clone(), Value::String(new_db_str));
+                    let updated = set_top_level(&content, "database", &Value::String(new_db_str));
 
-                    let serialized = serde_yaml::to_string(&yaml)
-                        .map_err(|e| io::Error::other(format!("serialize error: {}", e)))?;
-
-                    fs::write(&new_conf, serialized)
+                    write_config(&new_conf, &updated)
                         .map_err(|e| io::Error::other(format!("write error: {}", e)))?;
                 }
             }
@@ -328,7 +316,7 @@ pub fn migrate_add_show_weekday(conn: &Connection) -> Result<(), Error> {
         return Ok(()); // already applied
     }
 
-    let conf_file = super::Config::config_file();
+    let conf_file = super::Config::yaml_config_file();
 
     if conf_file.exists() {
         let content = fs::read_to_string(&conf_file).map_err(|e| {
@@ -338,44 +326,22 @@ pub fn migrate_add_show_weekday(conn: &Connection) -> Result<(), Error> {
             )
         })?;
 
-        if let Ok(mut yaml) = serde_yaml::from_str::<Value>(&content)
-            && let Some(map) = yaml.as_mapping_mut()
+        if let Ok(yaml) = serde_yaml::from_str::<Value>(&content)
+            && let Some(map) = yaml.as_mapping()
         {
-            let key = Value::String("show_weekday".to_string());
-
-            if !map.contains_key(&key) {
-                map.insert(key.clone(), Value::String("None".to_string()));
-
-                // Serialize updated YAML
-                let serialized = serde_yaml::to_string(&yaml).map_err(|e| {
-                    Error::SqliteFailure(
-                        rusqlite::ffi::Error::new(1),
-                        Some(format!(
-                            "Failed to serialize updated config {:?}: {}",
-                            conf_file, e
-                        )),
-                    )
-                })?;
-
-                // Inject documentation comment right after the `show_weekday` line
-                let mut new_content = String::new();
-
-                for line in serialized.lines() {
-                    new_content.push_str(line);
-                    new_content.push('\n');
-
-                    if line.starts_with("show_weekday:") {
-                        new_content.push_str(
-                            "  # show-weekday parameter options:\n\
-                             #   None   → do not show weekday\n\
-                             #   Short  → Mo, Tu, We, Th, Fr, Sa, Su\n\
-                             #   Medium → Mon, Tue, Wed, Thu, Fri, Sat, Sun\n\
-                             #   Long   → Monday, Tuesday, ...\n",
-                        );
-                    }
-                }
-
-                fs::write(&conf_file, new_content).map_err(|e| {
+            if !map.contains_key("show_weekday") {
+                // Append the key (and its documentation) keeping the rest of the file as is
+                let mut new_content =
+                    set_top_level(&content, "show_weekday", &Value::String("None".to_string()));
+                new_content.push_str(
+                    "  # show-weekday parameter options:\n\
+                     #   None   → do not show weekday\n\
+                     #   Short  → Mo, Tu, We, Th, Fr, Sa, Su\n\
+                     #   Medium → Mon, Tue, Wed, Thu, Fri, Sat, Sun\n\
+                     #   Long   → Monday, Tuesday, ...\n",
+                );
+
+                write_config(&conf_file, &new_content).map_err(|e| {
                     Error::SqliteFailure(
                         rusqlite::ffi::Error::new(1),
                         Some(format!(
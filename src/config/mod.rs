@@ -1,13 +1,43 @@
+#[cfg(feature = "cli")]
+pub mod format;
+#[cfg(feature = "cli")]
 pub mod migrate;
+pub mod presets;
+#[cfg(feature = "cli")]
+pub mod provenance;
+#[cfg(feature = "cli")]
+pub mod toml_lines;
+#[cfg(feature = "cli")]
+pub mod write;
+#[cfg(feature = "cli")]
+pub mod yaml_edit;
 
-use crate::ui::messages::{error, info, warning};
+use crate::core::calculator::overlap::OverlapMode;
+use crate::utils::formatting::SurplusFormat;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "cli")]
+use crate::db::pool::is_memory_db;
+#[cfg(feature = "cli")]
+use crate::errors::{AppError, AppResult};
+#[cfg(feature = "cli")]
+use crate::export::ExportFormat;
+#[cfg(feature = "cli")]
+use crate::ui::messages::{error, info, warning};
+#[cfg(feature = "cli")]
+use format::ConfigFormat;
+#[cfg(feature = "cli")]
 use std::env;
+#[cfg(feature = "cli")]
 use std::fs;
-use std::io::{self, Write};
-use std::path::PathBuf;
+#[cfg(feature = "cli")]
+use std::io;
+#[cfg(feature = "cli")]
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(default)]
 pub struct Config {
     pub database: String,
@@ -18,9 +48,155 @@ pub struct Config {
     pub min_duration_lunch_break: i32,
     #[serde(default = "default_max_lunch")]
     pub max_duration_lunch_break: i32,
+    /// Longest block of work allowed without a break (e.g. "6h"); unset = no check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mandatory_lunch_after: Option<String>,
+    /// Most work allowed in a day (e.g. "10h"); unset = no check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_daily_work: Option<String>,
+    /// Refuse the `add` that goes beyond `max_daily_work` (else only a warning)
+    #[serde(default)]
+    pub enforce_max_daily_work: bool,
+    /// Allow overlapping pairs of different activities (`project` metadata):
+    /// off (default), split (shared minutes) or parallel (counted per activity)
+    #[serde(default)]
+    pub overlap_mode: OverlapMode,
     #[serde(default = "default_separator_char")]
     pub separator_char: String,
     pub show_weekday: String,
+    /// Rounding step (in hours) used by `--decimal`, e.g. 0.01 or 0.25
+    #[serde(default = "default_decimal_step")]
+    pub decimal_step: f64,
+    /// Decimal separator of the `--decimal` figures in CSV / PDF exports:
+    /// ".", "," or "auto" (from `LC_ALL` / `LC_NUMERIC` / `LANG`)
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator: String,
+    /// Directory of `export` when `--file` is omitted (default: current directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_dir: Option<String>,
+    /// File name of `export` when `--file` is omitted: `{period}` and `{ext}`
+    /// are replaced with the exported range and the format
+    #[serde(default = "default_export_filename")]
+    pub export_filename: String,
+    /// How surplus / ΔWORK is shown: hm (`+01h15m`), minutes (`+75 min`), decimal (`+1.25h`)
+    #[serde(default)]
+    pub surplus_format: SurplusFormat,
+    /// Also record the OS user (besides the hostname) on every new event
+    #[serde(default)]
+    pub record_os_user: bool,
+    /// Plain ASCII messages (same as `--no-emoji`)
+    #[serde(default)]
+    pub no_emoji: bool,
+    /// Named databases selectable with `--db <name>` (name → path)
+    #[serde(default)]
+    pub databases: BTreeMap<String, String>,
+    /// Reports produced automatically by `closeout`
+    #[cfg(feature = "cli")]
+    #[serde(default)]
+    pub on_closeout: Vec<CloseoutExport>,
+    /// Rules picking the position of `add` when `--pos` is omitted (first match wins)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub position_rules: Vec<PositionRule>,
+    /// Hourly rate per project (`project` metadata key) for `report --earnings`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rates: BTreeMap<String, f64>,
+    /// Time bands weighting the worked minutes for `report --weighted`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shift_bands: Vec<ShiftBand>,
+    /// `serve`: badge-gateway receiver (optional `[api]` table)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api: Option<ApiConfig>,
+    /// Loaded with `--test`: the config file is never written
+    #[serde(skip)]
+    pub test_mode: bool,
+}
+
+/// One report of the `on_closeout` pipeline. `file` must be absolute and may
+/// contain `{YYYY}` / `{MM}`, replaced with the closed month.
+#[cfg(feature = "cli")]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CloseoutExport {
+    pub format: ExportFormat,
+    pub file: String,
+}
+
+/// `[[position_rules]]`: Wi-Fi SSID / hostname → position. A rule without
+/// conditions always matches (use it last as the "else" branch).
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct PositionRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// Position code (O, R, C, ...)
+    pub position: String,
+}
+
+/// `[[shift_bands]]`: minutes inside the band are multiplied by
+/// `multiplier` (night 22:00-06:00 ×1.3, Sunday ×1.5, ...). Without
+/// `from` / `to` the band covers the whole day; without `days` every day.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct ShiftBand {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// Start time (HH:MM)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// End time (HH:MM); before `from` the band crosses midnight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// Weekdays (mon..sun) the band applies to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<String>,
+    pub multiplier: f64,
+}
+
+/// `[api]`: settings of `rtimelogger serve`.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Listening address (keep it on localhost behind a reverse proxy)
+    pub bind: String,
+    /// Bearer token required by `POST /events` (`RTIMELOGGER_API_TOKEN` wins)
+    pub token: String,
+    pub mapping: ApiMapping,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:8787".to_string(),
+            token: String::new(),
+            mapping: ApiMapping::default(),
+        }
+    }
+}
+
+/// `[api.mapping]`: where the fields of a badge-reader payload are.
+/// Field names accept dotted paths (`event.ts`).
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(default)]
+pub struct ApiMapping {
+    /// RFC 3339 or `YYYY-MM-DD HH:MM[:SS]`
+    pub timestamp: String,
+    pub kind: String,
+    /// Optional in the payload: `default_position` is used when missing
+    pub position: String,
+    /// Values of `kind` meaning IN / OUT (case-insensitive)
+    pub in_values: Vec<String>,
+    pub out_values: Vec<String>,
+}
+
+impl Default for ApiMapping {
+    fn default() -> Self {
+        Self {
+            timestamp: "timestamp".to_string(),
+            kind: "direction".to_string(),
+            position: "position".to_string(),
+            in_values: vec!["in".into(), "entry".into()],
+            out_values: vec!["out".into(), "exit".into()],
+        }
+    }
 }
 
 // ---------------------------------------------
@@ -35,29 +211,92 @@ fn default_max_lunch() -> i32 {
 fn default_separator_char() -> String {
     "-".to_string()
 }
+fn default_decimal_step() -> f64 {
+    0.01
+}
+fn default_decimal_separator() -> String {
+    ".".to_string()
+}
+fn default_export_filename() -> String {
+    "timesheet-{period}.{ext}".to_string()
+}
 
 // ---------------------------------------------
 // CONFIG DEFAULT IMPL
 // ---------------------------------------------
 impl Default for Config {
     fn default() -> Self {
-        let db_path = Self::database_file();
+        // Without `cli` there is no config directory (e.g. wasm32)
+        #[cfg(feature = "cli")]
+        let database = Self::database_file().to_string_lossy().to_string();
+        #[cfg(not(feature = "cli"))]
+        let database = String::new();
+
         Self {
-            database: db_path.to_string_lossy().to_string(),
+            database,
             default_position: "O".to_string(),
             min_work_duration: "8h".to_string(),
             lunch_window: "12:30-14:00".to_string(),
             min_duration_lunch_break: default_min_lunch(),
             max_duration_lunch_break: default_max_lunch(),
+            mandatory_lunch_after: None,
+            max_daily_work: None,
+            enforce_max_daily_work: false,
+            overlap_mode: OverlapMode::default(),
             separator_char: default_separator_char(),
             show_weekday: "None".to_string(),
+            decimal_step: default_decimal_step(),
+            decimal_separator: default_decimal_separator(),
+            export_dir: None,
+            export_filename: default_export_filename(),
+            surplus_format: SurplusFormat::default(),
+            record_os_user: false,
+            no_emoji: false,
+            databases: BTreeMap::new(),
+            #[cfg(feature = "cli")]
+            on_closeout: Vec::new(),
+            position_rules: Vec::new(),
+            rates: BTreeMap::new(),
+            shift_bands: Vec::new(),
+            api: None,
+            test_mode: false,
         }
     }
 }
 
+/// Environment variable overriding the configuration directory
+/// (isolated setups, integration tests).
+pub const CONFIG_DIR_ENV: &str = "RTIMELOGGER_CONFIG_DIR";
+
+/// Files, directories and the config file itself: `cli` only.
+#[cfg(feature = "cli")]
 impl Config {
+    /// Resolve a `--db` value: a name listed under `databases` maps to its path
+    /// (relative paths are taken from the config directory), anything else is
+    /// used as a path as is.
+    pub fn resolve_database(&self, value: &str) -> String {
+        match self.databases.get(value) {
+            Some(path) => {
+                let p = std::path::Path::new(path);
+                if p.is_absolute() {
+                    path.clone()
+                } else {
+                    Self::config_dir().join(p).to_string_lossy().to_string()
+                }
+            }
+            None => value.to_string(),
+        }
+    }
+
     /// Return the standard configuration directory depending on the platform
+    /// (or `$RTIMELOGGER_CONFIG_DIR` when set)
     pub fn config_dir() -> PathBuf {
+        if let Ok(dir) = env::var(CONFIG_DIR_ENV)
+            && !dir.trim().is_empty()
+        {
+            return PathBuf::from(dir);
+        }
+
         if cfg!(target_os = "windows") {
             let appdata = env::var("APPDATA").unwrap_or_else(|_| ".".to_string());
             PathBuf::from(appdata).join("rtimelogger")
@@ -67,8 +306,24 @@ impl Config {
         }
     }
 
-    /// Return the full path of the config file
+    /// Return the full path of the config file: `rtimelogger.toml`, or the
+    /// legacy YAML `rtimelogger.conf` while it is the only one present
     pub fn config_file() -> PathBuf {
+        let toml = Self::toml_config_file();
+        let yaml = Self::yaml_config_file();
+        if !toml.exists() && yaml.exists() {
+            yaml
+        } else {
+            toml
+        }
+    }
+
+    pub fn toml_config_file() -> PathBuf {
+        Self::config_dir().join("rtimelogger.toml")
+    }
+
+    /// Legacy YAML config file
+    pub fn yaml_config_file() -> PathBuf {
         Self::config_dir().join("rtimelogger.conf")
     }
 
@@ -77,22 +332,69 @@ impl Config {
         Self::config_dir().join("rtimelogger.sqlite")
     }
 
+    /// When `database` points to a missing file (typically after moving the
+    /// files by hand) but a database with the same name, or the default one,
+    /// exists in the config directory: that file.
+    pub fn misplaced_database(&self) -> Option<PathBuf> {
+        let configured = Path::new(&self.database);
+        if self.test_mode || is_memory_db(&self.database) || configured.exists() {
+            return None;
+        }
+
+        let dir = Self::config_dir();
+        configured
+            .file_name()
+            .map(|name| dir.join(name))
+            .into_iter()
+            .chain(std::iter::once(Self::database_file()))
+            .find(|p| p.is_file() && p.as_path() != configured)
+    }
+
+    /// Default database used in test mode, kept apart from the real one
+    pub fn test_database_file() -> PathBuf {
+        Self::config_dir().join("rtimelogger-test.sqlite")
+    }
+
+    /// Load the configuration for the normal or the test mode.
+    ///
+    /// In test mode the config file is read but never created or updated,
+    /// and the default database is `rtimelogger-test.sqlite`.
+    pub fn load_for(test: bool) -> Self {
+        if !test {
+            return Self::load();
+        }
+
+        let mut cfg = Self::load_inner(false);
+        cfg.database = Self::test_database_file().to_string_lossy().to_string();
+        cfg.test_mode = true;
+        cfg
+    }
+
     /// Load configuration from file, or return defaults if not found.
-    /// If some fields are missing in the YAML, they are added with default values
+    /// If some fields are missing in the file, they are added with default values
     /// and the file is updated.
     pub fn load() -> Self {
+        Self::load_inner(true)
+    }
+
+    /// `persist = false` → never touch the file (test mode)
+    fn load_inner(persist: bool) -> Self {
         let path = Self::config_file();
+        let format = ConfigFormat::of(&path);
 
         // 1) Se il file non esiste → crea directory + file con default
         if !path.exists() {
             let defaults = Config::default();
+            if !persist {
+                return defaults;
+            }
 
             if let Some(parent) = path.parent() {
                 let _ = fs::create_dir_all(parent);
             }
 
-            if let Ok(yaml) = serde_yaml::to_string(&defaults)
-                && let Err(e) = fs::write(&path, yaml)
+            if let Ok(text) = format.serialize(&defaults)
+                && let Err(e) = write::write_config(&path, &text)
             {
                 error(format!("Failed to write default config file: {}", e));
             }
@@ -115,27 +417,31 @@ impl Config {
         if content.trim().is_empty() {
             warning("Config file is empty, regenerating defaults.");
             let defaults = Config::default();
-            if let Ok(yaml) = serde_yaml::to_string(&defaults) {
-                let _ = fs::write(&path, yaml);
+            if persist && let Ok(text) = format.serialize(&defaults) {
+                let _ = write::write_config(&path, &text);
             }
             return defaults;
         }
 
-        // 3) Parse raw YAML per vedere cosa esiste *realmente* nel file
-        let raw_yaml: serde_yaml::Value = match serde_yaml::from_str(&content) {
+        // 3) Parse raw per vedere cosa esiste *realmente* nel file
+        let raw_yaml: serde_yaml::Value = match format.parse_value(&content) {
             Ok(v) => v,
             Err(e) => {
-                error(format!("Failed to parse raw YAML ({}), using defaults.", e));
+                error(format!(
+                    "Failed to parse raw {} ({}), using defaults.",
+                    format.as_str(),
+                    e
+                ));
                 let defaults = Config::default();
-                if let Ok(yaml) = serde_yaml::to_string(&defaults) {
-                    let _ = fs::write(&path, yaml);
+                if persist && let Ok(text) = format.serialize(&defaults) {
+                    let _ = write::write_config(&path, &text);
                 }
                 return defaults;
             }
         };
 
         // 4) Parse in Config (qui Serde completa i campi mancanti in memoria)
-        let mut loaded: Config = match serde_yaml::from_str(&content) {
+        let mut loaded: Config = match format.parse(&content) {
             Ok(cfg) => cfg,
             Err(e) => {
                 error(format!(
@@ -143,15 +449,15 @@ impl Config {
                     e
                 ));
                 let defaults = Config::default();
-                if let Ok(yaml) = serde_yaml::to_string(&defaults) {
-                    let _ = fs::write(&path, yaml);
+                if persist && let Ok(text) = format.serialize(&defaults) {
+                    let _ = write::write_config(&path, &text);
                 }
                 return defaults;
             }
         };
 
         let defaults = Config::default();
-        let mut modified = false;
+        let mut missing: Vec<&str> = Vec::new();
 
         // Helper per i campi stringa: li consideriamo "mancanti" se la chiave non esiste nel YAML
         macro_rules! ensure_field {
@@ -163,7 +469,7 @@ impl Config {
                         "Missing field '{}' in config file, inserting default.",
                         $yaml_key
                     ));
-                    modified = true;
+                    missing.push($yaml_key);
                 }
             };
         }
@@ -175,26 +481,55 @@ impl Config {
         ensure_field!("lunch_window", lunch_window);
         ensure_field!("separator_char", separator_char);
         ensure_field!("show_weekday", show_weekday);
+        ensure_field!("databases", databases);
+        ensure_field!("on_closeout", on_closeout);
 
         // Numeric fields: se la chiave non esiste nel file, li impostiamo a default
         if raw_yaml.get("min_duration_lunch_break").is_none() {
             loaded.min_duration_lunch_break = defaults.min_duration_lunch_break;
             error("Missing field 'min_duration_lunch_break', inserting default.");
-            modified = true;
+            missing.push("min_duration_lunch_break");
         }
 
         if raw_yaml.get("max_duration_lunch_break").is_none() {
             loaded.max_duration_lunch_break = defaults.max_duration_lunch_break;
             error("Missing field 'max_duration_lunch_break', inserting default.");
-            modified = true;
+            missing.push("max_duration_lunch_break");
         }
 
-        // 5) Se abbiamo modificato qualcosa → riscriviamo il file aggiornato
-        if modified && let Ok(yaml) = serde_yaml::to_string(&loaded) {
-            if let Some(parent) = path.parent() {
-                let _ = fs::create_dir_all(parent);
+        if raw_yaml.get("decimal_step").is_none() {
+            loaded.decimal_step = defaults.decimal_step;
+            error("Missing field 'decimal_step', inserting default.");
+            missing.push("decimal_step");
+        }
+
+        if raw_yaml.get("record_os_user").is_none() {
+            loaded.record_os_user = defaults.record_os_user;
+            error("Missing field 'record_os_user', inserting default.");
+            missing.push("record_os_user");
+        }
+
+        if raw_yaml.get("no_emoji").is_none() {
+            loaded.no_emoji = defaults.no_emoji;
+            error("Missing field 'no_emoji', inserting default.");
+            missing.push("no_emoji");
+        }
+
+        // 5) Campi mancanti → li aggiungiamo in coda al file, senza toccare
+        //    commenti e valori esistenti
+        if !missing.is_empty()
+            && persist
+            && let Ok(defaults_yaml) = serde_yaml::to_value(&defaults)
+        {
+            let mut updated = content.clone();
+            for key in &missing {
+                if let Some(value) = defaults_yaml.get(*key)
+                    && let Ok(text) = format.set_top_level(&updated, key, value)
+                {
+                    updated = text;
+                }
             }
-            if let Err(e) = fs::write(&path, yaml) {
+            if let Err(e) = write::write_config(&path, &updated) {
                 error(format!("⚠️ Failed to update config file: {}", e));
             } else {
                 info("🔧 Config file updated with missing fields.");
@@ -204,8 +539,15 @@ impl Config {
         loaded
     }
 
-    /// Initialize configuration and database files
-    pub fn init_all(custom_name: Option<String>, is_test: bool) -> io::Result<()> {
+    /// Initialize configuration and database files from `settings` (the
+    /// defaults, or the answers of the `init` setup).
+    /// Returns the path of the database; in test mode the config file is not
+    /// written and the default database is the test one.
+    pub fn init_all(
+        custom_name: Option<String>,
+        is_test: bool,
+        settings: Config,
+    ) -> io::Result<PathBuf> {
         let dir = Self::config_dir();
         fs::create_dir_all(&dir)?;
 
@@ -217,21 +559,25 @@ impl Config {
             } else {
                 dir.join(p)
             }
+        } else if is_test {
+            Self::test_database_file()
         } else {
             dir.join("rtimelogger.sqlite")
         };
 
         let config = Config {
             database: db_path.to_string_lossy().to_string(),
-            ..Config::default()
+            ..settings
         };
 
-        // Write config file
+        // Write config file (keeping the legacy YAML format if that is in use)
         if !is_test {
-            let yaml = serde_yaml::to_string(&config).unwrap();
-            let mut file = fs::File::create(Self::config_file())?;
-            file.write_all(yaml.as_bytes())?;
-            info(format!("Config file: {:?}", Self::config_file()));
+            let path = Self::config_file();
+            let text = ConfigFormat::of(&path)
+                .serialize(&config)
+                .map_err(io::Error::other)?;
+            write::write_config(&path, &text)?;
+            info(format!("Config file: {:?}", path));
         }
 
         // Create empty DB file if not exists
@@ -241,6 +587,43 @@ impl Config {
 
         info(format!("✅ Database:    {:?}", db_path));
 
-        Ok(())
+        Ok(db_path)
+    }
+
+    /// Convert the legacy YAML config into `rtimelogger.toml`. The YAML file
+    /// is kept as `rtimelogger.conf.bak`; comments are not carried over.
+    pub fn migrate_format() -> AppResult<PathBuf> {
+        let yaml_path = Self::yaml_config_file();
+        let toml_path = Self::toml_config_file();
+
+        if toml_path.exists() {
+            return Err(AppError::Config(format!(
+                "{} already exists, nothing to convert.",
+                toml_path.display()
+            )));
+        }
+        if !yaml_path.exists() {
+            return Err(AppError::Config(format!(
+                "No YAML config found at {}.",
+                yaml_path.display()
+            )));
+        }
+
+        let content = fs::read_to_string(&yaml_path)?;
+        let cfg: Config = ConfigFormat::Yaml.parse(&content).map_err(|e| {
+            AppError::Config(format!(
+                "Cannot convert {}: {}\nFix the file (rtimelogger config --edit) and retry.",
+                yaml_path.display(),
+                e
+            ))
+        })?;
+        let text = ConfigFormat::Toml
+            .serialize(&cfg)
+            .map_err(AppError::Config)?;
+
+        write::write_config(&toml_path, &text)?;
+        fs::rename(&yaml_path, yaml_path.with_extension("conf.bak"))?;
+
+        Ok(toml_path)
     }
 }
@@ -0,0 +1,105 @@
+//! Contract presets of `init --preset <name>`: the settings the ΔWORK math
+//! depends on for common contracts. The expected time applies to every
+//! working day, so a weekly schedule is a daily duration over Mon–Fri.
+
+use super::Config;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ContractPreset {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Contract hours per week (Mon–Fri)
+    pub weekly_hours: f64,
+    pub min_work_duration: &'static str,
+    pub lunch_window: &'static str,
+    pub min_duration_lunch_break: i32,
+    pub max_duration_lunch_break: i32,
+    pub mandatory_lunch_after: Option<&'static str>,
+    pub max_daily_work: Option<&'static str>,
+}
+
+pub const PRESETS: &[ContractPreset] = &[
+    ContractPreset {
+        name: "it-40h",
+        description: "Italy, full time (CCNL 40h)",
+        weekly_hours: 40.0,
+        min_work_duration: "8h",
+        lunch_window: "12:30-14:00",
+        min_duration_lunch_break: 30,
+        max_duration_lunch_break: 90,
+        mandatory_lunch_after: Some("6h"),
+        max_daily_work: None,
+    },
+    ContractPreset {
+        name: "it-36h",
+        description: "Italy, 36h week (e.g. public sector)",
+        weekly_hours: 36.0,
+        min_work_duration: "7h12m",
+        lunch_window: "12:30-14:00",
+        min_duration_lunch_break: 30,
+        max_duration_lunch_break: 90,
+        mandatory_lunch_after: Some("6h"),
+        max_daily_work: None,
+    },
+    ContractPreset {
+        name: "de-38.5h",
+        description: "Germany, 38.5h week (ArbZG: break after 6h, 10h a day at most)",
+        weekly_hours: 38.5,
+        min_work_duration: "7h42m",
+        lunch_window: "12:00-13:30",
+        min_duration_lunch_break: 30,
+        max_duration_lunch_break: 60,
+        mandatory_lunch_after: Some("6h"),
+        max_daily_work: Some("10h"),
+    },
+    ContractPreset {
+        name: "part-time-50",
+        description: "Part time 50% (20h week, mornings, no lunch)",
+        weekly_hours: 20.0,
+        min_work_duration: "4h",
+        lunch_window: "12:30-14:00",
+        min_duration_lunch_break: 0,
+        max_duration_lunch_break: 60,
+        mandatory_lunch_after: None,
+        max_daily_work: None,
+    },
+];
+
+impl ContractPreset {
+    /// Preset called `name` (case-insensitive).
+    pub fn find(name: &str) -> Option<&'static ContractPreset> {
+        PRESETS
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// `cfg` with the settings of the preset.
+    pub fn apply(&self, cfg: Config) -> Config {
+        Config {
+            min_work_duration: self.min_work_duration.to_string(),
+            lunch_window: self.lunch_window.to_string(),
+            min_duration_lunch_break: self.min_duration_lunch_break,
+            max_duration_lunch_break: self.max_duration_lunch_break,
+            mandatory_lunch_after: self.mandatory_lunch_after.map(str::to_string),
+            max_daily_work: self.max_daily_work.map(str::to_string),
+            ..cfg
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::logic::Core;
+
+    #[test]
+    fn daily_duration_matches_weekly_hours() {
+        for p in PRESETS {
+            let daily = Core::parse_work_duration_to_minutes(p.min_work_duration);
+            assert_eq!(daily * 5, (p.weekly_hours * 60.0) as i64, "{}", p.name);
+            assert!(p.min_duration_lunch_break <= p.max_duration_lunch_break);
+        }
+        assert_eq!(ContractPreset::find("DE-38.5h").unwrap().weekly_hours, 38.5);
+        assert!(ContractPreset::find("us-40h").is_none());
+    }
+}
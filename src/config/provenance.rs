@@ -0,0 +1,222 @@
+//! `config --print`: where the effective value of every key comes from
+//! (default / file / env / CLI override), plus the keys of the file that are
+//! no longer read and the settings that contradict each other.
+
+use super::{CONFIG_DIR_ENV, Config};
+use crate::core::compliance::{mandatory_lunch_after, max_daily_work};
+use crate::core::serve::TOKEN_ENV;
+use crate::core::shift::invalid_bands;
+use crate::utils::time::parse_lunch_window;
+use schemars::schema_for;
+use serde_json::Value;
+use std::fmt;
+
+/// Origin of an effective value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File,
+    Env(&'static str),
+    Cli(&'static str),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File => write!(f, "file"),
+            Source::Env(var) => write!(f, "env {}", var),
+            Source::Cli(flag) => write!(f, "cli {}", flag),
+        }
+    }
+}
+
+/// One line of `config --print`.
+#[derive(Debug, Clone)]
+pub struct KeySource {
+    pub key: String,
+    /// JSON rendering of the effective value, `None` when unset
+    pub value: Option<String>,
+    pub source: Source,
+}
+
+/// Names of the keys read by [`Config`] (from its schema, so the keys that
+/// are omitted when unset are listed too).
+pub fn known_keys() -> Vec<String> {
+    serde_json::to_value(schema_for!(Config))
+        .ok()
+        .and_then(|s| s.get("properties")?.as_object().cloned())
+        .map(|props| props.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Effective value and origin of every key. `raw` is the file content as a
+/// generic value (`None` when there is no file); `ascii` tells whether plain
+/// ASCII output is active (`--no-emoji` or the `no_emoji` key).
+pub fn key_sources(cfg: &Config, raw: Option<&serde_yaml::Value>, ascii: bool) -> Vec<KeySource> {
+    let effective = serde_json::to_value(cfg).unwrap_or(Value::Null);
+    let in_file = |key: &str| raw.is_some_and(|r| r.get(key).is_some());
+
+    let mut out: Vec<KeySource> = known_keys()
+        .into_iter()
+        .map(|key| {
+            let mut source = if in_file(&key) {
+                Source::File
+            } else {
+                Source::Default
+            };
+
+            match key.as_str() {
+                "database" => {
+                    let expected = if cfg.test_mode {
+                        Config::test_database_file().to_string_lossy().to_string()
+                    } else {
+                        raw.and_then(|r| r.get("database")?.as_str().map(str::to_string))
+                            .unwrap_or_else(|| Config::default().database)
+                    };
+                    if cfg.database != expected {
+                        source = Source::Cli("--db");
+                    } else if cfg.test_mode {
+                        source = Source::Cli("--test");
+                    }
+                }
+                "no_emoji" if ascii && !cfg.no_emoji => source = Source::Cli("--no-emoji"),
+                _ => {}
+            }
+
+            let value = match (key.as_str(), effective.get(&key)) {
+                ("no_emoji", _) if ascii => Some("true".to_string()),
+                (_, Some(v)) => Some(v.to_string()),
+                (_, None) => None,
+            };
+
+            KeySource { key, value, source }
+        })
+        .collect();
+
+    if std::env::var(TOKEN_ENV).is_ok_and(|t| !t.is_empty()) {
+        out.push(KeySource {
+            key: "api.token".to_string(),
+            value: Some("\"***\"".to_string()),
+            source: Source::Env(TOKEN_ENV),
+        });
+    }
+
+    out
+}
+
+/// Config directory override, when `RTIMELOGGER_CONFIG_DIR` is set.
+pub fn config_dir_override() -> Option<String> {
+    std::env::var(CONFIG_DIR_ENV)
+        .ok()
+        .filter(|d| !d.trim().is_empty())
+}
+
+/// Keys of the file that are not read anymore (renamed, removed or misspelled).
+pub fn unused_keys(raw: &serde_yaml::Value) -> Vec<String> {
+    let known = known_keys();
+    raw.as_mapping()
+        .map(|m| {
+            m.keys()
+                .filter_map(|k| k.as_str())
+                .filter(|k| !known.iter().any(|n| n == k))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Settings that are accepted but silently change the calculations
+/// (mostly the lunch rules, used for the expected exit and the surplus).
+pub fn inconsistencies(cfg: &Config) -> Vec<String> {
+    let mut out = Vec::new();
+
+    match parse_lunch_window(&cfg.lunch_window) {
+        None => out.push(format!(
+            "lunch_window '{}' is not HH:MM-HH:MM: the lunch is never applied automatically.",
+            cfg.lunch_window
+        )),
+        Some((start, end)) if end <= start => out.push(format!(
+            "lunch_window '{}' ends before it starts: no pair can cross it.",
+            cfg.lunch_window
+        )),
+        Some((start, end)) => {
+            let window = (end - start).num_minutes();
+            if window < cfg.min_duration_lunch_break as i64 {
+                out.push(format!(
+                    "lunch_window '{}' ({} min) is shorter than min_duration_lunch_break ({} min).",
+                    cfg.lunch_window, window, cfg.min_duration_lunch_break
+                ));
+            }
+        }
+    }
+
+    if cfg.min_duration_lunch_break > cfg.max_duration_lunch_break {
+        out.push(format!(
+            "min_duration_lunch_break ({}) is above max_duration_lunch_break ({}).",
+            cfg.min_duration_lunch_break, cfg.max_duration_lunch_break
+        ));
+    }
+
+    if let Some(limit) = &cfg.mandatory_lunch_after
+        && !limit.trim().is_empty()
+        && mandatory_lunch_after(cfg).is_none()
+    {
+        out.push(format!(
+            "mandatory_lunch_after '{}' is not a duration: the break rule is disabled.",
+            limit
+        ));
+    }
+
+    match &cfg.max_daily_work {
+        Some(limit) if !limit.trim().is_empty() && max_daily_work(cfg).is_none() => {
+            out.push(format!(
+                "max_daily_work '{}' is not a duration: the daily cap is disabled.",
+                limit
+            ))
+        }
+        None if cfg.enforce_max_daily_work => out.push(
+            "enforce_max_daily_work is set but max_daily_work is not: nothing is enforced."
+                .to_string(),
+        ),
+        _ => {}
+    }
+
+    out.extend(invalid_bands(cfg));
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_keys_defaults_and_unused_keys() {
+        let raw: serde_yaml::Value =
+            serde_yaml::from_str("min_work_duration: 7h36m\nlunch_break: 45\n").unwrap();
+        let cfg = Config {
+            min_work_duration: "7h36m".into(),
+            ..Config::default()
+        };
+
+        let sources = key_sources(&cfg, Some(&raw), false);
+        let of = |key: &str| sources.iter().find(|s| s.key == key).unwrap();
+        assert_eq!(of("min_work_duration").source, Source::File);
+        assert_eq!(of("min_work_duration").value.as_deref(), Some("\"7h36m\""));
+        assert_eq!(of("lunch_window").source, Source::Default);
+        assert_eq!(of("mandatory_lunch_after").value, None);
+
+        assert_eq!(unused_keys(&raw), vec!["lunch_break".to_string()]);
+    }
+
+    #[test]
+    fn lunch_rules_contradicting_each_other() {
+        let cfg = Config {
+            lunch_window: "13:00-13:15".into(),
+            ..Config::default()
+        };
+        assert_eq!(inconsistencies(&cfg).len(), 1);
+        assert!(inconsistencies(&Config::default()).is_empty());
+    }
+}
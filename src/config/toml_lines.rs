@@ -0,0 +1,299 @@
+//! Line-based editor for the top-level keys of `rtimelogger.toml`, the TOML
+//! counterpart of `yaml_edit`: comments and layout of the file are kept.
+
+use super::yaml_edit::inline_comment;
+use std::collections::HashMap;
+use toml::{Table, Value};
+
+/// Set `key` to `value`.
+///
+/// Scalars are replaced in place, multi-line values included (keeping the
+/// trailing comment), or inserted before the first `[table]`. Tables and
+/// arrays of tables replace the old sections of `key` where the first one
+/// was (appended at the end when new), keeping their comments.
+pub fn set_top_level(content: &str, key: &str, value: &Value) -> String {
+    let entry = render_entry(key, value);
+
+    // Old sections of `key` (`[key]`, `[key.sub]`, `[[key]]`) go away in any case
+    let mut lines: Vec<&str> = Vec::new();
+    let mut old_sections: Vec<&str> = Vec::new();
+    let mut section_at = None;
+    let mut skipping = false;
+    for line in content.lines() {
+        if let Some(name) = header_name(line) {
+            skipping = name == key || name.starts_with(&format!("{}.", key));
+            if skipping && section_at.is_none() {
+                section_at = Some(lines.len());
+            }
+        }
+        if skipping {
+            old_sections.push(line);
+        } else {
+            lines.push(line);
+        }
+    }
+
+    let first_header = lines
+        .iter()
+        .position(|l| header_name(l).is_some())
+        .unwrap_or(lines.len());
+    let existing = lines[..first_header]
+        .iter()
+        .position(|l| is_key_line(l, key))
+        .map(|i| (i, value_span(&lines, i)));
+
+    let mut out: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+
+    if entry.starts_with('[') {
+        let mut block = annotate(&entry, &old_sections);
+        let mut at = section_at;
+        if let Some((i, span)) = existing {
+            out.drain(i..i + span);
+            // The key was above every table, so above the old sections too
+            at = at.map(|a| a - span);
+        }
+        match at {
+            Some(a) => {
+                if a < out.len() {
+                    block.push(String::new());
+                }
+                if a > 0 && !out[a - 1].trim().is_empty() {
+                    block.insert(0, String::new());
+                }
+                out.splice(a..a, block);
+            }
+            None => {
+                while out.last().is_some_and(|l| l.trim().is_empty()) {
+                    out.pop();
+                }
+                if !out.is_empty() {
+                    out.push(String::new());
+                }
+                out.extend(block);
+            }
+        }
+    } else {
+        let mut line = entry.trim_end().to_string();
+        match existing {
+            Some((i, span)) => {
+                if let Some(comment) = inline_comment(lines[i + span - 1]) {
+                    line = format!("{}  {}", line, comment);
+                }
+                out.splice(i..i + span, [line]);
+            }
+            None => {
+                // Right after the last top-level line, before blank lines and tables
+                let at = lines[..first_header]
+                    .iter()
+                    .rposition(|l| !l.trim().is_empty())
+                    .map_or(0, |i| i + 1);
+                out.insert(at, line);
+            }
+        }
+    }
+
+    let mut s = out.join("\n");
+    s.push('\n');
+    s
+}
+
+/// Lines taken by the `key = value` entry starting at `lines[i]`: arrays,
+/// inline tables and multi-line strings may go on over several lines.
+fn value_span(lines: &[&str], i: usize) -> usize {
+    let mut depth = 0i32;
+    let mut in_str: Option<&str> = None;
+    for (n, &line) in lines[i..].iter().enumerate() {
+        let mut rest = if n == 0 {
+            line.split_once('=').map_or("", |(_, v)| v)
+        } else {
+            line
+        };
+        while let Some(c) = rest.chars().next() {
+            match in_str {
+                Some(delim) if delim.starts_with('"') && c == '\\' => {
+                    rest = &rest[1..];
+                    if let Some(escaped) = rest.chars().next() {
+                        rest = &rest[escaped.len_utf8()..];
+                    }
+                    continue;
+                }
+                Some(delim) if rest.starts_with(delim) => {
+                    rest = &rest[delim.len()..];
+                    in_str = None;
+                    continue;
+                }
+                Some(_) => {}
+                None if rest.starts_with("\"\"\"") || rest.starts_with("'''") => {
+                    in_str = Some(&rest[..3]);
+                    rest = &rest[3..];
+                    continue;
+                }
+                None => match c {
+                    '"' | '\'' => in_str = Some(&rest[..1]),
+                    '[' | '{' => depth += 1,
+                    ']' | '}' => depth -= 1,
+                    '#' => break,
+                    _ => {}
+                },
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        // Single-line strings end with their line
+        if matches!(in_str, Some("\"") | Some("'")) {
+            in_str = None;
+        }
+        if depth <= 0 && in_str.is_none() {
+            return n + 1;
+        }
+    }
+    lines.len() - i
+}
+
+/// The rendered `entry` with the annotations of the sections it replaces:
+/// comment lines go right after the first header, inline comments follow the
+/// same key of the same section.
+fn annotate(entry: &str, old: &[&str]) -> Vec<String> {
+    let comments: HashMap<_, &str> = keyed_lines(old)
+        .into_iter()
+        .filter_map(|(id, i)| Some((id, inline_comment(old[i])?)))
+        .collect();
+
+    let lines: Vec<&str> = entry.lines().collect();
+    let mut out: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+    for (id, i) in keyed_lines(&lines) {
+        if let Some(comment) = comments.get(&id) {
+            out[i] = format!("{}  {}", lines[i], comment);
+        }
+    }
+
+    let notes = old
+        .iter()
+        .filter(|l| l.trim_start().starts_with('#'))
+        .map(|l| l.to_string());
+    let at = out
+        .iter()
+        .position(|l| header_name(l).is_some())
+        .map_or(0, |i| i + 1);
+    out.splice(at..at, notes);
+    out
+}
+
+/// `key = value` lines identified by (section, occurrence of the section,
+/// key), with their index: `[[array]]` sections repeat.
+fn keyed_lines(lines: &[&str]) -> Vec<((String, usize, String), usize)> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut section = (String::new(), 0);
+    let mut out = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(name) = header_name(line) {
+            let n = seen.entry(name.to_string()).or_default();
+            *n += 1;
+            section = (name.to_string(), *n);
+        } else if let Some((k, _)) = line.split_once('=')
+            && !k.trim().is_empty()
+            && !k.trim_start().starts_with('#')
+        {
+            out.push(((section.0.clone(), section.1, k.trim().to_string()), i));
+        }
+    }
+    out
+}
+
+/// `key = value` (or a `[key]` section) as the toml serializer writes it.
+fn render_entry(key: &str, value: &Value) -> String {
+    let mut table = Table::new();
+    table.insert(key.to_string(), value.clone());
+    toml::to_string(&table).unwrap_or_else(|_| format!("{} = \"\"\n", key))
+}
+
+fn is_key_line(line: &str, key: &str) -> bool {
+    line.trim_start()
+        .strip_prefix(key)
+        .is_some_and(|rest| rest.trim_start().starts_with('='))
+}
+
+/// Name of a `[table]` / `[[array]]` header line.
+fn header_name(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let inner = line.strip_prefix('[')?;
+    let inner = inner.strip_prefix('[').unwrap_or(inner);
+    let end = inner.find(']')?;
+    Some(inner[..end].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONF: &str = "# my settings\n\
+                        database = \"/tmp/a.sqlite\"  # work DB\n\
+                        show_weekday = \"None\"\n\
+                        \n\
+                        [databases]\n\
+                        work = \"/tmp/w.sqlite\"\n";
+
+    #[test]
+    fn scalars_stay_above_tables() {
+        let out = set_top_level(CONF, "database", &Value::String("/tmp/b.sqlite".into()));
+        assert!(out.starts_with("# my settings\ndatabase = \"/tmp/b.sqlite\"  # work DB\n"));
+
+        let out = set_top_level(CONF, "record_os_user", &Value::Boolean(false));
+        assert!(out.contains("show_weekday = \"None\"\nrecord_os_user = false\n\n[databases]\n"));
+    }
+
+    #[test]
+    fn tables_are_replaced() {
+        let mut dbs = Table::new();
+        dbs.insert("home".into(), Value::String("/tmp/h.sqlite".into()));
+        let out = set_top_level(CONF, "databases", &Value::Table(dbs));
+        assert!(!out.contains("work ="));
+        assert!(out.ends_with("\n\n[databases]\nhome = \"/tmp/h.sqlite\"\n"));
+    }
+
+    #[test]
+    fn multi_line_values_are_replaced_whole() {
+        let conf = "database = \"/tmp/a.sqlite\"\n\
+                    tags = [\n  \"a\",  # first\n  \"b]\",\n]  # list\n\
+                    motto = \"\"\"\nkeep [\ngoing\n\"\"\"\n\
+                    show_weekday = \"None\"\n";
+
+        let tags = Value::Array(vec![Value::String("c".into())]);
+        let out = set_top_level(conf, "tags", &tags);
+        assert!(out.contains("database = \"/tmp/a.sqlite\"\ntags = [\"c\"]  # list\nmotto = "));
+
+        let out = set_top_level(&out, "motto", &Value::String("short".into()));
+        assert!(
+            out.ends_with("tags = [\"c\"]  # list\nmotto = \"short\"\nshow_weekday = \"None\"\n")
+        );
+        assert!(out.parse::<Table>().is_ok());
+    }
+
+    #[test]
+    fn replaced_tables_keep_their_place_and_comments() {
+        let conf = "database = \"/tmp/a.sqlite\"\n\
+                    \n\
+                    [databases]\n\
+                    # laptop only\n\
+                    work = \"/tmp/w.sqlite\"  # VPN\n\
+                    \n\
+                    [api]\n\
+                    port = 8080\n";
+
+        let mut dbs = Table::new();
+        dbs.insert("home".into(), Value::String("/tmp/h.sqlite".into()));
+        dbs.insert("work".into(), Value::String("/tmp/w2.sqlite".into()));
+        let out = set_top_level(conf, "databases", &Value::Table(dbs));
+        assert_eq!(
+            out,
+            "database = \"/tmp/a.sqlite\"\n\
+             \n\
+             [databases]\n\
+             # laptop only\n\
+             home = \"/tmp/h.sqlite\"\n\
+             work = \"/tmp/w2.sqlite\"  # VPN\n\
+             \n\
+             [api]\n\
+             port = 8080\n"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Safe writes of the config file.
+//!
+//! The new content goes to a temporary file in the same directory, flushed to
+//! disk and then renamed over the config: a crash leaves either the old or the
+//! new file, never a truncated one. The previous version is kept next to it as
+//! `<file>.bak` (`rtimelogger.conf.bak`, `rtimelogger.toml.bak`) and
+//! `config --rollback` puts it back.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Backup of the previous version of `path`.
+pub fn backup_file(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+fn temp_file(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".tmp{}", std::process::id()));
+    path.with_file_name(name)
+}
+
+/// Write `path` atomically, copying the current file (if any) to its backup.
+pub fn write_config(path: &Path, text: &str) -> io::Result<()> {
+    if path.exists() {
+        fs::copy(path, backup_file(path))?;
+    }
+    replace(path, text)
+}
+
+/// Temp file + rename, without touching the backup.
+fn replace(path: &Path, text: &str) -> io::Result<()> {
+    let tmp = temp_file(path);
+    let result = (|| {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(text.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// `config --rollback`: restore the backup; the current file becomes the new
+/// backup, so a second rollback undoes the first one.
+pub fn rollback(path: &Path) -> io::Result<PathBuf> {
+    let backup = backup_file(path);
+    let previous = fs::read_to_string(&backup)?;
+    let current = fs::read_to_string(path).ok();
+
+    replace(path, &previous)?;
+    if let Some(current) = current {
+        replace(&backup, &current)?;
+    }
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_previous_version_and_rolls_back() {
+        let dir = std::env::temp_dir().join(format!("rtl-conf-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rtimelogger.conf");
+
+        write_config(&path, "a: 1\n").unwrap();
+        assert!(!backup_file(&path).exists());
+        write_config(&path, "a: 2\n").unwrap();
+        assert_eq!(fs::read_to_string(backup_file(&path)).unwrap(), "a: 1\n");
+        assert_eq!(
+            backup_file(&path).file_name().unwrap(),
+            "rtimelogger.conf.bak"
+        );
+
+        rollback(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a: 1\n");
+        assert_eq!(fs::read_to_string(backup_file(&path)).unwrap(), "a: 2\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
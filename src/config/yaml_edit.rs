@@ -0,0 +1,123 @@
+//! Minimal line-based editor for the top-level keys of the YAML config.
+//! Unlike a serde round-trip it keeps comments, blank lines and key order,
+//! so automatic additions and `config --set` never drop user annotations.
+
+use serde_yaml::{Mapping, Value};
+
+/// Set `key` to `value`.
+///
+/// An existing entry is replaced in place (keeping its trailing comment when
+/// old and new value fit on one line); a missing one is appended at the end.
+pub fn set_top_level(content: &str, key: &str, value: &Value) -> String {
+    let entry = render_entry(key, value);
+    let lines: Vec<&str> = content.lines().collect();
+
+    let Some(start) = lines.iter().position(|l| is_key_line(l, key)) else {
+        let mut out = content.to_string();
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&entry);
+        return out;
+    };
+
+    // The entry spans the indented lines below the key; comment-only lines
+    // closing the block belong to whatever follows and are kept.
+    let mut end = start + 1;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        if !is_continuation(line) {
+            break;
+        }
+        if !line.trim_start().starts_with('#') {
+            end = i + 1;
+        }
+    }
+
+    let mut replacement = entry;
+    if end == start + 1
+        && replacement.lines().count() == 1
+        && let Some(comment) = inline_comment(lines[start])
+    {
+        replacement = format!("{}  {}\n", replacement.trim_end(), comment);
+    }
+
+    let mut out = String::new();
+    for line in &lines[..start] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(&replacement);
+    for line in &lines[end..] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// `key: value` as serde_yaml would write it (quoting, nested blocks).
+fn render_entry(key: &str, value: &Value) -> String {
+    let mut map = Mapping::new();
+    map.insert(Value::String(key.to_string()), value.clone());
+    serde_yaml::to_string(&map).unwrap_or_else(|_| format!("{}: ~\n", key))
+}
+
+fn is_key_line(line: &str, key: &str) -> bool {
+    line.strip_prefix(key)
+        .is_some_and(|rest| rest.starts_with(':'))
+}
+
+/// Lines belonging to the value of the previous top-level key
+/// (nested mappings are indented, top-level sequences start with `-`).
+fn is_continuation(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t') || line.starts_with("- ") || line == "-"
+}
+
+/// Trailing `# comment` of a line, ignoring `#` inside quoted values.
+pub(crate) fn inline_comment(line: &str) -> Option<&str> {
+    let (mut single, mut double) = (false, false);
+    let mut prev = ' ';
+    for (i, c) in line.char_indices() {
+        match c {
+            '\'' if !double => single = !single,
+            '"' if !single => double = !double,
+            '#' if !single && !double && prev.is_whitespace() => return Some(&line[i..]),
+            _ => {}
+        }
+        prev = c;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONF: &str = "# my settings\n\
+                        database: /tmp/a.sqlite   # work DB\n\
+                        show_weekday: None\n  \
+                        # show-weekday parameter options:\n\
+                        #   None   → do not show weekday\n\
+                        databases:\n  \
+                        work: /tmp/w.sqlite\n\
+                        decimal_step: 0.01\n";
+
+    #[test]
+    fn replaces_in_place_keeping_comments() {
+        let out = set_top_level(CONF, "database", &Value::String("/tmp/b.sqlite".into()));
+        assert!(out.starts_with("# my settings\ndatabase: /tmp/b.sqlite  # work DB\n"));
+
+        let out = set_top_level(CONF, "show_weekday", &Value::String("Short".into()));
+        assert!(out.contains("show_weekday: Short\n  # show-weekday parameter options:\n"));
+    }
+
+    #[test]
+    fn replaces_blocks_and_appends_missing_keys() {
+        let out = set_top_level(CONF, "databases", &Value::Mapping(Mapping::new()));
+        assert!(out.contains("databases: {}\ndecimal_step: 0.01\n"));
+        assert!(!out.contains("work:"));
+
+        let out = set_top_level(CONF, "record_os_user", &Value::Bool(false));
+        assert!(out.starts_with(CONF));
+        assert!(out.ends_with("record_os_user: false\n"));
+    }
+}
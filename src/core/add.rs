@@ -1,6 +1,10 @@
 use crate::config::Config;
+use crate::core::calculator::overlap::{OverlapMode, TRACK_KEY, track_of};
 use crate::core::logic::Core;
+use crate::core::report::ReportLogic;
+use crate::db::log::ttlog;
 use crate::db::pool::DbPool;
+use crate::db::queries::locks::ensure_unlocked;
 use crate::db::queries::{
     insert_event, load_events_by_date, load_pair_by_index, recalc_pairs_for_date,
 };
@@ -8,14 +12,29 @@ use crate::errors::{AppError, AppResult};
 use crate::models::event::{Event, EventExtras};
 use crate::models::event_type::EventType;
 use crate::models::location::Location;
-use crate::ui::messages::success;
+use crate::ui::messages::{data, success};
+use crate::utils::colors::{BOLD, GREEN, RED, RESET, STRIKE};
 use crate::utils::date::{is_national_holiday, is_weekend};
-use chrono::{NaiveDate, NaiveTime, Timelike};
+use crate::utils::host::os_user;
+use crate::utils::meta::{merge_meta, meta_value};
+use crate::utils::period::Period;
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
 use rusqlite::params;
 
 /// High-level business logic for the `add` command.
 pub struct AddLogic;
 
+/// One-line summary of a day printed after `add`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayRecap {
+    /// Net minutes of the closed pairs so far
+    pub worked: i64,
+    /// Planned exit (TGT), only while the day is still open
+    pub target_exit: Option<NaiveTime>,
+    /// ΔWORK of the closed working days of the month, up to the day
+    pub month_surplus: i64,
+}
+
 fn upsert_event(conn: &rusqlite::Connection, ev: &Event) -> AppResult<()> {
     if ev.id == 0 {
         insert_event(conn, ev)?;
@@ -35,12 +54,13 @@ fn build_event_cli(
     Event::new(0, date, time, kind, location, event_extras)
 }
 
-fn extras_cli(lunch: Option<i32>, work_gap: bool) -> EventExtras {
+fn extras_cli(cfg: &Config, lunch: Option<i32>, work_gap: bool) -> EventExtras {
     EventExtras {
         lunch,
         work_gap,
         source: Some("cli".to_string()),
         meta: None,
+        os_user: if cfg.record_os_user { os_user() } else { None },
         ..Default::default()
     }
 }
@@ -62,6 +82,12 @@ fn set_notes(slot: &mut Option<Event>, notes: &Option<String>) {
     }
 }
 
+fn set_meta(slot: &mut Option<Event>, meta: &str) {
+    if let Some(e) = slot.as_mut() {
+        e.meta = Some(merge_meta(e.meta.as_deref(), meta));
+    }
+}
+
 fn last_pair_index(conn: &rusqlite::Connection, date: &NaiveDate) -> AppResult<usize> {
     let max_pair: Option<i64> = conn.query_row(
         "SELECT MAX(pair) FROM events WHERE date = ?1 AND pair > 0",
@@ -90,6 +116,92 @@ fn upsert_event_time(
     e.time = time;
 }
 
+/// One field of a pair changed by `add --edit`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+impl FieldChange {
+    /// `in  08:00 → 08:15`, old value struck through, new one highlighted.
+    fn colored(&self) -> String {
+        format!(
+            "   {:<9} {RED}{STRIKE}{}{RESET} → {GREEN}{BOLD}{}{RESET}",
+            self.field,
+            shown(&self.before),
+            shown(&self.after)
+        )
+    }
+
+    /// Same change for the audit log: `in 08:00→08:15`.
+    fn plain(&self) -> String {
+        format!(
+            "{} {}→{}",
+            self.field,
+            shown(&self.before),
+            shown(&self.after)
+        )
+    }
+}
+
+fn shown(value: &str) -> &str {
+    if value.is_empty() { "-" } else { value }
+}
+
+/// Fields of a pair compared before / after an edit.
+fn pair_fields(ev_in: &Option<Event>, ev_out: &Option<Event>) -> Vec<(&'static str, String)> {
+    let time = |e: &Option<Event>| e.as_ref().map(Event::time_str).unwrap_or_default();
+    let first = ev_in.as_ref().or(ev_out.as_ref());
+    let out = ev_out.as_ref();
+
+    vec![
+        ("in", time(ev_in)),
+        ("out", time(ev_out)),
+        (
+            "position",
+            first
+                .map(|e| e.location.code().to_string())
+                .unwrap_or_default(),
+        ),
+        (
+            "lunch",
+            out.and_then(|e| e.lunch)
+                .map(|m| format!("{} min", m))
+                .unwrap_or_default(),
+        ),
+        (
+            "work_gap",
+            out.map(|e| e.work_gap.to_string()).unwrap_or_default(),
+        ),
+        (
+            "notes",
+            first.and_then(|e| e.notes.clone()).unwrap_or_default(),
+        ),
+        (
+            "meta",
+            first.and_then(|e| e.meta.clone()).unwrap_or_default(),
+        ),
+    ]
+}
+
+fn field_changes(
+    before: &[(&'static str, String)],
+    after: &[(&'static str, String)],
+) -> Vec<FieldChange> {
+    before
+        .iter()
+        .zip(after)
+        .filter(|((_, b), (_, a))| a != b)
+        .map(|((field, b), (_, a))| FieldChange {
+            field: *field,
+            before: b.clone(),
+            after: a.clone(),
+        })
+        .collect()
+}
+
 impl AddLogic {
     #[allow(clippy::too_many_arguments)]
     pub fn apply(
@@ -104,23 +216,16 @@ impl AddLogic {
         edit_mode: bool,
         edit_pair: Option<usize>,
         to: Option<NaiveDate>,
-        pos: Option<String>,
+        pos: Option<Location>,
         notes: Option<String>,
+        meta: Option<String>,
     ) -> AppResult<()> {
         let notes = normalize_notes(notes);
 
         // ------------------------------------------------
-        // Resolve final position (only if --pos is provided)
+        // Final position (`pos` only if --pos is provided)
         // ------------------------------------------------
-        let pos_final = match &pos {
-            Some(code) => Location::from_code(code).ok_or_else(|| {
-                AppError::InvalidPosition(format!(
-                    "Invalid location code '{}'. Use a valid code such as 'O', 'R', 'H', 'N', 'C', 'M', 'S'.\n",
-                    code
-                ))
-            })?,
-            None => position,
-        };
+        let pos_final = pos.unwrap_or(position);
 
         // ------------------------------------------------
         // Sanity: range args only allowed for SickLeave
@@ -141,6 +246,14 @@ impl AddLogic {
             (_null, None) => None,
         };
 
+        // ------------------------------------------------
+        // Locked days cannot be modified
+        // ------------------------------------------------
+        let (first, last) = range.unwrap_or((date, date));
+        for day in Period::between(first, last).iter_dates() {
+            ensure_unlocked(&pool.conn, &day)?;
+        }
+
         // ------------------------------------------------
         // 1️⃣ EDIT MODE
         // ------------------------------------------------
@@ -157,6 +270,7 @@ impl AddLogic {
             };
 
             let (mut ev_in, mut ev_out) = load_pair_by_index(&pool.conn, &date, pair_num)?;
+            let before = pair_fields(&ev_in, &ev_out);
 
             // POSITION (apply only if --pos explicitly provided)
             if pos.is_some() {
@@ -176,7 +290,7 @@ impl AddLogic {
                     start_time,
                     EventType::In,
                     pos_final,
-                    extras_cli(lunch, false),
+                    extras_cli(cfg, lunch, false),
                 );
             }
 
@@ -188,7 +302,7 @@ impl AddLogic {
                     end_time,
                     EventType::Out,
                     pos_final,
-                    extras_cli(Some(0), false),
+                    extras_cli(cfg, Some(0), false),
                 );
             }
 
@@ -205,6 +319,12 @@ impl AddLogic {
                 set_notes(&mut ev_out, &notes);
             }
 
+            // META (merged into every event belonging to the pair)
+            if let Some(ref m) = meta {
+                set_meta(&mut ev_in, m);
+                set_meta(&mut ev_out, m);
+            }
+
             // WORK GAP (only if explicitly requested; requires OUT)
             if let Some(wg) = work_gap {
                 if let Some(ref mut e) = ev_out {
@@ -216,6 +336,17 @@ impl AddLogic {
                 }
             }
 
+            // OUT after IN, whichever side was edited
+            if let (Some(i), Some(o)) = (&ev_in, &ev_out)
+                && o.time <= i.time
+            {
+                return Err(AppError::PairOutBeforeIn {
+                    pair: pair_num,
+                    in_time: i.time_str(),
+                    out_time: o.time_str(),
+                });
+            }
+
             // Save
             if let Some(ref e) = ev_in {
                 upsert_event(&pool.conn, e)?;
@@ -224,15 +355,27 @@ impl AddLogic {
                 upsert_event(&pool.conn, e)?;
             }
 
-            recalc_pairs_for_date(&pool.conn, &date)?;
+            recalc_pairs_for_date(&pool.conn, &date, cfg.overlap_mode)?;
 
-            let (icon, msg) = match work_gap {
-                Some(true) => ("🔗", "Work gap enabled"),
-                Some(false) => ("✂️", "Work gap removed"),
-                None => ("✏️", "Pair updated"),
-            };
+            let changes = field_changes(&before, &pair_fields(&ev_in, &ev_out));
+            if changes.is_empty() {
+                success(format!("Pair {} of {} unchanged.\n", pair_num, date));
+                return Ok(());
+            }
 
-            success(format!("{} {} for pair {}.\n", icon, msg, pair_num));
+            success(format!("✏️ Pair {} of {} updated:", pair_num, date));
+            for c in &changes {
+                data(c.colored());
+            }
+            data("");
+
+            let summary: Vec<String> = changes.iter().map(FieldChange::plain).collect();
+            ttlog(
+                &pool.conn,
+                "edit",
+                &format!("{} pair {}", date, pair_num),
+                &summary.join("; "),
+            )?;
             return Ok(());
         }
 
@@ -276,7 +419,8 @@ impl AddLogic {
             let marker_time = NaiveTime::from_hms_opt(0, 0, 0)
                 .ok_or_else(|| AppError::Other("Invalid Sick Leave time sentinel.".into()))?;
 
-            let tx = pool.conn.transaction()?;
+            // Savepoint: nested in the transaction of the caller, if any
+            let tx = pool.conn.savepoint()?;
 
             let mut inserted = 0usize;
             let mut skipped_weekend = 0usize;
@@ -324,12 +468,13 @@ impl AddLogic {
                     marker_time, // 00:00
                     EventType::In,
                     Location::SickLeave,
-                    extras_cli(Some(0), false),
+                    extras_cli(cfg, Some(0), false),
                 );
                 ev.notes = notes.clone();
+                ev.meta = meta.clone();
 
                 insert_event(&tx, &ev)?;
-                recalc_pairs_for_date(&tx, &day)?;
+                recalc_pairs_for_date(&tx, &day, cfg.overlap_mode)?;
                 inserted += 1;
 
                 day = day
@@ -395,12 +540,13 @@ impl AddLogic {
                 holiday_time,
                 EventType::In,
                 pos_final,
-                extras_cli(lunch, false),
+                extras_cli(cfg, lunch, false),
             );
             ev_holiday.notes = notes.clone();
+            ev_holiday.meta = meta.clone();
 
             insert_event(&pool.conn, &ev_holiday)?;
-            recalc_pairs_for_date(&pool.conn, &date)?;
+            recalc_pairs_for_date(&pool.conn, &date, cfg.overlap_mode)?;
 
             success(match pos_final {
                 Location::Holiday => format!("Added HOLIDAY on {}.\n", date_str),
@@ -438,7 +584,7 @@ impl AddLogic {
             )?;
 
             success(format!(
-                "Lunch updated to {} minutes for {}.\n",
+                "Lunch updated to {} minutes for {}.",
                 lunch_val, date_str
             ));
             return Ok(());
@@ -447,7 +593,7 @@ impl AddLogic {
         // CASE B: nothing to do
         if start.is_none() && end.is_none() {
             return Err(AppError::InvalidArgs(
-                "Nothing to do: specify at least --start, --end, --lunch or use --edit --notes."
+                "Nothing to do: specify at least --start, --end, --lunch or use --edit --notes/--meta."
                     .into(),
             ));
         }
@@ -467,12 +613,13 @@ impl AddLogic {
                 start_time,
                 EventType::In,
                 pos_final,
-                extras_cli(lunch, false),
+                extras_cli(cfg, lunch, false),
             );
             ev_in.notes = notes.clone();
+            ev_in.meta = meta.clone();
 
             insert_event(&pool.conn, &ev_in)?;
-            recalc_pairs_for_date(&pool.conn, &date)?;
+            recalc_pairs_for_date(&pool.conn, &date, cfg.overlap_mode)?;
 
             let events_after = load_events_by_date(pool, &date)?;
             let summary = Core::build_daily_summary(&events_after, cfg);
@@ -483,7 +630,7 @@ impl AddLogic {
             let tgt_str = crate::utils::time::format_minutes(tgt_mins);
 
             success(format!(
-                "Added IN at {} on {}. TGT => {}\n",
+                "Added IN at {} on {}. TGT => {}",
                 start_time, date_str, tgt_str
             ));
             return Ok(());
@@ -499,9 +646,16 @@ impl AddLogic {
                 ));
             }
 
+            // With overlapping activities the OUT closes the IN of its own
+            // activity (`project` metadata)
+            let track = cfg
+                .overlap_mode
+                .is_on()
+                .then(|| meta_value(meta.as_deref(), TRACK_KEY).trim().to_string());
             let last_in = events_today
                 .iter()
                 .rev()
+                .filter(|ev| track.as_ref().is_none_or(|t| track_of(ev) == *t))
                 .find(|ev| ev.kind == EventType::In)
                 .cloned()
                 .ok_or_else(|| {
@@ -509,9 +663,11 @@ impl AddLogic {
                 })?;
 
             if end_time <= last_in.time {
-                return Err(AppError::InvalidArgs(
-                    "OUT must be later than the previous IN.".into(),
-                ));
+                return Err(AppError::InvalidArgs(format!(
+                    "OUT {} must be later than the previous IN ({}).",
+                    end_time.format("%H:%M"),
+                    last_in.time_str()
+                )));
             }
 
             // If --pos provided, use it; otherwise inherit last IN location
@@ -526,19 +682,20 @@ impl AddLogic {
                 end_time,
                 EventType::Out,
                 out_position,
-                extras_cli(lunch, false),
+                extras_cli(cfg, lunch, false),
             );
 
             if let Some(wg_explicit) = work_gap {
                 ev_out.work_gap = wg_explicit;
             }
             ev_out.notes = notes.clone();
+            ev_out.meta = meta.clone();
 
             insert_event(&pool.conn, &ev_out)?;
-            recalc_pairs_for_date(&pool.conn, &date)?;
+            recalc_pairs_for_date(&pool.conn, &date, cfg.overlap_mode)?;
 
             success(format!(
-                "Added OUT on {} ({} → {}).\n",
+                "Added OUT on {} ({} → {}).",
                 date_str, last_in.time, end_time
             ));
             return Ok(());
@@ -561,29 +718,31 @@ impl AddLogic {
                 start_time,
                 EventType::In,
                 pos_final,
-                extras_cli(lunch, false),
+                extras_cli(cfg, lunch, false),
             );
             ev_in.notes = notes.clone();
+            ev_in.meta = meta.clone();
 
             let mut ev_out = build_event_cli(
                 date,
                 end_time,
                 EventType::Out,
                 pos_final,
-                extras_cli(lunch, false),
+                extras_cli(cfg, lunch, false),
             );
 
             if let Some(wg_explicit) = work_gap {
                 ev_out.work_gap = wg_explicit;
             }
             ev_out.notes = notes.clone();
+            ev_out.meta = meta.clone();
 
             insert_event(&pool.conn, &ev_in)?;
             insert_event(&pool.conn, &ev_out)?;
-            recalc_pairs_for_date(&pool.conn, &date)?;
+            recalc_pairs_for_date(&pool.conn, &date, cfg.overlap_mode)?;
 
             success(format!(
-                "Added IN/OUT pair on {}: {} → {}.\n",
+                "Added IN/OUT pair on {}: {} → {}.",
                 date_str, start_time, end_time
             ));
             return Ok(());
@@ -593,4 +752,289 @@ impl AddLogic {
             "Unhandled combination of parameters.".into(),
         ))
     }
+
+    /// `add <DATE> --edit --pair N --move-to <TARGET>`: move a pair dated by
+    /// mistake to another day. Pair numbers of both days are recomputed in the
+    /// same transaction. Returns the pair number on both days (before, after).
+    pub fn move_pair(
+        pool: &mut DbPool,
+        date: NaiveDate,
+        pair: Option<usize>,
+        target: NaiveDate,
+        mode: OverlapMode,
+    ) -> AppResult<(usize, usize)> {
+        if target == date {
+            return Err(AppError::InvalidArgs(format!(
+                "--move-to {} is the day of the pair already.",
+                target
+            )));
+        }
+        ensure_unlocked(&pool.conn, &date)?;
+        ensure_unlocked(&pool.conn, &target)?;
+
+        let pair_num = match pair {
+            Some(p) => p,
+            None => last_pair_index(&pool.conn, &date)?,
+        };
+        let (ev_in, ev_out) = load_pair_by_index(&pool.conn, &date, pair_num)?;
+        let moved: Vec<Event> = ev_in.into_iter().chain(ev_out).collect();
+
+        if moved.iter().any(|e| e.location.is_marker()) {
+            return Err(AppError::InvalidArgs(format!(
+                "Pair {} of {} is a day marker: delete it and add it on {} instead.",
+                pair_num, date, target
+            )));
+        }
+
+        // The target day must be a working day without overlapping pairs
+        let target_events = load_events_by_date(pool, &target)?;
+        if target_events.iter().any(|e| e.location.is_marker()) {
+            return Err(AppError::InvalidArgs(format!(
+                "{} is marked as a non-working day: the pair cannot be moved there.",
+                target
+            )));
+        }
+        let (from, to) = match (moved.first(), moved.last()) {
+            (Some(a), Some(b)) => (a.time, b.time),
+            _ => return Err(AppError::InvalidPair(pair_num)),
+        };
+        let (t_in, t_out) = day_intervals(&target_events);
+        if let Some((s, e)) = t_in
+            .iter()
+            .zip(&t_out)
+            .map(|(s, e)| (*s, e.unwrap_or(*s)))
+            .find(|(s, e)| from <= *e && to >= *s)
+        {
+            return Err(AppError::InvalidArgs(format!(
+                "Pair {} of {} ({}-{}) overlaps {}-{} on {}.",
+                pair_num,
+                date,
+                from.format("%H:%M"),
+                to.format("%H:%M"),
+                s.format("%H:%M"),
+                e.format("%H:%M"),
+                target
+            )));
+        }
+
+        let tx = pool.conn.transaction()?;
+        for ev in &moved {
+            let mut ev = ev.clone();
+            ev.date = target;
+            crate::db::queries::update_event(&tx, &ev)?;
+        }
+        recalc_pairs_for_date(&tx, &date, mode)?;
+        recalc_pairs_for_date(&tx, &target, mode)?;
+
+        let new_pair: Option<i64> = tx.query_row(
+            "SELECT pair FROM events WHERE id = ?1",
+            params![moved[0].id],
+            |r| r.get(0),
+        )?;
+        ttlog(
+            &tx,
+            "move",
+            &format!("{} pair {}", date, pair_num),
+            &format!(
+                "{}-{} moved to {}",
+                from.format("%H:%M"),
+                to.format("%H:%M"),
+                target
+            ),
+        )?;
+        tx.commit()?;
+
+        Ok((pair_num, new_pair.unwrap_or(0) as usize))
+    }
+
+    /// Recap of `date` right after a change: worked time so far, planned
+    /// exit while the day is open and the month balance up to `date`.
+    /// None for days without working time (holiday, sick leave, ...).
+    pub fn day_recap(
+        pool: &mut DbPool,
+        cfg: &Config,
+        date: NaiveDate,
+    ) -> AppResult<Option<DayRecap>> {
+        let events = load_events_by_date(pool, &date)?;
+        if events.is_empty() {
+            return Ok(None);
+        }
+        let summary = Core::build_daily_summary(&events, cfg);
+        if Core::is_marker_day(&summary) {
+            return Ok(None);
+        }
+        let Some(fig) = Core::day_figures(&summary, &events, cfg) else {
+            return Ok(None);
+        };
+
+        let worked = fig.worked.unwrap_or_else(|| {
+            summary
+                .timeline
+                .pairs
+                .iter()
+                .filter(|p| p.out_event.is_some())
+                .map(|p| p.duration_minutes)
+                .sum()
+        });
+
+        let month_start = date.with_day(1).unwrap_or(date);
+        let dates = Period::between(month_start, date).iter_dates();
+        let month_surplus = ReportLogic::totals(pool, cfg, &dates)?.surplus();
+
+        Ok(Some(DayRecap {
+            worked,
+            target_exit: fig.worked.is_none().then(|| fig.target_exit.time()),
+            month_surplus,
+        }))
+    }
+}
+
+/// IN times of the pairs of a day with their OUT (None while open).
+fn day_intervals(events: &[Event]) -> (Vec<NaiveTime>, Vec<Option<NaiveTime>>) {
+    let mut ins = Vec::new();
+    let mut outs: Vec<Option<NaiveTime>> = Vec::new();
+    for e in events {
+        match e.kind {
+            EventType::In => {
+                ins.push(e.time);
+                outs.push(None);
+            }
+            EventType::Out => match outs.last_mut() {
+                Some(slot @ None) => *slot = Some(e.time),
+                _ => {
+                    ins.push(e.time);
+                    outs.push(Some(e.time));
+                }
+            },
+        }
+    }
+    (ins, outs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_support::day;
+
+    fn hm(s: Option<&str>) -> Option<NaiveTime> {
+        s.map(|s| NaiveTime::parse_from_str(s, "%H:%M").unwrap())
+    }
+
+    fn add(
+        cfg: &Config,
+        pool: &mut DbPool,
+        pos: Option<Location>,
+        start: Option<&str>,
+        end: Option<&str>,
+        meta: Option<&str>,
+    ) -> AppResult<()> {
+        AddLogic::apply(
+            cfg,
+            pool,
+            day(),
+            Location::Office,
+            hm(start),
+            None,
+            None,
+            hm(end),
+            false,
+            None,
+            None,
+            pos,
+            None,
+            meta.map(str::to_string),
+        )
+    }
+
+    fn edit(pool: &mut DbPool, start: Option<&str>, end: Option<&str>) -> AppResult<()> {
+        AddLogic::apply(
+            &Config::default(),
+            pool,
+            day(),
+            Location::Office,
+            hm(start),
+            None,
+            None,
+            hm(end),
+            true,
+            Some(1),
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    /// (time, position, pair, unmatched) of the day's events.
+    fn rows(pool: &DbPool) -> Vec<(String, String, i32, bool)> {
+        pool.conn
+            .prepare("SELECT time, position, pair, unmatched FROM events ORDER BY time")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn out_closes_the_in_of_its_own_activity() {
+        let cfg = Config {
+            overlap_mode: OverlapMode::Split,
+            ..Config::default()
+        };
+        let mut pool = DbPool::new(":memory:").unwrap();
+        let on_call = Some(r#"{"project":"oncall"}"#);
+
+        add(
+            &cfg,
+            &mut pool,
+            Some(Location::Remote),
+            Some("09:00"),
+            None,
+            None,
+        )
+        .unwrap();
+        add(&cfg, &mut pool, None, Some("10:00"), None, on_call).unwrap();
+        // The last IN is the on-call one (10:00): the OUT still closes 09:00
+        // and inherits its position
+        add(&cfg, &mut pool, None, None, Some("09:30"), None).unwrap();
+        add(&cfg, &mut pool, None, None, Some("11:00"), on_call).unwrap();
+
+        let r = |t: &str, p: &str, n| (t.to_string(), p.to_string(), n, false);
+        assert_eq!(
+            rows(&pool),
+            vec![
+                r("09:00", "R", 1),
+                r("09:30", "R", 1),
+                r("10:00", "O", 2),
+                r("11:00", "O", 2),
+            ]
+        );
+
+        // An on-call OUT before its own IN is refused, even after the main IN
+        let err = add(&cfg, &mut pool, None, Some("12:00"), None, on_call)
+            .and_then(|_| add(&cfg, &mut pool, None, None, Some("11:30"), on_call))
+            .unwrap_err();
+        assert!(err.to_string().contains("12:00"));
+    }
+
+    #[test]
+    fn one_sided_edit_cannot_cross_the_other_event() {
+        let cfg = Config::default();
+        let mut pool = DbPool::new(":memory:").unwrap();
+        add(&cfg, &mut pool, None, Some("09:00"), Some("17:00"), None).unwrap();
+        let saved = rows(&pool);
+
+        // Only the OUT moves, before the stored IN
+        let err = edit(&mut pool, None, Some("08:00")).unwrap_err();
+        assert!(matches!(err, AppError::PairOutBeforeIn { pair: 1, .. }));
+        assert!(err.to_string().contains("09:00"), "{err}");
+
+        // Only the IN moves, after the stored OUT
+        let err = edit(&mut pool, Some("18:00"), None).unwrap_err();
+        assert!(matches!(err, AppError::PairOutBeforeIn { pair: 1, .. }));
+        assert!(err.to_string().contains("17:00"), "{err}");
+
+        assert_eq!(rows(&pool), saved);
+    }
 }
@@ -0,0 +1,63 @@
+//! Move old events into a separate archive DB (`rtimelogger archive`),
+//! keeping the working database small and fast.
+
+use crate::db::initialize::init_db;
+use crate::db::log::ttlog;
+use crate::db::metadata::{ensure_rtimelogger_db, set_meta};
+use crate::db::pool::{DbPool, is_memory_db};
+use crate::db::queries::archive::{KEY_ARCHIVE_DB, attach_archive, detach_archive, move_before};
+use crate::errors::{AppError, AppResult};
+use crate::ui::messages::quietly;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::fs;
+
+#[derive(Debug)]
+pub struct ArchiveReport {
+    /// Absolute path of the archive DB
+    pub path: String,
+    pub events: usize,
+    pub logs: usize,
+}
+
+pub struct ArchiveLogic;
+
+impl ArchiveLogic {
+    /// Move every event dated before `before` (and the log rows written
+    /// before it) into `file`, created with the full schema if needed.
+    /// The archive path is remembered in the working DB for `list --archive`.
+    pub fn apply(pool: &mut DbPool, before: NaiveDate, file: &str) -> AppResult<ArchiveReport> {
+        if is_memory_db(file) {
+            return Err(AppError::InvalidArgs(
+                "the archive must be a file, not :memory:".into(),
+            ));
+        }
+
+        // 1) Archive DB with the same schema as the working one
+        {
+            let conn = Connection::open(file)?;
+            ensure_rtimelogger_db(&conn, file)?;
+            quietly(|| init_db(&conn))?;
+        }
+        let path = fs::canonicalize(file)?.to_string_lossy().to_string();
+
+        // 2) Move rows in a single transaction spanning both files
+        attach_archive(&pool.conn, &path)?;
+        let tx = pool.conn.transaction()?;
+        let (events, logs) = move_before(&tx, &before)?;
+        set_meta(&tx, KEY_ARCHIVE_DB, &path)?;
+        ttlog(
+            &tx,
+            "archive",
+            &path,
+            &format!(
+                "{} event(s) and {} log row(s) before {} archived",
+                events, logs, before
+            ),
+        )?;
+        tx.commit()?;
+        detach_archive(&pool.conn)?;
+
+        Ok(ArchiveReport { path, events, logs })
+    }
+}
@@ -1,25 +1,83 @@
 use crate::config::Config;
-use crate::db::pool::DbPool;
+use crate::db::pool::{DbPool, is_memory_db};
 use crate::errors::{AppError, AppResult};
 use crate::ui::messages::{info, success as ok, warning as warn};
+use crate::utils::archive::{gzip_single_file, read_passphrase, zip_single_file, zstd_single_file};
+use crate::utils::colors::{print, println};
+use crate::utils::path::day_template;
+use chrono::Local;
+use clap::ValueEnum;
 use rusqlite::Connection;
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use zip::ZipWriter;
-use zip::write::FileOptions;
+
+/// Compression applied to the backup copy (same choice on every platform).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BackupCompression {
+    /// `<file>.zip` (the only format supporting --encrypt)
+    Zip,
+    /// `<file>.gz`
+    Gzip,
+    /// `<file>.zst`, best ratio for large databases
+    Zstd,
+    /// Plain copy
+    None,
+}
+
+impl BackupCompression {
+    fn label(&self) -> &'static str {
+        match self {
+            BackupCompression::Zip => "zip",
+            BackupCompression::Gzip => "gzip",
+            BackupCompression::Zstd => "zstd",
+            BackupCompression::None => "none",
+        }
+    }
+}
 
 pub struct BackupLogic;
 
 impl BackupLogic {
     pub fn backup(
-        _pool: &mut DbPool,
+        pool: &mut DbPool,
         cfg: &Config,
         dest_file: &str,
-        compress: bool,
+        compression: BackupCompression,
+        encrypt: bool,
     ) -> AppResult<()> {
+        // Encryption is only available inside ZIP archives
+        let compression = match (encrypt, compression) {
+            (true, BackupCompression::Zip | BackupCompression::None) => BackupCompression::Zip,
+            (true, other) => {
+                return Err(AppError::InvalidArgs(format!(
+                    "--encrypt requires ZIP compression, not '{}'.",
+                    other.label()
+                )));
+            }
+            (false, c) => c,
+        };
+
+        if is_memory_db(&cfg.database) {
+            return Err(AppError::InvalidOperation(
+                "an in-memory database (--db :memory:) cannot be backed up".into(),
+            ));
+        }
+
         let src = Path::new(&cfg.database);
-        let dest = Path::new(dest_file);
+        let dest_path = day_template(dest_file, Local::now().date_naive());
+        let dest = dest_path.as_path();
+        let final_path = archive_path(dest, compression);
+
+        // `--file backup.zip` would name the copy like its own archive
+        if compression != BackupCompression::None && final_path == dest {
+            return Err(AppError::InvalidArgs(format!(
+                "The backup file '{}' already has the .{} extension: pass the name of the copy (e.g. '{}'), the archive name is derived from it.",
+                dest.display(),
+                compression.label(),
+                dest.with_extension("sqlite").display()
+            )));
+        }
 
         //
         // 1️⃣ Check database exists
@@ -39,12 +97,12 @@ impl BackupLogic {
         }
 
         //
-        // 3️⃣ If destination exists → ask user confirmation
+        // 3️⃣ If the final file (archive included) exists → ask user confirmation
         //
-        if dest.exists() {
+        if final_path.exists() {
             warn(format!(
                 "The backup file '{}' already exists.",
-                dest.display()
+                final_path.display()
             ));
             if !ask_overwrite_confirmation()? {
                 info("Backup cancelled by user.".to_string());
@@ -52,34 +110,44 @@ impl BackupLogic {
             }
         }
 
+        // Ask the passphrase before touching the filesystem
+        let passphrase = if encrypt {
+            Some(read_passphrase()?)
+        } else {
+            None
+        };
+
+        // In WAL mode committed data may still be in `<db>-wal`: move it into
+        // the main file, which is the only one copied
+        pool.checkpoint()?;
+        let wal = PathBuf::from(format!("{}-wal", cfg.database));
+        if fs::metadata(&wal).is_ok_and(|m| m.len() > 0) {
+            return Err(AppError::InvalidOperation(format!(
+                "{} is in use by another process (WAL not checkpointed): retry the backup",
+                src.display()
+            )));
+        }
+
         //
-        // 4️⃣ Copy DB
+        // 4️⃣ Copy DB (compressed / encrypted in a staging directory, then
+        //    renamed over the target: no plaintext copy is ever left behind)
         //
-        fs::copy(src, dest).map_err(AppError::Io)?;
-        ok(format!("Backup created: {}", dest.display()));
+        stage_backup(src, dest, &final_path, compression, passphrase.as_deref())?;
 
         //
-        // 5️⃣ Optional compression
+        // 5️⃣ Report only once the final file exists
         //
-        let final_path = if compress {
-            let zipped = compress_backup(dest)?;
-
-            // Remove uncompressed copy only if zip is different
-            if zipped != dest {
-                if let Err(e) = fs::remove_file(dest) {
-                    warn(format!(
-                        "Failed to delete temporary uncompressed backup: {}",
-                        e
-                    ));
-                } else {
-                    info(format!("Removed uncompressed backup: {}", dest.display()));
-                }
-            }
-
-            zipped
+        if encrypt {
+            ok(format!("Encrypted backup: {}", final_path.display()));
+        } else if compression != BackupCompression::None {
+            ok(format!(
+                "Compressed backup ({}): {}",
+                compression.label(),
+                final_path.display()
+            ));
         } else {
-            dest.to_path_buf()
-        };
+            ok(format!("Backup created: {}", final_path.display()));
+        }
 
         //
         // 6️⃣ Log operation inside DB
@@ -89,10 +157,12 @@ impl BackupLogic {
                 &conn,
                 "backup",
                 &final_path.to_string_lossy(),
-                if compress {
-                    "Backup created and compressed"
+                &if encrypt {
+                    "Backup created, compressed and encrypted".to_string()
+                } else if compression != BackupCompression::None {
+                    format!("Backup created and compressed ({})", compression.label())
                 } else {
-                    "Backup created"
+                    "Backup created".to_string()
                 },
             );
         }
@@ -123,35 +193,217 @@ fn ask_overwrite_confirmation() -> AppResult<bool> {
 
 //
 // ─────────────────────────────────────────────────────────────────────────────
-// Helper: Compress to ZIP
+// Helper: Compress the backup copy
 // ─────────────────────────────────────────────────────────────────────────────
 //
 
-fn compress_backup(path: &Path) -> AppResult<PathBuf> {
-    let zip_path = path.with_extension("zip");
-    let file = fs::File::create(&zip_path).map_err(AppError::Io)?;
-    let mut zip = ZipWriter::new(file);
+/// Copy `src` and compress it inside a staging directory next to `dest`,
+/// then rename the result to `final_path`. The staging directory (plaintext
+/// copy, partial archive) is removed whatever the outcome.
+fn stage_backup(
+    src: &Path,
+    dest: &Path,
+    final_path: &Path,
+    compression: BackupCompression,
+    passphrase: Option<&str>,
+) -> AppResult<()> {
+    let staging = staging_dir(dest);
+    fs::create_dir_all(&staging).map_err(AppError::Io)?;
+
+    let result = (|| {
+        let name = dest.file_name().ok_or_else(|| {
+            AppError::InvalidArgs(format!("Invalid backup file: {}", dest.display()))
+        })?;
+        let copy = staging.join(name);
+        fs::copy(src, &copy).map_err(AppError::Io)?;
+
+        let out = match compression {
+            BackupCompression::None => copy,
+            _ => {
+                let out = staging.join(final_path.file_name().unwrap_or(name));
+                compress_backup(&copy, &out, compression, passphrase)?;
+                out
+            }
+        };
+        fs::rename(&out, final_path).map_err(AppError::Io)
+    })();
+
+    if let Err(e) = fs::remove_dir_all(&staging) {
+        warn(format!(
+            "Failed to delete the staging directory {}: {}",
+            staging.display(),
+            e
+        ));
+    }
+    result
+}
+
+/// `backups/db.sqlite` → `backups/.db.sqlite.tmp<pid>`
+fn staging_dir(dest: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(dest.file_name().unwrap_or_default());
+    name.push(format!(".tmp{}", std::process::id()));
+    dest.with_file_name(name)
+}
+
+/// Name of the file produced by `compression` for the copy `dest`.
+fn archive_path(dest: &Path, compression: BackupCompression) -> PathBuf {
+    match compression {
+        BackupCompression::None => dest.to_path_buf(),
+        BackupCompression::Zip => dest.with_extension("zip"),
+        BackupCompression::Gzip => with_added_extension(dest, "gz"),
+        BackupCompression::Zstd => with_added_extension(dest, "zst"),
+    }
+}
+
+fn compress_backup(
+    src: &Path,
+    out: &Path,
+    compression: BackupCompression,
+    passphrase: Option<&str>,
+) -> AppResult<()> {
+    match compression {
+        BackupCompression::None => Ok(()),
+        BackupCompression::Zip => zip_single_file(src, out, passphrase),
+        BackupCompression::Gzip => gzip_single_file(src, out),
+        BackupCompression::Zstd => zstd_single_file(src, out),
+    }
+}
+
+/// `backup.sqlite` → `backup.sqlite.gz` (keeps the original extension).
+fn with_added_extension(path: &Path, ext: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_os_string();
+    s.push(".");
+    s.push(ext);
+    PathBuf::from(s)
+}
 
-    let options: FileOptions<'_, ()> =
-        FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Add the file to zip
-    let filename = path
-        .file_name()
-        .map(|s| s.to_string_lossy().to_string())
-        .ok_or_else(|| AppError::InvalidOperation("Invalid file name for backup".into()))?;
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rtl-backup-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
 
-    zip.start_file(filename, options)
-        .map_err(|e| AppError::Io(io::Error::other(e)))?;
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
 
-    // Copy DB content into ZIP
-    let mut f = fs::File::open(path).map_err(AppError::Io)?;
-    io::copy(&mut f, &mut zip).map_err(AppError::Io)?;
+    #[test]
+    fn encrypted_backup_leaves_no_plaintext() {
+        let dir = scratch("zip");
+        let src = dir.join("db.sqlite");
+        fs::write(&src, "secret rows").unwrap();
+        let dest = dir.join("backup.sqlite");
+        let final_path = archive_path(&dest, BackupCompression::Zip);
 
-    zip.finish()
-        .map_err(|e| AppError::Io(io::Error::other(e)))?;
+        stage_backup(&src, &dest, &final_path, BackupCompression::Zip, Some("pw")).unwrap();
 
-    ok(format!("Compressed backup: {}", zip_path.display()));
+        assert_eq!(entries(&dir), ["backup.zip", "db.sqlite"]);
+        let archive = fs::read(&final_path).unwrap();
+        assert!(archive.starts_with(b"PK"));
+        assert!(!archive.windows(11).any(|w| w == b"secret rows"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
 
-    Ok(zip_path)
+    #[test]
+    fn failed_backup_removes_copy_and_partial_archive() {
+        let dir = scratch("fail");
+        let src = dir.join("db.sqlite");
+        fs::write(&src, "rows").unwrap();
+        let dest = dir.join("backup.sqlite");
+        // The rename cannot replace a directory
+        let final_path = archive_path(&dest, BackupCompression::Gzip);
+        fs::create_dir(&final_path).unwrap();
+
+        assert!(stage_backup(&src, &dest, &final_path, BackupCompression::Gzip, None).is_err());
+        assert_eq!(entries(&dir), ["backup.sqlite.gz", "db.sqlite"]);
+        assert!(final_path.is_dir());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn zstd_backup_replaces_only_the_archive() {
+        let dir = scratch("zst");
+        let src = dir.join("db.sqlite");
+        fs::write(&src, "rows").unwrap();
+        let dest = dir.join("backup.sqlite");
+        let final_path = archive_path(&dest, BackupCompression::Zstd);
+        fs::write(&final_path, "old").unwrap();
+
+        stage_backup(&src, &dest, &final_path, BackupCompression::Zstd, None).unwrap();
+
+        assert_eq!(entries(&dir), ["backup.sqlite.zst", "db.sqlite"]);
+        let restored = zstd::stream::decode_all(fs::File::open(&final_path).unwrap()).unwrap();
+        assert_eq!(restored, b"rows");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn backup_includes_data_still_in_the_wal() {
+        let dir = scratch("wal");
+        let db = dir.join("db.sqlite");
+        let mut pool = DbPool::new(&db.to_string_lossy()).unwrap();
+        let _: String = pool
+            .conn
+            .query_row("PRAGMA journal_mode=WAL;", [], |row| row.get(0))
+            .unwrap();
+        pool.conn
+            .execute_batch("PRAGMA wal_autocheckpoint=0; CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (42);")
+            .unwrap();
+        let cfg = Config {
+            database: db.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        let dest = dir.join("copy.sqlite");
+
+        BackupLogic::backup(
+            &mut pool,
+            &cfg,
+            &dest.to_string_lossy(),
+            BackupCompression::None,
+            false,
+        )
+        .unwrap();
+
+        // The copy alone (no `-wal` next to it) has the row
+        let copy = Connection::open(&dest).unwrap();
+        let x: i64 = copy.query_row("SELECT x FROM t", [], |r| r.get(0)).unwrap();
+        assert_eq!(x, 42);
+        drop((copy, pool));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn archive_named_like_the_copy_is_refused() {
+        let dir = scratch("name");
+        let db = dir.join("db.sqlite");
+        let mut pool = DbPool::new(&db.to_string_lossy()).unwrap();
+        let cfg = Config {
+            database: db.to_string_lossy().to_string(),
+            ..Config::default()
+        };
+        let dest = dir.join("backup.zip");
+
+        let err = BackupLogic::backup(
+            &mut pool,
+            &cfg,
+            &dest.to_string_lossy(),
+            BackupCompression::Zip,
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, AppError::InvalidArgs(_)));
+        assert!(!dest.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,8 +1,13 @@
 use crate::config::Config;
 use crate::core::calculator::timeline::Timeline;
 use crate::core::logic::Core;
+use crate::utils::meta::meta_value;
 use crate::utils::time::parse_lunch_window;
 
+/// Meta key set to `false` by `add --no-auto-lunch`: the day never gets the
+/// automatic lunch.
+pub const AUTO_LUNCH_KEY: &str = "auto_lunch";
+
 /// Expected = work_minutes + effective_lunch (automatic or explicit)
 pub fn calculate_expected(timeline: &Timeline, cfg: &Config) -> i64 {
     if timeline.pairs.is_empty() {
@@ -13,21 +18,35 @@ pub fn calculate_expected(timeline: &Timeline, cfg: &Config) -> i64 {
     let work_minutes = Core::parse_work_duration_to_minutes(&cfg.min_work_duration);
 
     // Take lunch from the first IN of the day
-    let first_pair = &timeline.pairs[0];
-    let mut lunch = first_pair.lunch_minutes;
+    let lunch = auto_lunch(timeline, cfg).unwrap_or(timeline.pairs[0].lunch_minutes);
+
+    work_minutes + lunch
+}
+
+/// Lunch added automatically to the expected time: `min_duration_lunch_break`
+/// when the first pair has no lunch and starts before the end of
+/// `lunch_window`. `None` when the recorded lunch is used or the day opted
+/// out ([`AUTO_LUNCH_KEY`] on one of its events).
+pub fn auto_lunch(timeline: &Timeline, cfg: &Config) -> Option<i64> {
+    let first_pair = timeline.pairs.first()?;
+    if first_pair.lunch_minutes != 0 {
+        return None;
+    }
+
+    let opted_out = timeline
+        .pairs
+        .iter()
+        .flat_map(|p| std::iter::once(&p.in_event).chain(p.out_event.as_ref()))
+        .any(|ev| meta_value(ev.meta.as_deref(), AUTO_LUNCH_KEY) == "false");
+    if opted_out {
+        return None;
+    }
 
     // ---- Auto-lunch logic using lunch_window ----
     // If no lunch was specified, infer it from lunch_window based on the IN time.
-    if lunch == 0
-        && let Some((_win_start, win_end)) = parse_lunch_window(&cfg.lunch_window)
-    {
-        let start_time = first_pair.in_event.timestamp().time();
-
-        // If IN time is before the lunch window ends → apply min lunch
-        if start_time <= win_end {
-            lunch = cfg.min_duration_lunch_break as i64;
-        }
-    }
+    let (_win_start, win_end) = parse_lunch_window(&cfg.lunch_window)?;
+    let start_time = first_pair.in_event.timestamp().time();
 
-    work_minutes + lunch
+    // If IN time is before the lunch window ends → apply min lunch
+    (start_time <= win_end).then_some(cfg.min_duration_lunch_break as i64)
 }
@@ -2,9 +2,10 @@
 //! which gaps should be counted as work (work_gap = true).
 
 use crate::core::calculator::timeline::Timeline;
+use serde::{Deserialize, Serialize};
 
 /// Information about daily gaps (normal and work gaps)
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GapInfo {
     pub total_gap_minutes: i64,
     pub work_gap_minutes: i64,
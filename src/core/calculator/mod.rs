@@ -1,4 +1,5 @@
 pub mod expected;
 pub mod gaps;
+pub mod overlap;
 pub mod surplus;
 pub mod timeline;
@@ -0,0 +1,176 @@
+//! Overlapping activities (`overlap_mode`, off by default).
+//!
+//! When enabled, the events of a day are paired per activity, the activity
+//! being the `project` metadata of the event (no project = main activity):
+//! an on-call pair may then run inside the normal working day. The minutes
+//! covered by more than one pair are attributed according to the mode:
+//!
+//! - `split`: shared equally among the pairs running at the same time, so
+//!   the day total is the time covered by at least one pair;
+//! - `parallel`: credited in full to every pair, so the day total counts
+//!   them once per activity.
+
+use crate::core::calculator::timeline::Pair;
+use crate::models::event::Event;
+use crate::utils::meta::meta_value;
+use chrono::{DateTime, Local};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Metadata key naming the activity of an event.
+pub const TRACK_KEY: &str = "project";
+
+/// `overlap_mode` config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlapMode {
+    /// Pairs never overlap: one sequence of IN / OUT per day
+    #[default]
+    Off,
+    /// Overlapping minutes shared among the running activities
+    Split,
+    /// Overlapping minutes credited to every running activity
+    Parallel,
+}
+
+impl OverlapMode {
+    pub fn is_on(self) -> bool {
+        self != OverlapMode::Off
+    }
+}
+
+/// Activity of an event ("" for the main one).
+pub fn track_of(ev: &Event) -> String {
+    meta_value(ev.meta.as_deref(), TRACK_KEY).trim().to_string()
+}
+
+/// Events grouped by activity, in order of first appearance; each group
+/// keeps the order of `events`.
+pub fn split_tracks<'a>(events: &'a [Event]) -> Vec<Vec<&'a Event>> {
+    let mut names: Vec<String> = Vec::new();
+    let mut tracks: Vec<Vec<&Event>> = Vec::new();
+    for ev in events {
+        let name = track_of(ev);
+        match names.iter().position(|n| *n == name) {
+            Some(i) => tracks[i].push(ev),
+            None => {
+                names.push(name);
+                tracks.push(vec![ev]);
+            }
+        }
+    }
+    tracks
+}
+
+/// Closed interval of a pair.
+fn span(p: &Pair) -> Option<(DateTime<Local>, DateTime<Local>)> {
+    p.out_event
+        .as_ref()
+        .map(|out| (p.in_event.timestamp(), out.timestamp()))
+}
+
+/// Minutes covered by at least one closed pair.
+pub fn covered_minutes(pairs: &[Pair]) -> i64 {
+    let mut spans: Vec<_> = pairs.iter().filter_map(span).collect();
+    spans.sort();
+
+    let mut total = 0;
+    let mut current: Option<(DateTime<Local>, DateTime<Local>)> = None;
+    for (start, end) in spans {
+        match &mut current {
+            Some((_, cur_end)) if start <= *cur_end => *cur_end = (*cur_end).max(end),
+            _ => {
+                if let Some((s, e)) = current {
+                    total += (e - s).num_minutes();
+                }
+                current = Some((start, end));
+            }
+        }
+    }
+    if let Some((s, e)) = current {
+        total += (e - s).num_minutes();
+    }
+    total
+}
+
+/// `split`: replace the duration of every closed pair with its share of the
+/// covered time (minus its lunch). A rounding rest goes to the pair that
+/// started first.
+pub fn split_durations(pairs: &mut [Pair]) {
+    let spans: Vec<_> = pairs.iter().map(span).collect();
+
+    let mut points: Vec<DateTime<Local>> =
+        spans.iter().flatten().flat_map(|(s, e)| [*s, *e]).collect();
+    points.sort();
+    points.dedup();
+
+    let mut shares = vec![0i64; pairs.len()];
+    for w in points.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let active: Vec<usize> = spans
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.is_some_and(|(start, end)| start <= a && end >= b))
+            .map(|(i, _)| i)
+            .collect();
+        let Some(first) = active.first() else {
+            continue;
+        };
+        let len = (b - a).num_minutes();
+        let share = len / active.len() as i64;
+        for i in &active {
+            shares[*i] += share;
+        }
+        shares[*first] += len - share * active.len() as i64;
+    }
+
+    for (p, share) in pairs.iter_mut().zip(shares) {
+        if p.out_event.is_some() {
+            p.duration_minutes = share - p.lunch_minutes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::calculator::timeline::build_timeline_with;
+    use crate::models::event_type::EventType;
+    use crate::models::test_support;
+
+    fn ev(t: &str, kind: EventType, project: Option<&str>) -> Event {
+        let ev = test_support::ev(0, t, kind);
+        match project {
+            Some(name) => test_support::project(ev, name),
+            None => ev,
+        }
+    }
+
+    #[test]
+    fn on_call_inside_the_working_day() {
+        use EventType::{In, Out};
+        let events = [
+            ev("09:00", In, None),
+            ev("10:00", In, Some("oncall")),
+            ev("11:00", Out, Some("oncall")),
+            ev("13:00", Out, None),
+        ];
+
+        // Off: one sequence, the second IN breaks the pairing
+        assert_eq!(
+            build_timeline_with(&events, OverlapMode::Off).pairs.len(),
+            2
+        );
+
+        let split = build_timeline_with(&events, OverlapMode::Split);
+        assert_eq!(split.pairs.len(), 2);
+        assert_eq!(split.total_worked_minutes, 240);
+        assert_eq!(split.pairs[0].duration_minutes, 210);
+        assert_eq!(split.pairs[1].duration_minutes, 30);
+        assert!(split.gaps.is_empty());
+
+        let parallel = build_timeline_with(&events, OverlapMode::Parallel);
+        assert_eq!(parallel.total_worked_minutes, 300);
+        assert_eq!(parallel.overlap_minutes, 60);
+    }
+}
@@ -1,9 +1,14 @@
+use crate::core::calculator::overlap::{
+    OverlapMode, covered_minutes, split_durations, split_tracks,
+};
 use crate::models::event::Event;
 use crate::models::event_type::EventType;
 use crate::models::location::Location;
+use crate::utils::date::today;
 use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pair {
     pub in_event: Event,
     pub out_event: Option<Event>,
@@ -14,7 +19,7 @@ pub struct Pair {
     pub notes: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Gap {
     pub start: DateTime<Local>,
     pub end: DateTime<Local>,
@@ -22,15 +27,27 @@ pub struct Gap {
     pub is_work_gap: bool, // will be computed in 0.8.0-beta1
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Timeline {
     pub events: Vec<Event>,
     pub pairs: Vec<Pair>,
     pub gaps: Vec<Gap>,
     pub total_worked_minutes: i64,
+    /// `overlap_mode = "parallel"`: minutes credited more than once because
+    /// activities ran at the same time (0 otherwise)
+    #[serde(default)]
+    pub overlap_minutes: i64,
 }
 
+/// Timeline of a day paired as one IN / OUT sequence (`overlap_mode` off).
 pub fn build_timeline(events: &[Event]) -> Timeline {
+    build_timeline_with(events, OverlapMode::Off)
+}
+
+/// Like [`build_timeline`] with an explicit [`OverlapMode`]: when on, the
+/// events are paired per activity (see [`overlap`](super::overlap)) and the
+/// pairs are ordered by their IN.
+pub fn build_timeline_with(events: &[Event], mode: OverlapMode) -> Timeline {
     if events.is_empty() {
         return Timeline::default();
     }
@@ -41,17 +58,81 @@ pub fn build_timeline(events: &[Event]) -> Timeline {
     let mut sorted = events.to_vec();
     sorted.sort_by_key(|e| e.timestamp());
 
-    let mut pairs = Vec::new();
-    let mut gaps = Vec::new();
-    let mut total = 0;
+    // -----------------------------
+    // Build Pairs
+    // -----------------------------
+    let mut pairs = if mode.is_on() {
+        let mut pairs: Vec<Pair> = split_tracks(&sorted)
+            .into_iter()
+            .flat_map(|track| build_pairs(&track))
+            .collect();
+        pairs.sort_by_key(|p| p.in_event.timestamp());
+        pairs
+    } else {
+        build_pairs(&sorted.iter().collect::<Vec<_>>())
+    };
+
+    let mut overlap_minutes = 0;
+    match mode {
+        OverlapMode::Off => {}
+        OverlapMode::Split => split_durations(&mut pairs),
+        OverlapMode::Parallel => {
+            let raw: i64 = pairs
+                .iter()
+                .map(|p| p.duration_minutes + p.lunch_minutes)
+                .sum();
+            overlap_minutes = (raw - covered_minutes(&pairs)).max(0);
+        }
+    }
 
-    let mut i = 0;
+    let total = pairs.iter().map(|p| p.duration_minutes).sum();
 
     // -----------------------------
-    // Build Pairs
+    // Compute GAPS between pairs
     // -----------------------------
+    let gaps = if mode.is_on() {
+        uncovered_gaps(&pairs)
+    } else {
+        let mut gaps = Vec::new();
+        for w in pairs.windows(2) {
+            let p1 = &w[0];
+            let p2 = &w[1];
+
+            if let Some(out1) = &p1.out_event {
+                let start = out1.timestamp();
+                let end = p2.in_event.timestamp();
+
+                if end > start {
+                    gaps.push(Gap {
+                        start,
+                        end,
+                        duration_minutes: (end - start).num_minutes(),
+                        // ✅ il gap è lavorativo se l'OUT del pair precedente ha work_gap=true
+                        is_work_gap: out1.work_gap,
+                    });
+                }
+            }
+        }
+        gaps
+    };
+
+    Timeline {
+        events: sorted,
+        pairs,
+        gaps,
+        total_worked_minutes: total,
+        overlap_minutes,
+    }
+}
+
+/// Sequential pairing of chronologically sorted events: every IN is closed
+/// by the OUT right after it, an IN followed by anything else stays open.
+fn build_pairs(sorted: &[&Event]) -> Vec<Pair> {
+    let mut pairs = Vec::new();
+    let mut i = 0;
+
     while i < sorted.len() {
-        let ev = &sorted[i];
+        let ev = sorted[i];
 
         if ev.kind == EventType::In {
             // Case: IN followed by OUT → valid pair
@@ -71,8 +152,6 @@ pub fn build_timeline(events: &[Event]) -> Timeline {
                 let raw_minutes = (out_ev.timestamp() - in_ev.timestamp()).num_minutes();
                 let worked_minutes = raw_minutes - lunch_minutes;
 
-                total += worked_minutes;
-
                 pairs.push(Pair {
                     in_event: in_ev.clone(),
                     out_event: Some(out_ev.clone()),
@@ -104,33 +183,71 @@ pub fn build_timeline(events: &[Event]) -> Timeline {
         i += 1;
     }
 
-    // -----------------------------
-    // Compute GAPS between pairs
-    // -----------------------------
-    for w in pairs.windows(2) {
-        let p1 = &w[0];
-        let p2 = &w[1];
+    pairs
+}
 
-        if let Some(out1) = &p1.out_event {
-            let start = out1.timestamp();
-            let end = p2.in_event.timestamp();
+/// Gaps where no pair is running (pairs ordered by IN). An open pair covers
+/// the rest of the day. A gap is a work gap when the OUT that opens it is.
+fn uncovered_gaps(pairs: &[Pair]) -> Vec<Gap> {
+    let mut gaps = Vec::new();
+    let mut covered_until: Option<&Event> = None;
 
+    for p in pairs {
+        if let Some(out) = covered_until {
+            let (start, end) = (out.timestamp(), p.in_event.timestamp());
             if end > start {
                 gaps.push(Gap {
                     start,
                     end,
                     duration_minutes: (end - start).num_minutes(),
-                    // ✅ il gap è lavorativo se l'OUT del pair precedente ha work_gap=true
-                    is_work_gap: out1.work_gap,
+                    is_work_gap: out.work_gap,
                 });
             }
         }
-    }
 
-    Timeline {
-        events: sorted,
-        pairs,
-        gaps,
-        total_worked_minutes: total,
+        let Some(out) = &p.out_event else {
+            break;
+        };
+        if covered_until.is_none_or(|c| out.timestamp() > c.timestamp()) {
+            covered_until = Some(out);
+        }
     }
+
+    gaps
+}
+
+/// Events that cannot be paired (an IN followed by another IN, an OUT
+/// without a preceding IN, a trailing IN left open on a past day), as flagged
+/// by the pair assignment (`db::queries::pairs`) in `Event::unmatched`.
+/// Marker days (Holiday / National Holiday / Sick Leave) are never reported,
+/// and today's last IN is considered a session still in progress (the last
+/// IN of every activity with `mode` on).
+pub fn unmatched_events(events: &[Event], mode: OverlapMode) -> Vec<Event> {
+    let mut sorted = events.to_vec();
+    sorted.sort_by_key(|e| e.timestamp());
+
+    let tracks = if mode.is_on() {
+        split_tracks(&sorted)
+    } else {
+        vec![sorted.iter().collect()]
+    };
+    let in_progress: Vec<i32> = tracks
+        .iter()
+        .filter_map(|t| t.last())
+        .filter(|e| e.kind == EventType::In && e.date == today())
+        .map(|e| e.id)
+        .collect();
+
+    sorted
+        .iter()
+        .filter(|e| {
+            e.unmatched
+                && !in_progress.contains(&e.id)
+                && !matches!(
+                    e.location,
+                    Location::Holiday | Location::NationalHoliday | Location::SickLeave
+                )
+        })
+        .cloned()
+        .collect()
 }
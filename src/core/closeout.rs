@@ -0,0 +1,92 @@
+//! Month closeout (`rtimelogger closeout YYYY-MM`): lock every day of the
+//! month and produce the reports listed in the `on_closeout` config pipeline.
+//! Each generated file is recorded in the internal log with its SHA-256.
+
+use crate::config::Config;
+use crate::db::log::ttlog;
+use crate::db::pool::DbPool;
+use crate::db::queries::locks::lock_day;
+use crate::errors::{AppError, AppResult};
+use crate::export::ExportLogic;
+use crate::utils::checksum::sha256_file;
+use crate::utils::path::month_template;
+use crate::utils::period::Period;
+use chrono::NaiveDate;
+use std::path::PathBuf;
+
+#[derive(Debug, Default)]
+pub struct CloseoutReport {
+    /// Days locked by this run (already locked days are not counted)
+    pub locked: usize,
+    /// Generated files with their SHA-256
+    pub files: Vec<(PathBuf, String)>,
+    /// Pipeline entries skipped because the month has no events
+    pub skipped: usize,
+}
+
+pub struct CloseoutLogic;
+
+impl CloseoutLogic {
+    /// Parse the `YYYY-MM` month argument into its first and last day.
+    pub fn parse_month(month: &str) -> AppResult<(NaiveDate, NaiveDate)> {
+        let period = Period::parse(month)?;
+        match period.bounds() {
+            Some((from, to)) if month.trim().len() == 7 => Ok((from, to)),
+            _ => Err(AppError::InvalidPeriod {
+                input: month.to_string(),
+                hint: "closeout works on a single month (YYYY-MM)".into(),
+            }),
+        }
+    }
+
+    pub fn apply(
+        pool: &mut DbPool,
+        cfg: &Config,
+        month: &str,
+        force: bool,
+    ) -> AppResult<CloseoutReport> {
+        let (from, to) = Self::parse_month(month)?;
+        let label = from.format("%Y-%m").to_string();
+        let mut rep = CloseoutReport::default();
+
+        // 1) Reports first: a failing export leaves the month open
+        for job in &cfg.on_closeout {
+            let path = month_template(&job.file, from);
+
+            if !ExportLogic::export_range(pool, cfg, &job.format, &path, (from, to), force)? {
+                rep.skipped += 1;
+                continue;
+            }
+
+            let sha = sha256_file(&path)?;
+            ttlog(
+                &pool.conn,
+                "closeout_export",
+                &path.display().to_string(),
+                &format!("{} {} sha256={}", label, job.format.as_str(), sha),
+            )?;
+            rep.files.push((path, sha));
+        }
+
+        // 2) Lock the whole month
+        let tx = pool.conn.transaction()?;
+        for day in Period::between(from, to).iter_dates() {
+            if lock_day(&tx, &day)? {
+                rep.locked += 1;
+            }
+        }
+        ttlog(
+            &tx,
+            "closeout",
+            &label,
+            &format!(
+                "Month closed: {} day(s) locked, {} report(s)",
+                rep.locked,
+                rep.files.len()
+            ),
+        )?;
+        tx.commit()?;
+
+        Ok(rep)
+    }
+}
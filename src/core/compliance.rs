@@ -0,0 +1,159 @@
+//! Mandatory break rule (`mandatory_lunch_after`, e.g. "6h"): a block of
+//! continuous work longer than the limit must be interrupted by a break
+//! (D.Lgs. 66/2003, art. 8). A block ends at a pair with a recorded lunch or
+//! at a gap of at least [`MIN_BREAK_MINUTES`] not marked as work gap.
+//!
+//! Daily cap (`max_daily_work`, e.g. "10h"): `add` warns beyond it, or
+//! refuses the change with `enforce_max_daily_work` unless `--override`
+//! gives a reason (stored in the event meta under [`OVERRIDE_REASON_KEY`]).
+
+use crate::config::Config;
+use crate::core::logic::Core;
+use crate::models::day_summary::DaySummary;
+use chrono::NaiveTime;
+
+/// Shortest gap between two pairs that counts as a break.
+pub const MIN_BREAK_MINUTES: i64 = 10;
+
+/// Meta key of the reason given with `add --override`.
+pub const OVERRIDE_REASON_KEY: &str = "override_reason";
+
+/// A block of work over the limit without any break.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakViolation {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub minutes: i64,
+}
+
+/// Configured limit in minutes; None when the rule is disabled.
+pub fn mandatory_lunch_after(cfg: &Config) -> Option<i64> {
+    cfg.mandatory_lunch_after
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(Core::parse_work_duration_to_minutes)
+        .filter(|m| *m > 0)
+}
+
+/// Configured daily cap in minutes; None when unset.
+pub fn max_daily_work(cfg: &Config) -> Option<i64> {
+    cfg.max_daily_work
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(Core::parse_work_duration_to_minutes)
+        .filter(|m| *m > 0)
+}
+
+/// Blocks of the day longer than `limit` minutes. Open pairs are ignored.
+pub fn break_violations(summary: &DaySummary, limit: i64) -> Vec<BreakViolation> {
+    let mut out = Vec::new();
+    let mut block: Option<BreakViolation> = None;
+
+    let mut close = |block: &mut Option<BreakViolation>| {
+        if let Some(b) = block.take()
+            && b.minutes > limit
+        {
+            out.push(b);
+        }
+    };
+
+    for p in &summary.timeline.pairs {
+        let Some(out_ev) = &p.out_event else {
+            continue;
+        };
+
+        // The lunch of a pair has no position in time: the pair is compliant
+        if p.lunch_minutes > 0 {
+            close(&mut block);
+            continue;
+        }
+
+        let raw = (out_ev.timestamp() - p.in_event.timestamp()).num_minutes();
+
+        if let Some(b) = &mut block {
+            let gap = (p.in_event.time - b.end).num_minutes();
+            let work_gap = summary
+                .timeline
+                .gaps
+                .iter()
+                .any(|g| g.is_work_gap && g.end == p.in_event.timestamp());
+            if gap < MIN_BREAK_MINUTES || work_gap {
+                b.minutes += raw + gap.max(0);
+                b.end = out_ev.time;
+                continue;
+            }
+        }
+
+        close(&mut block);
+        block = Some(BreakViolation {
+            start: p.in_event.time,
+            end: out_ev.time,
+            minutes: raw,
+        });
+    }
+    close(&mut block);
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::event::Event;
+    use crate::models::event_type::EventType;
+    use crate::models::test_support;
+
+    fn ev(t: &str, kind: EventType, lunch: i32) -> Event {
+        Event {
+            lunch: Some(lunch),
+            ..test_support::ev(0, t, kind)
+        }
+    }
+
+    fn violations(events: &[Event]) -> Vec<BreakViolation> {
+        break_violations(&Core::build_daily_summary(events, &Config::default()), 360)
+    }
+
+    #[test]
+    fn long_blocks_without_break() {
+        use EventType::{In, Out};
+
+        // 08:00-15:00 in one go
+        let v = violations(&[ev("08:00", In, 0), ev("15:00", Out, 0)]);
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].minutes, 420);
+
+        // Same day with lunch recorded
+        assert!(violations(&[ev("08:00", In, 0), ev("15:00", Out, 30)]).is_empty());
+
+        // A 5-minute gap is not a break, a 30-minute one is
+        let short = [
+            ev("08:00", In, 0),
+            ev("12:00", Out, 0),
+            ev("12:05", In, 0),
+            ev("15:00", Out, 0),
+        ];
+        assert_eq!(violations(&short)[0].minutes, 420);
+        let long = [
+            ev("08:00", In, 0),
+            ev("12:00", Out, 0),
+            ev("12:30", In, 0),
+            ev("16:00", Out, 0),
+        ];
+        assert!(violations(&long).is_empty());
+    }
+
+    #[test]
+    fn daily_cap_from_config() {
+        let cfg = |v: Option<&str>| Config {
+            max_daily_work: v.map(str::to_string),
+            ..Config::default()
+        };
+        assert_eq!(max_daily_work(&cfg(Some("10h"))), Some(600));
+        assert_eq!(max_daily_work(&cfg(Some("9h 30m"))), Some(570));
+        assert_eq!(max_daily_work(&cfg(Some(" "))), None);
+        assert_eq!(max_daily_work(&cfg(None)), None);
+    }
+}
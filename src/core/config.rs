@@ -1,4 +1,5 @@
 use crate::errors::{AppError, AppResult};
+use crate::ui::messages::data;
 use std::fs;
 use std::process::Command;
 
@@ -7,7 +8,7 @@ pub struct ConfigLogic;
 impl ConfigLogic {
     pub fn print(path: &str) -> AppResult<()> {
         let content = fs::read_to_string(path).map_err(|_| AppError::ConfigLoad)?;
-        println!("{}", content);
+        data(content);
         Ok(())
     }
 
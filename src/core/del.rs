@@ -1,43 +1,127 @@
+use crate::core::calculator::overlap::OverlapMode;
 use crate::db::pool::DbPool;
-use crate::db::queries::{delete_event, load_events_by_date};
+use crate::db::queries::locks::ensure_unlocked;
+use crate::db::queries::trash::{next_batch, trash_event};
+use crate::db::queries::{load_events_by_date, rebuild_pairs_for_date};
 use crate::errors::{AppError, AppResult};
+use crate::models::event::Event;
+use crate::models::event_type::EventType;
 use crate::ui::messages::info;
 use chrono::NaiveDate;
 
 pub struct DeleteLogic;
 
 impl DeleteLogic {
-    pub fn apply(pool: &mut DbPool, date: NaiveDate, pair: Option<usize>) -> AppResult<()> {
+    /// Move the events of a day (or of one pair) to the trash.
+    /// Returns the trash batch, for `trash restore`.
+    pub fn apply(
+        pool: &mut DbPool,
+        date: NaiveDate,
+        pair: Option<usize>,
+        mode: OverlapMode,
+    ) -> AppResult<i64> {
         // la data è già un NaiveDate; se serve la stringa, formattiamola
         let date_str = date.format("%Y-%m-%d").to_string();
+        ensure_unlocked(&pool.conn, &date)?;
+
         let events = load_events_by_date(pool, &date)?;
 
         if events.is_empty() {
             return Err(AppError::NoEventsForDate(date_str));
         }
 
+        let tx = pool.conn.transaction()?;
+        let batch = next_batch(&tx)?;
+
         if let Some(p) = pair {
-            // Delete specific pair (in and out)
-            let idx = p - 1;
-            let pair_events = events
-                .chunks(2)
-                .nth(idx)
-                .ok_or_else(|| AppError::InvalidPair(p))?;
+            // Delete specific pair (in and out), using the persisted pair number
+            let pair_events: Vec<&Event> = events.iter().filter(|ev| ev.pair == p as i32).collect();
+            if p == 0 || pair_events.is_empty() {
+                return Err(AppError::InvalidPair(p));
+            }
 
             for ev in pair_events {
-                delete_event(pool, ev.id)?;
+                trash_event(&tx, ev.id, batch)?;
             }
 
+            // Renumber the remaining pairs of the day
+            rebuild_pairs_for_date(&tx, &date, mode)?;
+            tx.commit()?;
+
             info(format!("Deleted pair {} for {}", p, date));
-            return Ok(());
+            return Ok(batch);
         }
 
         // Delete all events for this date
         for ev in events {
-            delete_event(pool, ev.id)?;
+            trash_event(&tx, ev.id, batch)?;
         }
+        tx.commit()?;
 
         info(format!("Deleted all events for {}", date));
-        Ok(())
+        Ok(batch)
+    }
+
+    /// Days of `dates` that have events, with their events. A locked day
+    /// refuses the whole range.
+    pub fn period_events(
+        pool: &mut DbPool,
+        dates: &[NaiveDate],
+    ) -> AppResult<Vec<(NaiveDate, Vec<Event>)>> {
+        let mut days = Vec::new();
+        for d in dates {
+            let events = load_events_by_date(pool, d)?;
+            if events.is_empty() {
+                continue;
+            }
+            ensure_unlocked(&pool.conn, d)?;
+            days.push((*d, events));
+        }
+        Ok(days)
+    }
+
+    /// Move the events of several days to the trash in one transaction
+    /// (`del --period`). Returns the trash batch.
+    pub fn apply_period(pool: &mut DbPool, days: &[(NaiveDate, Vec<Event>)]) -> AppResult<i64> {
+        let tx = pool.conn.transaction()?;
+        let batch = next_batch(&tx)?;
+
+        for (d, events) in days {
+            ensure_unlocked(&tx, d)?;
+            for ev in events {
+                trash_event(&tx, ev.id, batch)?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(batch)
+    }
+
+    /// Most recently recorded event of `date` (by creation time), optionally
+    /// of one kind only.
+    pub fn last_event(
+        pool: &mut DbPool,
+        date: NaiveDate,
+        kind: Option<EventType>,
+    ) -> AppResult<Option<Event>> {
+        let events = load_events_by_date(pool, &date)?;
+        Ok(events
+            .into_iter()
+            .filter(|ev| kind.as_ref().is_none_or(|k| ev.kind == *k))
+            .max_by(|a, b| (&a.created_at, a.id).cmp(&(&b.created_at, b.id))))
+    }
+
+    /// Move a single event to the trash and renumber the pairs of its day.
+    /// Returns the trash batch.
+    pub fn apply_event(pool: &mut DbPool, ev: &Event, mode: OverlapMode) -> AppResult<i64> {
+        let tx = pool.conn.transaction()?;
+        ensure_unlocked(&tx, &ev.date)?;
+
+        let batch = next_batch(&tx)?;
+        trash_event(&tx, ev.id, batch)?;
+        rebuild_pairs_for_date(&tx, &ev.date, mode)?;
+        tx.commit()?;
+
+        Ok(batch)
     }
 }
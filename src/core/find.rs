@@ -0,0 +1,130 @@
+//! `rtimelogger find`: past days matching several conditions at once
+//! (ΔWORK and worked thresholds, position, notes). Position and notes are
+//! filtered in SQL, the computed figures on the day summaries.
+
+use crate::config::Config;
+use crate::core::logic::Core;
+use crate::db::pool::DbPool;
+use crate::db::queries::events::find_candidate_dates;
+use crate::db::queries::load_events_by_date;
+use crate::errors::AppResult;
+use crate::models::location::Location;
+use crate::utils::date::get_day_position;
+use chrono::NaiveDate;
+
+/// Conditions of a search; all of them must hold.
+#[derive(Debug, Clone, Default)]
+pub struct FindFilter {
+    pub bounds: Option<(NaiveDate, NaiveDate)>,
+    pub min_surplus: Option<i64>,
+    pub max_surplus: Option<i64>,
+    pub min_worked: Option<i64>,
+    pub max_worked: Option<i64>,
+    /// Days with a pair at this position (`Mixed`: days with several positions)
+    pub position: Option<Location>,
+    pub has_note: bool,
+}
+
+impl FindFilter {
+    /// True when a condition needs the worked minutes / ΔWORK of the day.
+    fn needs_figures(&self) -> bool {
+        self.min_surplus.is_some()
+            || self.max_surplus.is_some()
+            || self.min_worked.is_some()
+            || self.max_worked.is_some()
+    }
+
+    fn accepts(&self, worked: i64, delta: i64) -> bool {
+        self.min_surplus.is_none_or(|m| delta >= m)
+            && self.max_surplus.is_none_or(|m| delta <= m)
+            && self.min_worked.is_none_or(|m| worked >= m)
+            && self.max_worked.is_none_or(|m| worked <= m)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FoundDay {
+    pub date: NaiveDate,
+    pub position: Location,
+    pub worked: Option<i64>,
+    pub delta: Option<i64>,
+    pub notes: Vec<String>,
+}
+
+pub struct FindLogic;
+
+impl FindLogic {
+    /// Days matching `filter`, in date order. With thresholds on the figures,
+    /// days still open and non-working days (H, N, S) never match.
+    pub fn search(
+        pool: &mut DbPool,
+        cfg: &Config,
+        filter: &FindFilter,
+    ) -> AppResult<Vec<FoundDay>> {
+        let sql_position = filter.position.filter(|p| *p != Location::Mixed);
+        let dates = find_candidate_dates(&pool.conn, filter.bounds, sql_position, filter.has_note)?;
+
+        let mut out = Vec::new();
+        for date in dates {
+            let events = load_events_by_date(pool, &date)?;
+            let summary = Core::build_daily_summary(&events, cfg);
+            let position = get_day_position(&summary.timeline);
+
+            if filter.position == Some(Location::Mixed) && position != Location::Mixed {
+                continue;
+            }
+
+            let figures = if Core::is_marker_day(&summary) {
+                None
+            } else {
+                Core::day_figures(&summary, &events, cfg)
+            };
+            let worked = figures.as_ref().and_then(|f| f.worked);
+            let delta = figures.as_ref().and_then(|f| f.delta);
+
+            if filter.needs_figures() {
+                match (worked, delta) {
+                    (Some(w), Some(d)) if filter.accepts(w, d) => {}
+                    _ => continue,
+                }
+            }
+
+            let mut notes: Vec<String> = Vec::new();
+            for n in events.iter().filter_map(|ev| ev.notes.as_deref()) {
+                let n = n.trim();
+                if !n.is_empty() && !notes.iter().any(|x| x == n) {
+                    notes.push(n.to_string());
+                }
+            }
+
+            out.push(FoundDay {
+                date,
+                position,
+                worked,
+                delta,
+                notes,
+            });
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_are_inclusive_and_combined() {
+        let f = FindFilter {
+            min_surplus: Some(60),
+            max_worked: Some(9 * 60),
+            ..Default::default()
+        };
+        assert!(f.needs_figures());
+        assert!(f.accepts(8 * 60 + 36, 60));
+        assert!(!f.accepts(8 * 60 + 36, 59));
+        assert!(!f.accepts(9 * 60 + 1, 120));
+        assert!(!FindFilter::default().needs_figures());
+    }
+}
@@ -0,0 +1,208 @@
+//! Monthly invoice for freelancers (`rtimelogger invoice`).
+//!
+//! One line per day and project: the project is the `project` metadata key
+//! of the pair (`add --meta project=...`, IN event first). The worked time of
+//! the day is split between its projects in proportion to their pairs, so
+//! the lines always add up to the worked time shown by `list`.
+
+use crate::config::Config;
+use crate::core::calculator::timeline::Pair;
+use crate::core::logic::Core;
+use crate::db::pool::DbPool;
+use crate::db::queries::load_events_by_date;
+use crate::errors::AppResult;
+use crate::utils::formatting::round_hours;
+use crate::utils::meta::meta_value;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Metadata key holding the project of a pair.
+pub const PROJECT_KEY: &str = "project";
+
+/// Line label of pairs without a project.
+pub const NO_PROJECT: &str = "-";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceLine {
+    pub date: NaiveDate,
+    pub project: String,
+    pub minutes: i64,
+    /// `minutes` as decimal hours rounded to `decimal_step`
+    pub hours: f64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    pub client: String,
+    pub rate: f64,
+    pub lines: Vec<InvoiceLine>,
+    /// Tax percentage; None leaves placeholders in the document
+    pub tax_percent: Option<f64>,
+}
+
+impl Invoice {
+    pub fn hours(&self) -> f64 {
+        self.lines.iter().map(|l| l.hours).sum()
+    }
+
+    pub fn subtotal(&self) -> f64 {
+        self.lines.iter().map(|l| l.amount).sum()
+    }
+
+    pub fn tax(&self) -> Option<f64> {
+        self.tax_percent
+            .map(|p| round_cents(self.subtotal() * p / 100.0))
+    }
+
+    pub fn total(&self) -> Option<f64> {
+        self.tax().map(|t| self.subtotal() + t)
+    }
+
+    /// Hours per project over the whole period.
+    pub fn by_project(&self) -> BTreeMap<&str, f64> {
+        let mut map = BTreeMap::new();
+        for l in &self.lines {
+            *map.entry(l.project.as_str()).or_insert(0.0) += l.hours;
+        }
+        map
+    }
+}
+
+/// Worked minutes of one project on one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectDay {
+    pub date: NaiveDate,
+    pub project: String,
+    pub minutes: i64,
+}
+
+pub struct InvoiceLogic;
+
+impl InvoiceLogic {
+    /// Lines of the closed working days in `dates` (marker and open days are skipped).
+    pub fn build(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+        client: &str,
+        rate: f64,
+        tax_percent: Option<f64>,
+    ) -> AppResult<Invoice> {
+        let lines = Self::project_days(pool, cfg, dates)?
+            .into_iter()
+            .map(|d| {
+                let hours = round_hours(d.minutes, cfg.decimal_step);
+                InvoiceLine {
+                    date: d.date,
+                    project: d.project,
+                    minutes: d.minutes,
+                    hours,
+                    amount: round_cents(hours * rate),
+                }
+            })
+            .collect();
+
+        Ok(Invoice {
+            client: client.to_string(),
+            rate,
+            lines,
+            tax_percent,
+        })
+    }
+
+    /// Worked time of the closed working days in `dates`, per day and project.
+    pub fn project_days(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+    ) -> AppResult<Vec<ProjectDay>> {
+        let mut days = Vec::new();
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+
+            let summary = Core::build_daily_summary(&events, cfg);
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+            let Some(worked) = Core::day_figures(&summary, &events, cfg).and_then(|f| f.worked)
+            else {
+                continue;
+            };
+
+            let mut per_project: BTreeMap<String, i64> = BTreeMap::new();
+            for p in &summary.timeline.pairs {
+                let mut project = pair_meta(p, PROJECT_KEY);
+                if project.is_empty() {
+                    project = NO_PROJECT.to_string();
+                }
+                *per_project.entry(project).or_insert(0) += p.duration_minutes.max(0);
+            }
+
+            for (project, minutes) in split_minutes(worked.max(0), &per_project) {
+                days.push(ProjectDay {
+                    date: *day,
+                    project,
+                    minutes,
+                });
+            }
+        }
+
+        Ok(days)
+    }
+}
+
+/// Metadata value of a pair: IN event first, then the OUT event.
+pub(crate) fn pair_meta(p: &Pair, key: &str) -> String {
+    let value = meta_value(p.in_event.meta.as_deref(), key);
+    match &p.out_event {
+        Some(out) if value.is_empty() => meta_value(out.meta.as_deref(), key),
+        _ => value,
+    }
+}
+
+/// Split `total` minutes in proportion to `weights`; the rounding rest goes
+/// to the last project so that the parts add up to `total`.
+fn split_minutes(total: i64, weights: &BTreeMap<String, i64>) -> Vec<(String, i64)> {
+    let sum: i64 = weights.values().sum();
+    if sum == 0 || weights.len() == 1 {
+        let name = weights
+            .keys()
+            .next()
+            .cloned()
+            .unwrap_or_else(|| NO_PROJECT.to_string());
+        return vec![(name, total)];
+    }
+
+    let mut parts: Vec<(String, i64)> = weights
+        .iter()
+        .map(|(name, w)| (name.clone(), total * w / sum))
+        .collect();
+    let assigned: i64 = parts.iter().map(|(_, m)| m).sum();
+    if let Some(last) = parts.last_mut() {
+        last.1 += total - assigned;
+    }
+    parts
+}
+
+pub(crate) fn round_cents(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_keeps_the_day_total() {
+        let weights: BTreeMap<String, i64> =
+            [("acme".to_string(), 200), ("beta".to_string(), 100)].into();
+        let parts = split_minutes(460, &weights);
+        assert_eq!(parts, vec![("acme".into(), 306), ("beta".into(), 154)]);
+        assert_eq!(split_minutes(60, &BTreeMap::new()), vec![("-".into(), 60)]);
+    }
+}
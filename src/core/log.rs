@@ -1,6 +1,7 @@
 use crate::config::Config;
 use crate::db::pool::DbPool;
 use crate::errors::AppResult;
+use crate::ui::messages::data;
 use ansi_term::Colour;
 
 fn strip_ansi(s: &str) -> String {
@@ -76,7 +77,7 @@ impl LogLogic {
             .max()
             .unwrap();
 
-        println!("📜 Internal log:\n");
+        data("📜 Internal log:\n");
 
         for (id, date, operation_raw, op_target, message) in entries {
             let color = color_for_operation(&operation_raw);
@@ -119,7 +120,7 @@ impl LogLogic {
             // padding (calcolato sulle dimensioni reali SENZA ANSI)
             let padding = " ".repeat(op_w.saturating_sub(strip_ansi(&recolored).len()));
 
-            println!(
+            data(format!(
                 "{:>id_w$}: {:<date_w$} | {}{} => {}",
                 id,
                 date,
@@ -128,7 +129,7 @@ impl LogLogic {
                 message,
                 id_w = id_w,
                 date_w = date_w
-            );
+            ));
         }
 
         Ok(())
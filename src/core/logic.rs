@@ -1,13 +1,15 @@
 use crate::config::Config;
 use crate::core::calculator::{expected, surplus, timeline};
-use crate::models::{day_summary::DaySummary, event::Event};
+use crate::models::day_summary::{DayFigures, DaySummary};
+use crate::models::{event::Event, location::Location};
+use crate::utils::date::get_day_position;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 
 pub struct Core;
 
 impl Core {
     pub fn build_daily_summary(events: &[Event], cfg: &Config) -> DaySummary {
-        let timeline = timeline::build_timeline(events);
+        let timeline = timeline::build_timeline_with(events, cfg.overlap_mode);
 
         // expected = minuti teorici da lavorare (da config)
         let expected = expected::calculate_expected(&timeline, cfg);
@@ -23,6 +25,64 @@ impl Core {
         }
     }
 
+    /// Total minutes of gaps between pairs that are not marked as work gaps.
+    pub fn non_work_gap_minutes(summary: &DaySummary) -> i64 {
+        summary
+            .timeline
+            .gaps
+            .iter()
+            .filter(|g| !g.is_work_gap)
+            .map(|g| g.duration_minutes)
+            .sum()
+    }
+
+    /// Holiday / National Holiday / Sick Leave days carry no working time.
+    pub fn is_marker_day(summary: &DaySummary) -> bool {
+        !summary.timeline.pairs.is_empty()
+            && matches!(
+                get_day_position(&summary.timeline),
+                Location::Holiday | Location::NationalHoliday | Location::SickLeave
+            )
+    }
+
+    /// IN / OUT / TGT / WORKED / ΔWORK of a day (None if the day has no pairs).
+    /// TGT = first IN + expected (work + lunch) + non-work gaps, minus the
+    /// minutes credited twice by `overlap_mode = "parallel"`.
+    pub fn day_figures(summary: &DaySummary, events: &[Event], cfg: &Config) -> Option<DayFigures> {
+        let timeline = &summary.timeline;
+        let first_in = timeline.pairs.first()?.in_event.timestamp();
+
+        let last_out = timeline
+            .pairs
+            .iter()
+            .filter_map(|p| p.out_event.as_ref())
+            .map(|ev| ev.timestamp())
+            .max();
+
+        let mut lunch_total: i64 = timeline.pairs.iter().map(|p| p.lunch_minutes).sum();
+        if lunch_total == 0 {
+            lunch_total = events.iter().map(|ev| ev.lunch.unwrap_or(0) as i64).sum();
+        }
+
+        let target_exit = first_in
+            + chrono::Duration::minutes(summary.expected)
+            + chrono::Duration::minutes(Self::non_work_gap_minutes(summary))
+            - chrono::Duration::minutes(timeline.overlap_minutes);
+
+        let expected = Self::parse_work_duration_to_minutes(&cfg.min_work_duration);
+        let delta = last_out.map(|out| (out - target_exit).num_minutes());
+
+        Some(DayFigures {
+            first_in,
+            last_out,
+            lunch_total,
+            target_exit,
+            worked: delta.map(|d| expected + d),
+            expected,
+            delta,
+        })
+    }
+
     pub fn calculate_expected_exit(
         date: NaiveDate,   // aggiunto!
         time_in: &str,     // "HH:MM"
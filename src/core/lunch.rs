@@ -0,0 +1,138 @@
+//! `rtimelogger lunch <DATE> <MINUTES> [--pair N]`: set the lunch of one
+//! pair after the fact. The minutes go on the OUT event of the pair (the IN
+//! one is cleared, a pair uses the larger of the two).
+
+use crate::config::Config;
+use crate::core::calculator::timeline::Pair;
+use crate::core::logic::Core;
+use crate::db::pool::DbPool;
+use crate::db::queries::locks::ensure_unlocked;
+use crate::db::queries::{load_events_by_date, recalc_pairs_for_date, update_event};
+use crate::errors::{AppError, AppResult};
+use crate::models::day_summary::DayFigures;
+use crate::utils::time::{crosses_lunch_window, parse_lunch_window};
+use chrono::{NaiveDate, NaiveTime};
+
+#[derive(Debug)]
+pub struct LunchUpdate {
+    /// 1-based pair number
+    pub pair: usize,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub previous: i64,
+    /// False when the pair does not overlap `lunch_window`
+    pub in_window: bool,
+    /// Day figures after the update
+    pub figures: Option<DayFigures>,
+}
+
+pub struct LunchLogic;
+
+impl LunchLogic {
+    /// Set `minutes` of lunch on pair `pair` of `date`. Without `pair` the
+    /// pair crossing `lunch_window` is used, else the last closed one.
+    /// `0` removes the lunch; other values must be within the configured
+    /// min / max lunch break and shorter than the pair.
+    pub fn set(
+        pool: &mut DbPool,
+        cfg: &Config,
+        date: NaiveDate,
+        minutes: i32,
+        pair: Option<usize>,
+    ) -> AppResult<LunchUpdate> {
+        if minutes > cfg.max_duration_lunch_break {
+            return Err(AppError::LunchTooLong {
+                minutes,
+                max: cfg.max_duration_lunch_break,
+            });
+        }
+        if minutes != 0 && minutes < cfg.min_duration_lunch_break {
+            return Err(AppError::InvalidArgs(format!(
+                "Lunch of {} min is below the configured minimum of {} min (use 0 to remove it).",
+                minutes, cfg.min_duration_lunch_break
+            )));
+        }
+
+        ensure_unlocked(&pool.conn, &date)?;
+
+        let events = load_events_by_date(pool, &date)?;
+        if events.is_empty() {
+            return Err(AppError::NoEventsForDate(date.to_string()));
+        }
+        let summary = Core::build_daily_summary(&events, cfg);
+        if Core::is_marker_day(&summary) {
+            return Err(AppError::InvalidArgs(format!(
+                "{} is not a working day: no lunch to set.",
+                date
+            )));
+        }
+
+        let window = parse_lunch_window(&cfg.lunch_window);
+        let in_window = |p: &Pair| {
+            p.out_event.as_ref().is_some_and(|out| {
+                window
+                    .is_some_and(|(ws, we)| crosses_lunch_window(p.in_event.time, out.time, ws, we))
+            })
+        };
+
+        let pairs = &summary.timeline.pairs;
+        let index = match pair {
+            Some(n) => {
+                if n == 0 || n > pairs.len() {
+                    return Err(AppError::InvalidPair(n));
+                }
+                n - 1
+            }
+            None => pairs
+                .iter()
+                .position(in_window)
+                .or_else(|| pairs.iter().rposition(|p| p.out_event.is_some()))
+                .ok_or_else(|| {
+                    AppError::InvalidArgs(format!("{} has no closed pair yet.", date))
+                })?,
+        };
+
+        let target = &pairs[index];
+        let Some(out) = &target.out_event else {
+            return Err(AppError::InvalidArgs(format!(
+                "Pair #{} of {} has no OUT yet: record it first.",
+                index + 1,
+                date
+            )));
+        };
+
+        let raw = (out.timestamp() - target.in_event.timestamp()).num_minutes();
+        if minutes as i64 >= raw {
+            return Err(AppError::InvalidArgs(format!(
+                "Lunch of {} min does not fit in pair #{} ({}-{}, {} min).",
+                minutes,
+                index + 1,
+                target.in_event.time_str(),
+                out.time_str(),
+                raw
+            )));
+        }
+
+        let tx = pool.conn.transaction()?;
+        let mut ev_in = target.in_event.clone();
+        ev_in.lunch = Some(0);
+        update_event(&tx, &ev_in)?;
+        let mut ev_out = out.clone();
+        ev_out.lunch = Some(minutes);
+        update_event(&tx, &ev_out)?;
+        recalc_pairs_for_date(&tx, &date, cfg.overlap_mode)?;
+        tx.commit()?;
+
+        let events = load_events_by_date(pool, &date)?;
+        let after = Core::build_daily_summary(&events, cfg);
+
+        Ok(LunchUpdate {
+            pair: index + 1,
+            start: target.in_event.time,
+            end: out.time,
+            previous: target.lunch_minutes,
+            in_window: in_window(target),
+            figures: Core::day_figures(&after, &events, cfg),
+        })
+    }
+}
@@ -0,0 +1,122 @@
+//! Day bookmarks (`rtimelogger mark 2025-06-18 "release day"`): shown in
+//! `list` and usable as period anchors, e.g. `--period mark:release-day:today`.
+
+use crate::config::Config;
+use crate::db::pool::DbPool;
+use crate::db::queries::marks::{Mark, delete_mark, find_mark, insert_mark, load_marks};
+use crate::errors::{AppError, AppResult};
+use chrono::NaiveDate;
+use rusqlite::Connection;
+
+/// Prefix of a bookmark inside a period expression.
+const MARK_PREFIX: &str = "mark";
+
+/// Name of a label in `mark:<name>`: lowercase, words joined by `-`
+/// ("Release day v2" → "release-day-v2").
+pub fn mark_name(label: &str) -> String {
+    label
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+pub struct MarkLogic;
+
+impl MarkLogic {
+    /// Bookmark `date` with `label`. Names are unique: the same label cannot
+    /// anchor two days.
+    pub fn add(pool: &DbPool, date: NaiveDate, label: &str) -> AppResult<Mark> {
+        let label = label.trim();
+        let name = mark_name(label);
+        if name.is_empty() {
+            return Err(AppError::InvalidArgs(
+                "The bookmark label needs at least a letter or a digit.".into(),
+            ));
+        }
+        if let Some(existing) = find_mark(&pool.conn, &name)? {
+            return Err(AppError::InvalidArgs(format!(
+                "Bookmark '{}' already exists on {}: remove it with `mark --delete {}` or choose another label.",
+                name, existing.date, name
+            )));
+        }
+
+        insert_mark(&pool.conn, &date, label, &name)?;
+        find_mark(&pool.conn, &name)?
+            .ok_or_else(|| AppError::Other(format!("Bookmark '{}' not saved.", name)))
+    }
+
+    /// Remove the bookmark `name` (a label is accepted too).
+    pub fn delete(pool: &DbPool, name: &str) -> AppResult<bool> {
+        delete_mark(&pool.conn, &mark_name(name))
+    }
+
+    /// Bookmarks of `[from, to]` (all with None).
+    pub fn list(pool: &DbPool, bounds: Option<(NaiveDate, NaiveDate)>) -> AppResult<Vec<Mark>> {
+        load_marks(&pool.conn, bounds)
+    }
+}
+
+/// Replace the `mark:<name>` anchors of a period expression with their date
+/// (`mark:release-day:today` → `2025-06-18:today`).
+pub fn expand_marks(conn: &Connection, expr: &str) -> AppResult<String> {
+    let mut out = Vec::new();
+    let mut tokens = expr.split(':').map(str::trim);
+    while let Some(t) = tokens.next() {
+        if !t.eq_ignore_ascii_case(MARK_PREFIX) {
+            out.push(t.to_string());
+            continue;
+        }
+        let name = tokens.next().map(mark_name).unwrap_or_default();
+        match find_mark(conn, &name)? {
+            Some(m) => out.push(m.date.to_string()),
+            None => {
+                return Err(AppError::InvalidPeriod {
+                    input: expr.to_string(),
+                    hint: format!(
+                        "no bookmark named '{}' (see `rtimelogger mark --list`)",
+                        name
+                    ),
+                });
+            }
+        }
+    }
+    Ok(out.join(":"))
+}
+
+/// [`expand_marks`] for a `--period` value; the database is opened only
+/// when the expression refers to a bookmark.
+pub fn expand_period(cfg: &Config, expr: &str) -> AppResult<String> {
+    let has_mark = expr
+        .split(':')
+        .any(|t| t.trim().eq_ignore_ascii_case(MARK_PREFIX));
+    if !has_mark {
+        return Ok(expr.to_string());
+    }
+    let pool = DbPool::new(&cfg.database)?;
+    expand_marks(&pool.conn, expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::MEMORY_DB;
+
+    #[test]
+    fn marks_as_period_anchors() {
+        assert_eq!(mark_name("  Release day v2! "), "release-day-v2");
+
+        let pool = DbPool::new(MEMORY_DB).unwrap();
+        let d = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+        MarkLogic::add(&pool, d, "Release day").unwrap();
+        assert!(MarkLogic::add(&pool, d, "release DAY").is_err());
+
+        assert_eq!(
+            expand_marks(&pool.conn, "mark:release-day:2025-06").unwrap(),
+            "2025-06-18:2025-06"
+        );
+        assert_eq!(expand_marks(&pool.conn, "2025-06").unwrap(), "2025-06");
+        assert!(expand_marks(&pool.conn, "mark:unknown").is_err());
+    }
+}
@@ -1,9 +1,55 @@
+// Pure calculation layer: also built without the `cli` feature (wasm32)
+pub mod calculator;
+pub mod compliance;
+pub mod logic;
+pub mod shift;
+
+// Database, files, processes and terminal
+#[cfg(feature = "cli")]
 pub mod add;
+#[cfg(feature = "cli")]
+pub mod archive;
+#[cfg(feature = "cli")]
 pub mod backup;
+#[cfg(feature = "cli")]
+pub mod closeout;
+#[cfg(feature = "cli")]
 pub mod config;
+#[cfg(feature = "cli")]
 pub mod del;
-
-pub mod calculator;
+#[cfg(feature = "cli")]
+pub mod find;
+#[cfg(feature = "cli")]
 pub mod importer;
+#[cfg(feature = "cli")]
+pub mod invoice;
+#[cfg(feature = "cli")]
 pub mod log;
-pub mod logic;
+#[cfg(feature = "cli")]
+pub mod lunch;
+#[cfg(feature = "cli")]
+pub mod mark;
+#[cfg(feature = "cli")]
+pub mod plugin;
+#[cfg(feature = "cli")]
+pub mod policy;
+#[cfg(feature = "cli")]
+pub mod position_rules;
+#[cfg(feature = "cli")]
+pub mod predict;
+#[cfg(feature = "cli")]
+pub mod recompute;
+#[cfg(feature = "cli")]
+pub mod reminder;
+#[cfg(feature = "cli")]
+pub mod report;
+#[cfg(feature = "cli")]
+pub mod seed;
+#[cfg(feature = "cli")]
+pub mod serve;
+#[cfg(feature = "cli")]
+pub mod service;
+#[cfg(feature = "cli")]
+pub mod status;
+#[cfg(feature = "cli")]
+pub mod toil;
@@ -0,0 +1,136 @@
+//! External subcommands (git-style plugins): `rtimelogger foo ARGS` runs the
+//! first `rtimelogger-foo` executable found on PATH with `ARGS`. The resolved
+//! settings are passed in the environment, so a plugin reads the same
+//! database and config as the built-in commands.
+
+use crate::config::{CONFIG_DIR_ENV, Config};
+use crate::errors::{AppError, AppResult};
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefix of plugin executables.
+pub const PLUGIN_PREFIX: &str = "rtimelogger-";
+
+/// Database in use (after `--db` and the `databases` names are resolved).
+pub const DB_ENV: &str = "RTIMELOGGER_DB";
+/// Config file (may not exist yet).
+pub const CONFIG_FILE_ENV: &str = "RTIMELOGGER_CONFIG";
+/// The rtimelogger binary, to call it back (`$RTIMELOGGER_BIN list --period ...`).
+pub const BIN_ENV: &str = "RTIMELOGGER_BIN";
+/// Version of the calling rtimelogger.
+pub const VERSION_ENV: &str = "RTIMELOGGER_VERSION";
+/// "1" with `--test`.
+pub const TEST_ENV: &str = "RTIMELOGGER_TEST";
+/// "1" when plain ASCII output is active.
+pub const NO_EMOJI_ENV: &str = "RTIMELOGGER_NO_EMOJI";
+
+pub struct PluginLogic;
+
+impl PluginLogic {
+    /// `rtimelogger-<name>` in the PATH directories, in order.
+    pub fn find(name: &str) -> Option<PathBuf> {
+        let path = env::var_os("PATH")?;
+        Self::find_in(name, env::split_paths(&path))
+    }
+
+    fn find_in(name: &str, dirs: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+        let file = format!("{}{}", PLUGIN_PREFIX, name);
+        dirs.into_iter()
+            .flat_map(|dir| {
+                let mut candidates = vec![dir.join(&file)];
+                if cfg!(target_os = "windows") {
+                    candidates.insert(0, dir.join(format!("{}.exe", file)));
+                    candidates.push(dir.join(format!("{}.cmd", file)));
+                }
+                candidates
+            })
+            .find(|p| is_executable(p))
+    }
+
+    /// Variables set for the plugin process.
+    pub fn env(cfg: &Config, ascii: bool) -> Vec<(&'static str, String)> {
+        let flag = |b: bool| if b { "1" } else { "0" }.to_string();
+        let mut vars = vec![
+            (DB_ENV, cfg.database.clone()),
+            (
+                CONFIG_FILE_ENV,
+                Config::config_file().to_string_lossy().to_string(),
+            ),
+            (
+                CONFIG_DIR_ENV,
+                Config::config_dir().to_string_lossy().to_string(),
+            ),
+            (VERSION_ENV, env!("CARGO_PKG_VERSION").to_string()),
+            (TEST_ENV, flag(cfg.test_mode)),
+            (NO_EMOJI_ENV, flag(ascii)),
+        ];
+        if let Ok(exe) = env::current_exe() {
+            vars.push((BIN_ENV, exe.to_string_lossy().to_string()));
+        }
+        vars
+    }
+
+    /// Run the plugin for `args` (`args[0]` is the subcommand name) and
+    /// return its exit code.
+    pub fn run(cfg: &Config, args: &[OsString], ascii: bool) -> AppResult<i32> {
+        let Some(name) = args.first().and_then(|a| a.to_str()) else {
+            return Err(AppError::InvalidArgs("Missing command.".into()));
+        };
+
+        let Some(exe) = Self::find(name) else {
+            return Err(AppError::InvalidArgs(format!(
+                "Unknown command '{}' (no {}{} on PATH). See `rtimelogger --help`.",
+                name, PLUGIN_PREFIX, name
+            )));
+        };
+
+        let status = Command::new(&exe)
+            .args(&args[1..])
+            .envs(Self::env(cfg, ascii))
+            .status()?;
+
+        // Killed by a signal: no code, report a generic failure
+        Ok(status.code().unwrap_or(1))
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(p: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    p.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(p: &Path) -> bool {
+    p.is_file()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn first_executable_on_path_wins() {
+        let root = env::temp_dir().join(format!("rtl-plugin-{}", std::process::id()));
+        let (a, b) = (root.join("a"), root.join("b"));
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+
+        // Not executable in `a`, executable in `b`
+        fs::write(a.join("rtimelogger-hello"), "#!/bin/sh\n").unwrap();
+        let exe = b.join("rtimelogger-hello");
+        fs::write(&exe, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let dirs = || vec![a.clone(), b.clone()];
+        assert_eq!(PluginLogic::find_in("hello", dirs()), Some(exe));
+        assert_eq!(PluginLogic::find_in("missing", dirs()), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
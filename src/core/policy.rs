@@ -0,0 +1,185 @@
+//! `rtimelogger policy <DATE>`: the rules the calculations apply to one day,
+//! to explain its figures (e.g. a surplus that looks wrong).
+//!
+//! There are no per-weekday schedules nor dated overrides: every day is
+//! computed with the current config, past days included. What depends on the
+//! date is the kind of day, the shift bands of its weekday, the lock and what
+//! its events say (recorded lunch, `--no-auto-lunch`).
+
+use crate::config::Config;
+use crate::core::calculator::expected::{AUTO_LUNCH_KEY, auto_lunch};
+use crate::core::calculator::overlap::OverlapMode;
+use crate::core::compliance::{mandatory_lunch_after, max_daily_work};
+use crate::core::logic::Core;
+use crate::db::pool::DbPool;
+use crate::db::queries::load_events_by_date;
+use crate::db::queries::locks::is_locked;
+use crate::errors::AppResult;
+use crate::models::location::Location;
+use crate::utils::date::{get_day_position, is_weekend};
+use crate::utils::formatting::SurplusFormat;
+use crate::utils::meta::meta_value;
+use crate::utils::time::parse_lunch_window;
+use chrono::{Datelike, NaiveDate, Weekday};
+
+/// How the lunch of the day is counted in the expected time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LunchRule {
+    /// No events yet: `min_duration_lunch_break` is added when the first IN
+    /// comes before the end of `lunch_window`
+    Pending,
+    /// Lunch recorded on the first pair
+    Recorded(i64),
+    /// Added automatically (`min_duration_lunch_break`)
+    Automatic(i64),
+    /// `--no-auto-lunch` on one of the events
+    OptedOut,
+    /// First IN after the end of `lunch_window`
+    AfterWindow,
+    /// Holiday / sick leave: nothing expected
+    NotApplicable,
+}
+
+#[derive(Debug, Clone)]
+pub struct DayPolicy {
+    pub date: NaiveDate,
+    pub weekday: Weekday,
+    /// Marker of the day (holiday, national holiday, sick leave)
+    pub marker: Option<Location>,
+    pub weekend: bool,
+    pub locked: bool,
+    pub events: usize,
+    /// Minutes of work required (lunch excluded); 0 on marker days
+    pub expected_work: i64,
+    pub lunch_window: String,
+    /// False when `lunch_window` is not HH:MM-HH:MM
+    pub lunch_window_valid: bool,
+    pub min_lunch: i32,
+    pub max_lunch: i32,
+    pub lunch: LunchRule,
+    pub mandatory_lunch_after: Option<i64>,
+    pub max_daily_work: Option<i64>,
+    pub enforce_max_daily_work: bool,
+    pub decimal_step: f64,
+    pub surplus_format: SurplusFormat,
+    pub default_position: String,
+    pub position_rules: usize,
+    /// `name ×multiplier` of the shift bands applying on this weekday
+    pub shift_bands: Vec<String>,
+    pub overlap_mode: OverlapMode,
+}
+
+pub struct PolicyLogic;
+
+impl PolicyLogic {
+    pub fn for_date(pool: &mut DbPool, cfg: &Config, date: NaiveDate) -> AppResult<DayPolicy> {
+        let events = load_events_by_date(pool, &date)?;
+        let summary = Core::build_daily_summary(&events, cfg);
+
+        let marker = Core::is_marker_day(&summary).then(|| get_day_position(&summary.timeline));
+
+        let lunch = if marker.is_some() {
+            LunchRule::NotApplicable
+        } else if let Some(first) = summary.timeline.pairs.first() {
+            if let Some(m) = auto_lunch(&summary.timeline, cfg) {
+                LunchRule::Automatic(m)
+            } else if first.lunch_minutes > 0 {
+                LunchRule::Recorded(first.lunch_minutes)
+            } else if events
+                .iter()
+                .any(|ev| meta_value(ev.meta.as_deref(), AUTO_LUNCH_KEY) == "false")
+            {
+                LunchRule::OptedOut
+            } else {
+                LunchRule::AfterWindow
+            }
+        } else {
+            LunchRule::Pending
+        };
+
+        let weekday = date.weekday();
+        let shift_bands = cfg
+            .shift_bands
+            .iter()
+            .filter(|b| {
+                b.days.is_empty()
+                    || b.days
+                        .iter()
+                        .any(|d| d.trim().parse::<Weekday>().ok() == Some(weekday))
+            })
+            .map(|b| {
+                let name = if b.name.is_empty() { "band" } else { &b.name };
+                match (&b.from, &b.to) {
+                    (Some(from), Some(to)) => {
+                        format!("{} {}-{} ×{}", name, from, to, b.multiplier)
+                    }
+                    _ => format!("{} ×{}", name, b.multiplier),
+                }
+            })
+            .collect();
+
+        Ok(DayPolicy {
+            date,
+            weekday,
+            marker,
+            weekend: is_weekend(date),
+            locked: is_locked(&pool.conn, &date)?,
+            events: events.len(),
+            expected_work: if marker.is_some() {
+                0
+            } else {
+                Core::parse_work_duration_to_minutes(&cfg.min_work_duration)
+            },
+            lunch_window: cfg.lunch_window.clone(),
+            lunch_window_valid: parse_lunch_window(&cfg.lunch_window).is_some(),
+            min_lunch: cfg.min_duration_lunch_break,
+            max_lunch: cfg.max_duration_lunch_break,
+            lunch,
+            mandatory_lunch_after: mandatory_lunch_after(cfg),
+            max_daily_work: max_daily_work(cfg),
+            enforce_max_daily_work: cfg.enforce_max_daily_work,
+            decimal_step: cfg.decimal_step,
+            surplus_format: cfg.surplus_format,
+            default_position: cfg.default_position.clone(),
+            position_rules: cfg.position_rules.len(),
+            shift_bands,
+            overlap_mode: cfg.overlap_mode,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ShiftBand;
+
+    #[test]
+    fn lunch_rule_and_bands_of_the_day() {
+        let mut pool = DbPool::new(":memory:").unwrap();
+        let cfg = Config {
+            shift_bands: vec![ShiftBand {
+                name: "sunday".into(),
+                days: vec!["sun".into()],
+                multiplier: 1.5,
+                ..Default::default()
+            }],
+            ..Config::default()
+        };
+
+        // 2025-06-15 is a Sunday without events
+        let sunday = NaiveDate::from_ymd_opt(2025, 6, 15).unwrap();
+        let p = PolicyLogic::for_date(&mut pool, &cfg, sunday).unwrap();
+        assert!(p.weekend);
+        assert_eq!(p.lunch, LunchRule::Pending);
+        assert_eq!(p.expected_work, 480);
+        assert_eq!(p.shift_bands, vec!["sunday ×1.5".to_string()]);
+
+        let monday = sunday.succ_opt().unwrap();
+        assert!(
+            PolicyLogic::for_date(&mut pool, &cfg, monday)
+                .unwrap()
+                .shift_bands
+                .is_empty()
+        );
+    }
+}
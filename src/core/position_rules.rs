@@ -0,0 +1,100 @@
+//! Default position of `add` from local hints (`[[position_rules]]`).
+//!
+//! Only the Wi-Fi SSID and the hostname of this machine are looked at, both
+//! read locally; `--wifi-ssid` / `--hostname` replace the detected values.
+
+use crate::config::PositionRule;
+use crate::errors::{AppError, AppResult};
+use crate::models::location::Location;
+use crate::utils::host;
+
+/// What the rules are matched against.
+#[derive(Debug, Default, Clone)]
+pub struct LocationHint {
+    pub ssid: Option<String>,
+    pub hostname: Option<String>,
+}
+
+impl LocationHint {
+    /// Detect the missing values; the SSID is only probed when a rule needs it.
+    pub fn detect(rules: &[PositionRule], ssid: Option<String>, hostname: Option<String>) -> Self {
+        let needs_ssid = rules.iter().any(|r| r.ssid.is_some());
+        Self {
+            ssid: ssid.or_else(|| needs_ssid.then(host::wifi_ssid).flatten()),
+            hostname: hostname.or_else(host::hostname),
+        }
+    }
+}
+
+/// A rule that matched: its position and 1-based index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuleMatch {
+    pub position: Location,
+    pub rule: usize,
+}
+
+/// First rule whose conditions all hold (SSID exact, hostname case-insensitive).
+pub fn resolve(rules: &[PositionRule], hint: &LocationHint) -> AppResult<Option<RuleMatch>> {
+    for (i, rule) in rules.iter().enumerate() {
+        let ssid_ok = rule
+            .ssid
+            .as_deref()
+            .is_none_or(|s| hint.ssid.as_deref() == Some(s));
+        let host_ok = rule.hostname.as_deref().is_none_or(|h| {
+            hint.hostname
+                .as_deref()
+                .is_some_and(|cur| cur.eq_ignore_ascii_case(h))
+        });
+
+        if ssid_ok && host_ok {
+            let position = Location::from_code(&rule.position).ok_or_else(|| {
+                AppError::InvalidPosition(format!(
+                    "Invalid position '{}' in position_rules #{}",
+                    rule.position,
+                    i + 1
+                ))
+            })?;
+            return Ok(Some(RuleMatch {
+                position,
+                rule: i + 1,
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(ssid: Option<&str>, hostname: Option<&str>, position: &str) -> PositionRule {
+        PositionRule {
+            ssid: ssid.map(str::to_string),
+            hostname: hostname.map(str::to_string),
+            position: position.to_string(),
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = vec![
+            rule(Some("OfficeNet"), None, "O"),
+            rule(None, Some("client-laptop"), "C"),
+            rule(None, None, "R"),
+        ];
+        let hint = |ssid: Option<&str>, host: &str| LocationHint {
+            ssid: ssid.map(str::to_string),
+            hostname: Some(host.to_string()),
+        };
+
+        let pos = |h: &LocationHint| resolve(&rules, h).unwrap().map(|m| m.position);
+        assert_eq!(pos(&hint(Some("OfficeNet"), "x")), Some(Location::Office));
+        assert_eq!(
+            pos(&hint(Some("Home"), "Client-Laptop")),
+            Some(Location::OnSite)
+        );
+        assert_eq!(pos(&hint(None, "x")), Some(Location::Remote));
+        assert_eq!(resolve(&rules[..1], &hint(None, "x")).unwrap(), None);
+    }
+}
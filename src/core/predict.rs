@@ -0,0 +1,213 @@
+//! Exit-time prediction (`rtimelogger predict`, `Pred` line of `status`).
+//!
+//! Deliberately simple model: how long after the contractual target exit
+//! (TGT) the user actually left on the closed days of the last N weeks.
+//! The median of that delay (same weekday when there is enough data, all
+//! days otherwise) is added to today's TGT; the week total adds, for every
+//! remaining weekday usually worked, the median worked time of that weekday.
+
+use crate::config::Config;
+use crate::core::logic::Core;
+use crate::db::pool::DbPool;
+use crate::db::queries::load_events_by_date;
+use crate::errors::AppResult;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Weekday};
+
+/// Default history window of `predict`.
+pub const DEFAULT_WEEKS: u32 = 8;
+
+/// Minimum same-weekday samples before the model stops using all days.
+const MIN_WEEKDAY_SAMPLES: usize = 3;
+
+/// A closed working day of the history.
+#[derive(Debug, Clone, Copy)]
+struct DaySample {
+    weekday: Weekday,
+    /// Minutes between the target exit and the last OUT (negative = earlier)
+    delay: i64,
+    worked: i64,
+}
+
+#[derive(Debug)]
+pub struct Prediction {
+    pub date: NaiveDate,
+    pub weeks: u32,
+    /// Days the exit model is based on
+    pub samples: usize,
+    /// Model restricted to today's weekday
+    pub same_weekday: bool,
+    pub avg_delay: Option<i64>,
+    pub median_delay: Option<i64>,
+    /// Contractual target exit for today (needs today's first IN)
+    pub target_exit: Option<NaiveTime>,
+    /// Likely exit for today
+    pub exit: Option<NaiveTime>,
+    /// Worked / expected minutes of the closed days of the week before today
+    pub week_worked: i64,
+    pub week_expected: i64,
+    /// Likely total of the whole week (Mon..Sun)
+    pub week_predicted: i64,
+    pub week_predicted_expected: i64,
+}
+
+pub struct PredictLogic;
+
+impl PredictLogic {
+    /// Predict today's exit and the week total from the `weeks` weeks before `now`.
+    pub fn compute(
+        pool: &mut DbPool,
+        cfg: &Config,
+        now: DateTime<Local>,
+        weeks: u32,
+    ) -> AppResult<Prediction> {
+        let date = now.date_naive();
+        let week_start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        let history_start = (date - Duration::weeks(weeks as i64)).min(week_start);
+        let expected = Core::parse_work_duration_to_minutes(&cfg.min_work_duration);
+
+        let mut history = Vec::new();
+        let mut week_worked = 0;
+        let mut week_expected = 0;
+
+        let mut day = history_start;
+        while day < date {
+            if let Some(s) = closed_day(pool, cfg, day)? {
+                if day >= date - Duration::weeks(weeks as i64) {
+                    history.push(s);
+                }
+                if day >= week_start {
+                    week_worked += s.worked;
+                    week_expected += expected;
+                }
+            }
+            day = match day.succ_opt() {
+                Some(n) => n,
+                None => break,
+            };
+        }
+
+        // Today's target exit, if today is a working day already started
+        let events = load_events_by_date(pool, &date)?;
+        let summary = Core::build_daily_summary(&events, cfg);
+        let target_exit = if events.is_empty() || Core::is_marker_day(&summary) {
+            None
+        } else {
+            Core::day_figures(&summary, &events, cfg).map(|f| f.target_exit.time())
+        };
+
+        let same: Vec<i64> = history
+            .iter()
+            .filter(|s| s.weekday == date.weekday())
+            .map(|s| s.delay)
+            .collect();
+        let same_weekday = same.len() >= MIN_WEEKDAY_SAMPLES;
+        let delays: Vec<i64> = if same_weekday {
+            same
+        } else {
+            history.iter().map(|s| s.delay).collect()
+        };
+
+        let median_delay = median(&delays);
+        let avg_delay =
+            (!delays.is_empty()).then(|| delays.iter().sum::<i64>() / delays.len() as i64);
+        let exit = target_exit
+            .zip(median_delay)
+            .map(|(t, d)| t + Duration::minutes(d));
+
+        // Week: closed days + today + remaining weekdays usually worked
+        let mut week_predicted = week_worked;
+        let mut week_predicted_expected = week_expected;
+
+        if target_exit.is_some() {
+            week_predicted += expected + median_delay.unwrap_or(0);
+            week_predicted_expected += expected;
+        }
+        let mut next = date;
+        while let Some(d) = next.succ_opt() {
+            if d.weekday() == Weekday::Mon {
+                break;
+            }
+            let worked: Vec<i64> = history
+                .iter()
+                .filter(|s| s.weekday == d.weekday())
+                .map(|s| s.worked)
+                .collect();
+            if let Some(m) = median(&worked) {
+                week_predicted += m;
+                week_predicted_expected += expected;
+            }
+            next = d;
+        }
+
+        Ok(Prediction {
+            date,
+            weeks,
+            samples: delays.len(),
+            same_weekday,
+            avg_delay,
+            median_delay,
+            target_exit,
+            exit,
+            week_worked,
+            week_expected,
+            week_predicted,
+            week_predicted_expected,
+        })
+    }
+}
+
+/// Delay and worked time of a closed working day (None for empty, marker
+/// or still open days).
+fn closed_day(pool: &mut DbPool, cfg: &Config, day: NaiveDate) -> AppResult<Option<DaySample>> {
+    let events = load_events_by_date(pool, &day)?;
+    if events.is_empty() {
+        return Ok(None);
+    }
+
+    let summary = Core::build_daily_summary(&events, cfg);
+    if Core::is_marker_day(&summary)
+        || summary
+            .timeline
+            .pairs
+            .last()
+            .is_some_and(|p| p.out_event.is_none())
+    {
+        return Ok(None);
+    }
+
+    Ok(Core::day_figures(&summary, &events, cfg).and_then(|f| {
+        Some(DaySample {
+            weekday: day.weekday(),
+            delay: f.delta?,
+            worked: f.worked?,
+        })
+    }))
+}
+
+/// Median of a list of minutes (mean of the two middle values, rounded down).
+fn median(values: &[i64]) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut v = values.to_vec();
+    v.sort_unstable();
+    let mid = v.len() / 2;
+    Some(if v.len() % 2 == 0 {
+        (v[mid - 1] + v[mid]).div_euclid(2)
+    } else {
+        v[mid]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_odd_and_even_lists() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[30, -10, 5]), Some(5));
+        assert_eq!(median(&[10, 0, 20, 40]), Some(15));
+        assert_eq!(median(&[-5, 0]), Some(-3));
+    }
+}
@@ -0,0 +1,85 @@
+//! Hooks for library users writing to the `events` table directly (GUI,
+//! server, sync tools). The `pair` / `unmatched` columns are derived from the
+//! events of the day and every CLI command keeps them in line; code changing
+//! events through its own SQL must call [`on_events_changed`] afterwards,
+//! otherwise `list`, the exports and the day summaries see stale pairs.
+//!
+//! Day summaries and figures are never cached: `Core::build_daily_summary`
+//! recomputes them from the events, so the pair columns are the only state
+//! to refresh.
+
+use crate::core::calculator::overlap::OverlapMode;
+use crate::db::queries::pairs::rebuild_pairs_for_date;
+use crate::errors::AppResult;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::collections::BTreeSet;
+
+/// Recompute the derived columns of `date` after its events were inserted,
+/// updated or deleted. Never fails on a broken IN / OUT sequence: dangling
+/// events are flagged `unmatched`, as after `del`. When an event moves to
+/// another day, call it for both days (or use [`on_dates_changed`]).
+/// `mode` is the `overlap_mode` of the config.
+pub fn on_events_changed(conn: &Connection, date: NaiveDate, mode: OverlapMode) -> AppResult<()> {
+    rebuild_pairs_for_date(conn, &date, mode)
+}
+
+/// [`on_events_changed`] for every distinct date of `dates`, in one
+/// transaction when the connection is not already inside one.
+pub fn on_dates_changed(
+    conn: &Connection,
+    dates: &[NaiveDate],
+    mode: OverlapMode,
+) -> AppResult<()> {
+    let dates: BTreeSet<NaiveDate> = dates.iter().copied().collect();
+
+    let own_tx = conn.is_autocommit();
+    if own_tx {
+        conn.execute_batch("BEGIN")?;
+    }
+
+    for d in &dates {
+        if let Err(e) = rebuild_pairs_for_date(conn, d, mode) {
+            if own_tx {
+                let _ = conn.execute_batch("ROLLBACK");
+            }
+            return Err(e);
+        }
+    }
+
+    if own_tx {
+        conn.execute_batch("COMMIT")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::{DbPool, MEMORY_DB};
+
+    #[test]
+    fn pairs_follow_events_written_with_plain_sql() {
+        let pool = DbPool::new(MEMORY_DB).unwrap();
+        let conn = &pool.conn;
+        conn.execute_batch(
+            "INSERT INTO events (date, time, kind, position, created_at) VALUES
+                 ('2025-06-18', '08:00', 'in', 'O', ''),
+                 ('2025-06-18', '12:00', 'out', 'O', ''),
+                 ('2025-06-18', '13:00', 'in', 'O', '');",
+        )
+        .unwrap();
+
+        let d = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+        on_dates_changed(conn, &[d, d], OverlapMode::Off).unwrap();
+
+        let rows: Vec<(i64, i64)> = conn
+            .prepare("SELECT pair, unmatched FROM events ORDER BY time")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(rows, vec![(1, 0), (1, 0), (2, 1)]);
+    }
+}
@@ -0,0 +1,81 @@
+//! Reminder of the days left with an open pair (an IN never closed by its
+//! OUT), printed after a command so that a forgotten exit is fixed the next
+//! day instead of at month end. The check runs at most once a day: the day
+//! of the last check is stored in the metadata table.
+
+use crate::config::Config;
+use crate::db::metadata::{KEY_DANGLING_CHECKED_ON, get_meta, set_meta};
+use crate::db::pool::{DbPool, is_memory_db};
+use crate::db::queries::events::dangling_in_days;
+use crate::errors::AppResult;
+use crate::ui::messages::warning;
+use chrono::{Local, NaiveDate};
+use std::path::Path;
+
+/// Days before `today` still open, or None when already checked today.
+/// Records today as checked.
+pub fn due_open_pairs(
+    pool: &DbPool,
+    today: NaiveDate,
+) -> AppResult<Option<Vec<(NaiveDate, String)>>> {
+    let today_str = today.to_string();
+    if get_meta(&pool.conn, KEY_DANGLING_CHECKED_ON)?.as_deref() == Some(today_str.as_str()) {
+        return Ok(None);
+    }
+
+    let days = dangling_in_days(&pool.conn, today)?;
+    set_meta(&pool.conn, KEY_DANGLING_CHECKED_ON, &today_str)?;
+    Ok(Some(days))
+}
+
+/// One-line warning for the open days (None when there are none).
+pub fn reminder_line(days: &[(NaiveDate, String)]) -> Option<String> {
+    let (date, time) = days.last()?;
+    let others = match days.len() - 1 {
+        0 => String::new(),
+        n => format!(" and {} earlier day(s)", n),
+    };
+    Some(format!(
+        "⚠️  {} still has an open IN at {}{}: close it with `rtimelogger add {} --out HH:MM`.",
+        date, time, others, date
+    ))
+}
+
+/// Print the reminder when due. Never fails: a missing or unreadable
+/// database simply skips the check.
+pub fn remind_open_pairs(cfg: &Config) {
+    if is_memory_db(&cfg.database) || !Path::new(&cfg.database).exists() {
+        return;
+    }
+    let Ok(pool) = DbPool::new(&cfg.database) else {
+        return;
+    };
+    if let Ok(Some(days)) = due_open_pairs(&pool, Local::now().date_naive())
+        && let Some(line) = reminder_line(&days)
+    {
+        warning(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_once_a_day() {
+        let pool = DbPool::new(":memory:").unwrap();
+        pool.conn
+            .execute(
+                "INSERT INTO events (date, time, kind, position, lunch_break, pair, unmatched, created_at)
+                 VALUES ('2025-06-17', '08:30', 'in', 'O', 0, 1, 1, '2025-06-17T08:30:00')",
+                [],
+            )
+            .unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+
+        let days = due_open_pairs(&pool, today).unwrap().unwrap();
+        assert_eq!(days.len(), 1);
+        assert!(reminder_line(&days).unwrap().contains("2025-06-17"));
+        assert_eq!(due_open_pairs(&pool, today).unwrap(), None);
+    }
+}
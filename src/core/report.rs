@@ -0,0 +1,810 @@
+//! Aggregated reports over a period (`rtimelogger report`).
+
+use crate::config::Config;
+use crate::core::calculator::expected::auto_lunch;
+use crate::core::compliance::{BreakViolation, break_violations};
+use crate::core::invoice::{InvoiceLogic, round_cents};
+use crate::core::logic::Core;
+use crate::core::shift::ShiftBands;
+use crate::db::pool::DbPool;
+use crate::db::queries::focus::focus_sessions_by_date;
+use crate::db::queries::load_events_by_date;
+use crate::errors::{AppError, AppResult};
+use crate::models::event_type::EventType;
+use crate::models::location::Location;
+use crate::utils::date::get_day_position;
+use crate::utils::formatting::{mins2readable, round_hours};
+use crate::utils::period::Period;
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike, Weekday};
+use std::collections::BTreeMap;
+
+/// Worked time from which a day without lunch is reported.
+const LONG_DAY_MINUTES: i64 = 6 * 60;
+
+/// Closed days needed before worked-time outliers are computed.
+const MIN_OUTLIER_DAYS: usize = 5;
+
+/// Average / min / max of clock times (minutes from midnight).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TimeStats {
+    count: u32,
+    sum: u32,
+    min: u32,
+    max: u32,
+}
+
+impl TimeStats {
+    fn push(&mut self, t: NaiveTime) {
+        let m = t.hour() * 60 + t.minute();
+        if self.count == 0 {
+            self.min = m;
+            self.max = m;
+        } else {
+            self.min = self.min.min(m);
+            self.max = self.max.max(m);
+        }
+        self.count += 1;
+        self.sum += m;
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn avg(&self) -> Option<NaiveTime> {
+        (self.count > 0).then(|| to_time((self.sum + self.count / 2) / self.count))
+    }
+
+    pub fn min(&self) -> Option<NaiveTime> {
+        (self.count > 0).then(|| to_time(self.min))
+    }
+
+    pub fn max(&self) -> Option<NaiveTime> {
+        (self.count > 0).then(|| to_time(self.max))
+    }
+}
+
+fn to_time(mins: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(mins / 60, mins % 60, 0).unwrap_or(NaiveTime::MIN)
+}
+
+/// First IN / last OUT statistics of one weekday.
+#[derive(Debug, Clone)]
+pub struct WeekdayRow {
+    pub weekday: Weekday,
+    pub first_in: TimeStats,
+    pub last_out: TimeStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// Worked time beyond 2 standard deviations from the period mean
+    Outlier,
+    /// Long day without any lunch break
+    MissingLunch,
+    /// Values that cannot happen (negative durations or lunch)
+    Impossible,
+}
+
+impl AnomalyKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            AnomalyKind::Outlier => "outlier",
+            AnomalyKind::MissingLunch => "no lunch",
+            AnomalyKind::Impossible => "impossible",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Anomaly {
+    pub date: NaiveDate,
+    pub kind: AnomalyKind,
+    pub detail: String,
+}
+
+/// Worked / expected minutes of the closed working days of a period.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PeriodTotals {
+    pub days: u32,
+    pub worked: i64,
+    pub expected: i64,
+}
+
+impl PeriodTotals {
+    pub fn surplus(&self) -> i64 {
+        self.worked - self.expected
+    }
+
+    pub fn merge(&mut self, other: &PeriodTotals) {
+        self.days += other.days;
+        self.worked += other.worked;
+        self.expected += other.expected;
+    }
+}
+
+/// Days and worked minutes of one position (`--by-position`).
+#[derive(Debug, Clone)]
+pub struct PositionRow {
+    pub location: Location,
+    /// Days whose pairs all have this position
+    pub days: u32,
+    /// Net minutes of the closed pairs with this position (0 for Mixed and
+    /// the non-working markers)
+    pub minutes: i64,
+}
+
+/// Figures of one period for `report --compare`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PeriodProfile {
+    pub totals: PeriodTotals,
+    /// Closed working days spent entirely in the office / on site
+    pub office: u32,
+    /// Closed working days spent entirely in remote
+    pub remote: u32,
+    /// First IN of the closed working days
+    pub first_in: TimeStats,
+}
+
+impl PeriodProfile {
+    /// Office days as a percentage of office + remote days.
+    pub fn office_share(&self) -> Option<i64> {
+        let n = self.office + self.remote;
+        (n > 0).then(|| (self.office as i64 * 100 + n as i64 / 2) / n as i64)
+    }
+
+    /// Average worked minutes per closed day.
+    pub fn avg_worked(&self) -> Option<i64> {
+        (self.totals.days > 0).then(|| self.totals.worked / self.totals.days as i64)
+    }
+}
+
+/// Focused time of one day (`focus start` / `stop`), next to the worked time.
+#[derive(Debug, Clone)]
+pub struct FocusDay {
+    pub date: NaiveDate,
+    pub sessions: usize,
+    pub focused: i64,
+    /// Worked minutes of the day (None for open or marker days)
+    pub worked: Option<i64>,
+    /// Distinct labels, in order of first use
+    pub labels: Vec<String>,
+}
+
+/// Billable hours and amount of one project in one month (`--earnings`).
+#[derive(Debug, Clone)]
+pub struct EarningsRow {
+    /// "2025-06"
+    pub month: String,
+    pub project: String,
+    pub hours: f64,
+    /// None when the project is not listed under `rates`
+    pub rate: Option<f64>,
+    pub amount: f64,
+}
+
+/// One closed pair of `--weighted`.
+#[derive(Debug, Clone)]
+pub struct WeightedPair {
+    /// 1-based pair number
+    pub pair: usize,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub worked: i64,
+    pub weighted: i64,
+}
+
+/// Why a lunch is listed by `--lunch-audit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LunchIssueKind {
+    /// Recorded lunch below `min_duration_lunch_break`
+    BelowMin,
+    /// Recorded lunch above `max_duration_lunch_break`
+    AboveMax,
+    /// No lunch recorded: `min_duration_lunch_break` added automatically
+    Auto,
+}
+
+impl LunchIssueKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            LunchIssueKind::BelowMin => "below min",
+            LunchIssueKind::AboveMax => "above max",
+            LunchIssueKind::Auto => "auto-lunch",
+        }
+    }
+}
+
+/// One lunch worth fixing before an export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LunchIssue {
+    pub date: NaiveDate,
+    /// 1-based pair, `None` for the automatic lunch of the day
+    pub pair: Option<usize>,
+    pub kind: LunchIssueKind,
+    pub minutes: i64,
+}
+
+/// Closed pairs of one working day, raw and weighted (`--weighted`).
+#[derive(Debug, Clone)]
+pub struct WeightedDay {
+    pub date: NaiveDate,
+    pub pairs: Vec<WeightedPair>,
+}
+
+impl WeightedDay {
+    pub fn worked(&self) -> i64 {
+        self.pairs.iter().map(|p| p.worked).sum()
+    }
+
+    pub fn weighted(&self) -> i64 {
+        self.pairs.iter().map(|p| p.weighted).sum()
+    }
+}
+
+/// The two periods of `--compare`: `A:B` (e.g. `2025-05:2025-06`), or
+/// `A,B` when a side is itself a range (`2025-01:2025-03,2025-04:2025-06`).
+pub fn compare_periods(spec: &str) -> AppResult<(Period, Period)> {
+    let sides: Vec<&str> = if spec.contains(',') {
+        spec.split(',').collect()
+    } else {
+        spec.split(':').collect()
+    };
+
+    match sides.as_slice() {
+        [a, b] if !a.trim().is_empty() && !b.trim().is_empty() => {
+            Ok((Period::parse(a)?, Period::parse(b)?))
+        }
+        _ => Err(AppError::InvalidPeriod {
+            input: spec.to_string(),
+            hint: "expected two periods, e.g. 2025-05:2025-06 or 2025-01:2025-03,2025-04:2025-06"
+                .into(),
+        }),
+    }
+}
+
+pub struct ReportLogic;
+
+impl ReportLogic {
+    /// First IN and last OUT per weekday (Mon..Sun) over `dates`.
+    /// Holiday / sick-leave days are ignored; a day still missing its OUT
+    /// only counts for the first IN. Weekdays without data are omitted.
+    pub fn by_weekday(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+    ) -> AppResult<Vec<WeekdayRow>> {
+        let mut rows: Vec<WeekdayRow> = (0..7u8)
+            .filter_map(|i| Weekday::try_from(i).ok())
+            .map(|weekday| WeekdayRow {
+                weekday,
+                first_in: TimeStats::default(),
+                last_out: TimeStats::default(),
+            })
+            .collect();
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+
+            let summary = Core::build_daily_summary(&events, cfg);
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+
+            let row = &mut rows[day.weekday().num_days_from_monday() as usize];
+
+            if let Some(t) = events
+                .iter()
+                .filter(|e| e.kind == EventType::In)
+                .map(|e| e.time)
+                .min()
+            {
+                row.first_in.push(t);
+            }
+            if let Some(t) = events
+                .iter()
+                .filter(|e| e.kind == EventType::Out)
+                .map(|e| e.time)
+                .max()
+            {
+                row.last_out.push(t);
+            }
+        }
+
+        rows.retain(|r| r.first_in.count() > 0 || r.last_out.count() > 0);
+        Ok(rows)
+    }
+
+    /// Totals of the closed working days in `dates` (marker days and days
+    /// still missing their OUT are left out).
+    pub fn totals(pool: &mut DbPool, cfg: &Config, dates: &[NaiveDate]) -> AppResult<PeriodTotals> {
+        let mut t = PeriodTotals::default();
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+
+            let summary = Core::build_daily_summary(&events, cfg);
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+
+            if let Some(fig) = Core::day_figures(&summary, &events, cfg)
+                && let Some(worked) = fig.worked
+            {
+                t.days += 1;
+                t.worked += worked;
+                t.expected += fig.expected;
+            }
+        }
+
+        Ok(t)
+    }
+
+    /// Totals, office / remote split and average first IN of the closed
+    /// working days in `dates` (same days as [`ReportLogic::totals`]). Mixed
+    /// days count for the totals only.
+    pub fn profile(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+    ) -> AppResult<PeriodProfile> {
+        let mut p = PeriodProfile::default();
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+
+            let summary = Core::build_daily_summary(&events, cfg);
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+
+            let Some(fig) = Core::day_figures(&summary, &events, cfg) else {
+                continue;
+            };
+            let Some(worked) = fig.worked else {
+                continue;
+            };
+
+            p.totals.days += 1;
+            p.totals.worked += worked;
+            p.totals.expected += fig.expected;
+            p.first_in.push(fig.first_in.time());
+
+            match get_day_position(&summary.timeline) {
+                Location::Office | Location::OnSite => p.office += 1,
+                Location::Remote => p.remote += 1,
+                _ => {}
+            }
+        }
+
+        Ok(p)
+    }
+
+    /// Worked minutes per pair position and day count per day position
+    /// (O, R, C, M, H, N, S order; empty positions omitted). The pairs of a
+    /// Mixed day add their minutes to their own position.
+    pub fn by_position(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+    ) -> AppResult<Vec<PositionRow>> {
+        let mut rows: Vec<PositionRow> = [
+            Location::Office,
+            Location::Remote,
+            Location::OnSite,
+            Location::Mixed,
+            Location::Holiday,
+            Location::NationalHoliday,
+            Location::SickLeave,
+        ]
+        .into_iter()
+        .map(|location| PositionRow {
+            location,
+            days: 0,
+            minutes: 0,
+        })
+        .collect();
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+
+            let summary = Core::build_daily_summary(&events, cfg);
+            let position = get_day_position(&summary.timeline);
+            if let Some(r) = rows.iter_mut().find(|r| r.location == position) {
+                r.days += 1;
+            }
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+
+            for p in summary
+                .timeline
+                .pairs
+                .iter()
+                .filter(|p| p.out_event.is_some())
+            {
+                if let Some(r) = rows.iter_mut().find(|r| r.location == p.position) {
+                    r.minutes += p.duration_minutes;
+                }
+            }
+        }
+
+        rows.retain(|r| r.days > 0 || r.minutes > 0);
+        Ok(rows)
+    }
+
+    /// Focus sessions per day; days without any are omitted. Running
+    /// sessions are counted but add no time.
+    pub fn focus(pool: &mut DbPool, cfg: &Config, dates: &[NaiveDate]) -> AppResult<Vec<FocusDay>> {
+        let mut days = Vec::new();
+
+        for day in dates {
+            let sessions = focus_sessions_by_date(&pool.conn, day)?;
+            if sessions.is_empty() {
+                continue;
+            }
+
+            let mut labels: Vec<String> = Vec::new();
+            for l in sessions.iter().filter_map(|s| s.label.clone()) {
+                if !labels.contains(&l) {
+                    labels.push(l);
+                }
+            }
+
+            let events = load_events_by_date(pool, day)?;
+            let summary = Core::build_daily_summary(&events, cfg);
+            let worked = if events.is_empty() || Core::is_marker_day(&summary) {
+                None
+            } else {
+                Core::day_figures(&summary, &events, cfg).and_then(|f| f.worked)
+            };
+
+            days.push(FocusDay {
+                date: *day,
+                sessions: sessions.len(),
+                focused: sessions.iter().map(|s| s.minutes()).sum(),
+                worked,
+                labels,
+            });
+        }
+
+        Ok(days)
+    }
+
+    /// Hours and amounts per month and project, with the `rates` of the
+    /// config (matched case-insensitively). Unrated projects earn 0.
+    pub fn earnings(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+    ) -> AppResult<Vec<EarningsRow>> {
+        let mut minutes: BTreeMap<(String, String), i64> = BTreeMap::new();
+        for d in InvoiceLogic::project_days(pool, cfg, dates)? {
+            *minutes
+                .entry((d.date.format("%Y-%m").to_string(), d.project))
+                .or_insert(0) += d.minutes;
+        }
+
+        Ok(minutes
+            .into_iter()
+            .map(|((month, project), mins)| {
+                let rate = cfg.rates.get(&project).copied().or_else(|| {
+                    cfg.rates
+                        .iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case(&project))
+                        .map(|(_, r)| *r)
+                });
+                let hours = round_hours(mins, cfg.decimal_step);
+                EarningsRow {
+                    month,
+                    project,
+                    hours,
+                    rate,
+                    amount: round_cents(hours * rate.unwrap_or(0.0)),
+                }
+            })
+            .collect())
+    }
+
+    /// Closed pairs of the working days with their minutes weighted by
+    /// `shift_bands` (days without closed pairs are omitted).
+    pub fn weighted(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+    ) -> AppResult<Vec<WeightedDay>> {
+        let bands = ShiftBands::from_config(cfg);
+        let mut out = Vec::new();
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+            let summary = Core::build_daily_summary(&events, cfg);
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+
+            let pairs: Vec<WeightedPair> = summary
+                .timeline
+                .pairs
+                .iter()
+                .enumerate()
+                .filter_map(|(i, p)| {
+                    let out = p.out_event.as_ref()?;
+                    Some(WeightedPair {
+                        pair: i + 1,
+                        start: p.in_event.time,
+                        end: out.time,
+                        worked: p.duration_minutes,
+                        weighted: bands.pair_minutes(p)?,
+                    })
+                })
+                .collect();
+
+            if !pairs.is_empty() {
+                out.push(WeightedDay { date: *day, pairs });
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Blocks of work over `limit` minutes without a break
+    /// (`mandatory_lunch_after`), in date order.
+    pub fn break_violations(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+        limit: i64,
+    ) -> AppResult<Vec<(NaiveDate, BreakViolation)>> {
+        let mut out = Vec::new();
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+            let summary = Core::build_daily_summary(&events, cfg);
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+            out.extend(
+                break_violations(&summary, limit)
+                    .into_iter()
+                    .map(|v| (*day, v)),
+            );
+        }
+        Ok(out)
+    }
+
+    /// Lunches outside the configured min / max bounds and days where the
+    /// lunch was added automatically (see [`auto_lunch`]), in date order.
+    pub fn lunch_audit(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+    ) -> AppResult<Vec<LunchIssue>> {
+        let min = cfg.min_duration_lunch_break as i64;
+        let max = cfg.max_duration_lunch_break as i64;
+        let mut out = Vec::new();
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+            let summary = Core::build_daily_summary(&events, cfg);
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+
+            for (i, p) in summary.timeline.pairs.iter().enumerate() {
+                let kind = match p.lunch_minutes {
+                    0 => continue,
+                    m if m < min => LunchIssueKind::BelowMin,
+                    m if m > max => LunchIssueKind::AboveMax,
+                    _ => continue,
+                };
+                out.push(LunchIssue {
+                    date: *day,
+                    pair: Some(i + 1),
+                    kind,
+                    minutes: p.lunch_minutes,
+                });
+            }
+
+            if let Some(minutes) = auto_lunch(&summary.timeline, cfg) {
+                out.push(LunchIssue {
+                    date: *day,
+                    pair: None,
+                    kind: LunchIssueKind::Auto,
+                    minutes,
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    /// Days worth a second look before exporting: worked-time outliers,
+    /// long days without lunch and impossible values. Sorted by date.
+    pub fn anomalies(
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+    ) -> AppResult<Vec<Anomaly>> {
+        let mut out = Vec::new();
+        let mut worked_days = Vec::new();
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+
+            let summary = Core::build_daily_summary(&events, cfg);
+            if Core::is_marker_day(&summary) {
+                continue;
+            }
+
+            let mut push = |kind, detail: String| {
+                out.push(Anomaly {
+                    date: *day,
+                    kind,
+                    detail,
+                })
+            };
+
+            for ev in &events {
+                if let Some(l) = ev.lunch
+                    && l < 0
+                {
+                    push(
+                        AnomalyKind::Impossible,
+                        format!("negative lunch ({} min) at {}", l, ev.time_str()),
+                    );
+                }
+            }
+            for (i, p) in summary.timeline.pairs.iter().enumerate() {
+                if p.out_event.is_some() && p.duration_minutes < 0 {
+                    push(
+                        AnomalyKind::Impossible,
+                        format!(
+                            "pair {}: negative duration {} (lunch {} min)",
+                            i + 1,
+                            mins2readable(p.duration_minutes, true, true),
+                            p.lunch_minutes
+                        ),
+                    );
+                }
+            }
+
+            let Some(fig) = Core::day_figures(&summary, &events, cfg) else {
+                continue;
+            };
+            let Some(worked) = fig.worked else {
+                continue;
+            };
+
+            if worked < 0 {
+                push(
+                    AnomalyKind::Impossible,
+                    format!("negative worked time {}", mins2readable(worked, true, true)),
+                );
+                continue;
+            }
+            if worked >= LONG_DAY_MINUTES && fig.lunch_total == 0 {
+                push(
+                    AnomalyKind::MissingLunch,
+                    format!(
+                        "worked {} without lunch",
+                        mins2readable(worked, false, true)
+                    ),
+                );
+            }
+            worked_days.push((*day, worked));
+        }
+
+        if let Some((mean, sd)) = mean_sd(&worked_days) {
+            for (date, worked) in &worked_days {
+                if (*worked as f64 - mean).abs() > 2.0 * sd {
+                    out.push(Anomaly {
+                        date: *date,
+                        kind: AnomalyKind::Outlier,
+                        detail: format!(
+                            "worked {} (mean {} ± {})",
+                            mins2readable(*worked, false, true),
+                            mins2readable(mean.round() as i64, false, true),
+                            mins2readable(sd.round() as i64, false, true)
+                        ),
+                    });
+                }
+            }
+        }
+
+        out.sort_by_key(|a| a.date);
+        Ok(out)
+    }
+}
+
+/// Mean and population standard deviation of the worked minutes; None with
+/// too few days or no spread at all.
+fn mean_sd(days: &[(NaiveDate, i64)]) -> Option<(f64, f64)> {
+    if days.len() < MIN_OUTLIER_DAYS {
+        return None;
+    }
+    let n = days.len() as f64;
+    let mean = days.iter().map(|(_, w)| *w as f64).sum::<f64>() / n;
+    let var = days
+        .iter()
+        .map(|(_, w)| (*w as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    let sd = var.sqrt();
+    (sd > 0.0).then_some((mean, sd))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_stats_average_rounds_to_the_minute() {
+        let mut s = TimeStats::default();
+        assert!(s.avg().is_none());
+
+        for (h, m) in [(8, 40), (9, 5), (10, 0)] {
+            s.push(NaiveTime::from_hms_opt(h, m, 0).unwrap());
+        }
+        assert_eq!(s.avg(), NaiveTime::from_hms_opt(9, 15, 0));
+        assert_eq!(s.min(), NaiveTime::from_hms_opt(8, 40, 0));
+        assert_eq!(s.max(), NaiveTime::from_hms_opt(10, 0, 0));
+    }
+
+    #[test]
+    fn mean_sd_needs_enough_days() {
+        let d = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let days: Vec<(NaiveDate, i64)> = [480, 480, 480, 480].iter().map(|w| (d, *w)).collect();
+        assert_eq!(mean_sd(&days), None);
+
+        let days: Vec<(NaiveDate, i64)> =
+            [450, 470, 480, 490, 510].iter().map(|w| (d, *w)).collect();
+        let (mean, sd) = mean_sd(&days).unwrap();
+        assert_eq!(mean, 480.0);
+        assert_eq!(sd, 20.0);
+    }
+
+    #[test]
+    fn compare_periods_accepts_months_and_ranges() {
+        let (a, b) = compare_periods("2025-05:2025-06").unwrap();
+        assert_eq!(
+            a.bounds().unwrap().1,
+            NaiveDate::from_ymd_opt(2025, 5, 31).unwrap()
+        );
+        assert_eq!(
+            b.bounds().unwrap().0,
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+        );
+
+        let (a, _) = compare_periods("2025-01:2025-03,2025-04:2025-06").unwrap();
+        assert_eq!(
+            a.bounds().unwrap().1,
+            NaiveDate::from_ymd_opt(2025, 3, 31).unwrap()
+        );
+
+        assert!(compare_periods("2025-05").is_err());
+        assert!(compare_periods("2025-05:2025-06:2025-07").is_err());
+    }
+}
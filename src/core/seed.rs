@@ -0,0 +1,187 @@
+//! Developer sample-data generator (`rtimelogger dev seed`).
+//! Fills a database with plausible punches for demos, screenshots and
+//! performance testing. Days that already contain events are left untouched.
+
+use crate::core::calculator::overlap::OverlapMode;
+use crate::db::pool::DbPool;
+use crate::db::queries::{insert_event, recalc_pairs_for_date};
+use crate::errors::{AppError, AppResult};
+use crate::models::event::{Event, EventExtras};
+use crate::models::event_type::EventType;
+use crate::models::location::Location;
+use crate::utils::date::{is_weekend, today};
+use chrono::{Duration, NaiveDate, NaiveTime};
+use clap::ValueEnum;
+
+/// Shape of the generated data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SeedProfile {
+    /// Varying start times and lunches, holidays, mixed and client days
+    Realistic,
+    /// Always 09:00-18:00 in office with a 60 minute lunch
+    Uniform,
+}
+
+#[derive(Debug, Default)]
+pub struct SeedReport {
+    pub work_days: usize,
+    pub holidays: usize,
+    pub skipped_weekend: usize,
+    pub skipped_existing: usize,
+}
+
+/// Minimal xorshift64* generator: deterministic for a given seed, no extra deps.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform integer in `[lo, hi]`.
+    fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + (self.next_u64() % ((hi - lo + 1) as u64)) as i64
+    }
+
+    /// True with the given percentage.
+    fn chance(&mut self, pct: u64) -> bool {
+        self.next_u64() % 100 < pct
+    }
+}
+
+pub struct SeedLogic;
+
+impl SeedLogic {
+    /// Generate `days` calendar days of data ending today.
+    pub fn seed(
+        pool: &mut DbPool,
+        days: u32,
+        profile: SeedProfile,
+        seed: Option<u64>,
+    ) -> AppResult<SeedReport> {
+        let seed = seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(42)
+        });
+        let mut rng = Rng::new(seed);
+        let mut rep = SeedReport::default();
+
+        let last = today();
+        let first = last - Duration::days(days.saturating_sub(1) as i64);
+
+        let tx = pool.conn.transaction()?;
+
+        let mut day = first;
+        while day <= last {
+            if is_weekend(day) {
+                rep.skipped_weekend += 1;
+            } else if crate::db::queries::events::date_has_events(&tx, &day)? {
+                rep.skipped_existing += 1;
+            } else {
+                let events = match profile {
+                    SeedProfile::Uniform => uniform_day(day),
+                    SeedProfile::Realistic => realistic_day(day, &mut rng),
+                };
+
+                if events.len() == 1 {
+                    rep.holidays += 1;
+                } else {
+                    rep.work_days += 1;
+                }
+
+                for ev in &events {
+                    insert_event(&tx, ev)?;
+                }
+                // Seeded events carry no activity: one sequence per day
+                recalc_pairs_for_date(&tx, &day, OverlapMode::Off)?;
+            }
+
+            day = day
+                .succ_opt()
+                .ok_or_else(|| AppError::Other("Invalid date increment.".into()))?;
+        }
+
+        tx.commit()?;
+        Ok(rep)
+    }
+}
+
+fn hm(minutes: i64) -> NaiveTime {
+    let m = minutes.clamp(0, 23 * 60 + 59);
+    NaiveTime::from_hms_opt((m / 60) as u32, (m % 60) as u32, 0).unwrap_or(NaiveTime::MIN)
+}
+
+fn seed_event(
+    date: NaiveDate,
+    minutes: i64,
+    kind: EventType,
+    location: Location,
+    lunch: i32,
+) -> Event {
+    Event::new(
+        0,
+        date,
+        hm(minutes),
+        kind,
+        location,
+        EventExtras {
+            lunch: Some(lunch),
+            source: Some("seed".to_string()),
+            ..Default::default()
+        },
+    )
+}
+
+fn uniform_day(date: NaiveDate) -> Vec<Event> {
+    vec![
+        seed_event(date, 9 * 60, EventType::In, Location::Office, 60),
+        seed_event(date, 18 * 60, EventType::Out, Location::Office, 60),
+    ]
+}
+
+fn realistic_day(date: NaiveDate, rng: &mut Rng) -> Vec<Event> {
+    // Occasional holiday (~4%)
+    if rng.chance(4) {
+        return vec![seed_event(date, 0, EventType::In, Location::Holiday, 0)];
+    }
+
+    let start = rng.range(7 * 60 + 45, 9 * 60 + 30);
+
+    // Mixed day (~8%): office in the morning, remote in the afternoon
+    if rng.chance(8) {
+        let morning_out = rng.range(12 * 60 + 15, 13 * 60);
+        let afternoon_in = morning_out + rng.range(45, 80);
+        let afternoon_out = afternoon_in + rng.range(3 * 60 + 30, 5 * 60);
+        return vec![
+            seed_event(date, start, EventType::In, Location::Office, 0),
+            seed_event(date, morning_out, EventType::Out, Location::Office, 0),
+            seed_event(date, afternoon_in, EventType::In, Location::Remote, 0),
+            seed_event(date, afternoon_out, EventType::Out, Location::Remote, 0),
+        ];
+    }
+
+    let location = match rng.range(0, 99) {
+        0..=59 => Location::Office,
+        60..=94 => Location::Remote,
+        _ => Location::OnSite,
+    };
+
+    let lunch = rng.range(30, 75) as i32;
+    let end = start + 8 * 60 + lunch as i64 + rng.range(-30, 60);
+
+    vec![
+        seed_event(date, start, EventType::In, location, lunch),
+        seed_event(date, end, EventType::Out, location, lunch),
+    ]
+}
@@ -0,0 +1,384 @@
+//! `rtimelogger serve`: minimal HTTP/1.1 receiver for badge gateways.
+//!
+//! A single route, `POST /events`, protected by a bearer token. Requests are
+//! served one at a time (SQLite has a single writer anyway); only the std
+//! library is used, so put a reverse proxy with TLS in front of it when the
+//! gateway is not on the same machine.
+
+use crate::config::{ApiConfig, Config};
+use crate::db::pool::DbPool;
+use crate::errors::{AppError, AppResult};
+use crate::import::badge::{ingest, parse_payload};
+use crate::models::location::Location;
+use crate::ui::messages::{info, warning};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+/// Environment variable overriding `[api] token`.
+pub const TOKEN_ENV: &str = "RTIMELOGGER_API_TOKEN";
+
+/// Largest accepted request body.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// Largest request line + headers, and most headers accepted.
+const MAX_HEAD: usize = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+
+/// Time a client has to send the whole request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A response: status line and JSON body.
+struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    fn json(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            body: body.to_string(),
+        }
+    }
+
+    fn error(status: u16, msg: impl Into<String>) -> Self {
+        Self::json(status, serde_json::json!({ "error": msg.into() }))
+    }
+}
+
+pub struct ServeLogic;
+
+impl ServeLogic {
+    /// Token from the environment or the config; serving without one is refused.
+    pub fn token(api: &ApiConfig) -> AppResult<String> {
+        let token = std::env::var(TOKEN_ENV).unwrap_or_else(|_| api.token.clone());
+        if token.trim().is_empty() {
+            return Err(AppError::InvalidArgs(format!(
+                "No API token: set `token` under [api] in the config or {}.",
+                TOKEN_ENV
+            )));
+        }
+        Ok(token.trim().to_string())
+    }
+
+    /// Listen on `bind` until the process is stopped.
+    pub fn run(cfg: &Config, bind: &str) -> AppResult<()> {
+        let api = cfg.api.clone().unwrap_or_default();
+        let token = Self::token(&api)?;
+        let default = Location::from_code(&cfg.default_position).unwrap_or(Location::Office);
+
+        // Fail early on a missing / foreign database
+        let mut pool = DbPool::new(&cfg.database)?;
+
+        let listener = TcpListener::bind(bind)?;
+        info(format!("Listening on http://{} (POST /events)", bind));
+
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    warning(format!("⚠️  Connection failed: {}", e));
+                    continue;
+                }
+            };
+            let peer = stream
+                .peer_addr()
+                .map(|a| a.to_string())
+                .unwrap_or_else(|_| "?".into());
+
+            let reply = match read_request(&mut stream) {
+                Ok(req) => handle(&req, &token, |body| {
+                    let punches = parse_payload(body, &api.mapping, default)?;
+                    ingest(&mut pool, &punches, &peer, cfg.overlap_mode)
+                }),
+                Err(reply) => reply,
+            };
+
+            if reply.status >= 400 {
+                warning(format!("⚠️  {} → {} {}", peer, reply.status, reply.body));
+            } else {
+                info(format!("{} → {} {}", peer, reply.status, reply.body));
+            }
+            let _ = write_reply(&mut stream, &reply);
+        }
+
+        Ok(())
+    }
+}
+
+/// What the server needs of a request.
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: String,
+}
+
+/// Route and authorize a request; `store` inserts the payload.
+fn handle(req: &Request, token: &str, store: impl FnOnce(&str) -> AppResult<usize>) -> Reply {
+    if req.path != "/events" {
+        return Reply::error(404, "not found");
+    }
+    if req.method != "POST" {
+        return Reply::error(405, "only POST is allowed");
+    }
+
+    let authorized = req
+        .authorization
+        .as_deref()
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .is_some_and(|t| constant_time_eq(t.trim().as_bytes(), token.as_bytes()));
+    if !authorized {
+        return Reply::error(401, "missing or invalid bearer token");
+    }
+
+    match store(&req.body) {
+        Ok(n) => Reply::json(201, serde_json::json!({ "inserted": n })),
+        Err(AppError::InvalidArgs(msg)) => Reply::error(400, msg),
+        Err(e) => Reply::error(error_status(&e), e.to_string().trim()),
+    }
+}
+
+/// Status of a failed insert. The payload's fault gets a 4xx, so gateways do
+/// not retry it; only server-side failures are 5xx.
+fn error_status(e: &AppError) -> u16 {
+    match e {
+        AppError::InvalidDate(_)
+        | AppError::InvalidPosition(_)
+        | AppError::InvalidEventType(_)
+        | AppError::InvalidOperation(_)
+        | AppError::LunchTooLong { .. }
+        | AppError::MaxDailyWorkExceeded { .. } => 422,
+        // Sequence errors: the punches do not fit the events of the day
+        AppError::InvalidTime(_) | AppError::PairOutBeforeIn { .. } => 409,
+        AppError::DayLocked(_) => 423,
+        AppError::DbLocked => 503,
+        _ => 500,
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<Request, Reply> {
+    let bad = |msg: &str| Reply::error(400, msg);
+
+    // One deadline for the whole request, not per read: a client trickling
+    // bytes cannot hold the (single-threaded) server longer than that
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    let mut reader = BufReader::new(stream);
+    let mut budget = MAX_HEAD;
+
+    let line = read_line(&mut reader, deadline, &mut budget)?;
+    let mut parts = line.split_whitespace();
+    let method = parts
+        .next()
+        .ok_or_else(|| bad("empty request"))?
+        .to_string();
+    let path = parts.next().ok_or_else(|| bad("missing path"))?.to_string();
+
+    let mut length = 0usize;
+    let mut authorization = None;
+    let mut count = 0;
+    loop {
+        let header = read_line(&mut reader, deadline, &mut budget)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        count += 1;
+        if count > MAX_HEADERS {
+            return Err(Reply::error(431, "too many headers"));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.parse().map_err(|_| bad("invalid Content-Length"))?;
+            } else if name.eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.to_string());
+            }
+        }
+    }
+
+    if length > MAX_BODY {
+        return Err(Reply::error(413, "payload too large"));
+    }
+    let mut body = Vec::with_capacity(length);
+    while body.len() < length {
+        let chunk = fill(&mut reader, deadline)?;
+        if chunk.is_empty() {
+            return Err(bad("truncated body"));
+        }
+        let n = chunk.len().min(length - body.len());
+        body.extend_from_slice(&chunk[..n]);
+        reader.consume(n);
+    }
+
+    Ok(Request {
+        method,
+        path,
+        authorization,
+        body: String::from_utf8(body).map_err(|_| bad("body is not UTF-8"))?,
+    })
+}
+
+/// Next buffered bytes, waiting at most until `deadline`.
+fn fill<'a>(
+    reader: &'a mut BufReader<&mut TcpStream>,
+    deadline: Instant,
+) -> Result<&'a [u8], Reply> {
+    let left = deadline.saturating_duration_since(Instant::now());
+    if left.is_zero() {
+        return Err(Reply::error(408, "request timeout"));
+    }
+    reader.get_ref().set_read_timeout(Some(left)).ok();
+    reader.fill_buf().map_err(|e| match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Reply::error(408, "request timeout"),
+        _ => Reply::error(400, "unreadable request"),
+    })
+}
+
+/// One line of the request head, taken from `budget` (431 once exhausted).
+fn read_line(
+    reader: &mut BufReader<&mut TcpStream>,
+    deadline: Instant,
+    budget: &mut usize,
+) -> Result<String, Reply> {
+    let mut line = Vec::new();
+    loop {
+        let chunk = fill(reader, deadline)?;
+        if chunk.is_empty() {
+            break;
+        }
+        let (n, done) = match chunk.iter().position(|b| *b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (chunk.len(), false),
+        };
+        if n > *budget {
+            return Err(Reply::error(431, "request headers too large"));
+        }
+        *budget -= n;
+        line.extend_from_slice(&chunk[..n]);
+        reader.consume(n);
+        if done {
+            break;
+        }
+    }
+    String::from_utf8(line).map_err(|_| Reply::error(400, "headers are not UTF-8"))
+}
+
+fn write_reply(stream: &mut TcpStream, reply: &Reply) -> std::io::Result<()> {
+    let reason = match reply.status {
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Content",
+        423 => "Locked",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        reply.status,
+        reason,
+        reply.body.len(),
+        reply.body
+    )?;
+    stream.flush()
+}
+
+/// Token comparison whose time does not depend on where the bytes differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(method: &str, path: &str, auth: Option<&str>) -> Request {
+        Request {
+            method: method.into(),
+            path: path.into(),
+            authorization: auth.map(str::to_string),
+            body: "{}".into(),
+        }
+    }
+
+    #[test]
+    fn routes_and_token() {
+        let ok = |_: &str| Ok(2);
+        assert_eq!(handle(&req("GET", "/", None), "t", ok).status, 404);
+        assert_eq!(handle(&req("GET", "/events", None), "t", ok).status, 405);
+        assert_eq!(handle(&req("POST", "/events", None), "t", ok).status, 401);
+        assert_eq!(
+            handle(&req("POST", "/events", Some("Bearer x")), "t", ok).status,
+            401
+        );
+
+        let reply = handle(&req("POST", "/events", Some("Bearer t")), "t", ok);
+        assert_eq!(reply.status, 201);
+        assert_eq!(reply.body, r#"{"inserted":2}"#);
+
+        let invalid = |_: &str| Err(AppError::InvalidArgs("item 1: missing 'ts'".into()));
+        assert_eq!(
+            handle(&req("POST", "/events", Some("Bearer t")), "t", invalid).status,
+            400
+        );
+
+        let sequence = |_: &str| Err(AppError::InvalidTime("OUT without matching IN".into()));
+        assert_eq!(
+            handle(&req("POST", "/events", Some("Bearer t")), "t", sequence).status,
+            409
+        );
+        let locked = |_: &str| Err(AppError::DayLocked(chrono::NaiveDate::MIN));
+        assert_eq!(
+            handle(&req("POST", "/events", Some("Bearer t")), "t", locked).status,
+            423
+        );
+    }
+
+    /// Send `raw` to a local socket and read it back as a request.
+    fn receive(raw: Vec<u8>) -> Result<Request, Reply> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut s = TcpStream::connect(addr).unwrap();
+            let _ = s.write_all(&raw);
+            let _ = s.shutdown(std::net::Shutdown::Write);
+        });
+        let (mut stream, _) = listener.accept().unwrap();
+        let out = read_request(&mut stream);
+        client.join().unwrap();
+        out
+    }
+
+    #[test]
+    fn request_head_is_bounded() {
+        let ok = receive(
+            b"POST /events HTTP/1.1\r\nAuthorization: Bearer t\r\nContent-Length: 2\r\n\r\n{}"
+                .to_vec(),
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(ok.authorization.as_deref(), Some("Bearer t"));
+        assert_eq!(ok.body, "{}");
+
+        let mut long = b"POST /events HTTP/1.1\r\nX-Pad: ".to_vec();
+        long.extend(vec![b'a'; MAX_HEAD]);
+        long.extend(b"\r\n\r\n");
+        assert_eq!(receive(long).err().unwrap().status, 431);
+
+        let mut many = b"POST /events HTTP/1.1\r\n".to_vec();
+        for i in 0..=MAX_HEADERS {
+            many.extend(format!("X-{}: 1\r\n", i).bytes());
+        }
+        many.extend(b"\r\n");
+        assert_eq!(receive(many).err().unwrap().status, 431);
+    }
+}
@@ -0,0 +1,352 @@
+//! `rtimelogger install-service`: write the systemd unit / timer (or the
+//! Windows Task Scheduler XML) running a task of rTimelogger unattended, so
+//! nobody has to hand-write unit files.
+//!
+//! The files only call the current binary; enabling them is left to the user
+//! (`systemctl --user enable --now ...`, `schtasks /Create /XML ...`).
+
+use crate::config::{CONFIG_DIR_ENV, Config};
+use crate::errors::{AppError, AppResult};
+use chrono::{NaiveTime, Weekday};
+use clap::ValueEnum;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Task run by the installed service.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ServiceTask {
+    /// Scheduled `backup` (one compressed copy per day)
+    Backup,
+    /// `serve` kept running in the background (no schedule)
+    Serve,
+}
+
+impl ServiceTask {
+    pub fn name(self) -> &'static str {
+        match self {
+            ServiceTask::Backup => "backup",
+            ServiceTask::Serve => "serve",
+        }
+    }
+
+    /// Unit / task name, e.g. `rtimelogger-backup`.
+    pub fn unit_name(self) -> String {
+        format!("rtimelogger-{}", self.name())
+    }
+}
+
+/// `"Mon..Fri 18:30"`, `"Sat,Sun 09:00"`, `"18:30"` (every day).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    /// Empty = every day
+    pub days: Vec<Weekday>,
+    pub time: NaiveTime,
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+impl Schedule {
+    pub fn parse(s: &str) -> AppResult<Self> {
+        let invalid = |why: &str| {
+            AppError::InvalidArgs(format!(
+                "Invalid schedule '{}': {} (e.g. \"Mon..Fri 18:30\", \"Sat,Sun 09:00\", \"18:30\").",
+                s, why
+            ))
+        };
+
+        let mut parts = s.split_whitespace().collect::<Vec<_>>();
+        let time_str = parts.pop().ok_or_else(|| invalid("empty"))?;
+        let time = NaiveTime::parse_from_str(time_str, "%H:%M")
+            .map_err(|_| invalid("the time must be HH:MM"))?;
+
+        let mut days = Vec::new();
+        for item in parts.iter().flat_map(|p| p.split(',')) {
+            let item = item.trim();
+            if item.is_empty() {
+                continue;
+            }
+            let day = |d: &str| -> AppResult<usize> {
+                let wd: Weekday = d.parse().map_err(|_| invalid("unknown weekday"))?;
+                Ok(wd.num_days_from_monday() as usize)
+            };
+            match item.split_once("..") {
+                Some((from, to)) => {
+                    let (from, to) = (day(from)?, day(to)?);
+                    if from > to {
+                        return Err(invalid("a day range must go from Mon to Sun"));
+                    }
+                    days.extend_from_slice(&WEEKDAYS[from..=to]);
+                }
+                None => days.push(WEEKDAYS[day(item)?]),
+            }
+        }
+        days.sort_by_key(|d| d.num_days_from_monday());
+        days.dedup();
+
+        Ok(Self { days, time })
+    }
+
+    /// systemd `OnCalendar=` expression.
+    pub fn on_calendar(&self) -> String {
+        let time = self.time.format("%H:%M:00");
+        if self.days.is_empty() {
+            return format!("*-*-* {}", time);
+        }
+        let days: Vec<String> = self.days.iter().map(|d| d.to_string()).collect();
+        format!("{} *-*-* {}", days.join(","), time)
+    }
+}
+
+/// A file to write: where, and its content.
+#[derive(Debug)]
+pub struct ServiceFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// What `install-service` needs besides the task.
+#[derive(Debug)]
+pub struct ServiceSpec {
+    pub task: ServiceTask,
+    pub schedule: Option<Schedule>,
+    /// `--user`: systemd user units instead of system units
+    pub user: bool,
+    /// Backup destination (may contain `{YYYY}` `{MM}` `{DD}`)
+    pub backup_file: String,
+    pub exe: PathBuf,
+}
+
+impl ServiceSpec {
+    /// Arguments passed to the binary.
+    fn args(&self) -> Vec<String> {
+        match self.task {
+            ServiceTask::Backup => vec![
+                "--quiet".into(),
+                "backup".into(),
+                "--file".into(),
+                self.backup_file.clone(),
+                "--compress".into(),
+            ],
+            ServiceTask::Serve => vec!["serve".into()],
+        }
+    }
+}
+
+pub struct ServiceLogic;
+
+impl ServiceLogic {
+    /// Default backup template: one file per day next to the config.
+    pub fn default_backup_file() -> String {
+        Config::config_dir()
+            .join("backups")
+            .join("rtimelogger-{YYYY}-{MM}-{DD}.sqlite")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Directory of the systemd units.
+    pub fn systemd_dir(user: bool) -> PathBuf {
+        if !user {
+            return PathBuf::from("/etc/systemd/system");
+        }
+        let base = std::env::var("XDG_CONFIG_HOME")
+            .ok()
+            .filter(|d| !d.trim().is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|h| h.join(".config")))
+            .unwrap_or_else(|| PathBuf::from(".config"));
+        base.join("systemd").join("user")
+    }
+
+    /// Files for systemd: a service, plus a timer for scheduled tasks.
+    pub fn systemd_files(spec: &ServiceSpec, dir: &Path) -> Vec<ServiceFile> {
+        let name = spec.task.unit_name();
+        let exec = std::iter::once(spec.exe.to_string_lossy().to_string())
+            .chain(spec.args())
+            .map(|a| systemd_quote(&a))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let env = std::env::var(CONFIG_DIR_ENV)
+            .ok()
+            .filter(|d| !d.trim().is_empty())
+            .map(|d| {
+                format!(
+                    "Environment={}\n",
+                    systemd_quote(&format!("{}={}", CONFIG_DIR_ENV, d))
+                )
+            })
+            .unwrap_or_default();
+        let target = if spec.user {
+            "default.target"
+        } else {
+            "multi-user.target"
+        };
+
+        let service = match &spec.schedule {
+            Some(_) => format!(
+                "[Unit]\nDescription=rTimelogger {task}\n\n[Service]\nType=oneshot\n{env}ExecStart={exec}\n",
+                task = spec.task.name(),
+            ),
+            None => format!(
+                "[Unit]\nDescription=rTimelogger {task}\nAfter=network.target\n\n[Service]\n{env}ExecStart={exec}\nRestart=on-failure\n\n[Install]\nWantedBy={target}\n",
+                task = spec.task.name(),
+            ),
+        };
+
+        let mut files = vec![ServiceFile {
+            path: dir.join(format!("{}.service", name)),
+            content: service,
+        }];
+
+        if let Some(schedule) = &spec.schedule {
+            files.push(ServiceFile {
+                path: dir.join(format!("{}.timer", name)),
+                content: format!(
+                    "[Unit]\nDescription=rTimelogger {task} ({when})\n\n[Timer]\nOnCalendar={cal}\nPersistent=true\n\n[Install]\nWantedBy=timers.target\n",
+                    task = spec.task.name(),
+                    when = schedule_label(schedule),
+                    cal = schedule.on_calendar(),
+                ),
+            });
+        }
+        files
+    }
+
+    /// Task Scheduler XML (import with `schtasks /Create /XML`).
+    pub fn windows_task(spec: &ServiceSpec, dir: &Path) -> ServiceFile {
+        let trigger = match &spec.schedule {
+            Some(s) => {
+                let start = format!("2025-01-01T{}", s.time.format("%H:%M:00"));
+                if s.days.is_empty() {
+                    format!(
+                        "    <CalendarTrigger>\n      <StartBoundary>{start}</StartBoundary>\n      <ScheduleByDay><DaysInterval>1</DaysInterval></ScheduleByDay>\n    </CalendarTrigger>\n"
+                    )
+                } else {
+                    let days: String = s
+                        .days
+                        .iter()
+                        .map(|d| format!("<{}/>", windows_day(*d)))
+                        .collect();
+                    format!(
+                        "    <CalendarTrigger>\n      <StartBoundary>{start}</StartBoundary>\n      <ScheduleByWeek><WeeksInterval>1</WeeksInterval><DaysOfWeek>{days}</DaysOfWeek></ScheduleByWeek>\n    </CalendarTrigger>\n"
+                    )
+                }
+            }
+            None => "    <LogonTrigger />\n".to_string(),
+        };
+
+        let args = spec
+            .args()
+            .iter()
+            .map(|a| {
+                if a.contains(' ') {
+                    format!("\"{}\"", a)
+                } else {
+                    a.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let limit = if spec.schedule.is_some() {
+            "PT1H"
+        } else {
+            "PT0S"
+        };
+
+        ServiceFile {
+            path: dir.join(format!("{}.xml", spec.task.unit_name())),
+            content: format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Task version=\"1.2\" xmlns=\"http://schemas.microsoft.com/windows/2004/02/mit/task\">\n  <RegistrationInfo><Description>rTimelogger {task}</Description></RegistrationInfo>\n  <Triggers>\n{trigger}  </Triggers>\n  <Settings>\n    <StartWhenAvailable>true</StartWhenAvailable>\n    <ExecutionTimeLimit>{limit}</ExecutionTimeLimit>\n  </Settings>\n  <Actions>\n    <Exec>\n      <Command>{cmd}</Command>\n      <Arguments>{args}</Arguments>\n    </Exec>\n  </Actions>\n</Task>\n",
+                task = spec.task.name(),
+                cmd = xml_escape(&spec.exe.to_string_lossy()),
+                args = xml_escape(&args),
+            ),
+        }
+    }
+
+    /// Write the files; existing ones are kept unless `force`.
+    pub fn write(files: &[ServiceFile], force: bool) -> AppResult<()> {
+        if !force && let Some(f) = files.iter().find(|f| f.path.exists()) {
+            return Err(AppError::InvalidOperation(format!(
+                "{} already exists (use --force to overwrite it).",
+                f.path.display()
+            )));
+        }
+        for f in files {
+            if let Some(parent) = f.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&f.path, &f.content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Human form of a schedule, for descriptions.
+pub fn schedule_label(s: &Schedule) -> String {
+    let days: Vec<String> = s.days.iter().map(|d| d.to_string()).collect();
+    if days.is_empty() {
+        format!("daily {}", s.time.format("%H:%M"))
+    } else {
+        format!("{} {}", days.join(","), s.time.format("%H:%M"))
+    }
+}
+
+/// Double-quote an `ExecStart=` argument when needed (`%` is a systemd specifier).
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%");
+    if escaped.contains([' ', '"', '\\']) {
+        format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        escaped
+    }
+}
+
+fn windows_day(d: Weekday) -> &'static str {
+    match d {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_schedules() {
+        let s = Schedule::parse("Mon..Fri 18:30").unwrap();
+        assert_eq!(s.days.len(), 5);
+        assert_eq!(s.on_calendar(), "Mon,Tue,Wed,Thu,Fri *-*-* 18:30:00");
+
+        let s = Schedule::parse("sun,Sat 09:00").unwrap();
+        assert_eq!(s.days, vec![Weekday::Sat, Weekday::Sun]);
+
+        assert_eq!(
+            Schedule::parse("18:30").unwrap().on_calendar(),
+            "*-*-* 18:30:00"
+        );
+        assert!(Schedule::parse("Fri..Mon 18:30").is_err());
+        assert!(Schedule::parse("Mon 25:00").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! Shift differential (`shift_bands`): the minutes of a pair falling inside
+//! a band are weighted by its multiplier, for payroll checks
+//! (`report --weighted`, weighted column of the exports).
+//!
+//! - a minute inside several bands takes the highest multiplier
+//! - a minute outside every band counts ×1
+//! - the weekday of a band is the one of the minute (a Sunday night band
+//!   stops at midnight)
+//! - the lunch of the pair is taken evenly from the whole pair
+
+use crate::config::{Config, ShiftBand};
+use crate::core::calculator::timeline::Pair;
+use crate::utils::time::parse_time;
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Weekday};
+
+#[derive(Debug, Clone)]
+struct Band {
+    window: Option<(NaiveTime, NaiveTime)>,
+    days: Vec<Weekday>,
+    multiplier: f64,
+}
+
+impl Band {
+    fn parse(b: &ShiftBand) -> Result<Self, String> {
+        let time = |v: &Option<String>, key: &str| match v.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(t) => parse_time(t)
+                .map(Some)
+                .ok_or_else(|| format!("{} '{}' is not HH:MM", key, t)),
+        };
+        let window = match (time(&b.from, "from")?, time(&b.to, "to")?) {
+            (Some(from), Some(to)) => Some((from, to)),
+            (None, None) => None,
+            _ => return Err("from and to must be given together".into()),
+        };
+
+        let days = b
+            .days
+            .iter()
+            .map(|d| {
+                d.trim()
+                    .parse::<Weekday>()
+                    .map_err(|_| format!("unknown weekday '{}'", d))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if b.multiplier.is_nan() || b.multiplier <= 0.0 {
+            return Err(format!("multiplier {} must be above 0", b.multiplier));
+        }
+
+        Ok(Self {
+            window,
+            days,
+            multiplier: b.multiplier,
+        })
+    }
+
+    fn covers(&self, at: NaiveDateTime) -> bool {
+        if !self.days.is_empty() && !self.days.contains(&at.weekday()) {
+            return false;
+        }
+        match self.window {
+            None => true,
+            Some((from, to)) if from < to => at.time() >= from && at.time() < to,
+            // Crossing midnight (from == to: the whole day)
+            Some((from, to)) => at.time() >= from || at.time() < to,
+        }
+    }
+}
+
+/// The valid `shift_bands` of the config, ready for the calculations.
+#[derive(Debug, Clone, Default)]
+pub struct ShiftBands(Vec<Band>);
+
+impl ShiftBands {
+    /// Invalid bands are left out (see [`invalid_bands`]).
+    pub fn from_config(cfg: &Config) -> Self {
+        Self(
+            cfg.shift_bands
+                .iter()
+                .filter_map(|b| Band::parse(b).ok())
+                .collect(),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn multiplier_at(&self, at: NaiveDateTime) -> f64 {
+        self.0
+            .iter()
+            .filter(|b| b.covers(at))
+            .map(|b| b.multiplier)
+            .reduce(f64::max)
+            .unwrap_or(1.0)
+    }
+
+    /// Weighted minutes of `net` minutes worked between `start` and `end`.
+    pub fn weighted_minutes(&self, start: NaiveDateTime, end: NaiveDateTime, net: i64) -> i64 {
+        let raw = (end - start).num_minutes();
+        if raw <= 0 {
+            return net;
+        }
+
+        let sum: f64 = (0..raw)
+            .map(|m| self.multiplier_at(start + Duration::minutes(m)))
+            .sum();
+        (sum * net as f64 / raw as f64).round() as i64
+    }
+
+    /// Weighted net minutes of a closed pair (None while it is open).
+    pub fn pair_minutes(&self, pair: &Pair) -> Option<i64> {
+        let out = pair.out_event.as_ref()?;
+        let start = pair.in_event.date.and_time(pair.in_event.time);
+        let end = out.date.and_time(out.time);
+        Some(self.weighted_minutes(start, end, pair.duration_minutes))
+    }
+}
+
+/// Bands of the config that cannot be used, e.g. "shift_bands[1] (night):
+/// to '25:00' is not HH:MM".
+pub fn invalid_bands(cfg: &Config) -> Vec<String> {
+    cfg.shift_bands
+        .iter()
+        .enumerate()
+        .filter_map(|(i, b)| {
+            Band::parse(b).err().map(|e| {
+                let name = if b.name.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", b.name)
+                };
+                format!("shift_bands[{}]{}: {}, the band is ignored.", i, name, e)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn bands(list: Vec<ShiftBand>) -> ShiftBands {
+        ShiftBands::from_config(&Config {
+            shift_bands: list,
+            ..Config::default()
+        })
+    }
+
+    fn at(d: u32, t: &str) -> NaiveDateTime {
+        // June 2025: the 21st is a Saturday, the 22nd a Sunday
+        NaiveDate::from_ymd_opt(2025, 6, d)
+            .unwrap()
+            .and_time(parse_time(t).unwrap())
+    }
+
+    #[test]
+    fn night_and_sunday_bands() {
+        let b = bands(vec![
+            ShiftBand {
+                name: "night".into(),
+                from: Some("22:00".into()),
+                to: Some("06:00".into()),
+                multiplier: 1.5,
+                ..Default::default()
+            },
+            ShiftBand {
+                name: "sunday".into(),
+                days: vec!["sun".into()],
+                multiplier: 2.0,
+                ..Default::default()
+            },
+        ]);
+
+        // Saturday 21:00-23:00: 60 min ×1 + 60 min ×1.5
+        assert_eq!(
+            b.weighted_minutes(at(21, "21:00"), at(21, "23:00"), 120),
+            150
+        );
+        // The lunch is taken evenly: 90 net minutes out of 120
+        assert_eq!(
+            b.weighted_minutes(at(21, "21:00"), at(21, "23:00"), 90),
+            113
+        );
+        // Sunday night: the highest multiplier wins
+        assert_eq!(
+            b.weighted_minutes(at(22, "22:00"), at(22, "23:00"), 60),
+            120
+        );
+        // Weekday daytime: unchanged
+        assert_eq!(
+            b.weighted_minutes(at(23, "09:00"), at(23, "17:00"), 450),
+            450
+        );
+    }
+
+    #[test]
+    fn invalid_bands_are_reported_and_skipped() {
+        let cfg = Config {
+            shift_bands: vec![ShiftBand {
+                from: Some("22:00".into()),
+                multiplier: 1.3,
+                ..Default::default()
+            }],
+            ..Config::default()
+        };
+        assert_eq!(invalid_bands(&cfg).len(), 1);
+        assert!(ShiftBands::from_config(&cfg).is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+//! At-a-glance balance for `rtimelogger status`: today's state plus the
+//! week-to-date and month-to-date worked time and surplus.
+
+use crate::config::Config;
+use crate::core::logic::Core;
+use crate::db::pool::DbPool;
+use crate::db::queries::load_events_by_date;
+use crate::errors::AppResult;
+use crate::models::location::Location;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime};
+
+/// Worked / expected minutes accumulated over a span of days.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Balance {
+    pub worked: i64,
+    pub expected: i64,
+    pub days: i64,
+}
+
+impl Balance {
+    pub fn surplus(&self) -> i64 {
+        self.worked - self.expected
+    }
+
+    fn add(&mut self, worked: i64, expected: i64) {
+        self.worked += worked;
+        self.expected += expected;
+        self.days += 1;
+    }
+}
+
+#[derive(Debug)]
+pub struct StatusReport {
+    pub date: NaiveDate,
+    /// Holiday / National Holiday / Sick Leave recorded for today
+    pub marker: Option<Location>,
+    /// Time and position of the IN still waiting for its OUT
+    pub open_since: Option<(NaiveTime, Location)>,
+    /// Planned exit (TGT) for today
+    pub target_exit: Option<NaiveTime>,
+    /// Today's worked time and ΔWORK (live while the day is open)
+    pub today: Option<Balance>,
+    pub week: Balance,
+    pub month: Balance,
+}
+
+impl StatusReport {
+    pub fn is_open(&self) -> bool {
+        self.open_since.is_some()
+    }
+}
+
+pub struct StatusLogic;
+
+impl StatusLogic {
+    /// Compute the status at `now`. Past days count only once closed,
+    /// today counts up to `now` while an IN is still open.
+    pub fn compute(
+        pool: &mut DbPool,
+        cfg: &Config,
+        now: DateTime<Local>,
+    ) -> AppResult<StatusReport> {
+        let date = now.date_naive();
+
+        let mut report = StatusReport {
+            date,
+            marker: None,
+            open_since: None,
+            target_exit: None,
+            today: None,
+            week: Balance::default(),
+            month: Balance::default(),
+        };
+
+        let week_start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        let month_start = date.with_day(1).unwrap_or(date);
+        let first = week_start.min(month_start);
+
+        let mut day = first;
+        while day <= date {
+            let events = load_events_by_date(pool, &day)?;
+            if !events.is_empty() {
+                let summary = Core::build_daily_summary(&events, cfg);
+                let is_today = day == date;
+
+                if Core::is_marker_day(&summary) {
+                    if is_today {
+                        report.marker = summary.timeline.pairs.first().map(|p| p.position);
+                    }
+                } else if let Some(fig) = Core::day_figures(&summary, &events, cfg) {
+                    let open = summary
+                        .timeline
+                        .pairs
+                        .last()
+                        .filter(|p| p.out_event.is_none());
+
+                    let worked = match open {
+                        Some(_) if is_today => {
+                            Some((fig.expected + (now - fig.target_exit).num_minutes()).max(0))
+                        }
+                        Some(_) => None, // past day still missing its OUT
+                        None => fig.worked,
+                    };
+
+                    if is_today {
+                        report.target_exit = Some(fig.target_exit.time());
+                        report.open_since = open.map(|p| (p.in_event.time, p.position));
+                    }
+
+                    if let Some(w) = worked {
+                        if is_today {
+                            let mut b = Balance::default();
+                            b.add(w, fig.expected);
+                            report.today = Some(b);
+                        }
+                        if day >= week_start {
+                            report.week.add(w, fig.expected);
+                        }
+                        if day >= month_start {
+                            report.month.add(w, fig.expected);
+                        }
+                    }
+                }
+            }
+
+            day = match day.succ_opt() {
+                Some(n) => n,
+                None => break,
+            };
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::calculator::overlap::OverlapMode;
+    use crate::db::queries::events::insert_event;
+    use crate::db::queries::pairs::recalc_pairs_for_date;
+    use crate::models::event::{Event, EventExtras};
+    use crate::models::event_type::EventType;
+
+    const MON: u32 = 16;
+    const TUE: u32 = 17;
+    const WED: u32 = 18;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, d).unwrap()
+    }
+
+    fn hm(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    fn punch(pool: &DbPool, d: u32, t: &str, kind: EventType) {
+        let ev = Event::new(
+            0,
+            day(d),
+            hm(t),
+            kind,
+            Location::Office,
+            EventExtras::default(),
+        );
+        insert_event(&pool.conn, &ev).unwrap();
+        recalc_pairs_for_date(&pool.conn, &day(d), OverlapMode::Off).unwrap();
+    }
+
+    fn at(d: u32, t: &str) -> DateTime<Local> {
+        day(d).and_time(hm(t)).and_local_timezone(Local).unwrap()
+    }
+
+    /// 4h days starting after the lunch window (no automatic lunch);
+    /// Monday closed (+30), Tuesday still missing its OUT.
+    fn setup() -> (DbPool, Config) {
+        let cfg = Config {
+            min_work_duration: "4h".into(),
+            ..Config::default()
+        };
+        let pool = DbPool::new(":memory:").unwrap();
+        punch(&pool, MON, "14:30", EventType::In);
+        punch(&pool, MON, "19:00", EventType::Out);
+        punch(&pool, TUE, "14:30", EventType::In);
+        (pool, cfg)
+    }
+
+    #[test]
+    fn open_day_before_the_target_exit() {
+        let (mut pool, cfg) = setup();
+        punch(&pool, WED, "14:30", EventType::In);
+
+        let r = StatusLogic::compute(&mut pool, &cfg, at(WED, "16:30")).unwrap();
+        assert_eq!(r.open_since, Some((hm("14:30"), Location::Office)));
+        assert_eq!(r.target_exit, Some(hm("18:30")));
+        let today = r.today.unwrap();
+        assert_eq!((today.worked, today.surplus()), (120, -120));
+
+        // Monday + today; Tuesday's open IN is not counted
+        assert_eq!(
+            (r.week.days, r.week.worked, r.week.surplus()),
+            (2, 390, -90)
+        );
+        assert_eq!(r.month.worked, r.week.worked);
+    }
+
+    #[test]
+    fn open_day_after_the_target_exit() {
+        let (mut pool, cfg) = setup();
+        punch(&pool, WED, "14:30", EventType::In);
+
+        let r = StatusLogic::compute(&mut pool, &cfg, at(WED, "19:15")).unwrap();
+        assert!(r.is_open());
+        let today = r.today.unwrap();
+        assert_eq!((today.worked, today.surplus()), (285, 45));
+    }
+
+    #[test]
+    fn closed_day_ignores_now() {
+        let (mut pool, cfg) = setup();
+        punch(&pool, WED, "14:30", EventType::In);
+        punch(&pool, WED, "18:00", EventType::Out);
+
+        for now in ["18:15", "23:00"] {
+            let r = StatusLogic::compute(&mut pool, &cfg, at(WED, now)).unwrap();
+            assert!(!r.is_open());
+            let today = r.today.unwrap();
+            assert_eq!((today.worked, today.surplus()), (210, -30));
+            assert_eq!((r.week.days, r.week.surplus()), (2, 0));
+        }
+    }
+}
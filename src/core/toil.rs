@@ -0,0 +1,190 @@
+//! Time off in lieu (`rtimelogger toil`): part of the accumulated surplus is
+//! converted into time off (`accrue`), then taken on a day off (`use`).
+//!
+//! - surplus bank = ΔWORK of every closed working day - TOIL accrued
+//! - TOIL available = TOIL accrued - TOIL used
+//!
+//! Both must stay >= 0: `accrue` cannot exceed the bank, `use` the TOIL
+//! available. The ledger never changes events, pairs or their ΔWORK.
+
+use crate::config::Config;
+use crate::core::logic::Core;
+use crate::core::report::ReportLogic;
+use crate::db::pool::DbPool;
+use crate::db::queries::events::date_bounds;
+use crate::db::queries::load_events_by_date;
+use crate::db::queries::locks::ensure_unlocked;
+use crate::db::queries::toil::{ToilEntry, ToilKind, insert_toil, load_toil};
+use crate::errors::{AppError, AppResult};
+use crate::utils::formatting::{format_surplus, mins2readable};
+use crate::utils::period::Period;
+use chrono::NaiveDate;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ToilBalance {
+    /// ΔWORK of all the closed working days
+    pub surplus: i64,
+    pub accrued: i64,
+    pub used: i64,
+}
+
+impl ToilBalance {
+    /// Surplus that can still be converted with `accrue`.
+    pub fn bank(&self) -> i64 {
+        self.surplus - self.accrued
+    }
+
+    /// Time off that can still be taken with `use`.
+    pub fn available(&self) -> i64 {
+        self.accrued - self.used
+    }
+
+    fn from_ledger(surplus: i64, entries: &[ToilEntry]) -> Self {
+        let sum = |kind: ToilKind| {
+            entries
+                .iter()
+                .filter(|e| e.kind == kind)
+                .map(|e| e.minutes)
+                .sum()
+        };
+        Self {
+            surplus,
+            accrued: sum(ToilKind::Accrue),
+            used: sum(ToilKind::Use),
+        }
+    }
+}
+
+pub struct ToilLogic;
+
+impl ToilLogic {
+    /// Current balance over the whole database.
+    pub fn balance(pool: &mut DbPool, cfg: &Config) -> AppResult<ToilBalance> {
+        let dates = match date_bounds(&pool.conn)? {
+            Some((from, to)) => Period::between(from, to).iter_dates(),
+            None => Vec::new(),
+        };
+        let surplus = ReportLogic::totals(pool, cfg, &dates)?.surplus();
+        let entries = load_toil(&pool.conn, None)?;
+        Ok(ToilBalance::from_ledger(surplus, &entries))
+    }
+
+    /// Ledger entries of `[from, to]` (all with None).
+    pub fn entries(
+        pool: &DbPool,
+        bounds: Option<(NaiveDate, NaiveDate)>,
+    ) -> AppResult<Vec<ToilEntry>> {
+        load_toil(&pool.conn, bounds)
+    }
+
+    /// Convert `minutes` of surplus into TOIL, recorded on `date`.
+    pub fn accrue(
+        pool: &mut DbPool,
+        cfg: &Config,
+        minutes: i64,
+        date: NaiveDate,
+        note: Option<&str>,
+    ) -> AppResult<ToilBalance> {
+        if minutes <= 0 {
+            return Err(AppError::InvalidArgs(
+                "The TOIL to accrue must be a positive duration (e.g. 8h or 3h30m).".into(),
+            ));
+        }
+
+        let before = Self::balance(pool, cfg)?;
+        if minutes > before.bank() {
+            return Err(AppError::InvalidArgs(format!(
+                "Cannot accrue {}: the surplus available is {}.",
+                mins2readable(minutes, false, true),
+                format_surplus(before.bank(), cfg)
+            )));
+        }
+
+        insert_toil(&pool.conn, &date, ToilKind::Accrue, minutes, note)?;
+
+        Ok(ToilBalance {
+            accrued: before.accrued + minutes,
+            ..before
+        })
+    }
+
+    /// Take `minutes` of TOIL (default: one contractual day) on `date`.
+    /// The day must not be locked, hold working time or be taken already.
+    pub fn redeem(
+        pool: &mut DbPool,
+        cfg: &Config,
+        date: NaiveDate,
+        minutes: Option<i64>,
+        note: Option<&str>,
+    ) -> AppResult<(i64, ToilBalance)> {
+        let minutes =
+            minutes.unwrap_or_else(|| Core::parse_work_duration_to_minutes(&cfg.min_work_duration));
+        if minutes <= 0 {
+            return Err(AppError::InvalidArgs(
+                "The TOIL to use must be a positive duration (e.g. 8h or 4h).".into(),
+            ));
+        }
+
+        ensure_unlocked(&pool.conn, &date)?;
+
+        let events = load_events_by_date(pool, &date)?;
+        if !events.is_empty() && !Core::is_marker_day(&Core::build_daily_summary(&events, cfg)) {
+            return Err(AppError::InvalidArgs(format!(
+                "{} has working time recorded: TOIL is taken on a day off.",
+                date
+            )));
+        }
+
+        if Self::entries(pool, Some((date, date)))?
+            .iter()
+            .any(|e| e.kind == ToilKind::Use)
+        {
+            return Err(AppError::InvalidArgs(format!(
+                "TOIL is already taken on {}.",
+                date
+            )));
+        }
+
+        let before = Self::balance(pool, cfg)?;
+        if minutes > before.available() {
+            return Err(AppError::InvalidArgs(format!(
+                "Cannot use {} of TOIL: {} available (convert surplus with `toil accrue`).",
+                mins2readable(minutes, false, true),
+                mins2readable(before.available(), false, true)
+            )));
+        }
+
+        insert_toil(&pool.conn, &date, ToilKind::Use, minutes, note)?;
+
+        Ok((
+            minutes,
+            ToilBalance {
+                used: before.used + minutes,
+                ..before
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bank_and_available() {
+        let d = NaiveDate::from_ymd_opt(2025, 7, 4).unwrap();
+        let entry = |kind, minutes| ToilEntry {
+            id: 0,
+            date: d,
+            kind,
+            minutes,
+            note: None,
+        };
+        let b = ToilBalance::from_ledger(
+            600,
+            &[entry(ToilKind::Accrue, 480), entry(ToilKind::Use, 240)],
+        );
+        assert_eq!(b.bank(), 120);
+        assert_eq!(b.available(), 240);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::db::metadata::check_version_compat;
 use crate::db::migrate::run_pending_migrations;
 use crate::errors::AppResult;
 use rusqlite::Connection;
@@ -8,6 +9,7 @@ pub fn init_db(conn: &Connection) -> AppResult<()> {
     // NO direct CREATE TABLE here.
     // All schema is guaranteed by migrations.
 
+    check_version_compat(conn)?;
     run_pending_migrations(conn)?;
     Ok(())
 }
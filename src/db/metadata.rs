@@ -0,0 +1,207 @@
+//! Key/value metadata stored inside the database itself.
+//! Used to remember which rTimelogger version last wrote the DB, so that an
+//! older binary can refuse to work on a database upgraded by a newer one,
+//! and to mark the file as an rTimelogger database.
+
+use crate::errors::{AppError, AppResult};
+use chrono::Local;
+use rusqlite::{Connection, OptionalExtension, params};
+
+/// Key holding the version of the binary that last wrote the database.
+pub const KEY_APP_VERSION: &str = "app_version";
+
+/// Key holding the timestamp of the last write performed by `KEY_APP_VERSION`.
+pub const KEY_UPDATED_AT: &str = "updated_at";
+
+/// Key holding the schema version of the last `run_pending_migrations`.
+pub const KEY_SCHEMA_VERSION: &str = "schema_version";
+
+/// Key holding the day of the last check for open pairs left on past days.
+pub const KEY_DANGLING_CHECKED_ON: &str = "dangling_checked_on";
+
+/// Key marking the file as an rTimelogger database.
+pub const KEY_APP: &str = "app";
+
+const APP_MARKER: &str = "rtimelogger";
+
+/// Ensure that the `metadata` table exists.
+pub fn ensure_metadata_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS metadata (
+            key   TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Check if the `metadata` table exists.
+fn metadata_table_exists(conn: &Connection) -> rusqlite::Result<bool> {
+    let exists: Option<String> = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name='metadata'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(exists.is_some())
+}
+
+/// Read a single metadata value (None if the table or the key is missing).
+pub fn get_meta(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    if !metadata_table_exists(conn)? {
+        return Ok(None);
+    }
+
+    conn.query_row(
+        "SELECT value FROM metadata WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Insert or update a metadata value.
+pub fn set_meta(conn: &Connection, key: &str, value: &str) -> rusqlite::Result<()> {
+    ensure_metadata_table(conn)?;
+    conn.execute(
+        "INSERT INTO metadata (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Load all metadata rows ordered by key (used by `db --info`).
+pub fn load_all(conn: &Connection) -> rusqlite::Result<Vec<(String, String)>> {
+    if !metadata_table_exists(conn)? {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare("SELECT key, value FROM metadata ORDER BY key ASC")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r?);
+    }
+    Ok(out)
+}
+
+/// Record the running binary as the last writer of the database.
+pub fn record_app_version(conn: &Connection) -> rusqlite::Result<()> {
+    set_meta(conn, KEY_APP, APP_MARKER)?;
+    set_meta(conn, KEY_APP_VERSION, env!("CARGO_PKG_VERSION"))?;
+    set_meta(conn, KEY_UPDATED_AT, &Local::now().to_rfc3339())?;
+    Ok(())
+}
+
+/// True if the database belongs to rTimelogger: stamped with the marker,
+/// still empty, or a legacy DB created before the marker existed.
+pub fn is_rtimelogger_db(conn: &Connection) -> rusqlite::Result<bool> {
+    if get_meta(conn, KEY_APP)?.as_deref() == Some(APP_MARKER) {
+        return Ok(true);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )?;
+    let tables = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let has = |name: &str| tables.iter().any(|t| t == name);
+    Ok(tables.is_empty() || has("work_sessions") || (has("events") && has("log")))
+}
+
+/// Refuse to create our tables inside an unrelated SQLite file.
+pub fn ensure_rtimelogger_db(conn: &Connection, path: &str) -> AppResult<()> {
+    if !is_rtimelogger_db(conn)? {
+        return Err(AppError::ForeignDatabase(path.to_string()));
+    }
+    Ok(())
+}
+
+/// Compatibility key of a semver string.
+/// For 0.x versions the minor number is the breaking component (semver rules),
+/// otherwise the major number is used.
+fn compat_key(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major: u64 = parts.next()?.parse().ok()?;
+    let minor: u64 = parts
+        .next()
+        .and_then(|m| m.split('-').next())
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(0);
+
+    if major == 0 {
+        Some((0, minor))
+    } else {
+        Some((major, 0))
+    }
+}
+
+/// Refuse to work on a database written by a newer, incompatible version.
+pub fn check_version_compat(conn: &Connection) -> AppResult<()> {
+    let Some(stored) = get_meta(conn, KEY_APP_VERSION)? else {
+        return Ok(()); // DB never stamped → legacy, nothing to check
+    };
+
+    let current = env!("CARGO_PKG_VERSION");
+
+    if let (Some(db_key), Some(bin_key)) = (compat_key(&stored), compat_key(current))
+        && db_key > bin_key
+    {
+        return Err(AppError::IncompatibleDbVersion {
+            found: stored,
+            current: current.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compat_key_uses_minor_for_zero_major() {
+        assert_eq!(compat_key("0.8.8"), Some((0, 8)));
+        assert_eq!(compat_key("v0.9.0-beta1"), Some((0, 9)));
+        assert_eq!(compat_key("1.2.3"), Some((1, 0)));
+        assert_eq!(compat_key("garbage"), None);
+    }
+
+    #[test]
+    fn newer_major_is_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        set_meta(&conn, KEY_APP_VERSION, "99.0.0").unwrap();
+        assert!(check_version_compat(&conn).is_err());
+    }
+
+    #[test]
+    fn foreign_database_is_detected() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(is_rtimelogger_db(&conn).unwrap());
+
+        conn.execute_batch("CREATE TABLE customers (id INTEGER);")
+            .unwrap();
+        assert!(matches!(
+            ensure_rtimelogger_db(&conn, "other.sqlite"),
+            Err(AppError::ForeignDatabase(_))
+        ));
+
+        record_app_version(&conn).unwrap();
+        assert!(is_rtimelogger_db(&conn).unwrap());
+    }
+
+    #[test]
+    fn same_version_is_accepted() {
+        let conn = Connection::open_in_memory().unwrap();
+        record_app_version(&conn).unwrap();
+        assert!(check_version_compat(&conn).is_ok());
+    }
+}
@@ -1,4 +1,6 @@
-use crate::db::db_utils;
+use crate::core::calculator::overlap::OverlapMode;
+use crate::db::queries::{focus, locks, marks, pairs, toil, trash};
+use crate::db::{db_utils, metadata};
 use crate::ui::messages::{error, success, warning};
 use rusqlite::{Connection, Error, OptionalExtension, Result};
 
@@ -34,6 +36,56 @@ fn events_table_exists(conn: &Connection) -> Result<bool> {
     Ok(exists.is_some())
 }
 
+/// Check if a table exists.
+fn table_exists(conn: &Connection, name: &str) -> Result<bool> {
+    let exists: Option<String> = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name=?1",
+            [name],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(exists.is_some())
+}
+
+/// Steps of [`run_pending_migrations`] with the check telling whether a
+/// database already has them (shown by `--version --verbose`).
+type MigrationCheck = fn(&Connection) -> Result<bool>;
+const MIGRATIONS: &[(&str, MigrationCheck)] = &[
+    ("events table", events_table_exists),
+    ("events.pair", events_has_pair_column),
+    ("events.work_gap", |c| events_has_column(c, "work_gap")),
+    ("work_sessions dropped", |c| {
+        work_sessions_table_exists(c).map(|e| !e)
+    }),
+    ("position N (national holiday)", |c| {
+        Ok(events_table_exists(c)? && events_position_supports_national_holiday(c)?)
+    }),
+    ("position S (sick leave)", |c| {
+        Ok(events_table_exists(c)? && events_position_supports_sick_leave(c)?)
+    }),
+    ("events.notes", |c| events_has_column(c, "notes")),
+    ("events.unmatched", |c| events_has_column(c, "unmatched")),
+    ("events.host", |c| events_has_column(c, "host")),
+    ("locked_days table", |c| table_exists(c, "locked_days")),
+    ("focus_events table", |c| table_exists(c, "focus_events")),
+    ("events_trash table", |c| table_exists(c, "events_trash")),
+    ("toil_ledger table", |c| table_exists(c, "toil_ledger")),
+    ("marks table", |c| table_exists(c, "marks")),
+    ("metadata table", |c| table_exists(c, "metadata")),
+];
+
+/// Schema version of this binary: the number of migration steps.
+pub const SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+/// Every migration step with whether the database has it.
+pub fn applied_migrations(conn: &Connection) -> Result<Vec<(&'static str, bool)>> {
+    MIGRATIONS
+        .iter()
+        .map(|(name, check)| Ok((*name, check(conn)?)))
+        .collect()
+}
+
 /// Check if the `events` table has a `pair` column.
 fn events_has_pair_column(conn: &Connection) -> Result<bool> {
     let mut stmt = conn.prepare("PRAGMA table_info('events')")?;
@@ -72,10 +124,13 @@ fn create_events_table(conn: &Connection) -> Result<()> {
             position     TEXT NOT NULL DEFAULT 'O' CHECK(position IN ('O','R','H','N','C','M','S')),
             lunch_break  INTEGER NOT NULL DEFAULT 0,
             pair         INTEGER NOT NULL DEFAULT 0,
+            unmatched    INTEGER NOT NULL DEFAULT 0,
             work_gap     INTEGER NOT NULL DEFAULT 0,
             source       TEXT NOT NULL DEFAULT 'cli',
             meta         TEXT DEFAULT '',
             notes        TEXT DEFAULT '',
+            host         TEXT,
+            os_user      TEXT,
             created_at   TEXT NOT NULL
         );
 
@@ -113,10 +168,13 @@ fn migrate_add_pair_to_events(conn: &Connection) -> Result<()> {
             position     TEXT NOT NULL DEFAULT 'O' CHECK(position IN ('O','R','H','C','M')),
             lunch_break  INTEGER NOT NULL DEFAULT 0,
             pair         INTEGER NOT NULL DEFAULT 0,
+            unmatched    INTEGER NOT NULL DEFAULT 0,
             work_gap     INTEGER NOT NULL DEFAULT 0,
             source       TEXT NOT NULL DEFAULT 'cli',
             meta         TEXT DEFAULT '',
             notes        TEXT DEFAULT '',
+            host         TEXT,
+            os_user      TEXT,
             created_at   TEXT NOT NULL
         );
 
@@ -163,7 +221,8 @@ fn migrate_add_pair_to_events(conn: &Connection) -> Result<()> {
     };
 
     // Ricostruiamo i pair usando l’API moderna
-    match db_utils::rebuild_all_pairs(&mut pool) {
+    // Databases this old predate overlap_mode: one sequence per day
+    match db_utils::rebuild_all_pairs(&mut pool, OverlapMode::Off) {
         Ok(_) => success("Populated 'pair' column for existing events."),
         Err(e) => error(format!("Failed to rebuild pairs: {}", e)),
     }
@@ -462,6 +521,84 @@ fn migrate_add_notes_column(conn: &Connection) -> Result<(), Error> {
     Ok(())
 }
 
+/// Add the `unmatched` flag and persist pairs for every existing date,
+/// so that exports and external readers see the same pairing as the CLI.
+fn migrate_add_unmatched_column(conn: &Connection) -> Result<(), Error> {
+    let version = "20261016_0014_add_unmatched_to_events";
+
+    if !events_table_exists(conn)? || events_has_column(conn, "unmatched")? {
+        return Ok(());
+    }
+
+    conn.execute(
+        "ALTER TABLE events ADD COLUMN unmatched INTEGER NOT NULL DEFAULT 0;",
+        [],
+    )
+    .map_err(|e| {
+        Error::SqliteFailure(
+            rusqlite::ffi::Error::new(1),
+            Some(format!("Failed to add 'unmatched' column: {}", e)),
+        )
+    })?;
+
+    // Databases this old predate overlap_mode: one sequence per day
+    if let Err(e) = pairs::rebuild_all_pairs(conn, OverlapMode::Off) {
+        error(format!("Failed to rebuild pairs: {}", e));
+    }
+
+    conn.execute(
+        "INSERT INTO log (date, operation, target, message)
+         VALUES (datetime('now'), 'migration_applied', ?1, 'Added unmatched flag to events')",
+        [version],
+    )?;
+
+    success(format!(
+        "Migration applied: {} → added 'unmatched' to events table",
+        version
+    ));
+
+    Ok(())
+}
+
+/// Add `host` / `os_user` columns recording where each event was created.
+/// Existing events keep NULL: their origin is unknown.
+fn migrate_add_host_columns(conn: &Connection) -> Result<(), Error> {
+    let version = "20261016_0015_add_host_to_events";
+
+    if !events_table_exists(conn)? || events_has_column(conn, "host")? {
+        return Ok(());
+    }
+
+    for column in ["host", "os_user"] {
+        if events_has_column(conn, column)? {
+            continue;
+        }
+        conn.execute(
+            &format!("ALTER TABLE events ADD COLUMN {} TEXT;", column),
+            [],
+        )
+        .map_err(|e| {
+            Error::SqliteFailure(
+                rusqlite::ffi::Error::new(1),
+                Some(format!("Failed to add '{}' column: {}", column, e)),
+            )
+        })?;
+    }
+
+    conn.execute(
+        "INSERT INTO log (date, operation, target, message)
+         VALUES (datetime('now'), 'migration_applied', ?1, 'Added host and os_user to events')",
+        [version],
+    )?;
+
+    success(format!(
+        "Migration applied: {} → added 'host' and 'os_user' to events table",
+        version
+    ));
+
+    Ok(())
+}
+
 /// Public entry point: run all pending migrations.
 ///
 /// Invocata da db::init_db().
@@ -480,7 +617,8 @@ pub fn run_pending_migrations(conn: &Connection) -> Result<()> {
     // 3) Detect legacy schema (< 0.8.0-beta1)
     let work_sessions_exists = work_sessions_table_exists(conn)?;
 
-    let is_legacy_schema = work_sessions_exists || !events_has_pair;
+    // (a brand-new DB without any table is not legacy: nothing to back up)
+    let is_legacy_schema = work_sessions_exists || (events_exists && !events_has_pair);
 
     // 4) If legacy → perform PRE-MIGRATION BACKUP
     if is_legacy_schema {
@@ -526,6 +664,36 @@ pub fn run_pending_migrations(conn: &Connection) -> Result<()> {
     // 9) Add optional notes field to events.
     migrate_add_notes_column(conn)?;
 
+    // 10) Add unmatched flag and persist pairs.
+    migrate_add_unmatched_column(conn)?;
+
+    // 11) Record the host (and OS user) that created each event.
+    migrate_add_host_columns(conn)?;
+
+    // 12) Per-day locks.
+    locks::ensure_locked_days_table(conn)?;
+
+    // 13) Focus sessions (kept out of the events table).
+    focus::ensure_focus_table(conn)?;
+
+    // 14) Trash of deleted events.
+    trash::ensure_trash_table(conn)?;
+
+    // 15) Time off in lieu ledger.
+    toil::ensure_toil_table(conn)?;
+
+    // 16) Day bookmarks (`mark`).
+    marks::ensure_marks_table(conn)?;
+
+    // 17) Stamp the DB with the version of this binary (and of its schema).
+    metadata::ensure_metadata_table(conn)?;
+    metadata::record_app_version(conn)?;
+    metadata::set_meta(
+        conn,
+        metadata::KEY_SCHEMA_VERSION,
+        &SCHEMA_VERSION.to_string(),
+    )?;
+
     Ok(())
 }
 
@@ -560,3 +728,21 @@ fn events_position_supports_sick_leave(conn: &Connection) -> Result<bool> {
     // Check semplice e affidabile
     Ok(sql.contains("'S'"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::{DbPool, MEMORY_DB};
+
+    #[test]
+    fn fresh_database_has_every_migration() {
+        let pool = DbPool::new(MEMORY_DB).unwrap();
+        let applied = applied_migrations(&pool.conn).unwrap();
+        assert_eq!(applied.len(), SCHEMA_VERSION);
+        assert!(applied.iter().all(|(_, ok)| *ok), "{:?}", applied);
+        assert_eq!(
+            metadata::get_meta(&pool.conn, metadata::KEY_SCHEMA_VERSION).unwrap(),
+            Some(SCHEMA_VERSION.to_string())
+        );
+    }
+}
@@ -1,6 +1,7 @@
 pub mod db_utils;
 pub mod initialize;
 pub mod log;
+pub mod metadata;
 pub mod migrate;
 pub mod models;
 pub mod pool;
@@ -1,18 +1,76 @@
 //! SQLite connection pool wrapper (lightweight for CLI usage).
 
+use crate::db::initialize::init_db;
+use crate::db::metadata::{check_version_compat, ensure_rtimelogger_db};
+use crate::errors::AppResult;
+use crate::ui::messages::quietly;
 use rusqlite::{Connection, Result};
 use std::path::Path;
 
+/// `--db :memory:`: a throw-away database living for a single command.
+pub const MEMORY_DB: &str = ":memory:";
+
+pub fn is_memory_db(path: &str) -> bool {
+    path == MEMORY_DB
+}
+
 pub struct DbPool {
     pub conn: Connection,
 }
 
 impl DbPool {
-    pub fn new(path: &str) -> Result<Self> {
+    /// Open the database, refusing SQLite files that do not belong to
+    /// rTimelogger and DBs written by a newer incompatible version.
+    pub fn new(path: &str) -> AppResult<Self> {
+        if is_memory_db(path) {
+            // Nothing to open: create the schema right away
+            let conn = Connection::open_in_memory()?;
+            quietly(|| init_db(&conn))?;
+            return Ok(Self { conn });
+        }
+
         let conn = Connection::open(Path::new(path))?;
+        ensure_rtimelogger_db(&conn, path)?;
+        check_version_compat(&conn)?;
         Ok(Self { conn })
     }
 
+    /// Copy the write-ahead log back into the database file and truncate the
+    /// `-wal` file, so that a plain copy of the `.sqlite` file has all the
+    /// data. Nothing to do unless the database is in WAL mode.
+    pub fn checkpoint(&self) -> AppResult<()> {
+        let mode: String = self
+            .conn
+            .query_row("PRAGMA journal_mode;", [], |row| row.get(0))?;
+        if mode.eq_ignore_ascii_case("wal") {
+            // (busy, log frames, checkpointed frames): busy only means another
+            // process is reading, the next command will try again
+            self.conn
+                .query_row("PRAGMA wal_checkpoint(TRUNCATE);", [], |_| Ok(()))?;
+        }
+        Ok(())
+    }
+
+    /// Run `func` in a single write transaction: committed when it returns
+    /// `Ok`, rolled back otherwise, so a failure half-way leaves no partial
+    /// writes. Reads inside `func` see its own uncommitted changes.
+    pub fn in_transaction<F, T>(&mut self, func: F) -> AppResult<T>
+    where
+        F: FnOnce(&mut DbPool) -> AppResult<T>,
+    {
+        self.conn.execute_batch("BEGIN IMMEDIATE")?;
+        match func(self) {
+            Ok(v) => {
+                self.conn.execute_batch("COMMIT")?;
+                Ok(v)
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
     /// Helper to execute a closure with a mutable connection reference.
     pub fn with_conn<F, T>(&mut self, func: F) -> Result<T>
     where
@@ -21,3 +79,73 @@ impl DbPool {
         func(&mut self.conn)
     }
 }
+
+/// Checkpoint of `path` at the end of a write command; only opened when a
+/// `-wal` file is left next to the database.
+pub fn checkpoint_file(path: &str) -> AppResult<()> {
+    if is_memory_db(path) || !Path::new(&format!("{}-wal", path)).exists() {
+        return Ok(());
+    }
+    let pool = DbPool {
+        conn: Connection::open(Path::new(path))?,
+    };
+    pool.checkpoint()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_db_comes_with_schema() {
+        let pool = DbPool::new(MEMORY_DB).unwrap();
+        let n: i64 = pool
+            .conn
+            .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(n, 0);
+    }
+
+    #[test]
+    fn failed_transaction_leaves_no_writes() {
+        let mut pool = DbPool::new(MEMORY_DB).unwrap();
+        let res: AppResult<()> = pool.in_transaction(|p| {
+            p.conn.execute(
+                "INSERT INTO events (date, time, kind, position, created_at)
+                 VALUES ('2025-06-18', '08:00', 'in', 'O', '')",
+                [],
+            )?;
+            Err(crate::errors::AppError::Other("boom".into()))
+        });
+        assert!(res.is_err());
+
+        let n: i64 = pool
+            .conn
+            .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(n, 0);
+    }
+
+    #[test]
+    fn checkpoint_truncates_the_wal_file() {
+        let path = std::env::temp_dir().join(format!("rtl-wal-{}.sqlite", std::process::id()));
+        let path_str = path.to_string_lossy().to_string();
+        let wal = format!("{}-wal", path_str);
+
+        let conn = Connection::open(&path).unwrap();
+        let _: String = conn
+            .query_row("PRAGMA journal_mode=WAL;", [], |row| row.get(0))
+            .unwrap();
+        conn.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1);")
+            .unwrap();
+        assert!(std::fs::metadata(&wal).unwrap().len() > 0);
+
+        checkpoint_file(&path_str).unwrap();
+        assert_eq!(std::fs::metadata(&wal).unwrap().len(), 0);
+
+        drop(conn);
+        for f in [path_str.clone(), wal, format!("{}-shm", path_str)] {
+            let _ = std::fs::remove_file(f);
+        }
+    }
+}
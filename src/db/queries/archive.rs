@@ -0,0 +1,145 @@
+//! Archive DB: old events moved to a separate SQLite file by
+//! `rtimelogger archive`, read back transparently by `list --archive`.
+
+use crate::errors::{AppError, AppResult};
+use chrono::NaiveDate;
+use rusqlite::Connection;
+
+/// Metadata key (in the working DB) holding the path of its archive.
+pub const KEY_ARCHIVE_DB: &str = "archive_db";
+
+/// Attach `path` as the `archive` schema.
+pub fn attach_archive(conn: &Connection, path: &str) -> AppResult<()> {
+    conn.execute("ATTACH DATABASE ?1 AS archive", [path])?;
+    Ok(())
+}
+
+pub fn detach_archive(conn: &Connection) -> AppResult<()> {
+    conn.execute("DETACH DATABASE archive", [])?;
+    Ok(())
+}
+
+/// Columns of `schema.table` in declaration order.
+fn table_columns(conn: &Connection, schema: &str, table: &str) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info({})", schema, table))?;
+    let cols = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(cols)
+}
+
+/// Columns of `table` present in both schemas, `id` excluded (the archive
+/// assigns its own ids).
+fn shared_columns(conn: &Connection, table: &str) -> AppResult<String> {
+    let archive = table_columns(conn, "archive", table)?;
+    let cols: Vec<String> = table_columns(conn, "main", table)?
+        .into_iter()
+        .filter(|c| c != "id" && archive.contains(c))
+        .collect();
+    Ok(cols.join(", "))
+}
+
+/// Move the events dated before `before`, and the log rows written before
+/// it, from `main` into `archive`. Migration markers stay in `main`.
+/// Returns the number of events and log rows moved.
+pub fn move_before(conn: &Connection, before: &NaiveDate) -> AppResult<(usize, usize)> {
+    let cutoff = before.format("%Y-%m-%d").to_string();
+
+    let cols = shared_columns(conn, "events")?;
+    conn.execute(
+        &format!(
+            "INSERT INTO archive.events ({cols}) SELECT {cols} FROM main.events
+             WHERE date < ?1 ORDER BY date, time",
+            cols = cols
+        ),
+        [&cutoff],
+    )?;
+    let events = conn.execute("DELETE FROM main.events WHERE date < ?1", [&cutoff])?;
+
+    let log_filter = "date < ?1 AND operation <> 'migration_applied'";
+    let cols = shared_columns(conn, "log")?;
+    conn.execute(
+        &format!(
+            "INSERT INTO archive.log ({cols}) SELECT {cols} FROM main.log WHERE {filter} ORDER BY id",
+            cols = cols,
+            filter = log_filter
+        ),
+        [&cutoff],
+    )?;
+    let logs = conn.execute(
+        &format!("DELETE FROM main.log WHERE {}", log_filter),
+        [&cutoff],
+    )?;
+
+    Ok((events, logs))
+}
+
+/// Shadow `events` with a temporary view over working DB + archive: the temp
+/// schema is searched first, so every read query sees both.
+pub fn create_events_union_view(conn: &Connection) -> AppResult<()> {
+    let main = table_columns(conn, "main", "events")?;
+    let archive = table_columns(conn, "archive", "events")?;
+
+    if archive.is_empty() {
+        return Err(AppError::InvalidOperation(
+            "the archive database has no events table".into(),
+        ));
+    }
+
+    let from_archive: Vec<String> = main
+        .iter()
+        .map(|c| {
+            if archive.contains(c) {
+                c.clone()
+            } else {
+                format!("NULL AS {}", c)
+            }
+        })
+        .collect();
+
+    conn.execute_batch(&format!(
+        "CREATE TEMP VIEW events AS
+             SELECT {} FROM main.events
+             UNION ALL
+             SELECT {} FROM archive.events;",
+        main.join(", "),
+        from_archive.join(", ")
+    ))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moved_events_are_still_visible_through_the_view() {
+        let conn = Connection::open_in_memory().unwrap();
+        attach_archive(&conn, ":memory:").unwrap();
+        for schema in ["main", "archive"] {
+            conn.execute_batch(&format!(
+                "CREATE TABLE {s}.events (id INTEGER PRIMARY KEY, date TEXT, time TEXT);
+                 CREATE TABLE {s}.log (id INTEGER PRIMARY KEY, date TEXT, operation TEXT, target TEXT, message TEXT);",
+                s = schema
+            ))
+            .unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO events (date, time) VALUES ('2022-12-30', '09:00'), ('2023-01-02', '09:00');
+             INSERT INTO log (date, operation, target, message) VALUES
+                 ('2022-12-30T09:00:00+01:00', 'add', '', ''),
+                 ('2022-01-01T00:00:00+01:00', 'migration_applied', 'v1', '');",
+        )
+        .unwrap();
+
+        let before = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        assert_eq!(move_before(&conn, &before).unwrap(), (1, 1));
+
+        let count = |sql: &str| conn.query_row(sql, [], |r| r.get::<_, i64>(0)).unwrap();
+        assert_eq!(count("SELECT COUNT(*) FROM main.events"), 1);
+        assert_eq!(count("SELECT COUNT(*) FROM main.log"), 1);
+
+        create_events_union_view(&conn).unwrap();
+        assert_eq!(count("SELECT COUNT(*) FROM events"), 2);
+    }
+}
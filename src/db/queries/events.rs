@@ -24,6 +24,29 @@ pub fn load_events_by_date(pool: &mut DbPool, date: &NaiveDate) -> AppResult<Vec
     Ok(out)
 }
 
+/// Events of `[from, to]` (every event with None), in chronological order.
+pub fn load_events_between(
+    conn: &Connection,
+    bounds: Option<(NaiveDate, NaiveDate)>,
+) -> AppResult<Vec<Event>> {
+    let (from, to) = match bounds {
+        Some((f, t)) => (f.to_string(), t.to_string()),
+        None => ("0000-01-01".to_string(), "9999-12-31".to_string()),
+    };
+    let mut stmt = conn.prepare(
+        "SELECT * FROM events
+         WHERE date BETWEEN ?1 AND ?2
+         ORDER BY date ASC, time ASC",
+    )?;
+    let rows = stmt.query_map(params![from, to], map_row)?;
+
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r?);
+    }
+    Ok(out)
+}
+
 pub fn map_row(row: &Row) -> Result<Event> {
     let date_str: String = row.get("date")?;
     let time_str: String = row.get("time")?;
@@ -77,17 +100,20 @@ pub fn map_row(row: &Row) -> Result<Event> {
         lunch: row.get("lunch_break")?,
         work_gap: row.get::<_, i32>("work_gap")? == 1,
         pair: row.get("pair")?,
+        unmatched: row.get::<_, i32>("unmatched")? == 1,
         source: row.get("source")?,
         meta: row.get("meta")?,
         notes: row.get("notes")?,
+        host: row.get("host")?,
+        os_user: row.get("os_user")?,
         created_at: row.get("created_at")?,
     })
 }
 
 pub fn insert_event(conn: &Connection, ev: &Event) -> AppResult<()> {
     conn.execute(
-        "INSERT INTO events (date, time, kind, position, lunch_break, work_gap, pair, source, meta, notes, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        "INSERT INTO events (date, time, kind, position, lunch_break, work_gap, pair, source, meta, notes, host, os_user, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
         params![
             ev.date.format("%Y-%m-%d").to_string(),
             ev.time.format("%H:%M").to_string(),
@@ -99,6 +125,8 @@ pub fn insert_event(conn: &Connection, ev: &Event) -> AppResult<()> {
             ev.source,
             ev.meta,
             ev.notes,
+            ev.host,
+            ev.os_user,
             ev.created_at,
         ],
     )?;
@@ -192,3 +220,81 @@ pub fn date_has_events(conn: &Connection, date: &NaiveDate) -> AppResult<bool> {
     )?;
     Ok(exists == 1)
 }
+
+/// Dates of `[from, to]` (every date with None) having an event at
+/// `position` (any with None) and, with `with_notes`, a non-empty note.
+pub fn find_candidate_dates(
+    conn: &Connection,
+    bounds: Option<(NaiveDate, NaiveDate)>,
+    position: Option<Location>,
+    with_notes: bool,
+) -> AppResult<Vec<NaiveDate>> {
+    let (from, to) = match bounds {
+        Some((f, t)) => (f.to_string(), t.to_string()),
+        None => ("0000-01-01".to_string(), "9999-12-31".to_string()),
+    };
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT date FROM events
+         WHERE date BETWEEN ?1 AND ?2
+           AND (?3 IS NULL OR position = ?3)
+           AND (?4 = 0 OR TRIM(COALESCE(notes, '')) <> '')
+         ORDER BY date ASC",
+    )?;
+    let rows = stmt.query_map(
+        params![from, to, position.map(|p| p.code().to_string()), with_notes],
+        |row| row.get::<_, String>(0),
+    )?;
+
+    let mut v = Vec::new();
+    for r in rows {
+        let d = r?;
+        v.push(NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| AppError::InvalidDate(d))?);
+    }
+    Ok(v)
+}
+
+/// Days before `before` with an IN still waiting for its OUT (sick-leave
+/// markers excluded), with the time of the first such IN, in order.
+pub fn dangling_in_days(
+    conn: &Connection,
+    before: NaiveDate,
+) -> AppResult<Vec<(NaiveDate, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT date, MIN(time) FROM events
+         WHERE kind = 'in' AND unmatched = 1 AND position NOT IN ('H', 'N', 'S')
+           AND date < ?1
+         GROUP BY date
+         ORDER BY date ASC",
+    )?;
+    let rows = stmt.query_map(params![before.to_string()], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+
+    let mut v = Vec::new();
+    for r in rows {
+        let (d, time) = r?;
+        let date =
+            NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| AppError::InvalidDate(d))?;
+        v.push((date, time));
+    }
+    Ok(v)
+}
+
+/// First and last recorded date (None if the table is empty).
+pub fn date_bounds(conn: &Connection) -> AppResult<Option<(NaiveDate, NaiveDate)>> {
+    let (first, last): (Option<String>, Option<String>) =
+        conn.query_row("SELECT MIN(date), MAX(date) FROM events", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })?;
+
+    match (first, last) {
+        (Some(f), Some(l)) => {
+            let f =
+                NaiveDate::parse_from_str(&f, "%Y-%m-%d").map_err(|_| AppError::InvalidDate(f))?;
+            let l =
+                NaiveDate::parse_from_str(&l, "%Y-%m-%d").map_err(|_| AppError::InvalidDate(l))?;
+            Ok(Some((f, l)))
+        }
+        _ => Ok(None),
+    }
+}
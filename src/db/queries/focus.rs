@@ -0,0 +1,154 @@
+//! Focus (pomodoro / deep work) events: `start` / `stop` rows kept next to
+//! the punches but in their own table, so pairs and surplus never see them.
+
+use crate::errors::AppResult;
+use chrono::{Local, NaiveDate, NaiveTime};
+use rusqlite::{Connection, OptionalExtension, params};
+
+/// Ensure that the `focus_events` table exists.
+pub fn ensure_focus_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS focus_events (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            date       TEXT NOT NULL,
+            time       TEXT NOT NULL,
+            kind       TEXT NOT NULL CHECK(kind IN ('start','stop')),
+            label      TEXT,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_focus_events_date ON focus_events(date, time);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// A focus session of one day; `end` is None while it is running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusSession {
+    pub date: NaiveDate,
+    pub start: NaiveTime,
+    pub end: Option<NaiveTime>,
+    pub label: Option<String>,
+}
+
+impl FocusSession {
+    pub fn minutes(&self) -> i64 {
+        self.end
+            .map(|e| (e - self.start).num_minutes().max(0))
+            .unwrap_or(0)
+    }
+}
+
+/// Store a `start` (with its label) or `stop` event.
+pub fn insert_focus_event(
+    conn: &Connection,
+    date: &NaiveDate,
+    time: &NaiveTime,
+    start: bool,
+    label: Option<&str>,
+) -> AppResult<()> {
+    ensure_focus_table(conn)?;
+    conn.execute(
+        "INSERT INTO focus_events (date, time, kind, label, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            date.format("%Y-%m-%d").to_string(),
+            time.format("%H:%M").to_string(),
+            if start { "start" } else { "stop" },
+            label,
+            Local::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// The running session, if the latest focus event is a `start`.
+pub fn open_focus(conn: &Connection) -> AppResult<Option<FocusSession>> {
+    ensure_focus_table(conn)?;
+    let last = conn
+        .query_row(
+            "SELECT date, time, kind, label FROM focus_events ORDER BY date DESC, time DESC, id DESC LIMIT 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    Ok(last.and_then(|(d, t, kind, label)| {
+        (kind == "start").then_some(())?;
+        Some(FocusSession {
+            date: NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()?,
+            start: NaiveTime::parse_from_str(&t, "%H:%M").ok()?,
+            end: None,
+            label,
+        })
+    }))
+}
+
+/// Sessions of a day: every `start` is closed by the next `stop` of the same day.
+pub fn focus_sessions_by_date(conn: &Connection, date: &NaiveDate) -> AppResult<Vec<FocusSession>> {
+    ensure_focus_table(conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT time, kind, label FROM focus_events WHERE date = ?1 ORDER BY time ASC, id ASC",
+    )?;
+    let rows = stmt
+        .query_map([date.format("%Y-%m-%d").to_string()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut sessions: Vec<FocusSession> = Vec::new();
+    for (t, kind, label) in rows {
+        let Ok(time) = NaiveTime::parse_from_str(&t, "%H:%M") else {
+            continue;
+        };
+        match kind.as_str() {
+            "start" => sessions.push(FocusSession {
+                date: *date,
+                start: time,
+                end: None,
+                label,
+            }),
+            _ => {
+                if let Some(s) = sessions.last_mut().filter(|s| s.end.is_none()) {
+                    s.end = Some(time);
+                }
+            }
+        }
+    }
+
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_stop_make_sessions() {
+        let conn = Connection::open_in_memory().unwrap();
+        let d = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+        let t = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+        insert_focus_event(&conn, &d, &t(9, 0), true, Some("deep work")).unwrap();
+        insert_focus_event(&conn, &d, &t(9, 50), false, None).unwrap();
+        insert_focus_event(&conn, &d, &t(10, 0), true, None).unwrap();
+        assert_eq!(open_focus(&conn).unwrap().map(|s| s.start), Some(t(10, 0)));
+
+        let sessions = focus_sessions_by_date(&conn, &d).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].minutes(), 50);
+        assert_eq!(sessions[0].label.as_deref(), Some("deep work"));
+        assert_eq!(sessions[1].end, None);
+    }
+}
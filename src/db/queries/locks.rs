@@ -0,0 +1,100 @@
+//! Per-day locks: a locked day (e.g. approved or invoiced) cannot be
+//! modified by `add`, `del` or imports until it is unlocked.
+
+use crate::errors::{AppError, AppResult};
+use chrono::{Local, NaiveDate};
+use rusqlite::{Connection, OptionalExtension, params};
+
+/// Ensure that the `locked_days` table exists.
+pub fn ensure_locked_days_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS locked_days (
+            date      TEXT PRIMARY KEY,
+            locked_at TEXT NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+fn locked_days_table_exists(conn: &Connection) -> rusqlite::Result<bool> {
+    let exists: Option<String> = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name='locked_days'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(exists.is_some())
+}
+
+/// True if the day is locked (a DB without the table has no locks).
+pub fn is_locked(conn: &Connection, date: &NaiveDate) -> AppResult<bool> {
+    if !locked_days_table_exists(conn)? {
+        return Ok(false);
+    }
+
+    let found: Option<i64> = conn
+        .query_row(
+            "SELECT 1 FROM locked_days WHERE date = ?1",
+            [date.format("%Y-%m-%d").to_string()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(found.is_some())
+}
+
+/// Fail with `DayLocked` if the day is locked.
+pub fn ensure_unlocked(conn: &Connection, date: &NaiveDate) -> AppResult<()> {
+    if is_locked(conn, date)? {
+        return Err(AppError::DayLocked(*date));
+    }
+    Ok(())
+}
+
+/// Lock a day. Returns false if it was already locked.
+pub fn lock_day(conn: &Connection, date: &NaiveDate) -> AppResult<bool> {
+    ensure_locked_days_table(conn)?;
+    let n = conn.execute(
+        "INSERT OR IGNORE INTO locked_days (date, locked_at) VALUES (?1, ?2)",
+        params![
+            date.format("%Y-%m-%d").to_string(),
+            Local::now().to_rfc3339()
+        ],
+    )?;
+    Ok(n > 0)
+}
+
+/// Unlock a day. Returns false if it was not locked.
+pub fn unlock_day(conn: &Connection, date: &NaiveDate) -> AppResult<bool> {
+    if !locked_days_table_exists(conn)? {
+        return Ok(false);
+    }
+    let n = conn.execute(
+        "DELETE FROM locked_days WHERE date = ?1",
+        [date.format("%Y-%m-%d").to_string()],
+    )?;
+    Ok(n > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_and_unlock_roundtrip() {
+        let conn = Connection::open_in_memory().unwrap();
+        let d = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+
+        assert!(!is_locked(&conn, &d).unwrap());
+        assert!(lock_day(&conn, &d).unwrap());
+        assert!(!lock_day(&conn, &d).unwrap());
+        assert!(matches!(
+            ensure_unlocked(&conn, &d),
+            Err(AppError::DayLocked(_))
+        ));
+        assert!(unlock_day(&conn, &d).unwrap());
+        assert!(ensure_unlocked(&conn, &d).is_ok());
+    }
+}
@@ -0,0 +1,99 @@
+//! Day bookmarks (`rtimelogger mark`): a label on a date, referenced by its
+//! name (`mark:<name>`) in period expressions. Events are not touched.
+
+use crate::errors::{AppError, AppResult};
+use chrono::{Local, NaiveDate};
+use rusqlite::{Connection, OptionalExtension, params};
+
+/// Ensure that the `marks` table exists.
+pub fn ensure_marks_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS marks (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            date       TEXT NOT NULL,
+            label      TEXT NOT NULL,
+            name       TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_marks_date ON marks(date);
+        "#,
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mark {
+    pub id: i64,
+    pub date: NaiveDate,
+    pub label: String,
+    /// Name used in `mark:<name>` (the label in kebab-case)
+    pub name: String,
+}
+
+fn map_mark(row: &rusqlite::Row) -> rusqlite::Result<(i64, String, String, String)> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+}
+
+fn to_mark((id, d, label, name): (i64, String, String, String)) -> AppResult<Mark> {
+    Ok(Mark {
+        id,
+        date: NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| AppError::InvalidDate(d))?,
+        label,
+        name,
+    })
+}
+
+pub fn insert_mark(conn: &Connection, date: &NaiveDate, label: &str, name: &str) -> AppResult<()> {
+    ensure_marks_table(conn)?;
+    conn.execute(
+        "INSERT INTO marks (date, label, name, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            date.format("%Y-%m-%d").to_string(),
+            label,
+            name,
+            Local::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Remove the bookmark called `name`; false if there was none.
+pub fn delete_mark(conn: &Connection, name: &str) -> AppResult<bool> {
+    ensure_marks_table(conn)?;
+    let n = conn.execute("DELETE FROM marks WHERE name = ?1", params![name])?;
+    Ok(n > 0)
+}
+
+pub fn find_mark(conn: &Connection, name: &str) -> AppResult<Option<Mark>> {
+    ensure_marks_table(conn)?;
+    conn.query_row(
+        "SELECT id, date, label, name FROM marks WHERE name = ?1",
+        params![name],
+        map_mark,
+    )
+    .optional()?
+    .map(to_mark)
+    .transpose()
+}
+
+/// Bookmarks in date order, optionally limited to `[from, to]`.
+pub fn load_marks(
+    conn: &Connection,
+    bounds: Option<(NaiveDate, NaiveDate)>,
+) -> AppResult<Vec<Mark>> {
+    ensure_marks_table(conn)?;
+    let (from, to) = bounds
+        .map(|(f, t)| (f.to_string(), t.to_string()))
+        .unwrap_or_else(|| ("0000-01-01".into(), "9999-12-31".into()));
+
+    let mut stmt = conn.prepare(
+        "SELECT id, date, label, name FROM marks
+         WHERE date BETWEEN ?1 AND ?2 ORDER BY date ASC, id ASC",
+    )?;
+    let rows = stmt
+        .query_map(params![from, to], map_mark)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rows.into_iter().map(to_mark).collect()
+}
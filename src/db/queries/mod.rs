@@ -1,11 +1,19 @@
+pub mod archive;
 pub mod events;
+pub mod focus;
 pub mod import;
+pub mod locks;
 pub mod log;
+pub mod marks;
 pub mod pairs;
+pub mod toil;
+pub mod trash;
 
 // Re-export per non cambiare i use esistenti
 pub use events::{
     delete_event, insert_event, load_events_by_date, load_pair_by_index, map_row, update_event,
 };
 pub use log::load_log;
-pub use pairs::{recalc_all_pairs, recalc_pairs_for_date};
+pub use pairs::{
+    rebuild_all_pairs, rebuild_pairs_for_date, recalc_all_pairs, recalc_pairs_for_date,
+};
@@ -1,108 +1,352 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, Local, NaiveDate};
 use rusqlite::{Connection, params};
 
+use crate::core::calculator::overlap::{OverlapMode, split_tracks};
 use crate::errors::{AppError, AppResult};
+use crate::models::event::Event;
 use crate::models::location::Location;
 
 use super::events::map_row;
 
-/// Ricalcola i valori "pair" per tutti gli eventi di una data.
-pub fn recalc_pairs_for_date(conn: &Connection, date: &NaiveDate) -> AppResult<()> {
-    let date_str = date.format("%Y-%m-%d").to_string();
+/// Pair number and dangling flag computed for a single event.
+struct PairSlot {
+    id: i32,
+    pair: i32,
+    unmatched: bool,
+}
 
+fn load_day(conn: &Connection, date_str: &str) -> AppResult<Vec<Event>> {
     let mut stmt = conn.prepare(
         "SELECT * FROM events
          WHERE date = ?1
          ORDER BY time ASC",
     )?;
-    let rows = stmt.query_map([date_str.clone()], map_row)?;
+    let rows = stmt.query_map([date_str], map_row)?;
 
     let mut events = Vec::new();
     for r in rows {
         events.push(r?);
     }
+    Ok(events)
+}
 
-    if events.is_empty() {
-        return Ok(());
-    }
-
-    // ✅ Day-marker handling (Holiday OR NationalHoliday)
-    let has_marker = events
-        .iter()
-        .any(|e| e.location == Location::Holiday || e.location == Location::NationalHoliday);
+fn is_marker(ev: &Event) -> bool {
+    ev.location == Location::Holiday || ev.location == Location::NationalHoliday
+}
 
-    if has_marker {
+/// Strict sequence check used on write paths: refuses days where markers are
+/// mixed with IN/OUT, two INs are open at once or an OUT has no IN.
+/// With `overlap_mode` every activity is checked on its own.
+fn validate_sequence(events: &[Event], date_str: &str, mode: OverlapMode) -> AppResult<()> {
+    if events.iter().any(is_marker) {
         if events.len() > 1 {
             return Err(AppError::InvalidTime(format!(
                 "Invalid sequence on {}: Holiday/NationalHoliday cannot coexist with IN/OUT events.",
                 date_str
             )));
         }
+        return Ok(());
+    }
 
-        conn.execute(
-            "UPDATE events SET pair = 0 WHERE date = ?1",
-            params![date_str],
-        )?;
+    if mode.is_on() {
+        for track in split_tracks(events) {
+            validate_track(&track, date_str)?;
+        }
         return Ok(());
     }
+    validate_track(&events.iter().collect::<Vec<_>>(), date_str)
+}
 
+fn validate_track(events: &[&Event], date_str: &str) -> AppResult<()> {
     let mut current_pair = 1;
-    let mut open_in: Option<i32> = None;
+    let mut open_in = false;
 
-    for ev in &events {
+    for ev in events {
         if ev.kind.is_in() {
-            if open_in.is_some() {
+            if open_in {
                 return Err(AppError::InvalidTime(format!(
                     "Invalid sequence on {}: Found IN at {} but previous pair {} has no OUT.",
                     date_str, ev.time, current_pair
                 )));
             }
-
-            conn.execute(
-                "UPDATE events SET pair = ?1 WHERE id = ?2",
-                params![current_pair, ev.id],
-            )?;
-
-            open_in = Some(ev.id);
+            open_in = true;
         } else if ev.kind.is_out() {
-            if open_in.is_none() {
+            if !open_in {
                 return Err(AppError::InvalidTime(format!(
                     "Invalid sequence on {}: Found OUT at {} without matching IN.",
                     date_str, ev.time
                 )));
             }
+            open_in = false;
+            current_pair += 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Tolerant pairing of a day (events sorted by time).
+/// Markers get pair 0; every IN opens a new pair closed by the following OUT.
+/// An IN followed by another IN, an OUT without IN and a trailing open IN are
+/// flagged as unmatched and keep a pair number of their own.
+/// With `overlap_mode` each activity is paired on its own and the pairs are
+/// then numbered in order of start, as in the timeline.
+fn assign_pairs(events: &[Event], mode: OverlapMode) -> Vec<PairSlot> {
+    if !mode.is_on() {
+        return assign_track(&events.iter().collect::<Vec<_>>());
+    }
+
+    // (start of the pair, track, pair within the track) → slots
+    let mut groups: Vec<(Option<DateTime<Local>>, usize, i32, Vec<PairSlot>)> = Vec::new();
+    for (t, track) in split_tracks(events).into_iter().enumerate() {
+        for slot in assign_track(&track) {
+            let start = track
+                .iter()
+                .find(|e| e.id == slot.id)
+                .map(|e| e.timestamp());
+            match groups
+                .iter_mut()
+                .find(|g| g.1 == t && g.2 == slot.pair && slot.pair != 0)
+            {
+                Some(g) => g.3.push(slot),
+                None => groups.push((start, t, slot.pair, vec![slot])),
+            }
+        }
+    }
+    groups.sort_by_key(|g| g.0);
+
+    let mut slots = Vec::new();
+    let mut number = 0;
+    for (_, _, pair, group) in groups {
+        if pair != 0 {
+            number += 1;
+        }
+        for mut slot in group {
+            if slot.pair != 0 {
+                slot.pair = number;
+            }
+            slots.push(slot);
+        }
+    }
+    slots
+}
+
+fn assign_track(events: &[&Event]) -> Vec<PairSlot> {
+    let mut slots = Vec::with_capacity(events.len());
+    let mut current_pair = 1;
+    let mut open_in: Option<usize> = None;
 
-            conn.execute(
-                "UPDATE events SET pair = ?1 WHERE id = ?2",
-                params![current_pair, ev.id],
-            )?;
+    for ev in events {
+        if is_marker(ev) {
+            slots.push(PairSlot {
+                id: ev.id,
+                pair: 0,
+                unmatched: false,
+            });
+            continue;
+        }
 
-            open_in = None;
+        if ev.kind.is_in() {
+            if let Some(i) = open_in {
+                slots[i].unmatched = true;
+                current_pair += 1;
+            }
+            open_in = Some(slots.len());
+            slots.push(PairSlot {
+                id: ev.id,
+                pair: current_pair,
+                unmatched: false,
+            });
+        } else {
+            slots.push(PairSlot {
+                id: ev.id,
+                pair: current_pair,
+                unmatched: open_in.take().is_none(),
+            });
             current_pair += 1;
         }
     }
 
+    if let Some(i) = open_in {
+        slots[i].unmatched = true;
+    }
+
+    slots
+}
+
+fn persist_pairs(conn: &Connection, slots: &[PairSlot]) -> AppResult<()> {
+    let mut stmt = conn.prepare("UPDATE events SET pair = ?1, unmatched = ?2 WHERE id = ?3")?;
+    for s in slots {
+        stmt.execute(params![s.pair, if s.unmatched { 1 } else { 0 }, s.id])?;
+    }
     Ok(())
 }
 
-pub fn recalc_all_pairs(conn: &mut Connection) -> AppResult<()> {
-    let dates: Vec<String> = {
-        let mut stmt = conn.prepare("SELECT DISTINCT date FROM events ORDER BY date ASC")?;
-        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+/// Ricalcola i valori "pair" per tutti gli eventi di una data.
+/// Returns an error (without touching the DB) if the day sequence is invalid.
+/// `mode` is the `overlap_mode` of the config.
+pub fn recalc_pairs_for_date(
+    conn: &Connection,
+    date: &NaiveDate,
+    mode: OverlapMode,
+) -> AppResult<()> {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let events = load_day(conn, &date_str)?;
+
+    if events.is_empty() {
+        return Ok(());
+    }
 
-        let mut v = Vec::new();
-        for r in rows {
-            v.push(r?);
-        }
-        v
+    validate_sequence(&events, &date_str, mode)?;
+    persist_pairs(conn, &assign_pairs(&events, mode))
+}
+
+/// Like `recalc_pairs_for_date`, but never fails on broken sequences:
+/// dangling events are persisted with `unmatched = 1`.
+/// Used by delete, migrations and bulk rebuilds.
+pub fn rebuild_pairs_for_date(
+    conn: &Connection,
+    date: &NaiveDate,
+    mode: OverlapMode,
+) -> AppResult<()> {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let events = load_day(conn, &date_str)?;
+    persist_pairs(conn, &assign_pairs(&events, mode))
+}
+
+fn distinct_dates(conn: &Connection) -> AppResult<Vec<NaiveDate>> {
+    dates_with_events(conn, None)
+}
+
+/// Dates having events, optionally limited to `from..=to`, in order.
+pub fn dates_with_events(
+    conn: &Connection,
+    bounds: Option<(NaiveDate, NaiveDate)>,
+) -> AppResult<Vec<NaiveDate>> {
+    let (from, to) = match bounds {
+        Some((f, t)) => (f.to_string(), t.to_string()),
+        None => ("0000-01-01".to_string(), "9999-12-31".to_string()),
     };
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT date FROM events WHERE date BETWEEN ?1 AND ?2 ORDER BY date ASC",
+    )?;
+    let rows = stmt.query_map(params![from, to], |row| row.get::<_, String>(0))?;
+
+    let mut v = Vec::new();
+    for r in rows {
+        let d = r?;
+        v.push(NaiveDate::parse_from_str(&d, "%Y-%m-%d").map_err(|_| AppError::InvalidDate(d))?);
+    }
+    Ok(v)
+}
 
-    for d in dates {
-        let date = NaiveDate::parse_from_str(&d, "%Y-%m-%d")
-            .map_err(|_| AppError::InvalidDate(d.clone()))?;
+/// Tolerant rebuild of every date in the database.
+pub fn rebuild_all_pairs(conn: &Connection, mode: OverlapMode) -> AppResult<()> {
+    for date in distinct_dates(conn)? {
+        rebuild_pairs_for_date(conn, &date, mode)?;
+    }
+    Ok(())
+}
 
-        recalc_pairs_for_date(conn, &date)?;
+pub fn recalc_all_pairs(conn: &mut Connection, mode: OverlapMode) -> AppResult<()> {
+    for date in distinct_dates(conn)? {
+        recalc_pairs_for_date(conn, &date, mode)?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::calculator::timeline::unmatched_events;
+    use crate::models::event_type::EventType;
+    use crate::models::test_support::{ev, project};
+
+    fn on_call(id: i32, t: &str, kind: EventType) -> Event {
+        project(ev(id, t, kind), "oncall")
+    }
+
+    #[test]
+    fn dangling_events_are_flagged() {
+        let events = vec![
+            ev(1, "08:00", EventType::In),
+            ev(2, "09:00", EventType::In),
+            ev(3, "12:00", EventType::Out),
+            ev(4, "13:00", EventType::Out),
+            ev(5, "14:00", EventType::In),
+        ];
+
+        let got: Vec<(i32, bool)> = assign_pairs(&events, OverlapMode::Off)
+            .iter()
+            .map(|s| (s.pair, s.unmatched))
+            .collect();
+
+        assert_eq!(
+            got,
+            vec![(1, true), (2, false), (2, false), (3, true), (4, true)]
+        );
+        assert!(validate_sequence(&events, "2025-06-18", OverlapMode::Off).is_err());
+
+        // `list --unmatched` reports exactly the flagged events
+        let flagged: Vec<Event> = events
+            .iter()
+            .zip(assign_pairs(&events, OverlapMode::Off))
+            .map(|(e, s)| Event {
+                unmatched: s.unmatched,
+                ..e.clone()
+            })
+            .collect();
+        let ids: Vec<i32> = unmatched_events(&flagged, OverlapMode::Off)
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn activities_are_paired_on_their_own() {
+        // Working day 8-13 and 14-18, on-call 10-11 and 17-20
+        let events = vec![
+            ev(1, "08:00", EventType::In),
+            on_call(2, "10:00", EventType::In),
+            on_call(3, "11:00", EventType::Out),
+            ev(4, "13:00", EventType::Out),
+            ev(5, "14:00", EventType::In),
+            on_call(6, "17:00", EventType::In),
+            ev(7, "18:00", EventType::Out),
+            on_call(8, "20:00", EventType::Out),
+        ];
+
+        // One sequence: the on-call IN breaks the day
+        assert!(validate_sequence(&events, "2025-06-18", OverlapMode::Off).is_err());
+        assert!(validate_sequence(&events, "2025-06-18", OverlapMode::Split).is_ok());
+
+        // Pairs numbered by start across activities, none dangling
+        let mut got: Vec<(i32, i32, bool)> = assign_pairs(&events, OverlapMode::Split)
+            .iter()
+            .map(|s| (s.id, s.pair, s.unmatched))
+            .collect();
+        got.sort();
+        assert_eq!(
+            got,
+            vec![
+                (1, 1, false),
+                (2, 2, false),
+                (3, 2, false),
+                (4, 1, false),
+                (5, 3, false),
+                (6, 4, false),
+                (7, 3, false),
+                (8, 4, false),
+            ]
+        );
+
+        // An on-call OUT without its IN is still caught
+        let broken = vec![
+            ev(1, "08:00", EventType::In),
+            on_call(2, "09:00", EventType::Out),
+        ];
+        assert!(validate_sequence(&broken, "2025-06-18", OverlapMode::Parallel).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! Time off in lieu (TOIL) ledger: surplus converted into time off
+//! (`accrue`) and time off taken (`use`). Events and pairs are not touched.
+
+use crate::errors::{AppError, AppResult};
+use chrono::{Local, NaiveDate};
+use rusqlite::{Connection, params};
+
+/// Ensure that the `toil_ledger` table exists.
+pub fn ensure_toil_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS toil_ledger (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            date       TEXT NOT NULL,
+            kind       TEXT NOT NULL CHECK(kind IN ('accrue','use')),
+            minutes    INTEGER NOT NULL CHECK(minutes > 0),
+            note       TEXT,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_toil_ledger_date ON toil_ledger(date);
+        "#,
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToilKind {
+    Accrue,
+    Use,
+}
+
+impl ToilKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ToilKind::Accrue => "accrue",
+            ToilKind::Use => "use",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToilEntry {
+    pub id: i64,
+    pub date: NaiveDate,
+    pub kind: ToilKind,
+    pub minutes: i64,
+    pub note: Option<String>,
+}
+
+pub fn insert_toil(
+    conn: &Connection,
+    date: &NaiveDate,
+    kind: ToilKind,
+    minutes: i64,
+    note: Option<&str>,
+) -> AppResult<()> {
+    ensure_toil_table(conn)?;
+    conn.execute(
+        "INSERT INTO toil_ledger (date, kind, minutes, note, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            date.format("%Y-%m-%d").to_string(),
+            kind.as_str(),
+            minutes,
+            note,
+            Local::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// Ledger entries in date order, optionally limited to `[from, to]`.
+pub fn load_toil(
+    conn: &Connection,
+    bounds: Option<(NaiveDate, NaiveDate)>,
+) -> AppResult<Vec<ToilEntry>> {
+    ensure_toil_table(conn)?;
+    let (from, to) = bounds
+        .map(|(f, t)| (f.to_string(), t.to_string()))
+        .unwrap_or_else(|| ("0000-01-01".into(), "9999-12-31".into()));
+
+    let mut stmt = conn.prepare(
+        "SELECT id, date, kind, minutes, note FROM toil_ledger
+         WHERE date BETWEEN ?1 AND ?2 ORDER BY date ASC, id ASC",
+    )?;
+    let rows = stmt
+        .query_map(params![from, to], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rows.into_iter()
+        .map(|(id, d, kind, minutes, note)| {
+            Ok(ToilEntry {
+                id,
+                date: NaiveDate::parse_from_str(&d, "%Y-%m-%d")
+                    .map_err(|_| AppError::InvalidDate(d))?,
+                kind: if kind == "use" {
+                    ToilKind::Use
+                } else {
+                    ToilKind::Accrue
+                },
+                minutes,
+                note,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ledger_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        let d = NaiveDate::from_ymd_opt(2025, 7, 4).unwrap();
+        insert_toil(&conn, &d, ToilKind::Accrue, 480, Some("June overtime")).unwrap();
+        insert_toil(&conn, &d, ToilKind::Use, 480, None).unwrap();
+
+        let all = load_toil(&conn, None).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].note.as_deref(), Some("June overtime"));
+        assert_eq!(all[1].kind, ToilKind::Use);
+
+        let other = NaiveDate::from_ymd_opt(2025, 8, 1).unwrap();
+        assert!(load_toil(&conn, Some((other, other))).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+//! Trash of deleted events: `del` moves the rows here instead of dropping
+//! them, so every query on `events` ignores them without extra filters.
+//! One `del` run is one batch, restored or emptied as a whole.
+
+use crate::core::calculator::overlap::OverlapMode;
+use crate::db::queries::locks::ensure_unlocked;
+use crate::db::queries::rebuild_pairs_for_date;
+use crate::errors::{AppError, AppResult};
+use chrono::{Local, NaiveDate};
+use rusqlite::{Connection, params};
+use std::collections::BTreeSet;
+
+/// Event columns copied to and from the trash (original ids are kept: with
+/// AUTOINCREMENT they are never reused).
+const EVENT_COLUMNS: &str = "id, date, time, kind, position, lunch_break, pair, work_gap, unmatched, \
+                             source, meta, notes, host, os_user, created_at";
+
+/// Ensure that the `events_trash` table exists.
+pub fn ensure_trash_table(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS events_trash (
+            trash_id     INTEGER PRIMARY KEY AUTOINCREMENT,
+            batch        INTEGER NOT NULL,
+            deleted_at   TEXT NOT NULL,
+            id           INTEGER NOT NULL,
+            date         TEXT NOT NULL,
+            time         TEXT NOT NULL,
+            kind         TEXT NOT NULL,
+            position     TEXT NOT NULL,
+            lunch_break  INTEGER NOT NULL DEFAULT 0,
+            pair         INTEGER NOT NULL DEFAULT 0,
+            work_gap     INTEGER NOT NULL DEFAULT 0,
+            unmatched    INTEGER NOT NULL DEFAULT 0,
+            source       TEXT,
+            meta         TEXT,
+            notes        TEXT,
+            host         TEXT,
+            os_user      TEXT,
+            created_at   TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_events_trash_batch ON events_trash(batch);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// One `del` run in the trash.
+#[derive(Debug, Clone)]
+pub struct TrashBatch {
+    pub batch: i64,
+    pub deleted_at: String,
+    pub events: usize,
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+/// Id for a new batch.
+pub fn next_batch(conn: &Connection) -> AppResult<i64> {
+    ensure_trash_table(conn)?;
+    Ok(conn.query_row(
+        "SELECT COALESCE(MAX(batch), 0) + 1 FROM events_trash",
+        [],
+        |r| r.get(0),
+    )?)
+}
+
+/// Move one event into the trash under `batch`.
+pub fn trash_event(conn: &Connection, id: i32, batch: i64) -> AppResult<()> {
+    ensure_trash_table(conn)?;
+    conn.execute(
+        &format!(
+            "INSERT INTO events_trash (batch, deleted_at, {cols})
+             SELECT ?1, ?2, {cols} FROM events WHERE id = ?3",
+            cols = EVENT_COLUMNS
+        ),
+        params![batch, Local::now().to_rfc3339(), id],
+    )?;
+    conn.execute("DELETE FROM events WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+/// Batches in the trash, newest first.
+pub fn list_batches(conn: &Connection) -> AppResult<Vec<TrashBatch>> {
+    ensure_trash_table(conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT batch, MIN(deleted_at), COUNT(*), MIN(date), MAX(date)
+         FROM events_trash GROUP BY batch ORDER BY batch DESC",
+    )?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, i64>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, i64>(2)?,
+                r.get::<_, String>(3)?,
+                r.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    rows.into_iter()
+        .map(|(batch, deleted_at, events, first, last)| {
+            let parse = |s: &str| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map_err(|_| AppError::InvalidDate(s.to_string()))
+            };
+            Ok(TrashBatch {
+                batch,
+                deleted_at,
+                events: events as usize,
+                first: parse(&first)?,
+                last: parse(&last)?,
+            })
+        })
+        .collect()
+}
+
+/// Put a batch back into `events` and rebuild the pairs of its days.
+/// Locked days refuse the restore. Returns the number of events restored.
+pub fn restore_batch(conn: &Connection, batch: i64, mode: OverlapMode) -> AppResult<usize> {
+    ensure_trash_table(conn)?;
+    let mut stmt = conn.prepare("SELECT DISTINCT date FROM events_trash WHERE batch = ?1")?;
+    let dates = stmt
+        .query_map([batch], |r| r.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .iter()
+        .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .collect::<BTreeSet<_>>();
+
+    if dates.is_empty() {
+        return Err(AppError::InvalidArgs(format!(
+            "Trash batch #{} does not exist.",
+            batch
+        )));
+    }
+    for d in &dates {
+        ensure_unlocked(conn, d)?;
+    }
+
+    let n = conn.execute(
+        &format!(
+            "INSERT INTO events ({cols}) SELECT {cols} FROM events_trash WHERE batch = ?1",
+            cols = EVENT_COLUMNS
+        ),
+        [batch],
+    )?;
+    conn.execute("DELETE FROM events_trash WHERE batch = ?1", [batch])?;
+
+    for d in &dates {
+        rebuild_pairs_for_date(conn, d, mode)?;
+    }
+
+    Ok(n)
+}
+
+/// Permanently drop everything in the trash. Returns the number of events.
+pub fn empty_trash(conn: &Connection) -> AppResult<usize> {
+    ensure_trash_table(conn)?;
+    Ok(conn.execute("DELETE FROM events_trash", [])?)
+}
@@ -1,11 +1,13 @@
+use crate::db::metadata;
 use crate::db::pool::DbPool;
+use crate::ui::messages::data;
 use crate::utils::colors::{CYAN, GREEN, GREY, RESET, YELLOW};
 use chrono::NaiveDate;
 use rusqlite::OptionalExtension;
 use std::fs;
 
 pub fn print_db_info(pool: &mut DbPool, db_path: &str) -> rusqlite::Result<()> {
-    println!();
+    data("");
 
     //
     // 1) FILE SIZE
@@ -13,8 +15,11 @@ pub fn print_db_info(pool: &mut DbPool, db_path: &str) -> rusqlite::Result<()> {
     let file_size = fs::metadata(db_path).map(|m| m.len()).unwrap_or(0);
     let file_mb = (file_size as f64) / (1024.0 * 1024.0);
 
-    println!("{}• File:{} {}{}{}", CYAN, RESET, YELLOW, db_path, RESET);
-    println!("{}• Size:{} {:.2} MB", CYAN, RESET, file_mb);
+    data(format!(
+        "{}• File:{} {}{}{}",
+        CYAN, RESET, YELLOW, db_path, RESET
+    ));
+    data(format!("{}• Size:{} {:.2} MB", CYAN, RESET, file_mb));
 
     //
     // 2) TOTAL EVENTS
@@ -22,10 +27,10 @@ pub fn print_db_info(pool: &mut DbPool, db_path: &str) -> rusqlite::Result<()> {
     let count: i64 = pool
         .conn
         .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))?;
-    println!(
+    data(format!(
         "{}• Total events:{} {}{}{}",
         CYAN, RESET, GREEN, count, RESET
-    );
+    ));
 
     //
     // 3) DATE RANGE
@@ -55,9 +60,9 @@ pub fn print_db_info(pool: &mut DbPool, db_path: &str) -> rusqlite::Result<()> {
         .clone()
         .unwrap_or_else(|| format!("{GREY}--{RESET}"));
 
-    println!("{}• Date range:{}", CYAN, RESET);
-    println!("    from: {}", fmt_first);
-    println!("    to:   {}", fmt_last);
+    data(format!("{}• Date range:{}", CYAN, RESET));
+    data(format!("    from: {}", fmt_first));
+    data(format!("    to:   {}", fmt_last));
 
     //
     // 4) AVERAGE EVENTS/DAY
@@ -68,10 +73,24 @@ pub fn print_db_info(pool: &mut DbPool, db_path: &str) -> rusqlite::Result<()> {
         let days = (d2 - d1).num_days().max(1);
 
         let avg = count as f64 / days as f64;
-        println!("{}• Average events/day:{} {:.2}", CYAN, RESET, avg);
+        data(format!("{}• Average events/day:{} {:.2}", CYAN, RESET, avg));
     }
 
-    println!();
+    //
+    // 5) METADATA
+    //
+    let meta = metadata::load_all(&pool.conn)?;
+    data(format!("{}• Metadata:{}", CYAN, RESET));
+    if meta.is_empty() {
+        data(format!(
+            "    {GREY}(none — run `rtimelogger db --migrate`){RESET}"
+        ));
+    }
+    for (key, value) in meta {
+        data(format!("    {}: {}", key, value));
+    }
+
+    data("");
     Ok(())
 }
 
@@ -4,6 +4,7 @@
 
 use chrono::NaiveDate;
 use std::io;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,12 +18,28 @@ pub enum AppError {
     // ---------------------------
     // Database-related
     // ---------------------------
+    #[cfg(feature = "cli")]
     #[error("Database error: {0}")]
-    Db(#[from] rusqlite::Error),
+    Db(rusqlite::Error),
+
+    #[error(
+        "Database is locked by another process.\nClose other rTimelogger instances (or the tool syncing the DB file) and retry.\n"
+    )]
+    DbLocked,
 
     #[error("Database migration error: {0}")]
     Migration(String),
 
+    #[error(
+        "Database was written by rTimelogger {found}, which is newer than this binary ({current}).\nPlease upgrade rTimelogger before using this database.\n"
+    )]
+    IncompatibleDbVersion { found: String, current: String },
+
+    #[error(
+        "'{0}' is not an rTimelogger database (it already contains other tables).\nNothing was changed. To use it anyway run: rtimelogger --db {0} init --force-init\n"
+    )]
+    ForeignDatabase(String),
+
     // ---------------------------
     // Parsing errors
     // ---------------------------
@@ -32,6 +49,11 @@ pub enum AppError {
     #[error("Invalid time format: {0}")]
     InvalidTime(String),
 
+    #[error(
+        "Invalid lunch break: {minutes} min is above the configured maximum of {max} min (max_duration_lunch_break)\n"
+    )]
+    LunchTooLong { minutes: i32, max: i32 },
+
     #[error("Invalid position code: {0}")]
     InvalidPosition(String),
 
@@ -41,6 +63,11 @@ pub enum AppError {
     #[error("Invalid operation mode: {0}")]
     InvalidOperation(String),
 
+    #[error(
+        "Invalid period '{input}': {hint}\nExamples: 2025, 2025-09, 2025-09-15, 2025-01:2025-03, all\n"
+    )]
+    InvalidPeriod { input: String, hint: String },
+
     #[error("Invalid date range: from ({from}) must be <= to ({to})\n")]
     InvalidDateRange { from: NaiveDate, to: NaiveDate },
 
@@ -56,6 +83,25 @@ pub enum AppError {
     #[error("Invalid pair index: {0}")]
     InvalidPair(usize),
 
+    #[error("Pair {pair}: OUT {out_time} must be later than IN {in_time}.\n")]
+    PairOutBeforeIn {
+        pair: usize,
+        in_time: String,
+        out_time: String,
+    },
+
+    #[error("Day {0} is locked. Run `rtimelogger unlock {0}` to modify it.\n")]
+    DayLocked(NaiveDate),
+
+    #[error(
+        "{date}: {worked} of work is above max_daily_work ({max}).\nPass --override \"<reason>\" to record it anyway.\n"
+    )]
+    MaxDailyWorkExceeded {
+        date: NaiveDate,
+        worked: String,
+        max: String,
+    },
+
     #[error("Timeline error: {0}")]
     Timeline(String),
 
@@ -83,6 +129,16 @@ pub enum AppError {
     #[error("Export error: {0}")]
     Export(String),
 
+    #[error(
+        "Cannot write export file '{}': {source}\nCheck that the directory exists and is writable.\n",
+        path.display()
+    )]
+    ExportIo {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
     // ---------------------------
     // Generic fallback
     // ---------------------------
@@ -91,3 +147,40 @@ pub enum AppError {
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+/// SQLITE_BUSY / SQLITE_LOCKED become `DbLocked`, everything else `Db`.
+#[cfg(feature = "cli")]
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked) => {
+                AppError::DbLocked
+            }
+            _ => AppError::Db(e),
+        }
+    }
+}
+
+impl AppError {
+    /// Error raised while writing the export file `path`.
+    pub fn export_io(path: &Path, source: impl Into<io::Error>) -> Self {
+        AppError::ExportIo {
+            path: path.to_path_buf(),
+            source: source.into(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_database_maps_to_db_locked() {
+        let busy = rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(5), None);
+        assert!(matches!(AppError::from(busy), AppError::DbLocked));
+
+        let other = rusqlite::Error::QueryReturnedNoRows;
+        assert!(matches!(AppError::from(other), AppError::Db(_)));
+    }
+}
@@ -0,0 +1,336 @@
+// src/export/dashboard.rs
+
+//! `export --format dashboard`: a single offline HTML file with the figures
+//! precomputed as JSON and two canvas charts drawn by a small inline script
+//! (monthly surplus trend, distribution of the days by position). No external
+//! assets: the file can be mailed or opened from a USB stick.
+
+use crate::errors::{AppError, AppResult};
+use crate::export::hour_bank::BankDay;
+use crate::export::model::ConfigSnapshot;
+use crate::export::{EventExport, notify_export_success};
+use crate::models::location::Location;
+use crate::ui::messages::info;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Data embedded in the page.
+#[derive(Debug, Serialize)]
+struct Dashboard<'a> {
+    title: &'a str,
+    generated: String,
+    snapshot: &'a ConfigSnapshot,
+    months: Vec<MonthFigures>,
+    positions: Vec<PositionCount>,
+    days: Vec<DayFigures>,
+}
+
+/// Totals of one month, in minutes (closed working days only).
+#[derive(Debug, Serialize, PartialEq)]
+struct MonthFigures {
+    month: String,
+    days: usize,
+    worked: i64,
+    expected: i64,
+    surplus: i64,
+    /// Running surplus since the first exported month
+    balance: i64,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct PositionCount {
+    code: String,
+    label: String,
+    days: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct DayFigures {
+    date: String,
+    position: String,
+    worked: i64,
+    expected: i64,
+    surplus: i64,
+}
+
+fn month_figures(days: &[BankDay]) -> Vec<MonthFigures> {
+    let mut by_month: BTreeMap<String, (usize, i64, i64)> = BTreeMap::new();
+    for d in days {
+        let e = by_month
+            .entry(d.date.format("%Y-%m").to_string())
+            .or_default();
+        e.0 += 1;
+        e.1 += d.worked;
+        e.2 += d.expected;
+    }
+
+    let mut balance = 0;
+    by_month
+        .into_iter()
+        .map(|(month, (days, worked, expected))| {
+            balance += worked - expected;
+            MonthFigures {
+                month,
+                days,
+                worked,
+                expected,
+                surplus: worked - expected,
+                balance,
+            }
+        })
+        .collect()
+}
+
+/// One position per exported day (holidays and sick leave included).
+fn position_counts(events: &[EventExport]) -> Vec<PositionCount> {
+    let mut per_day: BTreeMap<&str, &str> = BTreeMap::new();
+    for ev in events {
+        if !ev.day_position.is_empty() {
+            per_day.insert(&ev.date, &ev.day_position);
+        }
+    }
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for code in per_day.values() {
+        *counts.entry(code).or_default() += 1;
+    }
+
+    let mut out: Vec<PositionCount> = counts
+        .into_iter()
+        .map(|(code, days)| PositionCount {
+            code: code.to_string(),
+            label: Location::from_db_str(code)
+                .map(|l| l.label().to_string())
+                .unwrap_or_else(|| code.to_string()),
+            days,
+        })
+        .collect();
+    out.sort_by(|a, b| b.days.cmp(&a.days));
+    out
+}
+
+/// JSON safe inside a `<script>` element.
+fn script_json(value: &impl Serialize) -> AppResult<String> {
+    serde_json::to_string(value)
+        .map(|s| s.replace("</", "<\\/"))
+        .map_err(|e| AppError::Export(format!("JSON serialization error: {e}")))
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn export_dashboard(
+    events: &[EventExport],
+    bank: &[BankDay],
+    snapshot: &ConfigSnapshot,
+    path: &Path,
+    title: &str,
+) -> AppResult<()> {
+    info(format!("Exporting to HTML dashboard: {}", path.display()));
+
+    let data = Dashboard {
+        title,
+        generated: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        snapshot,
+        months: month_figures(bank),
+        positions: position_counts(events),
+        days: bank
+            .iter()
+            .map(|d| DayFigures {
+                date: d.date.format("%Y-%m-%d").to_string(),
+                position: d.position.clone(),
+                worked: d.worked,
+                expected: d.expected,
+                surplus: d.worked - d.expected,
+            })
+            .collect(),
+    };
+
+    let html = TEMPLATE
+        .replace("{{TITLE}}", &escape_html(title))
+        .replace("{{DATA}}", &script_json(&data)?);
+
+    fs::write(path, html).map_err(|e| AppError::export_io(path, e))?;
+
+    notify_export_success("HTML dashboard", path);
+    Ok(())
+}
+
+const TEMPLATE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{{TITLE}}</title>
+<style>
+  body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 960px; color: #222; }
+  h1 { font-size: 1.4rem; margin-bottom: .2rem; }
+  .sub { color: #777; font-size: .85rem; margin-bottom: 1.5rem; }
+  .cards { display: flex; gap: 1rem; flex-wrap: wrap; margin-bottom: 1.5rem; }
+  .card { flex: 1; min-width: 140px; border: 1px solid #ddd; border-radius: 6px; padding: .8rem; }
+  .card b { display: block; font-size: 1.3rem; }
+  .pos { color: #2e7d32; } .neg { color: #c62828; }
+  canvas { width: 100%; height: 280px; border: 1px solid #eee; border-radius: 6px; margin-bottom: 1.5rem; }
+  table { border-collapse: collapse; width: 100%; font-size: .9rem; }
+  th, td { border-bottom: 1px solid #eee; padding: .3rem .5rem; text-align: right; }
+  th:first-child, td:first-child { text-align: left; }
+</style>
+</head>
+<body>
+<h1>{{TITLE}}</h1>
+<div class="sub" id="sub"></div>
+<div class="cards" id="cards"></div>
+<h2>Monthly surplus</h2>
+<canvas id="trend"></canvas>
+<h2>Days by position</h2>
+<canvas id="positions"></canvas>
+<h2>Months</h2>
+<table id="months"><thead><tr><th>Month</th><th>Days</th><th>Worked</th><th>Expected</th><th>Surplus</th><th>Balance</th></tr></thead><tbody></tbody></table>
+<script type="application/json" id="data">{{DATA}}</script>
+<script>
+(function () {
+  var data = JSON.parse(document.getElementById("data").textContent);
+
+  function hm(min) {
+    var sign = min < 0 ? "-" : "";
+    min = Math.abs(min);
+    return sign + Math.floor(min / 60) + "h" + String(min % 60).padStart(2, "0");
+  }
+  function signed(min) { return (min > 0 ? "+" : "") + hm(min); }
+  function cls(min) { return min < 0 ? "neg" : "pos"; }
+
+  var s = data.snapshot;
+  document.getElementById("sub").textContent =
+    "Generated " + data.generated + " by rTimelogger " + s.app_version +
+    " (min work " + s.min_work_duration + ", lunch " + s.lunch_window + ")";
+
+  var tot = data.days.reduce(function (a, d) {
+    a.worked += d.worked; a.expected += d.expected; return a;
+  }, { worked: 0, expected: 0 });
+  var cards = [
+    ["Working days", data.days.length, ""],
+    ["Worked", hm(tot.worked), ""],
+    ["Expected", hm(tot.expected), ""],
+    ["Surplus", signed(tot.worked - tot.expected), cls(tot.worked - tot.expected)]
+  ];
+  document.getElementById("cards").innerHTML = cards.map(function (c) {
+    return '<div class="card">' + c[0] + '<b class="' + c[2] + '">' + c[1] + "</b></div>";
+  }).join("");
+
+  var body = document.querySelector("#months tbody");
+  data.months.forEach(function (m) {
+    var tr = document.createElement("tr");
+    [m.month, m.days, hm(m.worked), hm(m.expected), signed(m.surplus), signed(m.balance)]
+      .forEach(function (v, i) {
+        var td = document.createElement("td");
+        td.textContent = v;
+        if (i === 4) td.className = cls(m.surplus);
+        if (i === 5) td.className = cls(m.balance);
+        tr.appendChild(td);
+      });
+    body.appendChild(tr);
+  });
+
+  function setup(id) {
+    var c = document.getElementById(id);
+    var ratio = window.devicePixelRatio || 1;
+    c.width = c.clientWidth * ratio;
+    c.height = c.clientHeight * ratio;
+    var ctx = c.getContext("2d");
+    ctx.scale(ratio, ratio);
+    ctx.font = "12px system-ui, sans-serif";
+    return { ctx: ctx, w: c.clientWidth, h: c.clientHeight };
+  }
+
+  // Monthly surplus (bars) and running balance (line)
+  (function () {
+    var g = setup("trend"), ctx = g.ctx, m = data.months;
+    if (!m.length) { ctx.fillText("No closed working days in the range.", 20, 30); return; }
+    var pad = 40, w = g.w - pad * 2, h = g.h - pad * 2;
+    var values = m.map(function (x) { return x.surplus; })
+      .concat(m.map(function (x) { return x.balance; }), [0]);
+    var max = Math.max.apply(null, values), min = Math.min.apply(null, values);
+    if (max === min) { max += 60; min -= 60; }
+    function y(v) { return pad + (max - v) / (max - min) * h; }
+    var step = w / m.length;
+
+    ctx.strokeStyle = "#999";
+    ctx.beginPath(); ctx.moveTo(pad, y(0)); ctx.lineTo(pad + w, y(0)); ctx.stroke();
+
+    m.forEach(function (x, i) {
+      ctx.fillStyle = x.surplus < 0 ? "#e57373" : "#81c784";
+      var top = Math.min(y(0), y(x.surplus));
+      ctx.fillRect(pad + i * step + step * 0.2, top, step * 0.6, Math.abs(y(x.surplus) - y(0)));
+      ctx.fillStyle = "#555";
+      ctx.textAlign = "center";
+      ctx.fillText(x.month, pad + i * step + step / 2, g.h - pad / 3);
+    });
+
+    ctx.strokeStyle = "#1565c0";
+    ctx.lineWidth = 2;
+    ctx.beginPath();
+    m.forEach(function (x, i) {
+      var px = pad + i * step + step / 2;
+      if (i === 0) ctx.moveTo(px, y(x.balance)); else ctx.lineTo(px, y(x.balance));
+    });
+    ctx.stroke();
+
+    ctx.textAlign = "left";
+    ctx.fillStyle = "#555";
+    ctx.fillText(signed(max) + "  (bars: month surplus, line: balance)", 4, pad - 20);
+    ctx.fillText(signed(min), 4, g.h - pad + 14);
+  })();
+
+  // Days by position (horizontal bars)
+  (function () {
+    var g = setup("positions"), ctx = g.ctx, p = data.positions;
+    if (!p.length) { ctx.fillText("No days in the range.", 20, 30); return; }
+    var colors = { O: "#42a5f5", R: "#66bb6a", C: "#ffa726", M: "#ab47bc", H: "#bdbdbd", N: "#8d6e63", S: "#ef5350" };
+    var total = p.reduce(function (a, x) { return a + x.days; }, 0);
+    var max = Math.max.apply(null, p.map(function (x) { return x.days; }));
+    var row = Math.min(36, (g.h - 20) / p.length), left = 140, w = g.w - left - 80;
+    p.forEach(function (x, i) {
+      var top = 10 + i * row;
+      ctx.fillStyle = "#333";
+      ctx.textAlign = "right";
+      ctx.fillText(x.label, left - 8, top + row / 2 + 4);
+      ctx.fillStyle = colors[x.code] || "#78909c";
+      ctx.fillRect(left, top + row * 0.15, w * x.days / max, row * 0.7);
+      ctx.fillStyle = "#333";
+      ctx.textAlign = "left";
+      ctx.fillText(x.days + " (" + Math.round(x.days * 100 / total) + "%)", left + w * x.days / max + 6, top + row / 2 + 4);
+    });
+  })();
+})();
+</script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn months_carry_the_balance() {
+        let day = |m, d, worked| BankDay {
+            date: NaiveDate::from_ymd_opt(2025, m, d).unwrap(),
+            position: "O".into(),
+            worked,
+            expected: 480,
+        };
+        let months = month_figures(&[day(5, 30, 510), day(5, 31, 470), day(6, 2, 450)]);
+        assert_eq!(months.len(), 2);
+        assert_eq!((months[0].surplus, months[0].balance), (20, 20));
+        assert_eq!((months[1].surplus, months[1].balance), (-30, -10));
+
+        assert_eq!(script_json(&"</script>").unwrap(), "\"<\\/script>\"");
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::errors::{AppError, AppResult};
 use crate::ui::messages::{info, warning};
+use crate::utils::colors::print;
 use std::io::{self, Write};
 use std::path::Path;
 
@@ -10,7 +11,7 @@ use std::path::Path;
 /// - Se il file NON esiste → Ok
 /// - Se esiste ed è abilitato `force` → Ok
 /// - Se esiste e `force == false` → chiede conferma all'utente.
-pub(crate) fn ensure_writable(path: &Path, force: bool) -> AppResult<()> {
+pub fn ensure_writable(path: &Path, force: bool) -> AppResult<()> {
     if !path.exists() || force {
         return Ok(());
     }
@@ -28,8 +29,9 @@ pub(crate) fn ensure_writable(path: &Path, force: bool) -> AppResult<()> {
         info("Existing file will be overwritten.");
         Ok(())
     } else {
-        Err(AppError::from(io::Error::other(
-            "Export cancelled: existing file not overwritten",
+        Err(AppError::Export(format!(
+            "cancelled, '{}' not overwritten.\nUse --force to overwrite without asking.",
+            path.display()
         )))
     }
 }
@@ -0,0 +1,245 @@
+// src/export/hour_bank.rs
+
+//! `Hour bank` sheet of the XLSX events export: one row per closed working
+//! day with worked / expected / surplus hours and the running balance of the
+//! month, ready for review (totals row frozen under the header, red-green
+//! scale on the surplus, data bars on the month balance).
+
+use crate::config::Config;
+use crate::core::logic::Core;
+use crate::db::pool::DbPool;
+use crate::db::queries::load_events_by_date;
+use crate::errors::AppResult;
+use crate::export::EventExport;
+use crate::export::xlsx::xlsx_err;
+use crate::utils::date::get_day_position;
+use chrono::NaiveDate;
+use rust_xlsxwriter::{
+    Color, ConditionalFormat3ColorScale, ConditionalFormatDataBar, ConditionalFormatType, Format,
+    FormatBorder, FormatPattern, Formula, Workbook,
+};
+use std::collections::BTreeSet;
+
+const HEADERS: [&str; 7] = [
+    "Month",
+    "Date",
+    "Position",
+    "Worked",
+    "Expected",
+    "Surplus",
+    "Month bank",
+];
+
+/// First data row (row 0: header, row 1: totals).
+const FIRST_ROW: u32 = 2;
+
+/// Figures of one closed working day.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BankDay {
+    pub date: NaiveDate,
+    pub position: String,
+    pub worked: i64,
+    pub expected: i64,
+}
+
+impl BankDay {
+    fn surplus(&self) -> i64 {
+        self.worked - self.expected
+    }
+}
+
+/// Closed working days among the dates of the exported events (holiday /
+/// sick-leave days and days still missing their OUT are left out).
+pub(crate) fn load(
+    pool: &mut DbPool,
+    cfg: &Config,
+    events: &[EventExport],
+) -> AppResult<Vec<BankDay>> {
+    let dates: BTreeSet<NaiveDate> = events
+        .iter()
+        .filter_map(|e| NaiveDate::parse_from_str(&e.date, "%Y-%m-%d").ok())
+        .collect();
+
+    let mut out = Vec::new();
+    for date in dates {
+        let day_events = load_events_by_date(pool, &date)?;
+        let summary = Core::build_daily_summary(&day_events, cfg);
+        if day_events.is_empty() || Core::is_marker_day(&summary) {
+            continue;
+        }
+        if let Some(fig) = Core::day_figures(&summary, &day_events, cfg)
+            && let Some(worked) = fig.worked
+        {
+            out.push(BankDay {
+                date,
+                position: get_day_position(&summary.timeline).code().to_string(),
+                worked,
+                expected: fig.expected,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Running surplus of each day, restarting from 0 every month.
+fn month_bank(days: &[BankDay]) -> Vec<i64> {
+    let mut out = Vec::with_capacity(days.len());
+    let mut running = 0;
+    let mut month = None;
+    for d in days {
+        let m = d.date.format("%Y-%m").to_string();
+        if month.as_ref() != Some(&m) {
+            running = 0;
+            month = Some(m);
+        }
+        running += d.surplus();
+        out.push(running);
+    }
+    out
+}
+
+fn hours(minutes: i64) -> f64 {
+    (minutes as f64 / 60.0 * 100.0).round() / 100.0
+}
+
+/// Add the `Hour bank` sheet (nothing when `days` is empty).
+pub(crate) fn write_sheet(workbook: &mut Workbook, days: &[BankDay]) -> AppResult<()> {
+    if days.is_empty() {
+        return Ok(());
+    }
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Hour bank").map_err(xlsx_err)?;
+
+    let header = Format::new()
+        .set_bold()
+        .set_font_color(Color::RGB(0xFFFFFF))
+        .set_background_color(Color::RGB(0x2F75B5))
+        .set_pattern(FormatPattern::Solid)
+        .set_border(FormatBorder::Thin);
+    let total = Format::new()
+        .set_bold()
+        .set_background_color(Color::RGB(0xEAF3FB))
+        .set_pattern(FormatPattern::Solid)
+        .set_border(FormatBorder::Thin);
+    let total_num = total.clone().set_num_format("0.00");
+    let total_signed = total.clone().set_num_format("+0.00;-0.00;0.00");
+    let text = Format::new().set_border(FormatBorder::Thin);
+    let num = text.clone().set_num_format("0.00");
+    let signed = text.clone().set_num_format("+0.00;-0.00;0.00");
+
+    for (col, h) in HEADERS.iter().enumerate() {
+        sheet
+            .write_with_format(0, col as u16, *h, &header)
+            .map_err(xlsx_err)?;
+    }
+
+    let bank = month_bank(days);
+    for (i, (d, running)) in days.iter().zip(&bank).enumerate() {
+        let row = FIRST_ROW + i as u32;
+        sheet
+            .write_with_format(row, 0, d.date.format("%Y-%m").to_string(), &text)
+            .map_err(xlsx_err)?;
+        sheet
+            .write_with_format(row, 1, d.date.format("%Y-%m-%d").to_string(), &text)
+            .map_err(xlsx_err)?;
+        sheet
+            .write_with_format(row, 2, d.position.as_str(), &text)
+            .map_err(xlsx_err)?;
+        sheet
+            .write_with_format(row, 3, hours(d.worked), &num)
+            .map_err(xlsx_err)?;
+        sheet
+            .write_with_format(row, 4, hours(d.expected), &num)
+            .map_err(xlsx_err)?;
+        sheet
+            .write_with_format(row, 5, hours(d.surplus()), &signed)
+            .map_err(xlsx_err)?;
+        sheet
+            .write_with_format(row, 6, hours(*running), &signed)
+            .map_err(xlsx_err)?;
+    }
+    let last_row = FIRST_ROW + days.len() as u32 - 1;
+
+    // Totals under the header: SUBTOTAL keeps them right when rows are filtered
+    sheet
+        .write_with_format(1, 0, "TOTAL", &total)
+        .map_err(xlsx_err)?;
+    for col in 1..=2 {
+        sheet.write_blank(1, col, &total).map_err(xlsx_err)?;
+    }
+    let sums = [
+        (
+            3u16,
+            "D",
+            days.iter().map(|d| d.worked).sum::<i64>(),
+            &total_num,
+        ),
+        (4, "E", days.iter().map(|d| d.expected).sum(), &total_num),
+        (
+            5,
+            "F",
+            days.iter().map(|d| d.surplus()).sum(),
+            &total_signed,
+        ),
+    ];
+    for (col, letter, minutes, fmt) in sums {
+        let formula = Formula::new(format!(
+            "=SUBTOTAL(9,{l}{a}:{l}{b})",
+            l = letter,
+            a = FIRST_ROW + 1,
+            b = last_row + 1
+        ))
+        .set_result(hours(minutes).to_string());
+        sheet
+            .write_formula_with_format(1, col, formula, fmt)
+            .map_err(xlsx_err)?;
+    }
+    sheet.write_blank(1, 6, &total).map_err(xlsx_err)?;
+    sheet.set_freeze_panes(FIRST_ROW, 0).map_err(xlsx_err)?;
+
+    // Surplus: red below zero, green above
+    let scale = ConditionalFormat3ColorScale::new()
+        .set_minimum_color(Color::RGB(0xF8696B))
+        .set_midpoint(ConditionalFormatType::Number, 0)
+        .set_midpoint_color(Color::RGB(0xFFFFFF))
+        .set_maximum_color(Color::RGB(0x63BE7B));
+    sheet
+        .add_conditional_format(FIRST_ROW, 5, last_row, 5, &scale)
+        .map_err(xlsx_err)?;
+
+    // Month bank: bars growing with the balance, red when negative
+    let bars = ConditionalFormatDataBar::new()
+        .set_fill_color(Color::RGB(0x63BE7B))
+        .set_negative_fill_color(Color::RGB(0xF8696B));
+    sheet
+        .add_conditional_format(FIRST_ROW, 6, last_row, 6, &bars)
+        .map_err(xlsx_err)?;
+
+    sheet.set_column_width(0, 9.0).map_err(xlsx_err)?;
+    sheet.set_column_width(1, 12.0).map_err(xlsx_err)?;
+    sheet.set_column_width(2, 9.0).map_err(xlsx_err)?;
+    for col in 3..=5 {
+        sheet.set_column_width(col, 10.0).map_err(xlsx_err)?;
+    }
+    sheet.set_column_width(6, 16.0).map_err(xlsx_err)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn month_bank_restarts_every_month() {
+        let day = |m, d, worked| BankDay {
+            date: NaiveDate::from_ymd_opt(2025, m, d).unwrap(),
+            position: "O".into(),
+            worked,
+            expected: 480,
+        };
+        let days = [day(5, 30, 510), day(5, 31, 470), day(6, 2, 450)];
+        assert_eq!(month_bank(&days), vec![30, 20, -30]);
+        assert_eq!(hours(-30), -0.5);
+    }
+}
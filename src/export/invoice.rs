@@ -0,0 +1,140 @@
+//! Invoice documents (`rtimelogger invoice`): PDF with a dedicated layout
+//! (header block, one line per day and project, totals) or plain CSV lines.
+
+use crate::core::invoice::Invoice;
+use crate::errors::{AppError, AppResult};
+use crate::export::notify_export_success;
+use crate::export::pdf::PdfManager;
+use crate::ui::messages::info;
+use clap::ValueEnum;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum InvoiceFormat {
+    Pdf,
+    Csv,
+}
+
+impl InvoiceFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            InvoiceFormat::Pdf => "pdf",
+            InvoiceFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Document data that is not computed from the events.
+#[derive(Debug, Clone)]
+pub struct InvoiceHeader {
+    /// Invoice number (placeholder when missing)
+    pub number: Option<String>,
+    pub issue_date: String,
+    /// Period label ("2025-06")
+    pub period: String,
+    pub currency: String,
+}
+
+const PLACEHOLDER: &str = "________";
+
+fn money(v: f64, currency: &str) -> String {
+    format!("{:.2} {}", v, currency)
+}
+
+/// Write the invoice in the chosen format.
+pub fn write_invoice(
+    invoice: &Invoice,
+    header: &InvoiceHeader,
+    format: InvoiceFormat,
+    path: &Path,
+) -> AppResult<()> {
+    match format {
+        InvoiceFormat::Pdf => write_pdf(invoice, header, path),
+        InvoiceFormat::Csv => write_csv(invoice, header, path),
+    }
+}
+
+fn write_pdf(invoice: &Invoice, header: &InvoiceHeader, path: &Path) -> AppResult<()> {
+    info(format!("Writing invoice PDF: {}", path.display()));
+    let cur = header.currency.as_str();
+
+    let preamble = vec![
+        format!(
+            "Invoice no.: {}",
+            header.number.as_deref().unwrap_or(PLACEHOLDER)
+        ),
+        format!("Date: {}", header.issue_date),
+        format!("Bill to: {}", invoice.client),
+        format!("Period: {}", header.period),
+        format!("Hourly rate: {}", money(invoice.rate, cur)),
+    ];
+
+    let rows: Vec<Vec<String>> = invoice
+        .lines
+        .iter()
+        .map(|l| {
+            vec![
+                l.date.format("%Y-%m-%d").to_string(),
+                l.project.clone(),
+                format!("{:.2}", l.hours),
+                money(invoice.rate, cur),
+                money(l.amount, cur),
+            ]
+        })
+        .collect();
+
+    let mut closing: Vec<String> = invoice
+        .by_project()
+        .iter()
+        .map(|(project, hours)| format!("Project {}: {:.2} h", project, hours))
+        .collect();
+    closing.push(format!("Total hours: {:.2}", invoice.hours()));
+    closing.push(format!("Subtotal: {}", money(invoice.subtotal(), cur)));
+    closing.push(match (invoice.tax_percent, invoice.tax()) {
+        (Some(p), Some(t)) => format!("Tax ({}%): {}", p, money(t, cur)),
+        _ => format!("Tax (____%): {}", PLACEHOLDER),
+    });
+    closing.push(match invoice.total() {
+        Some(t) => format!("Total due: {}", money(t, cur)),
+        None => format!("Total due: {}", PLACEHOLDER),
+    });
+
+    let mut pdf = PdfManager::new();
+    pdf.set_footer_note(&format!("{} - {}", invoice.client, header.period));
+    pdf.write_document(
+        "INVOICE",
+        &preamble,
+        &["DATE", "PROJECT", "HOURS", "RATE", "AMOUNT"],
+        &rows,
+        &closing,
+    );
+    pdf.save(path).map_err(|e| AppError::export_io(path, e))?;
+
+    notify_export_success("Invoice PDF", path);
+    Ok(())
+}
+
+fn write_csv(invoice: &Invoice, header: &InvoiceHeader, path: &Path) -> AppResult<()> {
+    info(format!("Writing invoice CSV: {}", path.display()));
+
+    let mut wtr = csv::Writer::from_path(path).map_err(|e| AppError::Export(e.to_string()))?;
+    let csv_err = |e: csv::Error| AppError::Export(e.to_string());
+
+    wtr.write_record(["date", "project", "hours", "rate", "amount", "currency"])
+        .map_err(csv_err)?;
+    for l in &invoice.lines {
+        wtr.write_record([
+            l.date.format("%Y-%m-%d").to_string(),
+            l.project.clone(),
+            format!("{:.2}", l.hours),
+            format!("{:.2}", invoice.rate),
+            format!("{:.2}", l.amount),
+            header.currency.clone(),
+        ])
+        .map_err(csv_err)?;
+    }
+    wtr.flush().map_err(|e| AppError::export_io(path, e))?;
+
+    notify_export_success("Invoice CSV", path);
+    Ok(())
+}
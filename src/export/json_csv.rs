@@ -1,41 +1,180 @@
 // src/export/json_csv.rs
 
 use crate::errors::{AppError, AppResult};
+use crate::export::model::{ConfigSnapshot, ExportColumns, event_to_row, get_headers};
 use crate::export::{EventExport, notify_export_success};
 use crate::ui::messages::info;
 use std::fs::File;
-use std::io::{self, Write};
+use std::io::Write;
 use std::path::Path;
 
 /// Export JSON pretty-printed.
-pub(crate) fn export_json(events: &[EventExport], path: &Path) -> AppResult<()> {
+///
+/// With a snapshot the events are wrapped: `{ "snapshot": {..}, "events": [..] }`;
+/// without it the output stays a plain array.
+pub(crate) fn export_json(
+    events: &[EventExport],
+    snapshot: Option<&ConfigSnapshot>,
+    path: &Path,
+) -> AppResult<()> {
     info(format!("Exporting to JSON: {}", path.display()));
 
-    let json_data = serde_json::to_string_pretty(events)
-        .map_err(|e| AppError::from(io::Error::other(format!("JSON serialization error: {e}"))))?;
+    let json_data = match snapshot {
+        Some(snapshot) => serde_json::to_string_pretty(&serde_json::json!({
+            "snapshot": snapshot,
+            "events": events,
+        })),
+        None => serde_json::to_string_pretty(events),
+    }
+    .map_err(|e| AppError::Export(format!("JSON serialization error: {e}")))?;
 
-    let mut file = File::create(path)?;
-    file.write_all(json_data.as_bytes())?;
+    let mut file = File::create(path).map_err(|e| AppError::export_io(path, e))?;
+    file.write_all(json_data.as_bytes())
+        .map_err(|e| AppError::export_io(path, e))?;
 
     notify_export_success("JSON", path);
     Ok(())
 }
 
-/// Export CSV (header incluso grazie a serde).
-pub(crate) fn export_csv(events: &[EventExport], path: &Path) -> AppResult<()> {
+/// Export CSV (header + `meta.<key>` columns for the selected keys).
+/// A snapshot is written first as `# key: value` comment lines.
+/// With a comma as decimal separator the fields are split by `;`, as the
+/// spreadsheets using that locale expect.
+pub(crate) fn export_csv(
+    events: &[EventExport],
+    columns: &ExportColumns,
+    snapshot: Option<&ConfigSnapshot>,
+    path: &Path,
+) -> AppResult<()> {
     info(format!("Exporting to CSV: {}", path.display()));
 
-    let mut wtr = csv::Writer::from_path(path)
-        .map_err(|e| AppError::from(io::Error::other(format!("CSV open error: {e}"))))?;
+    let mut file = File::create(path).map_err(|e| AppError::export_io(path, e))?;
+    if let Some(snapshot) = snapshot {
+        let mut block = String::new();
+        for (key, value) in snapshot.entries() {
+            block.push_str(&format!("# {}: {}\n", key, value));
+        }
+        file.write_all(block.as_bytes())
+            .map_err(|e| AppError::export_io(path, e))?;
+    }
+
+    let delimiter = if columns.decimal_step.is_some() && columns.decimal_separator == Some(',') {
+        b';'
+    } else {
+        b','
+    };
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(file);
 
+    let write_err = |e: csv::Error| AppError::export_io(path, e);
+
+    wtr.write_record(get_headers(columns)).map_err(write_err)?;
     for item in events {
-        wtr.serialize(item)
-            .map_err(|e| AppError::from(io::Error::other(format!("CSV write error: {e}"))))?;
+        wtr.write_record(event_to_row(item, columns))
+            .map_err(write_err)?;
     }
 
-    wtr.flush()
-        .map_err(|e| AppError::from(io::Error::other(format!("CSV flush error: {e}"))))?;
+    wtr.flush().map_err(|e| AppError::export_io(path, e))?;
 
     notify_export_success("CSV", path);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::import::parse_json_events;
+    use crate::models::event::Event;
+    use crate::models::event_type::EventType;
+    use crate::models::location::Location;
+    use crate::models::test_support::ev;
+    use chrono::NaiveTime;
+
+    fn events() -> Vec<Event> {
+        let start = Event {
+            location: Location::Remote,
+            lunch: Some(0),
+            work_gap: true,
+            pair: 1,
+            source: "api".into(),
+            meta: Some(r#"{"project":"acme","ticket":"0042"}"#.into()),
+            notes: Some("standup".into()),
+            host: Some("laptop".into()),
+            os_user: Some("jdoe".into()),
+            created_at: "2025-06-18T08:30:12+02:00".into(),
+            ..ev(1, "08:30", EventType::In)
+        };
+        let end = Event {
+            location: Location::Remote,
+            lunch: Some(30),
+            pair: 1,
+            host: None,
+            created_at: "2025-06-18T17:00:03+02:00".into(),
+            ..ev(2, "17:00", EventType::Out)
+        };
+        let stray = Event {
+            id: 3,
+            time: NaiveTime::from_hms_opt(18, 10, 0).unwrap(),
+            pair: 0,
+            unmatched: true,
+            ..end.clone()
+        };
+        vec![start, end, stray]
+    }
+
+    #[test]
+    fn exported_json_reimports_losslessly() {
+        let original = events();
+        let exported: Vec<EventExport> = original.iter().map(EventExport::from).collect();
+        let snapshot = ConfigSnapshot::from_config(&Config::default());
+        let dir = std::env::temp_dir();
+
+        for (name, snapshot) in [("plain", None), ("snapshot", Some(&snapshot))] {
+            let path = dir.join(format!("rtl-json-{}-{}.json", name, std::process::id()));
+            export_json(&exported, snapshot, &path).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            let reimported = parse_json_events(&content).unwrap();
+            assert_eq!(
+                serde_json::to_value(&reimported).unwrap(),
+                serde_json::to_value(&original).unwrap(),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn only_decimal_csv_switches_to_semicolons() {
+        let exported: Vec<EventExport> = events().iter().map(EventExport::from).collect();
+        let path = std::env::temp_dir().join(format!("rtl-csv-{}.csv", std::process::id()));
+        let header = |columns: &ExportColumns| {
+            export_csv(&exported, columns, None, &path).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            content.lines().next().unwrap().to_string()
+        };
+
+        let plain = ExportColumns {
+            decimal_separator: Some(','),
+            ..ExportColumns::default()
+        };
+        assert!(header(&plain).starts_with("id,"));
+
+        let decimal = ExportColumns {
+            decimal_step: Some(0.25),
+            ..plain
+        };
+        assert!(header(&decimal).starts_with("id;"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_exported_event_is_rejected() {
+        let mut exported = EventExport::from(&events()[0]);
+        exported.kind = "break".into();
+        let content = serde_json::to_string(&[exported]).unwrap();
+        assert!(parse_json_events(&content).is_err());
+    }
+}
@@ -1,21 +1,35 @@
 // src/export/logic.rs
 
+use crate::config::Config;
+use crate::core::calculator::overlap::OverlapMode;
+use crate::core::calculator::timeline::build_timeline_with;
+use crate::core::shift::ShiftBands;
 use crate::db::pool::DbPool;
+use crate::db::queries::events::{date_bounds, load_events_between};
 use crate::errors::{AppError, AppResult};
-use crate::export::ExportFormat;
 use crate::export::fs_utils::ensure_writable;
-use crate::export::model::EventExport;
-use crate::export::range::parse_range;
-use crate::ui::messages::warning;
+use crate::export::hour_bank;
+use crate::export::model::{ConfigSnapshot, EventExport, ExportColumns};
+use crate::export::preset::ExportPreset;
+use crate::export::{ExportFormat, SplitBy};
+use crate::models::event::Event;
+use crate::ui::messages::{info, warning};
+use crate::utils::archive::{read_passphrase, zip_single_file};
+use crate::utils::date::{all_days_of_month, get_day_position, month_name};
+use crate::utils::formatting::round_hours;
+use crate::utils::meta::meta_value;
+use crate::utils::path::{expand_tilde, month_template};
+use crate::utils::period::Period;
 
+use crate::export::dashboard::export_dashboard;
 use crate::export::json_csv::{export_csv, export_json};
 use crate::export::pdf_export::export_pdf;
+use crate::export::weekly_matrix::export_weekly_matrix;
 use crate::export::xlsx::export_xlsx;
-use chrono::NaiveDate;
-use rusqlite::Row;
-use rusqlite::params;
-use std::io;
-use std::path::Path;
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 /// Logica di alto livello per l'export.
 pub struct ExportLogic;
@@ -23,58 +37,368 @@ pub struct ExportLogic;
 impl ExportLogic {
     /// Export degli eventi.
     ///
-    /// - `format`: "csv" | "json" | "xlsx" | "pdf"
+    /// - `format`: "csv" | "json" | "xlsx" | "pdf" | "dashboard" (HTML)
     /// - `file`: path assoluto del file di output
-    /// - `range`: `None`, `"all"` oppure espressioni come:
-    ///   - `YYYY`
-    ///   - `YYYY-MM`
-    ///   - `YYYY-MM-DD`
-    ///   - `YYYY:YYYY`
-    ///   - `YYYY-MM:YYYY-MM`
-    ///   - `YYYY-MM-DD:YYYY-MM-DD`
+    /// - `range`: `None`, `"all"` oppure un'espressione supportata da
+    ///   [`Period::parse`] (`YYYY`, `YYYY-MM`, `YYYY-MM-DD` o `START:END`)
+    /// - `encrypt`: wrap the result in an AES-256 encrypted `<file>.zip`
+    /// - `columns`: optional columns (`--meta-keys`, `--decimal`, `--snapshot`)
+    #[allow(clippy::too_many_arguments)]
     pub fn export(
         pool: &mut DbPool,
+        cfg: &Config,
         format: ExportFormat,
         file: &str,
         range: &Option<String>,
         _events: bool,
         force: bool,
+        encrypt: bool,
+        columns: &ExportColumns,
     ) -> AppResult<()> {
         let path = Path::new(file);
+        let zip_out = prepare_output(path, force, encrypt)?;
 
-        if !path.is_absolute() {
-            return Err(AppError::from(io::Error::other(format!(
-                "Output file path must be absolute: {file}"
-            ))));
+        let date_bounds = Period::parse_opt(range.as_deref())?.bounds();
+        let title = build_pdf_title(range);
+
+        let snapshot = ConfigSnapshot::from_config(cfg);
+        if !write_events(
+            pool,
+            cfg,
+            &format,
+            path,
+            date_bounds,
+            columns,
+            &snapshot,
+            &title,
+        )? {
+            warning("⚠️  No events found for selected range.");
+            return Ok(());
         }
 
-        ensure_writable(path, force)?;
+        finish_output(path, zip_out)?;
 
-        let date_bounds: Option<(NaiveDate, NaiveDate)> = match range {
-            None => None,
-            Some(r) if r.eq_ignore_ascii_case("all") => None,
-            Some(r) => Some(parse_range(r)?),
-        };
+        Ok(())
+    }
 
-        let events_vec = load_events(pool, date_bounds)?;
+    /// Export one file per month of the range (`--split-by month`).
+    ///
+    /// `template` must contain `{YYYY}` and `{MM}`, e.g. `/out/report-{YYYY}-{MM}.csv`.
+    /// Months without events produce no file. With `--encrypt` the passphrase
+    /// is asked once and used for every archive.
+    #[allow(clippy::too_many_arguments)]
+    pub fn export_split(
+        pool: &mut DbPool,
+        cfg: &Config,
+        format: ExportFormat,
+        template: &str,
+        range: &Option<String>,
+        split: SplitBy,
+        force: bool,
+        encrypt: bool,
+        columns: &ExportColumns,
+    ) -> AppResult<()> {
+        if !template.contains("{YYYY}") || !template.contains("{MM}") {
+            return Err(AppError::InvalidArgs(format!(
+                "--split-by month needs a file template with {{YYYY}} and {{MM}} (e.g. /out/report-{{YYYY}}-{{MM}}.{}), got '{}'.",
+                format.extension(),
+                template
+            )));
+        }
 
-        if events_vec.is_empty() {
+        let bounds = match Period::parse_opt(range.as_deref())?.bounds() {
+            Some(b) => Some(b),
+            None => date_bounds(&pool.conn)?,
+        };
+        let Some((from, to)) = bounds else {
             warning("⚠️  No events found for selected range.");
             return Ok(());
+        };
+
+        // Validate every target before writing anything
+        let months = match split {
+            SplitBy::Month => month_slices(from, to),
+        };
+        let mut targets = Vec::new();
+        for (first, last) in &months {
+            let path = month_template(template, *first);
+            prepare_output(&path, force, false)?;
+            if encrypt {
+                ensure_writable(&PathBuf::from(format!("{}.zip", path.display())), force)?;
+            }
+            targets.push((path, *first, *last));
         }
 
-        match format {
-            ExportFormat::Csv => export_csv(&events_vec, path)?,
-            ExportFormat::Json => export_json(&events_vec, path)?,
-            ExportFormat::Xlsx => export_xlsx(&events_vec, path)?,
-            ExportFormat::Pdf => {
-                let title = build_pdf_title(range);
-                export_pdf(&events_vec, path, &title)?
+        let passphrase = if encrypt {
+            Some(read_passphrase()?)
+        } else {
+            None
+        };
+
+        let snapshot = ConfigSnapshot::from_config(cfg);
+        let mut written = 0usize;
+        for (path, first, last) in targets {
+            let title = month_title(first);
+
+            if !write_events(
+                pool,
+                cfg,
+                &format,
+                &path,
+                Some((first, last)),
+                columns,
+                &snapshot,
+                &title,
+            )? {
+                info(format!("No events in {}: skipped.", first.format("%Y-%m")));
+                continue;
             }
+
+            let zip_out = passphrase
+                .as_ref()
+                .map(|p| (PathBuf::from(format!("{}.zip", path.display())), p.clone()));
+            finish_output(&path, zip_out)?;
+            written += 1;
+        }
+
+        if written == 0 {
+            warning("⚠️  No events found for selected range.");
+        } else {
+            info(format!("{} monthly file(s) written.", written));
         }
 
         Ok(())
     }
+
+    /// Write the events of `[from, to]` to `path` (absolute, never encrypted).
+    /// Returns false, writing nothing, when the interval has no events.
+    pub fn export_range(
+        pool: &mut DbPool,
+        cfg: &Config,
+        format: &ExportFormat,
+        path: &Path,
+        (from, to): (NaiveDate, NaiveDate),
+        force: bool,
+    ) -> AppResult<bool> {
+        prepare_output(path, force, false)?;
+        write_events(
+            pool,
+            cfg,
+            format,
+            path,
+            Some((from, to)),
+            &ExportColumns::default(),
+            &ConfigSnapshot::from_config(cfg),
+            &month_title(from),
+        )
+    }
+
+    /// XLSX with one sheet per month of the range laid out as a weekly
+    /// matrix (see `export --layout weekly-matrix`).
+    pub fn export_weekly_matrix(
+        pool: &mut DbPool,
+        cfg: &Config,
+        file: &str,
+        range: &Option<String>,
+        force: bool,
+        encrypt: bool,
+    ) -> AppResult<()> {
+        let path = Path::new(file);
+
+        let bounds = match Period::parse_opt(range.as_deref())?.bounds() {
+            Some(b) => Some(b),
+            None => date_bounds(&pool.conn)?,
+        };
+        let Some((from, to)) = bounds else {
+            warning("⚠️  No events found for selected range.");
+            return Ok(());
+        };
+
+        let zip_out = prepare_output(path, force, encrypt)?;
+        if !export_weekly_matrix(pool, cfg, &month_slices(from, to), path)? {
+            warning("⚠️  No events found for selected range.");
+            return Ok(());
+        }
+
+        finish_output(path, zip_out)
+    }
+
+    /// Export one row per day using a preset layout (see [`ExportPreset`]),
+    /// as CSV or, with `pdf`, as a PDF table.
+    #[allow(clippy::too_many_arguments)]
+    pub fn export_preset(
+        pool: &mut DbPool,
+        cfg: &Config,
+        preset: &str,
+        file: &str,
+        range: &Option<String>,
+        force: bool,
+        encrypt: bool,
+        pdf: bool,
+    ) -> AppResult<()> {
+        let preset = ExportPreset::load(preset)?;
+        let period = Period::parse_opt(range.as_deref())?;
+
+        let path = Path::new(file);
+        let zip_out = prepare_output(path, force, encrypt)?;
+
+        if preset.write(pool, cfg, &period, path, pdf)? == 0 {
+            warning("⚠️  No days found for selected range.");
+            return Ok(());
+        }
+
+        finish_output(path, zip_out)
+    }
+}
+
+/// Output file used when `--file` is omitted: `export_filename` of the config
+/// in `export_dir` (created if missing; default: the current directory).
+/// `{period}` is the range (`all` without one, `{YYYY}-{MM}` with `split`),
+/// `{ext}` the format.
+pub fn default_output_file(
+    cfg: &Config,
+    format: &ExportFormat,
+    range: &Option<String>,
+    split: bool,
+) -> AppResult<String> {
+    let period = if split {
+        "{YYYY}-{MM}".to_string()
+    } else {
+        match range.as_deref().map(str::trim) {
+            None | Some("") => "all".to_string(),
+            Some(r) => r.replace([':', '/', '\\'], "_"),
+        }
+    };
+    let name = cfg
+        .export_filename
+        .replace("{period}", &period)
+        .replace("{ext}", format.extension());
+
+    let cwd = std::env::current_dir()?;
+    let dir = match cfg.export_dir.as_deref().map(str::trim) {
+        Some(d) if !d.is_empty() => cwd.join(expand_tilde(d)),
+        _ => cwd,
+    };
+    fs::create_dir_all(&dir)?;
+
+    Ok(dir.join(name).to_string_lossy().to_string())
+}
+
+/// Validate the output path and, with `--encrypt`, ask for the passphrase.
+/// Returns the archive path and passphrase when encryption is requested.
+fn prepare_output(path: &Path, force: bool, encrypt: bool) -> AppResult<Option<(PathBuf, String)>> {
+    if !path.is_absolute() {
+        return Err(AppError::InvalidArgs(format!(
+            "Output file path must be absolute: {}\nUse a full path, e.g. {}",
+            path.display(),
+            std::env::current_dir()
+                .map(|d| d.join(path).display().to_string())
+                .unwrap_or_else(|_| "/home/user/report.csv".into())
+        )));
+    }
+
+    ensure_writable(path, force)?;
+
+    if !encrypt {
+        return Ok(None);
+    }
+
+    let zip_path = PathBuf::from(format!("{}.zip", path.display()));
+    ensure_writable(&zip_path, force)?;
+    Ok(Some((zip_path, read_passphrase()?)))
+}
+
+/// Move the plaintext output into the encrypted archive, if requested.
+/// The plaintext file is removed whatever happens: on a failed archive no
+/// unencrypted timesheet is left behind (nor a half-written archive).
+fn finish_output(path: &Path, zip_out: Option<(PathBuf, String)>) -> AppResult<()> {
+    if let Some((zip_path, passphrase)) = zip_out {
+        let zipped = zip_single_file(path, &zip_path, Some(&passphrase));
+        let removed = fs::remove_file(path);
+        if let Err(e) = zipped {
+            let _ = fs::remove_file(&zip_path);
+            return Err(e);
+        }
+        removed?;
+        info(format!("Encrypted archive: {}", zip_path.display()));
+    }
+    Ok(())
+}
+
+/// Load, enrich and write the events in `bounds`. Returns false (and writes
+/// nothing) when there are no events.
+#[allow(clippy::too_many_arguments)]
+fn write_events(
+    pool: &mut DbPool,
+    cfg: &Config,
+    format: &ExportFormat,
+    path: &Path,
+    bounds: Option<(NaiveDate, NaiveDate)>,
+    columns: &ExportColumns,
+    snapshot: &ConfigSnapshot,
+    title: &str,
+) -> AppResult<bool> {
+    let events = load_events_between(&pool.conn, bounds)?;
+    if events.is_empty() {
+        return Ok(false);
+    }
+
+    let mut events_vec: Vec<EventExport> = events.iter().map(EventExport::from).collect();
+    fill_derived_fields(
+        &events,
+        &mut events_vec,
+        &columns.shift_bands,
+        cfg.overlap_mode,
+    );
+    fill_optional_fields(&mut events_vec, columns);
+
+    // CSV / JSON keep their plain layout unless `--snapshot` is given
+    let text_snapshot = columns.snapshot.then_some(snapshot);
+
+    match format {
+        ExportFormat::Csv => export_csv(&events_vec, columns, text_snapshot, path)?,
+        ExportFormat::Json => export_json(&events_vec, text_snapshot, path)?,
+        ExportFormat::Xlsx => {
+            let bank = hour_bank::load(pool, cfg, &events_vec)?;
+            export_xlsx(&events_vec, columns, snapshot, &bank, path)?
+        }
+        ExportFormat::Pdf => export_pdf(&events_vec, columns, snapshot, path, title)?,
+        ExportFormat::Dashboard => {
+            let bank = hour_bank::load(pool, cfg, &events_vec)?;
+            export_dashboard(&events_vec, &bank, snapshot, path, title)?
+        }
+    }
+
+    Ok(true)
+}
+
+/// PDF title for a monthly export ("Saved sessions for June 2025").
+fn month_title(date: NaiveDate) -> String {
+    format!(
+        "Saved sessions for {} {}",
+        month_name(&format!("{:02}", date.month())),
+        date.year()
+    )
+}
+
+/// Split `[from, to]` into calendar-month slices clamped to the interval.
+fn month_slices(from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut out = Vec::new();
+    let mut start = from;
+
+    while start <= to {
+        let days = all_days_of_month(start.year(), start.month());
+        let month_end = days[days.len() - 1];
+        let end = month_end.min(to);
+        out.push((start, end));
+
+        match month_end.succ_opt() {
+            Some(next) => start = next,
+            None => break,
+        }
+    }
+
+    out
 }
 
 /// Costruisce il titolo del PDF in base al periodo selezionato.
@@ -122,61 +446,103 @@ fn build_pdf_title(period: &Option<String>) -> String {
     }
 }
 
-/// Carica gli eventi dal DB secondo i bounds.
-fn load_events(
-    pool: &mut DbPool,
-    bounds: Option<(NaiveDate, NaiveDate)>,
-) -> AppResult<Vec<EventExport>> {
-    let conn = &mut pool.conn;
+/// Fill the optional columns: selected metadata keys and decimal lunch hours.
+fn fill_optional_fields(events: &mut [EventExport], columns: &ExportColumns) {
+    for ev in events {
+        ev.meta_fields = columns
+            .meta_keys
+            .iter()
+            .map(|k| (k.clone(), meta_value(ev.meta.as_deref(), k)))
+            .collect();
 
-    let mut events = Vec::new();
+        ev.lunch_hours = columns
+            .decimal_step
+            .map(|step| round_hours(ev.lunch_break as i64, step));
 
-    match bounds {
-        None => {
-            let mut stmt = conn.prepare(
-                "SELECT id, date, time, kind, position, lunch_break, pair, source
-                 FROM events
-                 ORDER BY date ASC, time ASC",
-            )?;
-
-            let rows = stmt.query_map([], map_row)?;
+        if columns.iso_datetime {
+            ev.datetime = Some(iso_datetime(&ev.date, &ev.time));
+        }
 
-            for r in rows {
-                events.push(r?);
-            }
+        if let Some(step) = columns.decimal_step {
+            ev.pair_hours = ev.pair_minutes.map(|m| round_hours(m, step));
+            ev.day_total_hours = ev.day_total_minutes.map(|m| round_hours(m, step));
+            ev.pair_weighted_hours = ev.pair_weighted_minutes.map(|m| round_hours(m, step));
         }
-        Some((start, end)) => {
-            let start_str = start.format("%Y-%m-%d").to_string();
-            let end_str = end.format("%Y-%m-%d").to_string();
+    }
+}
 
-            let mut stmt = conn.prepare(
-                "SELECT id, date, time, kind, position, lunch_break, pair, source
-                 FROM events
-                 WHERE date BETWEEN ?1 AND ?2
-                 ORDER BY date ASC, time ASC",
-            )?;
+/// `2025-06-18` + `08:55` → `2025-06-18T08:55:00` (no offset: times are local).
+fn iso_datetime(date: &str, time: &str) -> String {
+    match NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M") {
+        Ok(dt) => dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        Err(_) => format!("{}T{}", date, time),
+    }
+}
+
+/// Fill the figures derived from the pairing logic: net pair duration (on the
+/// OUT row), running day total and day position. Events that are not part of
+/// a pair keep empty durations. With `bands` the OUT rows also get the
+/// weighted pair minutes. `loaded` are the exported events (whole days), as
+/// read from the DB.
+fn fill_derived_fields(
+    loaded: &[Event],
+    events: &mut [EventExport],
+    bands: &ShiftBands,
+    mode: OverlapMode,
+) {
+    let mut days: BTreeMap<NaiveDate, Vec<Event>> = BTreeMap::new();
+    for ev in loaded {
+        days.entry(ev.date).or_default().push(ev.clone());
+    }
+
+    // event id → (pair minutes, running day total)
+    let mut figures: HashMap<i32, (Option<i64>, i64)> = HashMap::new();
+    let mut weighted: HashMap<i32, i64> = HashMap::new();
+    let mut positions: HashMap<String, String> = HashMap::new();
+
+    for (date, day_events) in &days {
+        let timeline = build_timeline_with(day_events, mode);
+        positions.insert(
+            date.format("%Y-%m-%d").to_string(),
+            get_day_position(&timeline).to_db_str().to_string(),
+        );
+
+        let mut running = 0;
+        let mut prev_out: Option<&Event> = None;
 
-            let rows = stmt.query_map(params![start_str, end_str], map_row)?;
+        for p in &timeline.pairs {
+            // Gaps flagged as work count as worked time
+            if let Some(out) = prev_out
+                && out.work_gap
+            {
+                running += (p.in_event.timestamp() - out.timestamp())
+                    .num_minutes()
+                    .max(0);
+            }
+
+            figures.insert(p.in_event.id, (None, running));
 
-            for r in rows {
-                events.push(r?);
+            if let Some(out) = &p.out_event {
+                running += p.duration_minutes;
+                figures.insert(out.id, (Some(p.duration_minutes), running));
+                if !bands.is_empty()
+                    && let Some(w) = bands.pair_minutes(p)
+                {
+                    weighted.insert(out.id, w);
+                }
             }
+            prev_out = p.out_event.as_ref();
         }
     }
 
-    Ok(events)
-}
-
-/// Mapping DB → EventExport (riusato per tutte le query).
-fn map_row(row: &Row<'_>) -> rusqlite::Result<EventExport> {
-    Ok(EventExport {
-        id: row.get(0)?,
-        date: row.get(1)?,
-        time: row.get(2)?,
-        kind: row.get(3)?,
-        position: row.get(4)?,
-        lunch_break: row.get(5)?,
-        pair: row.get(6)?,
-        source: row.get(7)?,
-    })
+    for ev in events {
+        if let Some((pair, total)) = figures.get(&ev.id) {
+            ev.pair_minutes = *pair;
+            ev.day_total_minutes = Some(*total);
+        }
+        ev.pair_weighted_minutes = weighted.get(&ev.id).copied();
+        if let Some(pos) = positions.get(&ev.date) {
+            ev.day_position = pos.clone();
+        }
+    }
 }
@@ -1,20 +1,27 @@
 // src/export/mod.rs
 
+mod dashboard;
 mod excel_date;
-mod fs_utils;
+pub(crate) mod fs_utils;
+mod hour_bank;
+pub mod invoice;
 mod json_csv;
 pub mod logic;
 mod model;
 mod pdf;
 mod pdf_export;
-mod range;
+pub mod preset;
+pub mod schema;
+mod weekly_matrix;
 mod xlsx;
 
 pub use logic::ExportLogic;
-pub use model::EventExport;
+pub use model::{ConfigSnapshot, DayExport, EventExport, ExportColumns, PairExport};
 
 use crate::ui::messages::success;
 use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 /// Helper comune per messaggi di completamento export.
@@ -22,12 +29,15 @@ pub(crate) fn notify_export_success(label: &str, path: &Path) {
     success(format!("{label} export completed: {}", path.display()));
 }
 
-#[derive(Clone, Debug, ValueEnum)]
+#[derive(Clone, Debug, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
 pub enum ExportFormat {
     Csv,
     Json,
     Xlsx,
     Pdf,
+    /// Single offline HTML file with charts (monthly surplus, days by position)
+    Dashboard,
 }
 
 impl ExportFormat {
@@ -37,6 +47,31 @@ impl ExportFormat {
             ExportFormat::Json => "json",
             ExportFormat::Xlsx => "xlsx",
             ExportFormat::Pdf => "pdf",
+            ExportFormat::Dashboard => "dashboard",
         }
     }
+
+    /// File extension of the output.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Dashboard => "html",
+            other => other.as_str(),
+        }
+    }
+}
+
+/// Sheet layout of `export --layout`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportLayout {
+    /// One row per event (default)
+    Events,
+    /// XLSX only: per week, days (Mon..Sun) × Start/End/Lunch/Worked/Surplus
+    WeeklyMatrix,
+}
+
+/// How `export --split-by` slices the range into files.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SplitBy {
+    /// One file per calendar month
+    Month,
 }
@@ -1,48 +1,446 @@
 // src/export/model.rs
 
-use serde::Serialize;
+//! Flat DTOs shared by the exports, the JSON outputs and the HTTP API:
+//! [`EventExport`] (one event), [`PairExport`] (one work session) and
+//! [`DayExport`] (one day), each built from the core model with `From`.
+//!
+//! Wire stability: within a major version fields are never renamed, removed
+//! or retyped; new fields are optional (`default` on input) so files written
+//! by an older release still parse. [`EventExport`] carries every stored
+//! column of an event, so an exported JSON converts back to the same
+//! [`Event`]s (`TryFrom<&EventExport>`, see `import::parse_json_events`).
+
+use crate::config::Config;
+use crate::core::calculator::timeline::Pair;
+use crate::core::shift::ShiftBands;
+use crate::errors::AppError;
+use crate::models::day_summary::DaySummary;
+use crate::models::event::Event;
+use crate::models::event_type::EventType;
+use crate::models::location::Location;
+use crate::utils::date::get_day_position;
+use crate::utils::formatting::mins2decimal;
+use chrono::{NaiveDate, NaiveTime};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Struttura “piatta” per export degli eventi.
-#[derive(Serialize, Clone, Debug)]
+/// `From<&Event>` fills the raw fields; the derived ones (durations, day
+/// position, optional columns) are left empty for the export to compute.
+/// The derived fields are ignored when converting back to an [`Event`].
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
 pub struct EventExport {
     pub id: i32,
     pub date: String,
     pub time: String,
+    /// `date` + `time` as ISO 8601 (only with `--iso-datetime`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datetime: Option<String>,
     pub kind: String,
     pub position: String,
     pub lunch_break: i32,
+    /// Lunch as decimal hours (only with `--decimal`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lunch_hours: Option<f64>,
     pub pair: i32,
+    pub unmatched: bool,
     pub source: String,
+    /// Machine that recorded the event ("" if unknown)
+    pub host: String,
+    pub os_user: String,
+    /// The gap after this event counts as work
+    #[serde(default)]
+    pub work_gap: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Creation timestamp of the row (ISO 8601)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub created_at: String,
+    /// Net minutes of the pair closed by this event (OUT rows only)
+    pub pair_minutes: Option<i64>,
+    /// `pair_minutes` weighted by the `shift_bands` of the config (OUT rows
+    /// only, exported when bands are configured)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pair_weighted_minutes: Option<i64>,
+    /// Minutes worked in the day up to this event (work gaps included)
+    pub day_total_minutes: Option<i64>,
+    /// Position of the whole day (O, R, M, H, ...)
+    pub day_position: String,
+    /// `pair_minutes` / `day_total_minutes` as decimal hours (only with `--decimal`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pair_hours: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day_total_hours: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pair_weighted_hours: Option<f64>,
+    /// Raw `events.meta` value (JSON only, the other formats use `meta.<key>`)
+    #[serde(rename = "meta_raw", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<String>,
+    /// Selected `--meta-keys` values (exported as `meta.<key>` columns)
+    #[serde(rename = "meta", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta_fields: BTreeMap<String, String>,
+}
+
+impl From<&Event> for EventExport {
+    fn from(ev: &Event) -> Self {
+        Self {
+            id: ev.id,
+            date: ev.date.format("%Y-%m-%d").to_string(),
+            time: ev.time_str(),
+            datetime: None,
+            kind: ev.kind.to_db_str().to_string(),
+            position: ev.location.to_db_str().to_string(),
+            lunch_break: ev.lunch.unwrap_or(0),
+            lunch_hours: None,
+            pair: ev.pair,
+            unmatched: ev.unmatched,
+            source: ev.source.clone(),
+            host: ev.host.clone().unwrap_or_default(),
+            os_user: ev.os_user.clone().unwrap_or_default(),
+            work_gap: ev.work_gap,
+            notes: ev.notes.clone(),
+            created_at: ev.created_at.clone(),
+            pair_minutes: None,
+            pair_weighted_minutes: None,
+            day_total_minutes: None,
+            day_position: String::new(),
+            pair_hours: None,
+            day_total_hours: None,
+            pair_weighted_hours: None,
+            meta: ev.meta.clone(),
+            meta_fields: BTreeMap::new(),
+        }
+    }
+}
+
+/// Back to the stored event: only the raw fields are read, the derived ones
+/// are recomputed by whoever consumes the events.
+impl TryFrom<&EventExport> for Event {
+    type Error = AppError;
+
+    fn try_from(ev: &EventExport) -> Result<Self, Self::Error> {
+        let date = NaiveDate::parse_from_str(&ev.date, "%Y-%m-%d")
+            .map_err(|_| AppError::InvalidDate(ev.date.clone()))?;
+        let time = NaiveTime::parse_from_str(&ev.time, "%H:%M")
+            .map_err(|_| AppError::InvalidTime(ev.time.clone()))?;
+        let kind = EventType::from_db_str(&ev.kind)
+            .ok_or_else(|| AppError::InvalidEventType(ev.kind.clone()))?;
+        let location = Location::from_db_str(&ev.position)
+            .ok_or_else(|| AppError::InvalidPosition(ev.position.clone()))?;
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+        Ok(Event {
+            id: ev.id,
+            date,
+            time,
+            kind,
+            location,
+            lunch: Some(ev.lunch_break),
+            work_gap: ev.work_gap,
+            pair: ev.pair,
+            unmatched: ev.unmatched,
+            source: ev.source.clone(),
+            meta: ev.meta.clone(),
+            notes: ev.notes.clone(),
+            host: non_empty(&ev.host),
+            os_user: non_empty(&ev.os_user),
+            created_at: ev.created_at.clone(),
+        })
+    }
+}
+
+/// One work session (IN / OUT pair) of a day.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct PairExport {
+    pub date: String,
+    /// Pair number within the day
+    pub pair: i32,
+    pub start: String,
+    /// None while the pair is still open
+    pub end: Option<String>,
+    pub position: String,
+    pub lunch_minutes: i64,
+    /// Net minutes (lunch excluded), None while open
+    pub worked_minutes: Option<i64>,
+    /// The gap after this pair counts as work
+    pub work_gap: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+impl From<&Pair> for PairExport {
+    fn from(p: &Pair) -> Self {
+        let notes = match (
+            &p.in_event.notes,
+            p.out_event.as_ref().and_then(|o| o.notes.as_ref()),
+        ) {
+            (Some(n), _) if !n.trim().is_empty() => n.clone(),
+            (_, Some(n)) => n.clone(),
+            _ => p.notes.clone(),
+        };
+        Self {
+            date: p.in_event.date.format("%Y-%m-%d").to_string(),
+            pair: p.in_event.pair,
+            start: p.in_event.time_str(),
+            end: p.out_event.as_ref().map(Event::time_str),
+            position: p.position.to_db_str().to_string(),
+            lunch_minutes: p.lunch_minutes,
+            worked_minutes: p.out_event.as_ref().map(|_| p.duration_minutes),
+            work_gap: p.work_gap,
+            notes,
+        }
+    }
+}
+
+/// One day with its pairs and figures (minutes).
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct DayExport {
+    /// Empty for a day without events
+    pub date: String,
+    /// Position of the whole day (O, R, M, H, ...)
+    pub position: String,
+    /// Work + lunch required by the config
+    pub expected_minutes: i64,
+    pub worked_minutes: i64,
+    pub surplus_minutes: i64,
+    pub pairs: Vec<PairExport>,
+}
+
+impl From<&DaySummary> for DayExport {
+    fn from(s: &DaySummary) -> Self {
+        let timeline = &s.timeline;
+        Self {
+            date: timeline
+                .events
+                .first()
+                .map(|e| e.date.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            position: if timeline.pairs.is_empty() {
+                String::new()
+            } else {
+                get_day_position(timeline).to_db_str().to_string()
+            },
+            expected_minutes: s.expected,
+            worked_minutes: timeline.total_worked_minutes,
+            surplus_minutes: s.surplus,
+            pairs: timeline.pairs.iter().map(PairExport::from).collect(),
+        }
+    }
+}
+
+/// Optional columns selected from the CLI.
+#[derive(Debug, Clone, Default)]
+pub struct ExportColumns {
+    /// `--meta-keys`: exported as `meta.<key>`
+    pub meta_keys: Vec<String>,
+    /// `--decimal`: lunch as decimal hours, rounded to this step
+    pub decimal_step: Option<f64>,
+    /// `decimal_separator` of the config when it is not `.` (text outputs
+    /// only); set only with `--decimal`, the figures it applies to
+    pub decimal_separator: Option<char>,
+    /// `--iso-datetime`: one `datetime` column instead of `date` + `time`
+    pub iso_datetime: bool,
+    /// `--snapshot`: config snapshot also in CSV (comment lines) and JSON
+    pub snapshot: bool,
+    /// `shift_bands` of the config: adds the weighted pair column when set
+    pub shift_bands: ShiftBands,
+}
+
+/// Settings used to compute the exported figures, written with the data so
+/// that old exports stay readable after the policy changes.
+/// XLSX (`Settings` sheet) and PDF (footer) always carry it.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+pub struct ConfigSnapshot {
+    pub app_version: String,
+    pub min_work_duration: String,
+    pub lunch_window: String,
+    pub min_duration_lunch_break: i32,
+    pub max_duration_lunch_break: i32,
+    pub decimal_step: f64,
+}
+
+impl ConfigSnapshot {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            min_work_duration: cfg.min_work_duration.clone(),
+            lunch_window: cfg.lunch_window.clone(),
+            min_duration_lunch_break: cfg.min_duration_lunch_break,
+            max_duration_lunch_break: cfg.max_duration_lunch_break,
+            decimal_step: cfg.decimal_step,
+        }
+    }
+
+    /// `(key, value)` pairs, in a stable order.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("app_version", self.app_version.clone()),
+            ("min_work_duration", self.min_work_duration.clone()),
+            ("lunch_window", self.lunch_window.clone()),
+            (
+                "min_duration_lunch_break",
+                self.min_duration_lunch_break.to_string(),
+            ),
+            (
+                "max_duration_lunch_break",
+                self.max_duration_lunch_break.to_string(),
+            ),
+            ("decimal_step", self.decimal_step.to_string()),
+        ]
+    }
+
+    /// One-line summary for the PDF footer.
+    pub fn summary(&self) -> String {
+        format!(
+            "rTimelogger {} - min work {} - lunch {} ({}-{} min)",
+            self.app_version,
+            self.min_work_duration,
+            self.lunch_window,
+            self.min_duration_lunch_break,
+            self.max_duration_lunch_break
+        )
+    }
 }
 
 /// Header per CSV / JSON / XLSX / PDF
-pub(crate) fn get_headers() -> Vec<&'static str> {
-    vec![
-        "id",
-        "date",
-        "time",
+pub(crate) fn get_headers(columns: &ExportColumns) -> Vec<String> {
+    let decimal = columns.decimal_step.is_some();
+    let mut headers: Vec<&str> = vec!["id"];
+    if columns.iso_datetime {
+        headers.push("datetime");
+    } else {
+        headers.extend(["date", "time"]);
+    }
+    headers.extend([
         "kind",
         "position",
-        "lunch_break",
+        if decimal {
+            "lunch_hours"
+        } else {
+            "lunch_break"
+        },
         "pair",
+        "unmatched",
         "source",
-    ]
+        "host",
+        "os_user",
+        if decimal {
+            "pair_hours"
+        } else {
+            "pair_minutes"
+        },
+        if decimal {
+            "day_total_hours"
+        } else {
+            "day_total_minutes"
+        },
+        "day_position",
+    ]);
+    if !columns.shift_bands.is_empty() {
+        headers.push(if decimal {
+            "pair_weighted_hours"
+        } else {
+            "pair_weighted_minutes"
+        });
+    }
+
+    let mut headers: Vec<String> = headers.into_iter().map(String::from).collect();
+    headers.extend(columns.meta_keys.iter().map(|k| format!("meta.{}", k)));
+    headers
 }
 
 /// Convert events in una tabella di stringhe (per PDF).
-pub(crate) fn event_to_row(e: &EventExport) -> Vec<String> {
-    vec![
-        e.id.to_string(),
-        e.date.clone(),
-        e.time.clone(),
+pub(crate) fn event_to_row(e: &EventExport, columns: &ExportColumns) -> Vec<String> {
+    let minutes = |m: i64| match columns.decimal_step {
+        Some(step) => match columns.decimal_separator {
+            Some(sep) => mins2decimal(m, step, false).replace('.', &sep.to_string()),
+            None => mins2decimal(m, step, false),
+        },
+        None => m.to_string(),
+    };
+
+    let mut row = vec![e.id.to_string()];
+    if columns.iso_datetime {
+        row.push(e.datetime.clone().unwrap_or_default());
+    } else {
+        row.extend([e.date.clone(), e.time.clone()]);
+    }
+    row.extend([
         e.kind.clone(),
         e.position.clone(),
-        e.lunch_break.to_string(),
+        minutes(e.lunch_break as i64),
         e.pair.to_string(),
+        if e.unmatched { "1" } else { "0" }.to_string(),
         e.source.clone(),
-    ]
+        e.host.clone(),
+        e.os_user.clone(),
+        e.pair_minutes.map(minutes).unwrap_or_default(),
+        e.day_total_minutes.map(minutes).unwrap_or_default(),
+        e.day_position.clone(),
+    ]);
+    if !columns.shift_bands.is_empty() {
+        row.push(e.pair_weighted_minutes.map(minutes).unwrap_or_default());
+    }
+
+    row.extend(
+        columns
+            .meta_keys
+            .iter()
+            .map(|k| e.meta_fields.get(k).cloned().unwrap_or_default()),
+    );
+    row
 }
 
-pub(crate) fn events_to_table(events: &[EventExport]) -> Vec<Vec<String>> {
-    events.iter().map(event_to_row).collect()
+pub(crate) fn events_to_table(events: &[EventExport], columns: &ExportColumns) -> Vec<Vec<String>> {
+    events.iter().map(|e| event_to_row(e, columns)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::logic::Core;
+    use crate::models::test_support;
+
+    fn ev(id: i32, t: &str, kind: EventType) -> Event {
+        Event {
+            location: Location::Remote,
+            lunch: Some(30),
+            host: Some("laptop".into()),
+            pair: 1,
+            ..test_support::ev(id, t, kind)
+        }
+    }
+
+    #[test]
+    fn conversions_from_core_models() {
+        let events = vec![
+            ev(1, "08:30", EventType::In),
+            ev(2, "17:00", EventType::Out),
+        ];
+
+        let row = EventExport::from(&events[1]);
+        assert_eq!(
+            (row.date.as_str(), row.time.as_str()),
+            ("2025-06-18", "17:00")
+        );
+        assert_eq!((row.kind.as_str(), row.position.as_str()), ("out", "R"));
+        assert_eq!((row.lunch_break, row.host.as_str()), (30, "laptop"));
+
+        // CSV keeps the historical 1 / 0 encoding of `unmatched`
+        let columns = ExportColumns::default();
+        let unmatched = get_headers(&columns)
+            .iter()
+            .position(|h| h == "unmatched")
+            .unwrap();
+        assert_eq!(event_to_row(&row, &columns)[unmatched], "0");
+
+        let summary = Core::build_daily_summary(&events, &Config::default());
+        let day = DayExport::from(&summary);
+        assert_eq!(day.date, "2025-06-18");
+        assert_eq!(day.position, "R");
+        assert_eq!(day.worked_minutes, 480);
+        assert_eq!(day.pairs[0].end.as_deref(), Some("17:00"));
+        assert_eq!(day.pairs[0].worked_minutes, Some(480));
+    }
 }
@@ -21,6 +21,9 @@ pub struct PdfManager {
     font_size: f32,
     header_font_size: f32,
     title_font_size: f32,
+
+    /// Testo a sinistra nel footer di ogni pagina (es. snapshot config)
+    footer_note: Option<String>,
 }
 
 impl Default for PdfManager {
@@ -60,9 +63,16 @@ impl PdfManager {
             font_size: 10.0,
             header_font_size: 11.0,
             title_font_size: 14.0,
+
+            footer_note: None,
         }
     }
 
+    /// Imposta la nota mostrata nel footer di ogni pagina
+    pub fn set_footer_note(&mut self, note: &str) {
+        self.footer_note = Some(note.to_string());
+    }
+
     /// Genera un nuovo Ref univoco
     fn fresh_ref(&mut self) -> Ref {
         let id = self.next_id;
@@ -172,6 +182,11 @@ impl PdfManager {
             title,
         );
 
+        // Nota (snapshot config)
+        if let Some(note) = &self.footer_note {
+            self.draw_text(content, self.margin, self.margin - 35.0, 8.0, note);
+        }
+
         // Numero pagina
         let pg = format!("Page {}", page);
         self.draw_text(
@@ -185,46 +200,40 @@ impl PdfManager {
 
     /// Tabella multipagina con titolo
     pub fn write_table(&mut self, title: &str, headers: &[&str], rows: &[Vec<String>]) {
-        // Se non ci sono righe, evita PDF vuoto: una pagina con solo header
-        if rows.is_empty() {
-            let col_widths = self.compute_col_widths(headers, &[]);
-            let header_row: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
-
-            let mut content = self.new_page();
-            self.draw_page_header_footer(&mut content, title, 1);
-
-            let y = self.page_h - self.margin - 30.0;
-
-            content.save_state();
-            content.set_fill_rgb(0.85, 0.87, 0.90);
-            content.rect(self.margin, y, col_widths.iter().sum(), self.row_h);
-            content.fill_nonzero();
-            content.restore_state();
-
-            self.draw_row(
-                &mut content,
-                y,
-                &col_widths,
-                self.margin,
-                &header_row,
-                self.header_font_size,
-            );
-
-            self.finalize_page(content);
-            return;
-        }
+        self.write_document(title, &[], headers, rows, &[]);
+    }
 
+    /// Documento multipagina: righe di testo sotto il titolo (solo prima
+    /// pagina), tabella, righe di chiusura dopo l'ultima riga della tabella.
+    pub fn write_document(
+        &mut self,
+        title: &str,
+        preamble: &[String],
+        headers: &[&str],
+        rows: &[Vec<String>],
+        closing: &[String],
+    ) {
         let col_widths = self.compute_col_widths(headers, rows);
         let header_row: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
+        let line_h = self.font_size + 4.0;
+        let top = self.page_h - self.margin - 30.0;
 
         let mut remaining: &[Vec<String>] = rows;
         let mut page_idx = 1;
 
-        while !remaining.is_empty() {
+        loop {
             let mut content = self.new_page();
             self.draw_page_header_footer(&mut content, title, page_idx);
 
-            let mut y = self.page_h - self.margin - 30.0;
+            let mut y = top;
+
+            if page_idx == 1 && !preamble.is_empty() {
+                for line in preamble {
+                    self.draw_text(&mut content, self.margin, y + 5.0, self.font_size, line);
+                    y -= line_h;
+                }
+                y -= line_h;
+            }
 
             // header tabella
             content.save_state();
@@ -273,9 +282,32 @@ impl PdfManager {
                 consumed += 1;
             }
 
-            self.finalize_page(content);
             remaining = &remaining[consumed..];
-            page_idx += 1;
+            if !remaining.is_empty() {
+                self.finalize_page(content);
+                page_idx += 1;
+                continue;
+            }
+
+            if !closing.is_empty() {
+                // Le righe di chiusura restano insieme: se non ci stanno, nuova pagina
+                if y - line_h * (closing.len() as f32 + 1.0) < self.margin {
+                    self.finalize_page(content);
+                    page_idx += 1;
+                    content = self.new_page();
+                    self.draw_page_header_footer(&mut content, title, page_idx);
+                    y = top + self.row_h;
+                }
+
+                y -= line_h;
+                for line in closing {
+                    self.draw_text(&mut content, self.margin, y + 5.0, self.font_size, line);
+                    y -= line_h;
+                }
+            }
+
+            self.finalize_page(content);
+            break;
         }
     }
 
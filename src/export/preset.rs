@@ -0,0 +1,718 @@
+// src/export/preset.rs
+
+//! Export presets: fixed column layouts expected by third-party import tools
+//! (e.g. SAP CATS, Clockify). A preset produces one CSV row per day, or one
+//! per IN/OUT pair with `rows: pair`.
+//!
+//! Built-in presets can be overridden (or new ones added) by dropping a YAML
+//! file in `<config dir>/presets/<name>.yaml`. With `--format pdf` the same
+//! rows are written as a PDF table (e.g. the `client-visits` expense annex).
+//!
+//! The `balance` and `toil` columns make a time-bank statement (`timebank`):
+//! the running balance starts from the bank carried over from the days
+//! before the period and drops by the TOIL accrued (see `core::toil`).
+
+use crate::config::Config;
+use crate::core::invoice::{PROJECT_KEY, pair_meta};
+use crate::core::logic::Core;
+use crate::core::report::ReportLogic;
+use crate::db::pool::DbPool;
+use crate::db::queries::events::date_bounds;
+use crate::db::queries::load_events_by_date;
+use crate::db::queries::toil::{ToilEntry, ToilKind, load_toil};
+use crate::errors::{AppError, AppResult};
+use crate::export::notify_export_success;
+use crate::export::pdf::PdfManager;
+use crate::models::location::Location;
+use crate::ui::messages::info;
+use crate::utils::date::get_day_position;
+use crate::utils::formatting::mins2decimal;
+use crate::utils::period::Period;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{NaiveDate, NaiveTime};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Metadata key holding the description of a pair.
+pub const DESCRIPTION_KEY: &str = "description";
+
+/// Metadata key holding the customer of a pair (falls back to `project`).
+pub const CUSTOMER_KEY: &str = "customer";
+
+/// Built-in presets: (name, YAML definition).
+const BUILTIN_PRESETS: &[(&str, &str)] = &[
+    ("sap-cats", SAP_CATS_YAML),
+    ("clockify", CLOCKIFY_YAML),
+    ("client-visits", CLIENT_VISITS_YAML),
+    ("timebank", TIMEBANK_YAML),
+];
+
+/// SAP CATS upload layout. `personnel_no` and the attendance types are
+/// company-specific: copy this to `presets/sap-cats.yaml` and adjust them.
+const SAP_CATS_YAML: &str = r#"
+name: sap-cats
+delimiter: ";"
+date_format: "%d.%m.%Y"
+decimal_separator: ","
+personnel_no: "00000000"
+attendance_types:
+  O: "0800"
+  R: "0810"
+  C: "0820"
+  M: "0800"
+columns:
+  - header: PERNR
+    field: personnel_no
+  - header: WORKDATE
+    field: date
+  - header: CATSHOURS
+    field: hours
+  - header: AWART
+    field: attendance_type
+"#;
+
+/// Clockify "Import time entries" layout: one entry per pair, the project and
+/// the description come from the pair metadata (`project`, `description`).
+const CLOCKIFY_YAML: &str = r#"
+name: clockify
+rows: pair
+date_format: "%Y-%m-%d"
+time_format: "%H:%M:%S"
+columns:
+  - header: Project
+    field: project
+  - header: Description
+    field: description
+  - header: Start Date
+    field: date
+  - header: Start Time
+    field: start_time
+  - header: End Date
+    field: date
+  - header: End Time
+    field: end_time
+  - header: Duration (h)
+    field: duration
+"#;
+
+/// Client-site visits (position C) for expense reports, usually written as
+/// a PDF annex (`--format pdf`).
+const CLIENT_VISITS_YAML: &str = r#"
+name: client-visits
+title: Client-site visits
+rows: pair
+attendance_types:
+  C: ""
+columns:
+  - header: Date
+    field: date
+  - header: Customer
+    field: customer
+  - header: Start
+    field: start_time
+  - header: End
+    field: end_time
+  - header: Hours
+    field: hours
+"#;
+
+/// Time-bank statement for flex-time reconciliation: one row per day with
+/// expected, worked and ΔWORK hours and the running balance (marker days
+/// count as a full day worked, so their delta is 0).
+const TIMEBANK_YAML: &str = r#"
+name: timebank
+title: Time-bank statement
+columns:
+  - header: Date
+    field: date
+  - header: Position
+    field: position
+  - header: Expected
+    field: expected
+  - header: Worked
+    field: hours
+  - header: Delta
+    field: delta
+  - header: TOIL
+    field: toil
+  - header: Balance
+    field: balance
+"#;
+
+/// Granularity of the exported rows.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PresetRows {
+    /// One row per day (marker days count as a full contractual day)
+    #[default]
+    Day,
+    /// One row per closed IN/OUT pair; marker days are not exported
+    Pair,
+}
+
+/// Value written in a preset column.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PresetField {
+    PersonnelNo,
+    Date,
+    /// Worked hours as a decimal number
+    Hours,
+    /// Mapped from the day position through `attendance_types`
+    AttendanceType,
+    /// Position code (O, R, C, M, ...)
+    Position,
+    /// Fixed text taken from the column `value`
+    Constant,
+    /// IN time (first IN of the day in `day` rows)
+    StartTime,
+    /// OUT time (last OUT of the day in `day` rows)
+    EndTime,
+    /// Worked time as `HH:MM:SS`
+    Duration,
+    /// `project` metadata of the pair (distinct values joined in `day` rows)
+    Project,
+    /// `description` metadata of the pair (distinct values joined in `day` rows)
+    Description,
+    /// `customer` metadata of the pair, else its `project`
+    Customer,
+    /// Expected hours of the day (`day` rows)
+    Expected,
+    /// Worked - expected hours, signed (`day` rows)
+    Delta,
+    /// TOIL accrued since the previous row, in hours
+    Toil,
+    /// Time bank after the row: carried-over balance + deltas - TOIL accrued
+    Balance,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PresetColumn {
+    pub header: String,
+    pub field: PresetField,
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportPreset {
+    pub name: String,
+    /// Title of the PDF table (default: the name)
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub rows: PresetRows,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: String,
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    #[serde(default = "default_time_format")]
+    pub time_format: String,
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator: String,
+    #[serde(default)]
+    pub personnel_no: String,
+    /// Position code → attendance type. Days whose position is not mapped
+    /// (e.g. holidays, unless `H`/`N`/`S` are listed) are not exported; an
+    /// empty map exports every position.
+    #[serde(default)]
+    pub attendance_types: BTreeMap<String, String>,
+    pub columns: Vec<PresetColumn>,
+}
+
+/// Values of one exported day or pair.
+struct PresetRow {
+    date: NaiveDate,
+    start: Option<NaiveTime>,
+    end: Option<NaiveTime>,
+    minutes: i64,
+    position: Location,
+    attendance: String,
+    project: String,
+    description: String,
+    customer: String,
+    expected: i64,
+    delta: i64,
+    toil: i64,
+    balance: i64,
+}
+
+fn default_delimiter() -> String {
+    ",".to_string()
+}
+fn default_date_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+fn default_time_format() -> String {
+    "%H:%M".to_string()
+}
+fn default_decimal_separator() -> String {
+    ".".to_string()
+}
+
+/// Directory holding user-defined presets.
+pub fn presets_dir() -> PathBuf {
+    Config::config_dir().join("presets")
+}
+
+impl ExportPreset {
+    /// Resolve a preset by name: user file first, then built-ins.
+    pub fn load(name: &str) -> AppResult<Self> {
+        let user_file = presets_dir().join(format!("{}.yaml", name));
+
+        let (yaml, origin) = if user_file.exists() {
+            (
+                fs::read_to_string(&user_file)?,
+                user_file.display().to_string(),
+            )
+        } else if let Some((_, y)) = BUILTIN_PRESETS.iter().find(|(n, _)| *n == name) {
+            (y.to_string(), "built-in".to_string())
+        } else {
+            return Err(AppError::InvalidArgs(format!(
+                "Unknown export preset '{}'. Available presets: {}.",
+                name,
+                Self::available().join(", ")
+            )));
+        };
+
+        Self::parse(name, &origin, &yaml)
+    }
+
+    /// Parse and check a preset: formatting with a bad strftime spec would
+    /// panic later, so `date_format` / `time_format` are checked here.
+    fn parse(name: &str, origin: &str, yaml: &str) -> AppResult<Self> {
+        let preset: ExportPreset = serde_yaml::from_str(yaml).map_err(|e| {
+            AppError::InvalidArgs(format!("Invalid preset '{}' ({}): {}", name, origin, e))
+        })?;
+
+        if preset.columns.is_empty() {
+            return Err(AppError::InvalidArgs(format!(
+                "Preset '{}' ({}) defines no columns.",
+                name, origin
+            )));
+        }
+
+        for (key, spec) in [
+            ("date_format", &preset.date_format),
+            ("time_format", &preset.time_format),
+        ] {
+            if StrftimeItems::new(spec).any(|i| i == Item::Error) {
+                return Err(AppError::InvalidArgs(format!(
+                    "Preset '{}' ({}): invalid {} '{}' (see strftime, e.g. %Y-%m-%d, %H:%M).",
+                    name, origin, key, spec
+                )));
+            }
+        }
+
+        Ok(preset)
+    }
+
+    /// Names of built-in and user presets (sorted, deduplicated).
+    pub fn available() -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_PRESETS.iter().map(|(n, _)| n.to_string()).collect();
+
+        if let Ok(entries) = fs::read_dir(presets_dir()) {
+            for e in entries.flatten() {
+                let path = e.path();
+                if path.extension().is_some_and(|x| x == "yaml")
+                    && let Some(stem) = path.file_stem()
+                {
+                    names.push(stem.to_string_lossy().to_string());
+                }
+            }
+        }
+
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Decimal hours rounded to `decimal_step`, with the preset separator.
+    fn format_hours(&self, minutes: i64, step: f64) -> String {
+        mins2decimal(minutes, step, false).replace('.', &self.decimal_separator)
+    }
+
+    /// True when the rows carry the running time bank.
+    fn tracks_balance(&self) -> bool {
+        self.columns
+            .iter()
+            .any(|c| matches!(c.field, PresetField::Balance | PresetField::Toil))
+    }
+
+    /// Signed decimal hours (`+1.25`, `-0.50`), with the preset separator.
+    fn format_signed_hours(&self, minutes: i64, step: f64) -> String {
+        mins2decimal(minutes, step, true).replace('.', &self.decimal_separator)
+    }
+
+    /// Attendance type of a position; None when the preset does not export it.
+    fn attendance(&self, position: Location) -> Option<String> {
+        if self.attendance_types.is_empty() {
+            return Some(String::new());
+        }
+        self.attendance_types.get(position.to_db_str()).cloned()
+    }
+
+    /// Build the CSV rows (header excluded) for the given dates; `opening`
+    /// is the time bank before the first date, `toil` the TOIL ledger.
+    /// Returns the rows and the closing balance.
+    fn rows(
+        &self,
+        pool: &mut DbPool,
+        cfg: &Config,
+        dates: &[NaiveDate],
+        opening: i64,
+        toil: &[ToilEntry],
+    ) -> AppResult<(Vec<Vec<String>>, i64)> {
+        let mut rows = Vec::new();
+        let mut balance = opening;
+        let mut accruals = toil
+            .iter()
+            .filter(|e| e.kind == ToilKind::Accrue)
+            .peekable();
+        // TOIL accrued up to `day` and not shown yet (entries on days
+        // without a row move to the next one)
+        let mut take_toil = |day: NaiveDate| {
+            let mut minutes = 0;
+            while let Some(e) = accruals.next_if(|e| e.date <= day) {
+                minutes += e.minutes;
+            }
+            minutes
+        };
+
+        for day in dates {
+            let events = load_events_by_date(pool, day)?;
+            if events.is_empty() {
+                continue;
+            }
+
+            let summary = Core::build_daily_summary(&events, cfg);
+            let pairs = &summary.timeline.pairs;
+            if pairs.is_empty() {
+                continue;
+            }
+
+            if self.rows == PresetRows::Pair {
+                if Core::is_marker_day(&summary) {
+                    continue;
+                }
+                for p in pairs {
+                    let (Some(out), Some(attendance)) = (&p.out_event, self.attendance(p.position))
+                    else {
+                        continue;
+                    };
+                    rows.push(self.render(
+                        cfg,
+                        &PresetRow {
+                            date: *day,
+                            start: Some(p.in_event.time),
+                            end: Some(out.time),
+                            minutes: p.duration_minutes.max(0),
+                            position: p.position,
+                            attendance,
+                            project: pair_meta(p, PROJECT_KEY),
+                            description: pair_meta(p, DESCRIPTION_KEY),
+                            customer: pair_meta(p, CUSTOMER_KEY),
+                            expected: 0,
+                            delta: 0,
+                            toil: 0,
+                            balance,
+                        },
+                    ));
+                }
+                continue;
+            }
+
+            let position = get_day_position(&summary.timeline);
+            let Some(attendance) = self.attendance(position) else {
+                continue;
+            };
+
+            // Marker days count as a full contractual day, working days as worked time
+            let (minutes, expected, delta) = if Core::is_marker_day(&summary) {
+                let day = Core::parse_work_duration_to_minutes(&cfg.min_work_duration);
+                (day, day, 0)
+            } else {
+                match Core::day_figures(&summary, &events, cfg) {
+                    Some(f) => match (f.worked, f.delta) {
+                        (Some(w), Some(d)) => (w.max(0), f.expected, d),
+                        _ => continue, // day still open
+                    },
+                    None => continue,
+                }
+            };
+            let toil = take_toil(*day);
+            balance += delta - toil;
+
+            let joined = |key: &str| {
+                let mut values: Vec<String> = Vec::new();
+                for v in pairs.iter().map(|p| pair_meta(p, key)) {
+                    if !v.is_empty() && !values.contains(&v) {
+                        values.push(v);
+                    }
+                }
+                values.join(" / ")
+            };
+
+            rows.push(
+                self.render(
+                    cfg,
+                    &PresetRow {
+                        date: *day,
+                        start: pairs.first().map(|p| p.in_event.time),
+                        end: pairs
+                            .last()
+                            .and_then(|p| p.out_event.as_ref())
+                            .map(|e| e.time),
+                        minutes,
+                        position,
+                        attendance,
+                        project: joined(PROJECT_KEY),
+                        description: joined(DESCRIPTION_KEY),
+                        customer: joined(CUSTOMER_KEY),
+                        expected,
+                        delta,
+                        toil,
+                        balance,
+                    },
+                ),
+            );
+        }
+
+        if let Some(last) = dates.last() {
+            balance -= take_toil(*last);
+        }
+        Ok((rows, balance))
+    }
+
+    /// One CSV record in the order of the preset columns.
+    fn render(&self, cfg: &Config, row: &PresetRow) -> Vec<String> {
+        let time = |t: Option<NaiveTime>| {
+            t.map(|t| t.format(&self.time_format).to_string())
+                .unwrap_or_default()
+        };
+
+        self.columns
+            .iter()
+            .map(|c| match c.field {
+                PresetField::PersonnelNo => self.personnel_no.clone(),
+                PresetField::Date => row.date.format(&self.date_format).to_string(),
+                PresetField::Hours => self.format_hours(row.minutes, cfg.decimal_step),
+                PresetField::AttendanceType => row.attendance.clone(),
+                PresetField::Position => row.position.to_db_str().to_string(),
+                PresetField::Constant => c.value.clone(),
+                PresetField::StartTime => time(row.start),
+                PresetField::EndTime => time(row.end),
+                PresetField::Duration => {
+                    format!("{:02}:{:02}:00", row.minutes / 60, row.minutes % 60)
+                }
+                PresetField::Project => row.project.clone(),
+                PresetField::Description => row.description.clone(),
+                PresetField::Customer if row.customer.is_empty() => row.project.clone(),
+                PresetField::Customer => row.customer.clone(),
+                PresetField::Expected => self.format_hours(row.expected, cfg.decimal_step),
+                PresetField::Delta => self.format_signed_hours(row.delta, cfg.decimal_step),
+                PresetField::Toil => self.format_hours(row.toil, cfg.decimal_step),
+                PresetField::Balance => self.format_signed_hours(row.balance, cfg.decimal_step),
+            })
+            .collect()
+    }
+
+    /// Write the preset CSV (or PDF table with `pdf`) for `period` to `path`.
+    /// Returns the number of rows.
+    pub fn write(
+        &self,
+        pool: &mut DbPool,
+        cfg: &Config,
+        period: &Period,
+        path: &Path,
+        pdf: bool,
+    ) -> AppResult<usize> {
+        info(format!(
+            "Exporting preset '{}': {}",
+            self.name,
+            path.display()
+        ));
+
+        let dates = match period.bounds() {
+            Some(_) => period.iter_dates(),
+            None => match date_bounds(&pool.conn)? {
+                Some((from, to)) => Period::between(from, to).iter_dates(),
+                None => Vec::new(),
+            },
+        };
+
+        let (opening, toil) = match (self.tracks_balance(), dates.first()) {
+            (true, Some(first)) => (
+                carried_over(pool, cfg, *first)?,
+                load_toil(&pool.conn, None)?
+                    .into_iter()
+                    .filter(|e| e.date >= *first)
+                    .collect(),
+            ),
+            _ => (0, Vec::new()),
+        };
+
+        let (rows, closing) = self.rows(pool, cfg, &dates, opening, &toil)?;
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        if pdf {
+            let bank = self.tracks_balance().then_some((opening, closing));
+            self.write_pdf(cfg, &rows, &dates, bank, path)?;
+            return Ok(rows.len());
+        }
+
+        let delimiter = self.delimiter.bytes().next().unwrap_or(b',');
+        let mut wtr = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_path(path)
+            .map_err(|e| AppError::export_io(path, e))?;
+
+        let write_err = |e: csv::Error| AppError::export_io(path, e);
+
+        wtr.write_record(self.columns.iter().map(|c| c.header.as_str()))
+            .map_err(write_err)?;
+        for r in &rows {
+            wtr.write_record(r).map_err(write_err)?;
+        }
+        wtr.flush().map_err(|e| AppError::export_io(path, e))?;
+
+        notify_export_success(&format!("Preset '{}'", self.name), path);
+        Ok(rows.len())
+    }
+
+    /// PDF table with the preset columns; the `hours` column is totalled.
+    /// `bank` is the (carried-over, closing) time bank of a statement.
+    fn write_pdf(
+        &self,
+        cfg: &Config,
+        rows: &[Vec<String>],
+        dates: &[NaiveDate],
+        bank: Option<(i64, i64)>,
+        path: &Path,
+    ) -> AppResult<()> {
+        let title = self.title.clone().unwrap_or_else(|| self.name.clone());
+        let mut preamble = match (dates.first(), dates.last()) {
+            (Some(from), Some(to)) => vec![format!("Period: {} - {}", from, to)],
+            _ => Vec::new(),
+        };
+        if let Some((opening, _)) = bank {
+            preamble.push(format!(
+                "Carried over: {}",
+                self.format_signed_hours(opening, cfg.decimal_step)
+            ));
+        }
+
+        let mut closing = vec![format!("Entries: {}", rows.len())];
+        if let Some(i) = self
+            .columns
+            .iter()
+            .position(|c| c.field == PresetField::Hours)
+        {
+            let total: f64 = rows
+                .iter()
+                .filter_map(|r| {
+                    r[i].replace(&self.decimal_separator, ".")
+                        .parse::<f64>()
+                        .ok()
+                })
+                .sum();
+            closing
+                .push(format!("Total hours: {:.2}", total).replace('.', &self.decimal_separator));
+        }
+        if let Some((_, balance)) = bank {
+            closing.push(format!(
+                "Closing balance: {}",
+                self.format_signed_hours(balance, cfg.decimal_step)
+            ));
+        }
+
+        let headers: Vec<&str> = self.columns.iter().map(|c| c.header.as_str()).collect();
+        let mut pdf = PdfManager::new();
+        pdf.set_footer_note(&title);
+        pdf.write_document(&title, &preamble, &headers, rows, &closing);
+        pdf.save(path).map_err(|e| AppError::export_io(path, e))?;
+
+        notify_export_success(&format!("Preset '{}' (PDF)", self.name), path);
+        Ok(())
+    }
+}
+
+/// Time bank before `from`: ΔWORK of the closed working days before it,
+/// minus the TOIL accrued before it.
+fn carried_over(pool: &mut DbPool, cfg: &Config, from: NaiveDate) -> AppResult<i64> {
+    let surplus = match (date_bounds(&pool.conn)?, from.pred_opt()) {
+        (Some((first, _)), Some(before)) if first <= before => {
+            ReportLogic::totals(pool, cfg, &Period::between(first, before).iter_dates())?.surplus()
+        }
+        _ => 0,
+    };
+    let accrued: i64 = load_toil(&pool.conn, None)?
+        .iter()
+        .filter(|e| e.kind == ToilKind::Accrue && e.date < from)
+        .map(|e| e.minutes)
+        .sum();
+    Ok(surplus - accrued)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_sap_cats_parses() {
+        let p: ExportPreset = serde_yaml::from_str(SAP_CATS_YAML).unwrap();
+        assert_eq!(p.columns.len(), 4);
+        assert_eq!(p.format_hours(450, 0.01), "7,50");
+        assert_eq!(p.format_hours(445, 0.25), "7,50");
+        assert_eq!(
+            p.attendance_types.get("R").map(String::as_str),
+            Some("0810")
+        );
+    }
+
+    #[test]
+    fn builtin_clockify_exports_every_pair() {
+        let p: ExportPreset = serde_yaml::from_str(CLOCKIFY_YAML).unwrap();
+        assert_eq!(p.rows, PresetRows::Pair);
+        assert_eq!(p.time_format, "%H:%M:%S");
+        assert_eq!(p.attendance(Location::Remote), Some(String::new()));
+    }
+
+    #[test]
+    fn builtin_client_visits_keeps_only_client_pairs() {
+        let p: ExportPreset = serde_yaml::from_str(CLIENT_VISITS_YAML).unwrap();
+        assert_eq!(p.rows, PresetRows::Pair);
+        assert_eq!(p.attendance(Location::OnSite), Some(String::new()));
+        assert_eq!(p.attendance(Location::Office), None);
+    }
+
+    #[test]
+    fn invalid_strftime_spec_is_rejected() {
+        let yaml = "name: mine\ndate_format: \"%Q\"\ncolumns:\n  - header: DAY\n    field: date\n";
+        let err = ExportPreset::parse("mine", "test", yaml).unwrap_err();
+        assert!(err.to_string().contains("'mine'"));
+        assert!(err.to_string().contains("date_format"));
+
+        let ok = "name: mine\ntime_format: \"%H.%M\"\ncolumns:\n  - header: DAY\n    field: date\n";
+        assert!(ExportPreset::parse("mine", "test", ok).is_ok());
+        for (name, yaml) in BUILTIN_PRESETS {
+            assert!(ExportPreset::parse(name, "built-in", yaml).is_ok());
+        }
+    }
+
+    #[test]
+    fn builtin_timebank_tracks_the_balance() {
+        let p: ExportPreset = serde_yaml::from_str(TIMEBANK_YAML).unwrap();
+        assert_eq!(p.rows, PresetRows::Day);
+        assert!(p.tracks_balance());
+        assert_eq!(p.format_signed_hours(-90, 0.01), "-1.50");
+        assert_eq!(p.format_signed_hours(75, 0.01), "+1.25");
+
+        let sap: ExportPreset = serde_yaml::from_str(SAP_CATS_YAML).unwrap();
+        assert!(!sap.tracks_balance());
+    }
+}
@@ -0,0 +1,68 @@
+//! JSON Schema of what rTimelogger writes (`rtimelogger schema`), generated
+//! from the same structs used by the exporters and the config loader.
+
+use crate::config::Config;
+use crate::export::{ConfigSnapshot, DayExport, EventExport, PairExport};
+use clap::ValueEnum;
+use schemars::{JsonSchema, schema_for};
+
+/// Documents `schema` can describe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SchemaTarget {
+    /// A JSON export file (`export --format json`, with or without `--snapshot`)
+    Export,
+    /// One exported event
+    Event,
+    /// One work session (IN / OUT pair)
+    Pair,
+    /// One day with its pairs and figures
+    Day,
+    /// The configuration file (`rtimelogger.toml`)
+    Config,
+}
+
+/// Output format of `schema`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SchemaFormat {
+    JsonSchema,
+}
+
+/// Shape of a JSON export: a plain array, or `{snapshot, events}` with `--snapshot`.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum JsonExport {
+    Events(Vec<EventExport>),
+    WithSnapshot {
+        snapshot: ConfigSnapshot,
+        events: Vec<EventExport>,
+    },
+}
+
+/// Pretty-printed JSON Schema of `target`.
+pub fn json_schema(target: SchemaTarget) -> String {
+    let schema = match target {
+        SchemaTarget::Export => schema_for!(JsonExport),
+        SchemaTarget::Event => schema_for!(EventExport),
+        SchemaTarget::Pair => schema_for!(PairExport),
+        SchemaTarget::Day => schema_for!(DayExport),
+        SchemaTarget::Config => schema_for!(Config),
+    };
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_schema_lists_exported_fields_only() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&json_schema(SchemaTarget::Event)).unwrap();
+        let props = schema["properties"].as_object().unwrap();
+
+        assert!(props.contains_key("pair_minutes"));
+        assert!(props.contains_key("meta"));
+        assert!(!props.contains_key("meta_fields"));
+    }
+}
@@ -0,0 +1,265 @@
+// src/export/weekly_matrix.rs
+
+//! `export --format xlsx --layout weekly-matrix`: one sheet per month, one
+//! block per week with the days (Mon..Sun) as columns and Start / End /
+//! Lunch / Worked / Surplus as rows, plus a weekly total column.
+
+use crate::config::Config;
+use crate::core::logic::Core;
+use crate::db::pool::DbPool;
+use crate::db::queries::load_events_by_date;
+use crate::errors::{AppError, AppResult};
+use crate::export::model::ConfigSnapshot;
+use crate::export::notify_export_success;
+use crate::export::xlsx::{path_str, write_settings_sheet, xlsx_err};
+use crate::ui::messages::info;
+use crate::utils::date::get_day_position;
+use crate::utils::formatting::{format_surplus, mins2readable};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+use rust_xlsxwriter::{
+    Color, Format, FormatAlign, FormatBorder, FormatPattern, Workbook, Worksheet,
+};
+use std::io;
+use std::path::Path;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const ROWS: [&str; 5] = ["Start", "End", "Lunch", "Worked", "Surplus"];
+
+/// Figures of one day of the matrix.
+#[derive(Debug, Default)]
+struct MatrixDay {
+    start: Option<NaiveTime>,
+    end: Option<NaiveTime>,
+    lunch: i64,
+    worked: Option<i64>,
+    surplus: Option<i64>,
+    /// Holiday / sick leave label, shown instead of the times
+    marker: Option<&'static str>,
+}
+
+impl MatrixDay {
+    fn load(pool: &mut DbPool, cfg: &Config, date: NaiveDate) -> AppResult<Option<Self>> {
+        let events = load_events_by_date(pool, &date)?;
+        if events.is_empty() {
+            return Ok(None);
+        }
+
+        let summary = Core::build_daily_summary(&events, cfg);
+        if Core::is_marker_day(&summary) {
+            return Ok(Some(Self {
+                marker: Some(get_day_position(&summary.timeline).label()),
+                ..Default::default()
+            }));
+        }
+
+        Ok(Core::day_figures(&summary, &events, cfg).map(|f| Self {
+            start: Some(f.first_in.time()),
+            end: f.last_out.map(|t| t.time()),
+            lunch: f.lunch_total,
+            worked: f.worked,
+            surplus: f.delta,
+            marker: None,
+        }))
+    }
+
+    /// Text of the cell at matrix row `row` (index in [`ROWS`]).
+    fn cell(&self, row: usize, cfg: &Config) -> String {
+        if let Some(label) = self.marker {
+            return if row == 0 {
+                label.to_string()
+            } else {
+                String::new()
+            };
+        }
+        let time = |t: Option<NaiveTime>| t.map(|t| t.format("%H:%M").to_string());
+        match row {
+            0 => time(self.start),
+            1 => time(self.end),
+            2 => Some(mins2readable(self.lunch, false, true)),
+            3 => self.worked.map(|w| mins2readable(w, false, true)),
+            _ => self.surplus.map(|s| format_surplus(s, cfg)),
+        }
+        .unwrap_or_default()
+    }
+}
+
+/// Write the matrix of the given months. Returns false (writing nothing)
+/// when no day of the months has events.
+pub(crate) fn export_weekly_matrix(
+    pool: &mut DbPool,
+    cfg: &Config,
+    months: &[(NaiveDate, NaiveDate)],
+    path: &Path,
+) -> AppResult<bool> {
+    let mut loaded = Vec::new();
+    for (first, last) in months {
+        let mut days = Vec::new();
+        for week in weeks_of(*first, *last) {
+            let mut row = Vec::new();
+            for date in week {
+                let day = if date < *first || date > *last {
+                    None
+                } else {
+                    MatrixDay::load(pool, cfg, date)?
+                };
+                row.push((date, day));
+            }
+            days.push(row);
+        }
+        if days.iter().flatten().any(|(_, d)| d.is_some()) {
+            loaded.push(((*first, *last), days));
+        }
+    }
+
+    if loaded.is_empty() {
+        return Ok(false);
+    }
+
+    info(format!(
+        "Exporting weekly matrix to XLSX: {}",
+        path.display()
+    ));
+
+    let mut workbook = Workbook::new();
+    for ((first, last), weeks) in &loaded {
+        let sheet = workbook.add_worksheet();
+        sheet
+            .set_name(first.format("%Y-%m").to_string())
+            .map_err(xlsx_err)?;
+        write_month(sheet, cfg, (*first, *last), weeks)?;
+    }
+
+    write_settings_sheet(&mut workbook, &ConfigSnapshot::from_config(cfg))?;
+    workbook
+        .save(path_str(path)?)
+        .map_err(|e| AppError::export_io(path, io::Error::other(e)))?;
+
+    notify_export_success("XLSX weekly matrix", path);
+    Ok(true)
+}
+
+/// Blocks of one month: header row, the five metric rows, a blank line.
+/// Days outside `[first, last]` are greyed out.
+fn write_month(
+    sheet: &mut Worksheet,
+    cfg: &Config,
+    (first, last): (NaiveDate, NaiveDate),
+    weeks: &[Vec<(NaiveDate, Option<MatrixDay>)>],
+) -> AppResult<()> {
+    let header = Format::new()
+        .set_bold()
+        .set_font_color(Color::RGB(0xFFFFFF))
+        .set_background_color(Color::RGB(0x2F75B5))
+        .set_pattern(FormatPattern::Solid)
+        .set_border(FormatBorder::Thin)
+        .set_align(FormatAlign::Center);
+    let label = Format::new()
+        .set_bold()
+        .set_background_color(Color::RGB(0xEAF3FB))
+        .set_pattern(FormatPattern::Solid)
+        .set_border(FormatBorder::Thin);
+    let cell = Format::new()
+        .set_border(FormatBorder::Thin)
+        .set_align(FormatAlign::Right);
+    let outside = Format::new()
+        .set_border(FormatBorder::Thin)
+        .set_background_color(Color::RGB(0xF2F2F2))
+        .set_pattern(FormatPattern::Solid);
+    let total = cell.clone().set_bold();
+
+    let mut row = 0u32;
+    for week in weeks {
+        let Some((monday, _)) = week.first() else {
+            continue;
+        };
+
+        sheet
+            .write_with_format(
+                row,
+                0,
+                format!("Week {}", monday.iso_week().week()),
+                &header,
+            )
+            .map_err(xlsx_err)?;
+        for (col, (date, _)) in week.iter().enumerate() {
+            let title = format!("{} {}", WEEKDAYS[col], date.format("%d.%m"));
+            sheet
+                .write_with_format(row, col as u16 + 1, title, &header)
+                .map_err(xlsx_err)?;
+        }
+        sheet
+            .write_with_format(row, 8, "Total", &header)
+            .map_err(xlsx_err)?;
+
+        for (i, name) in ROWS.iter().enumerate() {
+            let r = row + 1 + i as u32;
+            sheet
+                .write_with_format(r, 0, *name, &label)
+                .map_err(xlsx_err)?;
+
+            for (col, (date, day)) in week.iter().enumerate() {
+                let col = col as u16 + 1;
+                if *date < first || *date > last {
+                    sheet.write_blank(r, col, &outside).map_err(xlsx_err)?;
+                } else {
+                    let text = day.as_ref().map(|d| d.cell(i, cfg)).unwrap_or_default();
+                    sheet
+                        .write_with_format(r, col, text, &cell)
+                        .map_err(xlsx_err)?;
+                }
+            }
+
+            let days = week.iter().filter_map(|(_, d)| d.as_ref());
+            let sum = match i {
+                2 => Some(mins2readable(days.map(|d| d.lunch).sum(), false, true)),
+                3 => Some(mins2readable(
+                    days.filter_map(|d| d.worked).sum(),
+                    false,
+                    true,
+                )),
+                4 => Some(format_surplus(days.filter_map(|d| d.surplus).sum(), cfg)),
+                _ => None,
+            };
+            sheet
+                .write_with_format(r, 8, sum.unwrap_or_default(), &total)
+                .map_err(xlsx_err)?;
+        }
+
+        row += ROWS.len() as u32 + 2;
+    }
+
+    sheet.set_column_width(0, 10.0).map_err(xlsx_err)?;
+    for col in 1..=8 {
+        sheet.set_column_width(col, 12.0).map_err(xlsx_err)?;
+    }
+    Ok(())
+}
+
+/// Monday-based weeks touching `[first, last]`, seven dates each.
+fn weeks_of(first: NaiveDate, last: NaiveDate) -> Vec<[NaiveDate; 7]> {
+    let mut out = Vec::new();
+    let mut monday = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+
+    while monday <= last {
+        out.push(std::array::from_fn(|i| monday + Duration::days(i as i64)));
+        monday += Duration::days(7);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weeks_cover_the_whole_month() {
+        let d = |m, day| NaiveDate::from_ymd_opt(2025, m, day).unwrap();
+
+        // June 2025: Sun 1st .. Mon 30th → six blocks
+        let weeks = weeks_of(d(6, 1), d(6, 30));
+        assert_eq!(weeks.len(), 6);
+        assert_eq!(weeks[0][0], d(5, 26));
+        assert_eq!(weeks[0][6], d(6, 1));
+        assert_eq!(weeks[5][0], d(6, 30));
+    }
+}
@@ -2,7 +2,8 @@
 
 use crate::errors::{AppError, AppResult};
 use crate::export::excel_date::parse_to_excel_date;
-use crate::export::model::{event_to_row, get_headers};
+use crate::export::hour_bank::{self, BankDay};
+use crate::export::model::{ConfigSnapshot, ExportColumns, event_to_row, get_headers};
 use crate::export::{EventExport, notify_export_success};
 use crate::ui::messages::info;
 use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, FormatPattern, Workbook};
@@ -11,9 +12,23 @@ use std::path::Path;
 use unicode_width::UnicodeWidthStr;
 
 /// Export XLSX con styling e auto-larghezza colonne.
-pub(crate) fn export_xlsx(events: &[EventExport], path: &Path) -> AppResult<()> {
+/// Il foglio `Hour bank` riassume i giorni chiusi (`bank`), il foglio
+/// `Settings` riporta la configurazione usata per i calcoli.
+pub(crate) fn export_xlsx(
+    events: &[EventExport],
+    columns: &ExportColumns,
+    snapshot: &ConfigSnapshot,
+    bank: &[BankDay],
+    path: &Path,
+) -> AppResult<()> {
     info(format!("Exporting to XLSX: {}", path.display()));
 
+    // Le celle sono numeri veri: il separatore lo sceglie Excel dal locale
+    let columns = &ExportColumns {
+        decimal_separator: None,
+        ..columns.clone()
+    };
+
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet();
 
@@ -23,8 +38,11 @@ pub(crate) fn export_xlsx(events: &[EventExport], path: &Path) -> AppResult<()>
     if events.is_empty() {
         worksheet
             .write(0, 0, "No data available")
-            .map_err(to_io_app_error)?;
-        workbook.save(path_str(path)?).map_err(to_io_app_error)?;
+            .map_err(xlsx_err)?;
+        write_settings_sheet(&mut workbook, snapshot)?;
+        workbook
+            .save(path_str(path)?)
+            .map_err(|e| AppError::export_io(path, io::Error::other(e)))?;
         notify_export_success("XLSX (empty dataset)", path);
         return Ok(());
     }
@@ -32,7 +50,7 @@ pub(crate) fn export_xlsx(events: &[EventExport], path: &Path) -> AppResult<()>
     // ---------------------------
     // Header
     // ---------------------------
-    let headers = get_headers();
+    let headers = get_headers(columns);
 
     let header_format = Format::new()
         .set_bold()
@@ -43,8 +61,8 @@ pub(crate) fn export_xlsx(events: &[EventExport], path: &Path) -> AppResult<()>
 
     for (col, header) in headers.iter().enumerate() {
         worksheet
-            .write_with_format(0, col as u16, *header, &header_format)
-            .map_err(to_io_app_error)?;
+            .write_with_format(0, col as u16, header.as_str(), &header_format)
+            .map_err(xlsx_err)?;
     }
 
     worksheet.set_freeze_panes(1, 0).ok();
@@ -52,7 +70,10 @@ pub(crate) fn export_xlsx(events: &[EventExport], path: &Path) -> AppResult<()>
     // ---------------------------
     // Calcolo larghezze colonne
     // ---------------------------
-    let mut col_widths: Vec<usize> = headers.iter().map(|h| UnicodeWidthStr::width(*h)).collect();
+    let mut col_widths: Vec<usize> = headers
+        .iter()
+        .map(|h| UnicodeWidthStr::width(h.as_str()))
+        .collect();
 
     let band1 = Color::RGB(0xEAF3FB);
     let band2 = Color::RGB(0xFFFFFF);
@@ -66,7 +87,7 @@ pub(crate) fn export_xlsx(events: &[EventExport], path: &Path) -> AppResult<()>
         let band_color = if row_index % 2 == 0 { band1 } else { band2 };
 
         // campi in ordine
-        let values = event_to_row(ev);
+        let values = event_to_row(ev, columns);
 
         for (col, value) in values.iter().enumerate() {
             let v = value.as_str();
@@ -83,15 +104,47 @@ pub(crate) fn export_xlsx(events: &[EventExport], path: &Path) -> AppResult<()>
     for (c, w) in col_widths.iter().enumerate() {
         worksheet
             .set_column_width(c as u16, *w as f64 + 2.0)
-            .map_err(to_io_app_error)?;
+            .map_err(xlsx_err)?;
     }
 
-    workbook.save(path_str(path)?).map_err(to_io_app_error)?;
+    hour_bank::write_sheet(&mut workbook, bank)?;
+    write_settings_sheet(&mut workbook, snapshot)?;
+
+    workbook
+        .save(path_str(path)?)
+        .map_err(|e| AppError::export_io(path, io::Error::other(e)))?;
 
     notify_export_success("XLSX", path);
     Ok(())
 }
 
+/// Foglio `Settings`: una riga `key | value` per ogni voce dello snapshot.
+pub(crate) fn write_settings_sheet(
+    workbook: &mut Workbook,
+    snapshot: &ConfigSnapshot,
+) -> AppResult<()> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Settings").map_err(xlsx_err)?;
+
+    let bold = Format::new().set_bold();
+    sheet
+        .write_with_format(0, 0, "key", &bold)
+        .map_err(xlsx_err)?;
+    sheet
+        .write_with_format(0, 1, "value", &bold)
+        .map_err(xlsx_err)?;
+
+    for (i, (key, value)) in snapshot.entries().into_iter().enumerate() {
+        let row = (i + 1) as u32;
+        sheet.write(row, 0, key).map_err(xlsx_err)?;
+        sheet.write(row, 1, value).map_err(xlsx_err)?;
+    }
+
+    sheet.set_column_width(0, 28.0).map_err(xlsx_err)?;
+    sheet.set_column_width(1, 20.0).map_err(xlsx_err)?;
+    Ok(())
+}
+
 /// Scrive una singola cella, interpretando stringhe come data/ora/numero se possibile.
 fn write_xlsx_cell(
     worksheet: &mut rust_xlsxwriter::Worksheet,
@@ -111,7 +164,7 @@ fn write_xlsx_cell(
 
         worksheet
             .write_with_format(row, col, serial, &fmt)
-            .map_err(to_io_app_error)?;
+            .map_err(xlsx_err)?;
         return Ok(());
     }
 
@@ -125,7 +178,7 @@ fn write_xlsx_cell(
 
         worksheet
             .write_with_format(row, col, num, &fmt)
-            .map_err(to_io_app_error)?;
+            .map_err(xlsx_err)?;
         return Ok(());
     }
 
@@ -137,16 +190,20 @@ fn write_xlsx_cell(
 
     worksheet
         .write_with_format(row, col, s, &fmt)
-        .map_err(to_io_app_error)?;
+        .map_err(xlsx_err)?;
 
     Ok(())
 }
 
-fn to_io_app_error<E: std::fmt::Display>(e: E) -> AppError {
-    AppError::from(io::Error::other(e.to_string()))
+pub(crate) fn xlsx_err<E: std::fmt::Display>(e: E) -> AppError {
+    AppError::Export(format!("XLSX error: {e}"))
 }
 
-fn path_str(path: &Path) -> AppResult<&str> {
-    path.to_str()
-        .ok_or_else(|| AppError::from(io::Error::other("invalid path")))
+pub(crate) fn path_str(path: &Path) -> AppResult<&str> {
+    path.to_str().ok_or_else(|| {
+        AppError::InvalidArgs(format!(
+            "Output path is not valid UTF-8: {}",
+            path.display()
+        ))
+    })
 }
@@ -0,0 +1,256 @@
+//! Badge-reader payloads received by `rtimelogger serve` (`POST /events`).
+//!
+//! The body is a JSON object or an array of objects; `[api.mapping]` tells
+//! where timestamp, direction and (optionally) position are. Every punch is
+//! stored as an IN / OUT event with `source = 'api'`.
+
+use crate::config::ApiMapping;
+use crate::core::calculator::overlap::OverlapMode;
+use crate::db::log::ttlog;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::db::queries::locks::ensure_unlocked;
+use crate::db::queries::{insert_event, recalc_pairs_for_date};
+use crate::errors::{AppError, AppResult};
+use crate::models::event::{Event, EventExtras};
+use crate::models::event_type::EventType;
+use crate::models::location::Location;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use rusqlite::{Connection, params};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Source recorded on events received through the API.
+pub const API_SOURCE: &str = "api";
+
+/// One punch extracted from the payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Punch {
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+    pub kind: EventType,
+    pub position: Location,
+}
+
+/// Map the payload to punches. Any invalid item rejects the whole request.
+pub fn parse_payload(body: &str, mapping: &ApiMapping, default: Location) -> AppResult<Vec<Punch>> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| AppError::InvalidArgs(format!("payload is not valid JSON: {}", e)))?;
+
+    let items = match value {
+        Value::Array(items) => items,
+        obj @ Value::Object(_) => vec![obj],
+        _ => {
+            return Err(AppError::InvalidArgs(
+                "payload must be a JSON object or an array of objects".into(),
+            ));
+        }
+    };
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            parse_item(item, mapping, default)
+                .map_err(|e| AppError::InvalidArgs(format!("item {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+fn parse_item(item: &Value, mapping: &ApiMapping, default: Location) -> Result<Punch, String> {
+    let ts = field_str(item, &mapping.timestamp)
+        .ok_or_else(|| format!("missing '{}'", mapping.timestamp))?;
+    let dt = parse_timestamp(&ts).ok_or_else(|| format!("invalid timestamp '{}'", ts))?;
+
+    let raw_kind =
+        field_str(item, &mapping.kind).ok_or_else(|| format!("missing '{}'", mapping.kind))?;
+    let matches = |values: &[String]| values.iter().any(|v| v.eq_ignore_ascii_case(&raw_kind));
+    let kind = if matches(&mapping.in_values) {
+        EventType::In
+    } else if matches(&mapping.out_values) {
+        EventType::Out
+    } else {
+        return Err(format!("unknown direction '{}'", raw_kind));
+    };
+
+    let position = match field_str(item, &mapping.position) {
+        Some(code) => {
+            Location::from_code(&code).ok_or_else(|| format!("invalid position '{}'", code))?
+        }
+        None => default,
+    };
+
+    // Minute resolution, like the rest of the events
+    let time = NaiveTime::from_hms_opt(dt.hour(), dt.minute(), 0).unwrap_or(dt.time());
+
+    Ok(Punch {
+        date: dt.date(),
+        time,
+        kind,
+        position,
+    })
+}
+
+/// Value at a dotted path, numbers and strings alike.
+fn field_str(item: &Value, path: &str) -> Option<String> {
+    if path.is_empty() {
+        return None;
+    }
+    let mut cur = item;
+    for key in path.split('.') {
+        cur = cur.get(key)?;
+    }
+    match cur {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// RFC 3339 (converted to local time) or a naive local `YYYY-MM-DD HH:MM[:SS]`.
+fn parse_timestamp(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Local).naive_local());
+    }
+    [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+}
+
+/// Whether the punch was already received (same date, minute and direction
+/// from the API): gateways resend on timeouts and 5xx.
+fn already_received(conn: &Connection, p: &Punch) -> AppResult<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM events
+                       WHERE date = ?1 AND time = ?2 AND kind = ?3 AND source = ?4)",
+        params![
+            p.date.format("%Y-%m-%d").to_string(),
+            p.time.format("%H:%M").to_string(),
+            p.kind.to_db_str(),
+            API_SOURCE
+        ],
+        |row| row.get(0),
+    )?)
+}
+
+/// Store the punches in one transaction and return how many were new.
+/// Punches already received are skipped, so a redelivered request is
+/// harmless. Locked days reject the request. `mode` is the `overlap_mode`
+/// of the config.
+pub fn ingest(
+    pool: &mut DbPool,
+    punches: &[Punch],
+    peer: &str,
+    mode: OverlapMode,
+) -> AppResult<usize> {
+    let tx = pool.conn.transaction()?;
+    let mut dates = BTreeSet::new();
+    let mut inserted = 0;
+
+    for p in punches {
+        if already_received(&tx, p)? {
+            continue;
+        }
+        ensure_unlocked(&tx, &p.date)?;
+
+        let ev = Event::new(
+            0,
+            p.date,
+            p.time,
+            p.kind.clone(),
+            p.position,
+            EventExtras {
+                source: Some(API_SOURCE.to_string()),
+                ..Default::default()
+            },
+        );
+        insert_event(&tx, &ev)?;
+        dates.insert(p.date);
+        inserted += 1;
+    }
+
+    for d in &dates {
+        recalc_pairs_for_date(&tx, d, mode)?;
+    }
+
+    ttlog(
+        &tx,
+        "api_import",
+        peer,
+        &format!(
+            "{} event(s) received, {} already recorded",
+            inserted,
+            punches.len() - inserted
+        ),
+    )?;
+    tx.commit()?;
+    record_app_version(&pool.conn)?;
+
+    Ok(inserted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_nested_fields_and_direction_values() {
+        let mapping = ApiMapping {
+            timestamp: "event.ts".into(),
+            kind: "event.dir".into(),
+            position: "site".into(),
+            in_values: vec!["ENTRY".into()],
+            out_values: vec!["EXIT".into()],
+        };
+        let body = r#"[{"event":{"ts":"2025-06-18 08:55:41","dir":"entry"}},
+                       {"event":{"ts":"2025-06-18 17:30","dir":"EXIT"},"site":"r"}]"#;
+
+        let punches = parse_payload(body, &mapping, Location::Office).unwrap();
+        assert_eq!(punches.len(), 2);
+        assert_eq!(punches[0].kind, EventType::In);
+        assert_eq!(punches[0].time, NaiveTime::from_hms_opt(8, 55, 0).unwrap());
+        assert_eq!(punches[0].position, Location::Office);
+        assert_eq!(punches[1].kind, EventType::Out);
+        assert_eq!(punches[1].position, Location::Remote);
+
+        let bad = r#"{"event":{"ts":"2025-06-18 08:55","dir":"badge-lost"}}"#;
+        assert!(parse_payload(bad, &mapping, Location::Office).is_err());
+    }
+
+    #[test]
+    fn redelivered_punches_are_skipped() {
+        let mut pool = DbPool::new(":memory:").unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+        let punch = |h, kind| Punch {
+            date,
+            time: NaiveTime::from_hms_opt(h, 0, 0).unwrap(),
+            kind,
+            position: Location::Office,
+        };
+        let punches = [punch(9, EventType::In), punch(13, EventType::Out)];
+
+        assert_eq!(
+            ingest(&mut pool, &punches, "gw", OverlapMode::Off).unwrap(),
+            2
+        );
+        // Gateway retry after a timeout: same request again
+        assert_eq!(
+            ingest(&mut pool, &punches, "gw", OverlapMode::Off).unwrap(),
+            0
+        );
+        // Partly new batch: only the new punch is stored
+        let more = [punch(13, EventType::Out), punch(14, EventType::In)];
+        assert_eq!(ingest(&mut pool, &more, "gw", OverlapMode::Off).unwrap(), 1);
+
+        let count: i64 = pool
+            .conn
+            .query_row("SELECT COUNT(*) FROM events", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+}
@@ -3,17 +3,21 @@ use std::collections::BTreeMap;
 use chrono::{NaiveDate, NaiveTime};
 
 use crate::config::Config;
+use crate::core::calculator::overlap::OverlapMode;
+use crate::db::metadata::record_app_version;
 use crate::db::pool::DbPool;
 use crate::db::queries;
 use crate::db::queries::import as qimp;
+use crate::db::queries::locks::is_locked;
 use crate::errors::{AppError, AppResult};
 use crate::models::event::{Event, EventExtras};
 use crate::models::event_type::EventType;
 use crate::models::location::Location;
+use crate::utils::host::os_user;
 
 use super::parser_csv::parse_csv_days;
 use super::parser_json::parse_json_days;
-use super::types::{ImportDay, ImportInputFormat, ImportReport};
+use super::types::{ImportAction, ImportDay, ImportInputFormat, ImportReport};
 
 pub fn import_days_from_str(
     cfg: &Config,
@@ -35,81 +39,100 @@ pub fn import_days_from_str(
     };
 
     // normalize + validate + dedup(last wins)
-    let mut dedup: BTreeMap<NaiveDate, ImportDay> = BTreeMap::new();
+    let mut dedup: BTreeMap<NaiveDate, (usize, ImportDay)> = BTreeMap::new();
 
-    for row in parsed {
+    for (line, row) in parsed {
         match row {
             Ok(day) => {
                 // Accept only day-markers
                 if day.position != Location::Holiday && day.position != Location::NationalHoliday {
-                    rep.invalid += 1;
+                    rep.record(
+                        line,
+                        Some(day.date),
+                        ImportAction::Invalid,
+                        format!("position '{}' is not a holiday marker", day.position.code()),
+                    );
                     continue;
                 }
-                dedup.insert(day.date, day);
+                let date = day.date;
+                if let Some((prev, _)) = dedup.insert(date, (line, day)) {
+                    rep.record(
+                        prev,
+                        Some(date),
+                        ImportAction::Duplicate,
+                        format!("superseded by line {}", line),
+                    );
+                }
             }
-            Err(_) => rep.invalid += 1,
+            Err(e) => rep.record(line, None, ImportAction::Invalid, e.to_string()),
         }
     }
 
     let mut pool = DbPool::new(&cfg.database)?;
 
     if dry_run {
-        for (_, day) in dedup {
-            evaluate_one(&pool, &day, replace, &mut rep)?;
+        for (line, day) in dedup.into_values() {
+            let action = classify(&pool.conn, &day, replace)?;
+            rep.record(line, Some(day.date), action, describe(&day));
         }
         return Ok(rep);
     }
 
+    let user = if cfg.record_os_user { os_user() } else { None };
     let tx = pool.conn.transaction()?;
 
-    for (_, day) in dedup {
-        apply_one(&tx, &day, replace, source, &mut rep)?;
+    for (line, day) in dedup.into_values() {
+        let action = classify(&tx, &day, replace)?;
+        if matches!(action, ImportAction::Insert | ImportAction::Replace) {
+            apply_one(&tx, &day, action, source, user.as_deref(), cfg.overlap_mode)?;
+        }
+        rep.record(line, Some(day.date), action, describe(&day));
     }
 
     tx.commit()?;
+    record_app_version(&pool.conn)?;
     Ok(rep)
 }
 
-fn evaluate_one(
-    pool: &DbPool,
+/// Position code and name of the marker, as shown by `--diff`.
+fn describe(day: &ImportDay) -> String {
+    match &day.meta {
+        Some(name) => format!("{} {}", day.position.code(), name),
+        None => day.position.code().to_string(),
+    }
+}
+
+/// What importing `day` does to the database.
+fn classify(
+    conn: &rusqlite::Connection,
     day: &ImportDay,
     replace: bool,
-    rep: &mut ImportReport,
-) -> AppResult<()> {
-    if qimp::day_marker_exists(&pool.conn, &day.date)? {
-        rep.skipped_existing += 1;
-        return Ok(());
+) -> AppResult<ImportAction> {
+    if is_locked(conn, &day.date)? {
+        return Ok(ImportAction::SkipLocked);
     }
 
-    let has_work = qimp::date_has_work_events(&pool.conn, &day.date)?;
-    if has_work && !replace {
-        rep.conflicts += 1;
-        return Ok(());
+    if qimp::day_marker_exists(conn, &day.date)? {
+        return Ok(ImportAction::SkipExisting);
     }
 
-    rep.imported += 1;
-    Ok(())
+    let has_work = qimp::date_has_work_events(conn, &day.date)?;
+    Ok(match (has_work, replace) {
+        (true, false) => ImportAction::Conflict,
+        (true, true) => ImportAction::Replace,
+        (false, _) => ImportAction::Insert,
+    })
 }
 
 fn apply_one(
     conn: &rusqlite::Connection, // tx deref -> Connection
     day: &ImportDay,
-    replace: bool,
+    action: ImportAction,
     source: &str,
-    rep: &mut ImportReport,
+    user: Option<&str>,
+    mode: OverlapMode,
 ) -> AppResult<()> {
-    if qimp::day_marker_exists(conn, &day.date)? {
-        rep.skipped_existing += 1;
-        return Ok(());
-    }
-
-    let has_work = qimp::date_has_work_events(conn, &day.date)?;
-    if has_work && !replace {
-        rep.conflicts += 1;
-        return Ok(());
-    }
-
-    if has_work && replace {
+    if action == ImportAction::Replace {
         qimp::delete_events_for_date(conn, &day.date)?;
     }
 
@@ -128,13 +151,15 @@ fn apply_one(
             work_gap: false,
             source: Some(source.to_string()),
             meta: day.meta.clone(), // ✅ persisted into events.meta
+            os_user: user.map(str::to_string),
             ..Default::default()
         },
     );
 
     queries::insert_event(conn, &ev)?;
 
-    // For markers, pair can stay 0 (recalc_pairs_for_date will keep it at 0 for marker-only days).
-    rep.imported += 1;
+    // Marker-only day: persists pair = 0 / unmatched = 0
+    queries::recalc_pairs_for_date(conn, &day.date, mode)?;
+
     Ok(())
 }
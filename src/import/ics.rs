@@ -0,0 +1,217 @@
+//! Minimal iCalendar (.ics) reader for `rtimelogger suggest`: meetings are
+//! used as evidence of presence on days that were not logged.
+//!
+//! Only what is needed is read: `DTSTART` / `DTEND` (or `DURATION`) and
+//! `SUMMARY` of each `VEVENT`. UTC times are converted to local time, times
+//! with a `TZID` are taken as local; all-day and cancelled events are
+//! skipped, recurrences (`RRULE`) are not expanded.
+
+use crate::errors::{AppError, AppResult};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+/// A timed calendar event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meeting {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub summary: String,
+}
+
+/// Proposed punches for a day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub date: NaiveDate,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub meetings: Vec<Meeting>,
+}
+
+/// Parse the timed events of an .ics file.
+pub fn parse_ics(content: &str) -> AppResult<Vec<Meeting>> {
+    if !content.contains("BEGIN:VCALENDAR") {
+        return Err(AppError::InvalidArgs(
+            "not an iCalendar file (BEGIN:VCALENDAR missing)".into(),
+        ));
+    }
+
+    let mut meetings = Vec::new();
+    let mut current: Option<Vec<(String, String)>> = None;
+
+    for line in unfold(content) {
+        match line.as_str() {
+            "BEGIN:VEVENT" => current = Some(Vec::new()),
+            "END:VEVENT" => {
+                if let Some(props) = current.take()
+                    && let Some(m) = to_meeting(&props)
+                {
+                    meetings.push(m);
+                }
+            }
+            _ => {
+                if let Some(props) = current.as_mut()
+                    && let Some((name, value)) = line.split_once(':')
+                {
+                    props.push((name.to_string(), value.to_string()));
+                }
+            }
+        }
+    }
+
+    meetings.sort_by_key(|m| m.start);
+    Ok(meetings)
+}
+
+/// First meeting start → IN, last meeting end → OUT (None without meetings).
+pub fn suggest(meetings: &[Meeting], date: NaiveDate) -> Option<Suggestion> {
+    let day: Vec<Meeting> = meetings
+        .iter()
+        .filter(|m| m.start.date() == date)
+        .cloned()
+        .collect();
+
+    let start = day.iter().map(|m| m.start.time()).min()?;
+    let end = day
+        .iter()
+        .map(|m| {
+            if m.end.date() > date {
+                NaiveTime::from_hms_opt(23, 59, 0).unwrap_or(m.start.time())
+            } else {
+                m.end.time()
+            }
+        })
+        .max()?;
+
+    Some(Suggestion {
+        date,
+        start,
+        end,
+        meetings: day,
+    })
+}
+
+/// RFC 5545 line unfolding: a line starting with a space continues the previous one.
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(rest) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+        } else {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+fn to_meeting(props: &[(String, String)]) -> Option<Meeting> {
+    let get = |key: &str| {
+        props
+            .iter()
+            .find(|(name, _)| name.split(';').next() == Some(key))
+    };
+
+    if get("STATUS").is_some_and(|(_, v)| v.eq_ignore_ascii_case("CANCELLED")) {
+        return None;
+    }
+
+    let (start_name, start_value) = get("DTSTART")?;
+    let start = parse_datetime(start_name, start_value)?;
+    let end = match get("DTEND") {
+        Some((name, value)) => parse_datetime(name, value)?,
+        None => start.checked_add_signed(parse_duration(&get("DURATION")?.1)?)?,
+    };
+
+    Some(Meeting {
+        start,
+        end: end.max(start),
+        summary: get("SUMMARY")
+            .map(|(_, v)| v.replace("\\,", ",").replace("\\;", ";"))
+            .unwrap_or_default(),
+    })
+}
+
+/// `20250618T090000Z` (UTC), `20250618T090000` (local / TZID); all-day values → None.
+fn parse_datetime(name: &str, value: &str) -> Option<NaiveDateTime> {
+    if name.contains("VALUE=DATE") && !name.contains("VALUE=DATE-TIME") {
+        return None;
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(
+            Utc.from_utc_datetime(&naive)
+                .with_timezone(&Local)
+                .naive_local(),
+        );
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()
+}
+
+/// `PT1H30M`, `PT45M`, `P1D` (weeks and seconds too); None when malformed
+/// or out of range (`P99999999999999W`).
+fn parse_duration(value: &str) -> Option<Duration> {
+    let body = value.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut num = String::new();
+    for c in body.chars() {
+        match c {
+            '0'..='9' => num.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = num.parse().ok()?;
+                num.clear();
+                let part = match unit {
+                    'W' => Duration::try_weeks(n),
+                    'D' => Duration::try_days(n),
+                    'H' => Duration::try_hours(n),
+                    'M' => Duration::try_minutes(n),
+                    'S' => Duration::try_seconds(n),
+                    _ => return None,
+                }?;
+                total = total.checked_add(&part)?;
+            }
+        }
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_and_last_meeting_of_the_day() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\nDTSTART;TZID=Europe/Rome:20250618T093000\r\nDTEND;TZID=Europe/Rome:20250618T100000\r\nSUMMARY:Daily\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART:20250618T150000\r\nDURATION:PT1H30M\r\nSUMMARY:Review with\r\n  the team\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250618\r\nSUMMARY:Holiday\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART:20250618T180000\r\nDTEND:20250618T190000\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+        let meetings = parse_ics(ics).unwrap();
+        assert_eq!(meetings.len(), 2);
+        assert_eq!(meetings[1].summary, "Review with the team");
+
+        let d = NaiveDate::from_ymd_opt(2025, 6, 18).unwrap();
+        let s = suggest(&meetings, d).unwrap();
+        assert_eq!(s.start, NaiveTime::from_hms_opt(9, 30, 0).unwrap());
+        assert_eq!(s.end, NaiveTime::from_hms_opt(16, 30, 0).unwrap());
+        assert!(suggest(&meetings, d.succ_opt().unwrap()).is_none());
+    }
+
+    #[test]
+    fn oversized_durations_are_rejected() {
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P99999999999999W"), None);
+        assert_eq!(parse_duration("P9223372036854775807D"), None);
+        assert_eq!(parse_duration("P15000000000W2000000000D"), None);
+
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\nDTSTART:20250618T150000\r\nDURATION:P99999999999999W\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART:20250618T150000\r\nDURATION:P20000000W\r\nEND:VEVENT\r\n\
+END:VCALENDAR\r\n";
+        assert!(parse_ics(ics).unwrap().is_empty());
+    }
+}
@@ -1,7 +1,12 @@
+pub mod badge;
 mod engine;
+pub mod ics;
 mod parser_csv;
 mod parser_json;
+mod toggl;
 mod types;
 
 pub use engine::import_days_from_str;
-pub use types::{ImportInputFormat, ImportReport};
+pub use parser_json::parse_json_events;
+pub use toggl::import_toggl_from_str;
+pub use types::{ImportAction, ImportInputFormat, ImportReport, ImportRow};
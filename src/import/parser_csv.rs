@@ -13,47 +13,74 @@ struct CsvDay {
     name: Option<String>,
 }
 
-pub(crate) fn parse_csv_days(input: &str) -> Vec<AppResult<ImportDay>> {
+/// Parse the rows of a CSV file, each with its line number.
+pub(crate) fn parse_csv_days(input: &str) -> Vec<(usize, AppResult<ImportDay>)> {
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(true)
         .from_reader(input.as_bytes());
 
+    let headers = match rdr.headers() {
+        Ok(h) => h.clone(),
+        Err(e) => {
+            return vec![(
+                1,
+                Err(AppError::InvalidArgs(format!("Invalid CSV header: {e}"))),
+            )];
+        }
+    };
+
     let mut out = Vec::new();
+    let mut rec = csv::StringRecord::new();
 
-    for rec in rdr.deserialize::<CsvDay>() {
-        match rec {
-            Ok(r) => {
-                let date = match NaiveDate::parse_from_str(&r.date, "%Y-%m-%d") {
-                    Ok(d) => d,
-                    Err(_) => {
-                        out.push(Err(AppError::InvalidDate(format!(
-                            "Invalid date: {}",
-                            r.date
-                        ))));
-                        continue;
-                    }
-                };
-
-                let pos = match Location::from_code(&r.position) {
-                    Some(p) => p,
-                    None => {
-                        out.push(Err(AppError::InvalidPosition(format!(
-                            "Invalid position: {}",
-                            r.position
-                        ))));
-                        continue;
-                    }
-                };
-
-                out.push(Ok(ImportDay {
-                    date,
-                    position: pos,
-                    meta: r.name,
-                }));
+    loop {
+        let line = rdr.position().line() as usize;
+        match rdr.read_record(&mut rec) {
+            Ok(false) => break,
+            Ok(true) => {
+                let line = rec.position().map_or(line, |p| p.line() as usize);
+                let day = rec
+                    .deserialize::<CsvDay>(Some(&headers))
+                    .map_err(|e| AppError::InvalidArgs(format!("Invalid CSV row: {e}")))
+                    .and_then(day_from_row);
+                out.push((line, day));
             }
-            Err(e) => out.push(Err(AppError::InvalidArgs(format!("Invalid CSV row: {e}")))),
+            Err(e) => out.push((
+                line,
+                Err(AppError::InvalidArgs(format!("Invalid CSV row: {e}"))),
+            )),
         }
     }
 
     out
 }
+
+fn day_from_row(r: CsvDay) -> AppResult<ImportDay> {
+    let date = NaiveDate::parse_from_str(&r.date, "%Y-%m-%d")
+        .map_err(|_| AppError::InvalidDate(format!("Invalid date: {}", r.date)))?;
+
+    let position = Location::from_code(&r.position)
+        .ok_or_else(|| AppError::InvalidPosition(format!("Invalid position: {}", r.position)))?;
+
+    Ok(ImportDay {
+        date,
+        position,
+        meta: r.name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_keep_their_line_numbers() {
+        let csv = "date,position,name\n2025-12-25,N,Christmas\n\n2025-13-01,N,\n2025-12-26,X,\n";
+        let rows = parse_csv_days(csv);
+
+        let lines: Vec<usize> = rows.iter().map(|(l, _)| *l).collect();
+        assert_eq!(lines, vec![2, 4, 5]);
+        assert!(rows[0].1.is_ok());
+        assert!(rows[1].1.is_err());
+        assert!(rows[2].1.is_err());
+    }
+}
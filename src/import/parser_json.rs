@@ -1,5 +1,7 @@
 use crate::errors::{AppError, AppResult};
+use crate::export::EventExport;
 use crate::import::types::{ImportDay, ImportDayJson};
+use crate::models::event::Event;
 use crate::models::location::Location;
 use chrono::NaiveDate;
 use serde::Deserialize;
@@ -12,14 +14,43 @@ enum ImportJsonRoot {
     Array(Vec<ImportDayJson>),
 }
 
-pub(crate) fn parse_json_days(input: &str) -> Vec<AppResult<ImportDay>> {
+/// Shape of `export --format json`: a plain array or `{snapshot, events}`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum EventsJsonRoot {
+    WithSnapshot { events: Vec<EventExport> },
+    Array(Vec<EventExport>),
+}
+
+/// Read back the events of a JSON export. The derived columns are ignored;
+/// the first invalid event aborts the whole file.
+pub fn parse_json_events(input: &str) -> AppResult<Vec<Event>> {
+    let root: EventsJsonRoot = serde_json::from_str(input).map_err(|e| {
+        AppError::InvalidArgs(format!(
+            "Invalid JSON export. Expected [...] or {{\"snapshot\":{{..}},\"events\":[...]}}. Details: {}",
+            e
+        ))
+    })?;
+
+    let rows = match root {
+        EventsJsonRoot::WithSnapshot { events } => events,
+        EventsJsonRoot::Array(v) => v,
+    };
+    rows.iter().map(Event::try_from).collect()
+}
+
+/// Parse the items of a JSON file, each with its 1-based index.
+pub(crate) fn parse_json_days(input: &str) -> Vec<(usize, AppResult<ImportDay>)> {
     let root: ImportJsonRoot = match serde_json::from_str(input) {
         Ok(v) => v,
         Err(e) => {
-            return vec![Err(AppError::InvalidArgs(format!(
-                "Invalid JSON. Expected one of: {{\"days\":[...]}}, {{\"holidays\":[...]}}, or a root array [...]. Details: {}",
-                e
-            )))];
+            return vec![(
+                1,
+                Err(AppError::InvalidArgs(format!(
+                    "Invalid JSON. Expected one of: {{\"days\":[...]}}, {{\"holidays\":[...]}}, or a root array [...]. Details: {}",
+                    e
+                ))),
+            )];
         }
     };
 
@@ -30,27 +61,29 @@ pub(crate) fn parse_json_days(input: &str) -> Vec<AppResult<ImportDay>> {
     };
 
     rows.into_iter()
-        .map(|r| {
-            let date = NaiveDate::parse_from_str(&r.date, "%Y-%m-%d")
-                .map_err(|_| AppError::InvalidDate(r.date.clone()))?;
-
-            let position = match r.position.as_deref() {
-                Some(code) => Location::from_db_str(&code.to_uppercase()).ok_or_else(|| {
-                    AppError::InvalidPosition(format!("Invalid position '{}'", code))
-                })?,
-                None => Location::NationalHoliday, // ✅ default
-            };
-
-            let meta = r
-                .name
-                .map(|n| n.trim().to_string())
-                .filter(|s| !s.is_empty());
-
-            Ok(ImportDay {
-                date,
-                position,
-                meta,
-            })
-        })
+        .enumerate()
+        .map(|(i, r)| (i + 1, day_from_item(r)))
         .collect()
 }
+
+fn day_from_item(r: ImportDayJson) -> AppResult<ImportDay> {
+    let date = NaiveDate::parse_from_str(&r.date, "%Y-%m-%d")
+        .map_err(|_| AppError::InvalidDate(r.date.clone()))?;
+
+    let position = match r.position.as_deref() {
+        Some(code) => Location::from_db_str(&code.to_uppercase())
+            .ok_or_else(|| AppError::InvalidPosition(format!("Invalid position '{}'", code)))?,
+        None => Location::NationalHoliday, // ✅ default
+    };
+
+    let meta = r
+        .name
+        .map(|n| n.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    Ok(ImportDay {
+        date,
+        position,
+        meta,
+    })
+}
@@ -0,0 +1,245 @@
+//! `import --from toggl`: Toggl Track "Detailed report" CSV export.
+//!
+//! Every time entry becomes an IN / OUT pair at the default position; the
+//! Toggl project, client and description are stored in the IN event metadata
+//! (`project`, `client`, `description`) so that `invoice`, `report --earnings`
+//! and `export --preset clockify` see them. Only the columns below are read,
+//! any other column of the export is ignored.
+
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, NaiveTime, Timelike};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::core::invoice::PROJECT_KEY;
+use crate::db::metadata::record_app_version;
+use crate::db::pool::DbPool;
+use crate::db::queries;
+use crate::db::queries::import as qimp;
+use crate::db::queries::locks::is_locked;
+use crate::errors::{AppError, AppResult};
+use crate::export::preset::DESCRIPTION_KEY;
+use crate::models::event::{Event, EventExtras};
+use crate::models::event_type::EventType;
+use crate::models::location::Location;
+use crate::utils::host::os_user;
+
+use super::types::ImportReport;
+
+#[derive(Debug, Deserialize)]
+struct TogglRow {
+    #[serde(rename = "Client", default)]
+    client: String,
+    #[serde(rename = "Project", default)]
+    project: String,
+    #[serde(rename = "Description", default)]
+    description: String,
+    #[serde(rename = "Start date")]
+    start_date: String,
+    #[serde(rename = "Start time")]
+    start_time: String,
+    #[serde(rename = "End date")]
+    end_date: String,
+    #[serde(rename = "End time")]
+    end_time: String,
+}
+
+/// One Toggl time entry, at minute resolution.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TogglEntry {
+    pub date: NaiveDate,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub meta: Option<String>,
+}
+
+pub(crate) fn parse_toggl_csv(input: &str) -> Vec<AppResult<TogglEntry>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .trim(csv::Trim::All)
+        .from_reader(input.trim_start_matches('\u{feff}').as_bytes());
+
+    rdr.deserialize::<TogglRow>()
+        .map(|rec| {
+            let r = rec.map_err(|e| AppError::InvalidArgs(format!("Invalid CSV row: {e}")))?;
+            entry_from_row(&r)
+        })
+        .collect()
+}
+
+fn entry_from_row(r: &TogglRow) -> AppResult<TogglEntry> {
+    let date = parse_day(&r.start_date)?;
+    if parse_day(&r.end_date)? != date {
+        return Err(AppError::InvalidArgs(format!(
+            "Entry of {} ends on another day: split it at midnight.",
+            r.start_date
+        )));
+    }
+
+    let start = parse_clock(&r.start_time)?;
+    let end = parse_clock(&r.end_time)?;
+    if end <= start {
+        return Err(AppError::InvalidArgs(format!(
+            "Entry of {} ends before it starts ({} → {}).",
+            r.start_date, r.start_time, r.end_time
+        )));
+    }
+
+    let mut meta = serde_json::Map::new();
+    for (key, value) in [
+        (PROJECT_KEY, &r.project),
+        ("client", &r.client),
+        (DESCRIPTION_KEY, &r.description),
+    ] {
+        if !value.is_empty() {
+            meta.insert(key.to_string(), value.clone().into());
+        }
+    }
+
+    Ok(TogglEntry {
+        date,
+        start,
+        end,
+        meta: (!meta.is_empty()).then(|| serde_json::Value::Object(meta).to_string()),
+    })
+}
+
+fn parse_day(s: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| AppError::InvalidDate(format!("Invalid date: {}", s)))
+}
+
+/// `HH:MM:SS` (Toggl) or `HH:MM`, seconds dropped like the rest of the events.
+fn parse_clock(s: &str) -> AppResult<NaiveTime> {
+    let t = NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+        .map_err(|_| AppError::InvalidTime(s.to_string()))?;
+    Ok(t.with_second(0).unwrap_or(t))
+}
+
+/// Import a Toggl CSV. Counters of the report are time entries; a day that
+/// already has work events is a conflict unless `replace` is set.
+pub fn import_toggl_from_str(
+    cfg: &Config,
+    content: &str,
+    dry_run: bool,
+    replace: bool,
+    source: &str,
+) -> AppResult<ImportReport> {
+    let parsed = parse_toggl_csv(content);
+
+    let mut rep = ImportReport {
+        total: parsed.len(),
+        ..Default::default()
+    };
+
+    let mut by_day: BTreeMap<NaiveDate, Vec<TogglEntry>> = BTreeMap::new();
+    for row in parsed {
+        match row {
+            Ok(e) => by_day.entry(e.date).or_default().push(e),
+            Err(_) => rep.invalid += 1,
+        }
+    }
+
+    // Overlapping entries cannot become IN/OUT pairs: keep the first one
+    for entries in by_day.values_mut() {
+        entries.sort_by_key(|e| e.start);
+        let before = entries.len();
+        let mut last_end: Option<NaiveTime> = None;
+        entries.retain(|e| {
+            let keep = last_end.is_none_or(|end| e.start >= end);
+            if keep {
+                last_end = Some(e.end);
+            }
+            keep
+        });
+        rep.invalid += before - entries.len();
+    }
+
+    let position = Location::from_code(&cfg.default_position).unwrap_or(Location::Office);
+    let user = if cfg.record_os_user { os_user() } else { None };
+
+    let mut pool = DbPool::new(&cfg.database)?;
+    let tx = pool.conn.transaction()?;
+
+    for (date, entries) in &by_day {
+        let n = entries.len();
+
+        if is_locked(&tx, date)? {
+            rep.locked += n;
+            continue;
+        }
+        if qimp::day_marker_exists(&tx, date)? {
+            rep.skipped_existing += n;
+            continue;
+        }
+        let has_work = qimp::date_has_work_events(&tx, date)?;
+        if has_work && !replace {
+            rep.conflicts += n;
+            continue;
+        }
+
+        rep.imported += n;
+        if dry_run {
+            continue;
+        }
+
+        if has_work {
+            qimp::delete_events_for_date(&tx, date)?;
+        }
+
+        for e in entries {
+            let extras = |kind: &EventType| EventExtras {
+                lunch: (*kind == EventType::In).then_some(0),
+                source: Some(source.to_string()),
+                meta: if *kind == EventType::In {
+                    e.meta.clone()
+                } else {
+                    None
+                },
+                os_user: user.clone(),
+                ..Default::default()
+            };
+            for (time, kind) in [(e.start, EventType::In), (e.end, EventType::Out)] {
+                let ev = Event::new(0, *date, time, kind.clone(), position, extras(&kind));
+                queries::insert_event(&tx, &ev)?;
+            }
+        }
+        queries::recalc_pairs_for_date(&tx, date, cfg.overlap_mode)?;
+    }
+
+    if dry_run {
+        return Ok(rep);
+    }
+
+    tx.commit()?;
+    record_app_version(&pool.conn)?;
+    Ok(rep)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_detailed_report_rows() {
+        let csv = "\u{feff}User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags,Amount (EUR)\n\
+                   Ann,a@x.io,ACME,Portal,,Sprint review,Yes,2025-06-18,09:00:41,2025-06-18,12:30:00,03:29:19,,\n\
+                   Ann,a@x.io,,,,,No,2025-06-18,23:00:00,2025-06-19,01:00:00,02:00:00,,\n";
+
+        let rows = parse_toggl_csv(csv);
+        assert_eq!(rows.len(), 2);
+
+        let e = rows[0].as_ref().unwrap();
+        assert_eq!(e.start, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(e.end, NaiveTime::from_hms_opt(12, 30, 0).unwrap());
+        let meta: serde_json::Value = serde_json::from_str(e.meta.as_deref().unwrap()).unwrap();
+        assert_eq!(meta["project"], "Portal");
+        assert_eq!(meta["client"], "ACME");
+        assert_eq!(meta["description"], "Sprint review");
+
+        // Crosses midnight
+        assert!(rows[1].is_err());
+    }
+}
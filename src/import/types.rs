@@ -1,6 +1,6 @@
 use crate::models::location::Location;
 use chrono::NaiveDate;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImportInputFormat {
@@ -13,8 +13,80 @@ pub struct ImportReport {
     pub total: usize,
     pub imported: usize,
     pub skipped_existing: usize,
+    /// Dates skipped because the day is locked
+    pub locked: usize,
     pub conflicts: usize,
     pub invalid: usize,
+    /// Outcome of every row, in file order (`import --diff`)
+    pub rows: Vec<ImportRow>,
+}
+
+/// What happens (or would happen) to one row of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Insert,
+    /// Insert after deleting the work events of the day (`--replace`)
+    Replace,
+    /// The day already has a marker
+    SkipExisting,
+    SkipLocked,
+    /// The day has work events and `--replace` is not set
+    Conflict,
+    /// Another row of the file has the same date (the last one wins)
+    Duplicate,
+    Invalid,
+}
+
+impl ImportAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportAction::Insert => "insert",
+            ImportAction::Replace => "replace",
+            ImportAction::SkipExisting => "skip (already present)",
+            ImportAction::SkipLocked => "skip (locked)",
+            ImportAction::Conflict => "conflict",
+            ImportAction::Duplicate => "duplicate",
+            ImportAction::Invalid => "invalid",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRow {
+    /// Line of the CSV file, or 1-based item of the JSON array
+    pub line: usize,
+    pub date: Option<NaiveDate>,
+    pub action: ImportAction,
+    /// Position code, holiday name or validation error
+    pub detail: String,
+}
+
+impl ImportReport {
+    /// Count one row and keep it for the diff view.
+    pub(crate) fn record(
+        &mut self,
+        line: usize,
+        date: Option<NaiveDate>,
+        action: ImportAction,
+        detail: impl Into<String>,
+    ) {
+        match action {
+            ImportAction::Insert | ImportAction::Replace => self.imported += 1,
+            ImportAction::SkipExisting => self.skipped_existing += 1,
+            ImportAction::SkipLocked => self.locked += 1,
+            ImportAction::Conflict => self.conflicts += 1,
+            ImportAction::Invalid => self.invalid += 1,
+            // Not counted: the row with the same date stands for it
+            ImportAction::Duplicate => {}
+        }
+        self.rows.push(ImportRow {
+            line,
+            date,
+            action,
+            detail: detail.into(),
+        });
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,53 +1,156 @@
 //! rTimeLogger library root.
 //! Exposes CLI parser, high-level run() function, and internal modules.
 
+//!
+//! Without the default `cli` feature only the calculation layer is built
+//! (`models`, `core::logic`, `core::calculator`, `core::compliance`,
+//! `config::Config`, the date / time / period helpers): no SQLite, files,
+//! processes or terminal, so it compiles to `wasm32-unknown-unknown`.
+
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod config;
 pub mod core;
+#[cfg(feature = "cli")]
 pub mod db;
 pub mod errors;
+#[cfg(feature = "cli")]
 pub mod export;
+#[cfg(feature = "cli")]
 pub mod import;
 pub mod models;
+#[cfg(feature = "cli")]
 pub mod ui;
 pub mod utils;
 
+#[cfg(feature = "cli")]
 use clap::Parser;
+#[cfg(feature = "cli")]
 use cli::parser::{Cli, Commands};
+#[cfg(feature = "cli")]
 use config::Config;
-use errors::AppResult;
+#[cfg(feature = "cli")]
+use errors::{AppError, AppResult};
+#[cfg(feature = "cli")]
+use ui::messages::OutputMode;
 
 /// Central command dispatcher
+#[cfg(feature = "cli")]
 pub fn dispatch(cli: &Cli, cfg: &Config) -> AppResult<()> {
-    match &cli.command {
-        Commands::Init => cli::commands::init::handle(cli),
-        Commands::Config { .. } => cli::commands::config::handle(&cli.command, cfg),
-        Commands::Db { .. } => cli::commands::db::handle(&cli.command, cfg),
-        Commands::Add { .. } => cli::commands::add::handle(&cli.command, cfg),
-        Commands::List { .. } => cli::commands::list::handle(&cli.command, cfg),
-        Commands::Del { .. } => cli::commands::del::handle(&cli.command, cfg),
-        Commands::Backup { .. } => cli::commands::backup::handle(&cli.command, cfg),
-        Commands::Log { .. } => cli::commands::log::handle(&cli.command, cfg),
-        Commands::Export { .. } => cli::commands::export::handle(&cli.command, cfg),
-        Commands::Import { .. } => cli::commands::import::handle(&cli.command, cfg),
+    let Some(command) = &cli.command else {
+        return Ok(());
+    };
+    match command {
+        Commands::Init { .. } => cli::commands::init::handle(cli),
+        Commands::Config { .. } => cli::commands::config::handle(command, cfg),
+        Commands::Db { .. } => cli::commands::db::handle(command, cfg),
+        Commands::Add { .. } => cli::commands::add::handle(command, cfg),
+        Commands::List { .. } => cli::commands::list::handle(command, cfg),
+        Commands::Del { .. } => cli::commands::del::handle(command, cfg),
+        Commands::Archive { .. } => cli::commands::archive::handle(command, cfg),
+        Commands::Closeout { .. } => cli::commands::closeout::handle(command, cfg),
+        Commands::Lock { .. } | Commands::Unlock { .. } => {
+            cli::commands::lock::handle(command, cfg)
+        }
+        Commands::Backup { .. } => cli::commands::backup::handle(command, cfg),
+        Commands::Report { .. } => {
+            let databases: Vec<String> = cli.db.iter().map(|d| cfg.resolve_database(d)).collect();
+            cli::commands::report::handle(command, cfg, &databases)
+        }
+        Commands::Find { .. } => cli::commands::find::handle(command, cfg),
+        Commands::Policy { .. } => cli::commands::policy::handle(command, cfg),
+        Commands::Status { .. } => cli::commands::status::handle(command, cfg),
+        Commands::Predict { .. } => cli::commands::predict::handle(command, cfg),
+        Commands::Invoice { .. } => cli::commands::invoice::handle(command, cfg),
+        Commands::Suggest { .. } => cli::commands::suggest::handle(command, cfg),
+        Commands::Trash { .. } => cli::commands::trash::handle(command, cfg),
+        Commands::Toil { .. } => cli::commands::toil::handle(command, cfg),
+        Commands::Focus { .. } => cli::commands::focus::handle(command, cfg),
+        Commands::Lunch { .. } => cli::commands::lunch::handle(command, cfg),
+        Commands::Mark { .. } => cli::commands::mark::handle(command, cfg),
+        Commands::Schema { .. } => cli::commands::schema::handle(command, cfg),
+        Commands::Serve { .. } => cli::commands::serve::handle(command, cfg),
+        Commands::Log { .. } => cli::commands::log::handle(command, cfg),
+        Commands::InstallService { .. } => cli::commands::install_service::handle(command, cfg),
+        Commands::Export { .. } => cli::commands::export::handle(command, cfg),
+        Commands::Import { .. } => cli::commands::import::handle(command, cfg),
+        Commands::Dev { .. } => cli::commands::dev::handle(command, cfg),
+        Commands::Plugin(_) => cli::commands::plugin::handle(command, cfg),
     }
 }
 
 /// Entry point usato da main.rs
+#[cfg(feature = "cli")]
 pub fn run() -> AppResult<()> {
     // 1️⃣ parse CLI
     let cli = Cli::parse();
+    ui::messages::set_ascii(cli.no_emoji);
+    if cli.quiet {
+        ui::messages::set_mode(OutputMode::Quiet);
+    }
+    if cli.version
+        || cli
+            .command
+            .as_ref()
+            .is_some_and(Commands::is_machine_readable)
+    {
+        ui::messages::set_mode(OutputMode::Data);
+    }
+    if ui::messages::mode() != OutputMode::Data {
+        ui::messages::data("");
+    }
 
-    // 2️⃣ carica config UNA sola volta
-    let mut cfg = Config::load();
+    // 2️⃣ carica config UNA sola volta (`--test`: file in sola lettura, DB di test)
+    let mut cfg = Config::load_for(cli.test);
+    if cfg.no_emoji {
+        ui::messages::set_ascii(true);
+    }
 
-    // 3️⃣ applica eventuale override del DB da riga di comando
-    if let Some(custom_db) = &cli.db {
-        cfg.database = custom_db.clone();
+    // 3️⃣ applica eventuale override del DB da riga di comando (path o nome in `databases`)
+    if cli.db.len() > 1 && !matches!(cli.command, Some(Commands::Report { .. })) {
+        return Err(AppError::InvalidArgs(
+            "--db can be repeated only with `report` (consolidated report).".into(),
+        ));
+    }
+    if let Some(custom_db) = cli.db() {
+        cfg.database = cfg.resolve_database(custom_db);
+    } else if !matches!(
+        cli.command,
+        None | Some(Commands::Init { .. } | Commands::Config { .. })
+    ) && let Some(found) = cfg.misplaced_database()
+    {
+        ui::messages::warning(format!(
+            "⚠️  Database {} not found, but {} exists: run `rtimelogger config --repair-db-path` to use it.",
+            cfg.database,
+            found.display()
+        ));
     }
 
-    // (per ora `cli.test` lo ignoriamo qui; lo usi solo dove serve davvero)
+    // `--version [--verbose]` (dopo la config: il DB di `--db` è quello mostrato)
+    if cli.version {
+        return cli::commands::version::handle(&cfg, cli.verbose);
+    }
+    if cli.command.is_none() {
+        return Err(AppError::InvalidArgs(
+            "Missing command: see `rtimelogger --help`.".into(),
+        ));
+    }
 
     // 4️⃣ passa tutto al dispatcher
-    dispatch(&cli, &cfg)
+    dispatch(&cli, &cfg)?;
+
+    // 5️⃣ promemoria (una volta al giorno) delle giornate passate rimaste aperte
+    if cli
+        .command
+        .as_ref()
+        .is_some_and(Commands::reminds_open_pairs)
+    {
+        crate::core::reminder::remind_open_pairs(&cfg);
+    }
+
+    // 6️⃣ comandi di scrittura: checkpoint del WAL (se attivo) prima di uscire
+    if cli.command.as_ref().is_some_and(Commands::writes_db) {
+        db::pool::checkpoint_file(&cfg.database)?;
+    }
+    Ok(())
 }
@@ -1,9 +1,9 @@
 //! rTimeLogger main entrypoint.
 
 use rtimelogger::run;
+use rtimelogger::utils::colors::eprintln;
 
 fn main() {
-    println!();
     if let Err(e) = run() {
         eprintln!("Error: {}", e);
         std::process::exit(1);
@@ -1,10 +1,28 @@
 use crate::core::calculator::gaps::GapInfo;
 use crate::core::calculator::timeline::Timeline;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DaySummary {
     pub timeline: Timeline,
     pub gaps: GapInfo,
     pub expected: i64,
     pub surplus: i64,
 }
+
+/// Worked / expected / ΔWORK figures of a working day, consistent with TGT:
+/// `ΔWORK = OUT - TGT = WORKED - EXP`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayFigures {
+    pub first_in: DateTime<Local>,
+    pub last_out: Option<DateTime<Local>>,
+    pub lunch_total: i64,
+    /// Planned exit (TGT)
+    pub target_exit: DateTime<Local>,
+    /// Net worked minutes (None while the day has no OUT yet)
+    pub worked: Option<i64>,
+    /// Minutes of work required by the contract (lunch excluded)
+    pub expected: i64,
+    pub delta: Option<i64>,
+}
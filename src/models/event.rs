@@ -1,23 +1,41 @@
 use super::{event_type::EventType, location::Location};
+#[cfg(feature = "cli")]
 use crate::db::pool::DbPool;
+#[cfg(feature = "cli")]
+use crate::utils::host::hostname;
 use chrono::{Local, NaiveDate, NaiveTime};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+/// Wire format: see [`crate::models`]. Fields after `location` may be
+/// missing in the input (older producers) and take their default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
     pub id: i32,
     pub date: NaiveDate,    // ⇔ events.date (TEXT "YYYY-MM-DD")
     pub time: NaiveTime,    // ⇔ events.time (TEXT "HH:MM")
     pub kind: EventType,    // ⇔ events.kind  ('in' | 'out')
     pub location: Location, // ⇔ events.position ('O','R','H','C','M')
+    #[serde(default)]
     pub lunch: Option<i32>, // ⇔ events.lunch_break (INT, default 0)
-    pub work_gap: bool,     // ⇔ events.meta/work_gap logica futura
+    #[serde(default)]
+    pub work_gap: bool, // ⇔ events.meta/work_gap logica futura
 
-    pub pair: i32,             // ⇔ events.pair (INT NOT NULL DEFAULT 0)
-    pub source: String,        // ⇔ events.source (TEXT, default 'cli')
-    pub meta: Option<String>,  // ⇔ events.meta (TEXT, default '')
+    #[serde(default)]
+    pub pair: i32, // ⇔ events.pair (INT NOT NULL DEFAULT 0)
+    #[serde(default)]
+    pub unmatched: bool, // ⇔ events.unmatched (INT NOT NULL DEFAULT 0)
+    #[serde(default)]
+    pub source: String, // ⇔ events.source (TEXT, default 'cli')
+    #[serde(default)]
+    pub meta: Option<String>, // ⇔ events.meta (TEXT, default '')
+    #[serde(default)]
     pub notes: Option<String>, // ⇔ events.notes (TEXT, optional workday notes)
-    pub created_at: String,    // ⇔ events.created_at (TEXT, ISO8601)
+    #[serde(default)]
+    pub host: Option<String>, // ⇔ events.host (TEXT, machine that recorded the event)
+    #[serde(default)]
+    pub os_user: Option<String>, // ⇔ events.os_user (TEXT, only with record_os_user)
+    #[serde(default)]
+    pub created_at: String, // ⇔ events.created_at (TEXT, ISO8601)
 }
 
 #[derive(Debug, Clone, Default)]
@@ -28,6 +46,8 @@ pub struct EventExtras {
     pub source: Option<String>,
     pub notes: Option<String>,
     pub pair: Option<i32>,
+    pub host: Option<String>,
+    pub os_user: Option<String>,
     pub created_at: Option<String>,
 }
 
@@ -35,6 +55,7 @@ impl Event {
     /// Costruttore "di alto livello" per eventi creati dalla CLI.
     /// - Imposta `pair = 0` (sarà ricalcolato da recalc_all_pairs)
     /// - Imposta `created_at = now() in ISO8601`
+    /// - Imposta `host` = hostname corrente (se non fornito)
     pub fn new(
         id: i32,
         date: NaiveDate,
@@ -52,9 +73,12 @@ impl Event {
             lunch: extras.lunch,
             work_gap: extras.work_gap,
             pair: extras.pair.unwrap_or(0),
+            unmatched: false,
             source: extras.source.unwrap_or_else(|| "cli".to_string()),
             meta: extras.meta,
             notes: extras.notes,
+            host: host_or_current(extras.host),
+            os_user: extras.os_user,
             created_at: extras
                 .created_at
                 .unwrap_or_else(|| Local::now().to_rfc3339()),
@@ -81,6 +105,7 @@ impl Event {
             .to_string()
     }
 
+    #[cfg(feature = "cli")]
     pub fn has_events_for_dates(pool: &mut DbPool, dates: &[NaiveDate]) -> rusqlite::Result<bool> {
         if dates.is_empty() {
             return Ok(false);
@@ -127,9 +152,12 @@ impl Event {
             lunch: None,
             work_gap: false,
             pair: 0,
+            unmatched: false,
             source: "".to_string(),
             meta: meta.map(|s| s.to_string()),
             notes: None,
+            host: None,
+            os_user: None,
             // Inizializza qui TUTTI gli altri campi con valori “dummy” validi.
             // Esempi tipici:
             // id: 0,
@@ -145,3 +173,14 @@ impl Event {
         }
     }
 }
+
+/// The given host, else the current machine (none without `cli`).
+#[cfg(feature = "cli")]
+fn host_or_current(host: Option<String>) -> Option<String> {
+    host.or_else(hostname)
+}
+
+#[cfg(not(feature = "cli"))]
+fn host_or_current(host: Option<String>) -> Option<String> {
+    host
+}
@@ -1,6 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Location {
     Office,          // O
     Remote,          // R
@@ -24,6 +24,14 @@ impl Location {
         }
     }
 
+    /// Non-working day markers (holiday, national holiday, sick leave)
+    pub fn is_marker(&self) -> bool {
+        matches!(
+            self,
+            Location::Holiday | Location::NationalHoliday | Location::SickLeave
+        )
+    }
+
     /// Convert enum → DB string
     pub fn to_db_str(&self) -> &str {
         self.code()
@@ -74,3 +82,9 @@ impl Location {
         }
     }
 }
+
+/// clap value parser for `--pos` (codes are case-insensitive).
+pub fn parse_location(s: &str) -> Result<Location, String> {
+    Location::from_code(s.trim())
+        .ok_or_else(|| format!("Invalid position '{}': use O, R, C, M, H, N or S", s))
+}
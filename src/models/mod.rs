@@ -1,6 +1,98 @@
+//! Data model shared by the CLI, the exports and library users.
+//!
+//! Wire format (serde, e.g. JSON) of [`event::Event`], [`day_summary::DaySummary`],
+//! [`day_summary::DayFigures`] and the timeline types they contain
+//! (`core::calculator::timeline::{Timeline, Pair, Gap}`):
+//!
+//! - field names and the variant names of [`location::Location`] and
+//!   [`event_type::EventType`] (`"Office"`, `"In"`, ...) do not change within
+//!   a major version;
+//! - dates are `YYYY-MM-DD`, times `HH:MM:SS`, instants RFC 3339 with offset;
+//! - new fields are only added with a default, so older JSON keeps
+//!   deserializing; consumers should ignore unknown fields.
+//!
+//! Serializing and deserializing back gives the same value.
+
 pub mod day_summary;
 pub mod event;
 pub mod event_type;
 pub mod events;
 pub mod gap_type;
 pub mod location;
+#[cfg(test)]
+pub(crate) mod test_support;
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::core::logic::Core;
+    use crate::models::day_summary::{DayFigures, DaySummary};
+    use crate::models::event::Event;
+    use crate::models::event_type::EventType;
+    use crate::models::location::Location;
+    use crate::models::test_support;
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+
+    fn ev(t: &str, kind: EventType, location: Location, lunch: i32) -> Event {
+        Event {
+            location,
+            lunch: Some(lunch),
+            host: Some("laptop".into()),
+            ..test_support::project(test_support::ev(7, t, kind), "acme")
+        }
+    }
+
+    /// value → JSON → value → JSON gives the same JSON.
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+        let json = serde_json::to_value(value).unwrap();
+        let back: T = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&back).unwrap(), json);
+        back
+    }
+
+    #[test]
+    fn summary_and_figures_round_trip() {
+        use EventType::{In, Out};
+
+        let events = vec![
+            ev("08:30", In, Location::Office, 0),
+            ev("12:30", Out, Location::Office, 0),
+            ev("13:15", In, Location::Remote, 0),
+            ev("17:45", Out, Location::Remote, 30),
+        ];
+        let cfg = Config::default();
+        let summary = Core::build_daily_summary(&events, &cfg);
+        let figures = Core::day_figures(&summary, &events, &cfg).unwrap();
+
+        let back: DaySummary = round_trip(&summary);
+        assert_eq!(back.timeline.pairs.len(), 2);
+        assert_eq!(back.timeline.pairs[1].position, Location::Remote);
+        assert_eq!(back.surplus, summary.surplus);
+
+        let back: DayFigures = round_trip(&figures);
+        assert_eq!(back.worked, figures.worked);
+        assert_eq!(back.target_exit, figures.target_exit);
+
+        // The same figures from the deserialized events
+        let events: Vec<Event> = round_trip(&events);
+        let again = Core::build_daily_summary(&events, &cfg);
+        assert_eq!(again.surplus, summary.surplus);
+    }
+
+    #[test]
+    fn event_without_optional_fields() {
+        let json: Value = serde_json::json!({
+            "id": 1,
+            "date": "2025-06-18",
+            "time": "08:30:00",
+            "kind": "In",
+            "location": "Office"
+        });
+        let e: Event = serde_json::from_value(json).unwrap();
+        assert_eq!(e.kind, EventType::In);
+        assert_eq!(e.lunch, None);
+        assert!(e.created_at.is_empty());
+    }
+}
@@ -0,0 +1,33 @@
+//! Event fixtures shared by the unit tests: every event is on [`day`], at
+//! the Office, with the defaults of [`Event::new`]; tests override the
+//! fields they care about with struct update syntax.
+
+use super::event::{Event, EventExtras};
+use super::event_type::EventType;
+use super::location::Location;
+use chrono::{NaiveDate, NaiveTime};
+
+/// 2025-06-18, a Wednesday.
+pub fn day() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 18).unwrap()
+}
+
+/// Event `id` of [`day`] at `t` (`HH:MM`).
+pub fn ev(id: i32, t: &str, kind: EventType) -> Event {
+    Event::new(
+        id,
+        day(),
+        NaiveTime::parse_from_str(t, "%H:%M").unwrap(),
+        kind,
+        Location::Office,
+        EventExtras::default(),
+    )
+}
+
+/// `ev` tagged with a `project` metadata (its activity with `overlap_mode`).
+pub fn project(ev: Event, name: &str) -> Event {
+    Event {
+        meta: Some(format!(r#"{{"project":"{}"}}"#, name)),
+        ..ev
+    }
+}
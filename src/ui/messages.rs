@@ -1,4 +1,7 @@
+use crate::utils::colors::{eprintln, println};
+use std::cell::{Cell, RefCell};
 use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 /// ANSI colors
 const RESET: &str = "\x1b[0m";
@@ -15,26 +18,245 @@ const ICON_OK: &str = "✅";
 const ICON_WARN: &str = "⚠️";
 const ICON_ERR: &str = "❌";
 
+/// Plain ASCII prefixes (`--no-emoji` / `no_emoji` config)
+const ASCII_INFO: &str = "INFO";
+const ASCII_OK: &str = "OK";
+const ASCII_WARN: &str = "WARN";
+const ASCII_ERR: &str = "ERR";
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+static MODE: AtomicU8 = AtomicU8::new(OutputMode::Normal as u8);
+
+/// Kind of a user-facing message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Success,
+    Info,
+    Warning,
+    Error,
+    /// Command output proper, never suppressed
+    Data,
+    Header,
+}
+
+/// What the user asked to see (`--quiet`, `--porcelain`, `--json`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OutputMode {
+    /// Everything
+    Normal = 0,
+    /// `--quiet`: no info / success / headers, warnings and errors kept
+    Quiet = 1,
+    /// Machine-readable output: only data on stdout, errors (and
+    /// warnings) on stderr
+    Data = 2,
+}
+
+impl Level {
+    fn visible(self, mode: OutputMode, quiet: bool) -> bool {
+        match self {
+            Level::Data | Level::Warning | Level::Error => true,
+            Level::Success | Level::Info | Level::Header => mode == OutputMode::Normal && !quiet,
+        }
+    }
+}
+
+/// A message as emitted, without prefix and colors (see [`capture`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub level: Level,
+    pub text: String,
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+    static CAPTURE: RefCell<Option<Vec<Message>>> = const { RefCell::new(None) };
+}
+
+/// Set the output mode for the rest of the process. Modes only get stricter:
+/// `--porcelain` wins over `--quiet`.
+pub fn set_mode(mode: OutputMode) {
+    MODE.fetch_max(mode as u8, Ordering::Relaxed);
+}
+
+pub fn mode() -> OutputMode {
+    match MODE.load(Ordering::Relaxed) {
+        0 => OutputMode::Normal,
+        1 => OutputMode::Quiet,
+        _ => OutputMode::Data,
+    }
+}
+
+/// Run `f` collecting the messages it emits instead of printing them.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Message>) {
+    let prev = CAPTURE.with(|c| c.replace(Some(Vec::new())));
+    let out = f();
+    let msgs = CAPTURE.with(|c| c.replace(prev)).unwrap_or_default();
+    (out, msgs)
+}
+
+/// Emit again messages collected by [`capture`], e.g. once the operation
+/// that produced them is known to have succeeded.
+pub fn replay(msgs: impl IntoIterator<Item = Message>) {
+    for m in msgs {
+        emit(m.level, m.text);
+    }
+}
+
+/// Run `f` with info/success messages suppressed (warnings and errors are
+/// still shown). Used for housekeeping the user did not ask for.
+pub fn quietly<R>(f: impl FnOnce() -> R) -> R {
+    let prev = QUIET.with(|q| q.replace(true));
+    let out = f();
+    QUIET.with(|q| q.set(prev));
+    out
+}
+
+fn is_quiet() -> bool {
+    QUIET.with(Cell::get)
+}
+
+/// Switch every message to plain ASCII: `OK` / `WARN` / `ERR` prefixes
+/// instead of the icons, and emoji removed from the text.
+pub fn set_ascii(on: bool) {
+    ASCII.store(on, Ordering::Relaxed);
+}
+
+pub fn is_ascii() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+/// Remove pictographs and emoji modifiers, plus the blanks left by a
+/// leading icon (indentation of plain lines is kept).
+pub fn strip_emoji(text: &str) -> String {
+    let out: String = text.chars().filter(|c| !is_emoji(*c)).collect();
+    match text.chars().next() {
+        Some(c) if is_emoji(c) => out.trim_start().to_string(),
+        _ => out,
+    }
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF   // pictographs, emoticons, transport, symbols
+        | 0x2600..=0x27BF   // misc symbols, dingbats (✅ ❌ ⚠)
+        | 0x2B00..=0x2BFF   // arrows & stars (⭐)
+        | 0x2300..=0x23FF   // technical (⏱ ⌛)
+        | 0x2139            // ℹ
+        | 0x200D            // zero width joiner
+        | 0xFE00..=0xFE0F   // variation selectors
+        | 0x20E3            // keycap
+    )
+}
+
 pub fn info<T: fmt::Display>(msg: T) {
-    println!("{}{}{} {}{}", FG_BLUE, BOLD, ICON_INFO, RESET, msg);
+    emit(Level::Info, msg);
 }
 
 pub fn success<T: fmt::Display>(msg: T) {
-    println!("{}{}{} {}{}", FG_GREEN, BOLD, ICON_OK, RESET, msg);
+    emit(Level::Success, msg);
 }
 
 pub fn warning<T: fmt::Display>(msg: T) {
-    println!("{}{}{} {}{}", FG_YELLOW, BOLD, ICON_WARN, RESET, msg);
+    emit(Level::Warning, msg);
 }
 
 pub fn error<T: fmt::Display>(msg: T) {
-    eprintln!("{}{}{} {}{}", FG_RED, BOLD, ICON_ERR, RESET, msg);
+    emit(Level::Error, msg);
+}
+
+/// Command output proper (tables, porcelain lines, JSON): printed as is,
+/// in every output mode.
+pub fn data<T: fmt::Display>(msg: T) {
+    emit(Level::Data, msg);
 }
 
 /// Optional: formatted section header
 pub fn header<T: fmt::Display>(msg: T) {
-    println!(
-        "{}{}====================== {}\n{}",
-        FG_BLUE, BOLD, msg, RESET
-    );
+    emit(Level::Header, msg);
+}
+
+/// Single exit point of every message.
+fn emit<T: fmt::Display>(level: Level, msg: T) {
+    if !level.visible(mode(), is_quiet()) {
+        return;
+    }
+
+    let text = msg.to_string();
+    let text = if is_ascii() { strip_emoji(&text) } else { text };
+
+    let captured = CAPTURE.with(|c| match c.borrow_mut().as_mut() {
+        Some(buf) => {
+            buf.push(Message {
+                level,
+                text: text.clone(),
+            });
+            true
+        }
+        None => false,
+    });
+    if captured {
+        return;
+    }
+
+    let (color, icon, label) = match level {
+        Level::Info => (FG_BLUE, ICON_INFO, ASCII_INFO),
+        Level::Success => (FG_GREEN, ICON_OK, ASCII_OK),
+        Level::Warning => (FG_YELLOW, ICON_WARN, ASCII_WARN),
+        Level::Error => (FG_RED, ICON_ERR, ASCII_ERR),
+        Level::Data => {
+            println!("{}", text);
+            return;
+        }
+        Level::Header => {
+            println!(
+                "{}{}====================== {}\n{}",
+                FG_BLUE, BOLD, text, RESET
+            );
+            return;
+        }
+    };
+    let prefix = if is_ascii() { label } else { icon };
+
+    // Diagnostics go to stderr when stdout carries machine-readable data
+    if level == Level::Error || mode() == OutputMode::Data {
+        eprintln!("{}{}{} {}{}", color, BOLD, prefix, RESET, text);
+    } else {
+        println!("{}{}{} {}{}", color, BOLD, prefix, RESET, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_emoji_keeps_plain_text() {
+        assert_eq!(strip_emoji("⚠️  No events found"), "No events found");
+        assert_eq!(strip_emoji("🔧 Config file updated"), "Config file updated");
+        assert_eq!(strip_emoji("Saved 08:30 → 17:00"), "Saved 08:30 → 17:00");
+        assert_eq!(strip_emoji("    from: 2025-06-01"), "    from: 2025-06-01");
+    }
+
+    #[test]
+    fn capture_collects_structured_messages() {
+        let ((), msgs) = capture(|| {
+            success("✅ saved");
+            quietly(|| info("hidden"));
+            data("a=1");
+        });
+        assert_eq!(
+            msgs,
+            vec![
+                Message {
+                    level: Level::Success,
+                    text: "✅ saved".into()
+                },
+                Message {
+                    level: Level::Data,
+                    text: "a=1".into()
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,104 @@
+//! Archive utilities: single-file ZIP creation with optional AES encryption,
+//! gzip / zstd compression, and passphrase input for encrypted archives.
+
+use crate::errors::{AppError, AppResult};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::io;
+use std::path::Path;
+use zip::write::FileOptions;
+use zip::{AesMode, CompressionMethod, ZipWriter};
+
+/// Environment variable used to provide the passphrase non-interactively.
+pub const PASSPHRASE_ENV: &str = "RTIMELOGGER_PASSPHRASE";
+
+/// zstd level used for backups: slow-ish but much smaller than the defaults,
+/// which matters for large databases.
+const ZSTD_LEVEL: i32 = 19;
+
+/// Store `src` inside a new ZIP archive at `zip_path`.
+/// When `passphrase` is given the entry is encrypted with AES-256.
+pub fn zip_single_file(src: &Path, zip_path: &Path, passphrase: Option<&str>) -> AppResult<()> {
+    let filename = src
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| AppError::InvalidOperation("Invalid file name for archive".into()))?;
+
+    let file = fs::File::create(zip_path).map_err(AppError::Io)?;
+    let mut zip = ZipWriter::new(file);
+
+    let options: FileOptions<'_, ()> =
+        FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let options = match passphrase {
+        Some(p) => options.with_aes_encryption(AesMode::Aes256, p),
+        None => options,
+    };
+
+    zip.start_file(filename, options)
+        .map_err(|e| AppError::Io(io::Error::other(e)))?;
+
+    let mut f = fs::File::open(src).map_err(AppError::Io)?;
+    io::copy(&mut f, &mut zip).map_err(AppError::Io)?;
+
+    zip.finish()
+        .map_err(|e| AppError::Io(io::Error::other(e)))?;
+
+    Ok(())
+}
+
+/// Compress `src` into a gzip stream at `dest`.
+pub fn gzip_single_file(src: &Path, dest: &Path) -> AppResult<()> {
+    let mut input = fs::File::open(src)?;
+    let output = fs::File::create(dest)?;
+
+    let mut encoder = GzEncoder::new(output, Compression::best());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Compress `src` into a zstd stream at `dest`.
+pub fn zstd_single_file(src: &Path, dest: &Path) -> AppResult<()> {
+    let input = fs::File::open(src)?;
+    let output = fs::File::create(dest)?;
+
+    zstd::stream::copy_encode(input, output, ZSTD_LEVEL)?;
+    Ok(())
+}
+
+/// Obtain the passphrase for an encrypted archive.
+///
+/// - `RTIMELOGGER_PASSPHRASE` wins when set (scripts, CI)
+/// - otherwise the user is prompted twice (without echo) and both entries
+///   must match
+pub fn read_passphrase() -> AppResult<String> {
+    if let Ok(p) = std::env::var(PASSPHRASE_ENV)
+        && !p.is_empty()
+    {
+        return Ok(p);
+    }
+
+    let first = prompt("Passphrase: ")?;
+    if first.is_empty() {
+        return Err(AppError::InvalidArgs("Passphrase cannot be empty.".into()));
+    }
+
+    let second = prompt("Repeat passphrase: ")?;
+    if first != second {
+        return Err(AppError::InvalidArgs("Passphrases do not match.".into()));
+    }
+
+    Ok(first)
+}
+
+/// Read a line from the terminal without echoing it.
+fn prompt(label: &str) -> AppResult<String> {
+    let s = rpassword::prompt_password(label).map_err(|e| {
+        AppError::InvalidArgs(format!(
+            "Cannot read the passphrase from the terminal ({}). Set {} instead.",
+            e, PASSPHRASE_ENV
+        ))
+    })?;
+    Ok(s.trim_end_matches(['\r', '\n']).to_string())
+}
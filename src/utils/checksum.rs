@@ -0,0 +1,15 @@
+//! File checksums recorded in the internal log (e.g. closeout exports).
+
+use crate::errors::AppResult;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// SHA-256 of a file as lowercase hex.
+pub fn sha256_file(path: &Path) -> AppResult<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -1,5 +1,16 @@
 /// ANSI color helper utilities for terminal output.
+///
+/// Colors are always written as ANSI sequences; the print macros re-exported
+/// here adapt them to the terminal: VT processing is enabled on Windows
+/// consoles when possible (legacy consoles get native colors), and escapes
+/// are stripped when the output is not a terminal or `NO_COLOR` is set
+/// (`CLICOLOR_FORCE=1` keeps them). Every module printing to the console
+/// imports them instead of the std macros.
+pub use anstream::{eprint, eprintln, print, println};
+
 pub const RESET: &str = "\x1b[0m";
+pub const BOLD: &str = "\x1b[1m";
+pub const STRIKE: &str = "\x1b[9m";
 
 pub const GREY: &str = "\x1b[90m";
 pub const WHITE: &str = "\x1b[37m";
@@ -1,5 +1,5 @@
 use crate::core::calculator::timeline::Timeline;
-use crate::errors::AppResult;
+use crate::errors::{AppError, AppResult};
 use crate::models::location::Location;
 use chrono::{Datelike, NaiveDate, Weekday};
 
@@ -7,48 +7,6 @@ pub fn today() -> NaiveDate {
     chrono::Local::now().date_naive()
 }
 
-pub fn generate_from_period(p: &str) -> Result<Vec<NaiveDate>, String> {
-    // YYYY-MM-DD
-    if let Ok(d) = NaiveDate::parse_from_str(p, "%Y-%m-%d") {
-        return Ok(vec![d]);
-    }
-
-    // YYYY-MM
-    if let Ok(dm) = chrono::NaiveDate::parse_from_str(&(p.to_string() + "-01"), "%Y-%m-%d") {
-        return Ok(all_days_of_month(dm.year(), dm.month()));
-    }
-
-    // YYYY
-    if let Ok(year) = p.parse::<i32>() {
-        return Ok(all_days_of_year(year));
-    }
-
-    Err(format!("Invalid period: {}", p))
-}
-
-pub fn generate_range(start: &str, end: &str) -> Result<Vec<NaiveDate>, String> {
-    let s = generate_from_period(start)?;
-    let e = generate_from_period(end)?;
-
-    let start_date = *s.first().unwrap();
-    let end_date = *e.last().unwrap();
-
-    let mut out = Vec::new();
-    let mut d = start_date;
-
-    while d <= end_date {
-        out.push(d);
-        d = d.succ_opt().unwrap();
-    }
-
-    Ok(out)
-}
-
-pub fn current_month_dates() -> Result<Vec<NaiveDate>, String> {
-    let today = today();
-    Ok(all_days_of_month(today.year(), today.month()))
-}
-
 pub fn all_days_of_month(year: i32, month: u32) -> Vec<NaiveDate> {
     let mut out = Vec::new();
     let mut d = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
@@ -73,11 +31,6 @@ pub fn all_days_of_year(year: i32) -> Vec<NaiveDate> {
     v
 }
 
-pub fn generate_all_dates() -> Result<Vec<NaiveDate>, String> {
-    // placeholder: load all dates for current year
-    Ok(all_days_of_year(today().year()))
-}
-
 pub fn parse_date(s: &str) -> Result<NaiveDate, String> {
     NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| format!("Invalid date '{}': {}", s, e))
 }
@@ -161,12 +114,36 @@ pub fn get_day_position(timeline: &Timeline) -> Location {
     }
 }
 
+/// Parse a weekday list such as `fri` or `mon,fri` (short or long English names).
+pub fn parse_weekdays(spec: &str) -> AppResult<Vec<Weekday>> {
+    let mut days = Vec::new();
+    for name in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let wd: Weekday = name.parse().map_err(|_| {
+            AppError::InvalidArgs(format!(
+                "Unknown weekday '{}'. Use mon, tue, wed, thu, fri, sat, sun (e.g. --weekday mon,fri).",
+                name
+            ))
+        })?;
+        if !days.contains(&wd) {
+            days.push(wd);
+        }
+    }
+
+    if days.is_empty() {
+        return Err(AppError::InvalidArgs(
+            "--weekday requires at least one day (e.g. --weekday fri).".into(),
+        ));
+    }
+    Ok(days)
+}
+
 // helper weekend
 pub fn is_weekend(d: NaiveDate) -> bool {
     matches!(d.weekday(), Weekday::Sat | Weekday::Sun)
 }
 
 // helper national holiday in DB (position = 'N')
+#[cfg(feature = "cli")]
 pub fn is_national_holiday(conn: &rusqlite::Connection, d: NaiveDate) -> AppResult<bool> {
     let date_str = d.to_string();
     let exists: i64 = conn.query_row(
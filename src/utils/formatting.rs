@@ -1,5 +1,9 @@
 //! Formatting utilities used for CLI and export outputs.
 
+use crate::config::Config;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
 pub const FOOTER_INDENT: usize = 75;
 
 pub fn bold(s: &str) -> String {
@@ -41,6 +45,129 @@ pub fn mins2readable(mins: i64, want_sign: bool, short: bool) -> String {
     }
 }
 
+/// Minutes as hours rounded to the nearest `step` (non-positive step → 0.01).
+pub fn round_hours(mins: i64, step: f64) -> f64 {
+    let step = if step > 0.0 { step } else { 0.01 };
+    ((mins as f64 / 60.0) / step).round() * step
+}
+
+/// Decimals needed to write multiples of `step` (0.25 → 2, 0.1 → 1, 1 → 0).
+fn step_decimals(step: f64) -> usize {
+    let step = if step > 0.0 { step } else { 0.01 };
+    (0..6)
+        .find(|&d| {
+            let scaled = step * 10f64.powi(d as i32);
+            (scaled - scaled.round()).abs() < 1e-9
+        })
+        .unwrap_or(6)
+}
+
+/// Minutes as decimal hours rounded to `step` hours, with as many decimals
+/// as the step has (e.g. 450 → "7.50" at 0.01, "7.5" at 0.1).
+/// A non-positive `step` falls back to 0.01.
+pub fn mins2decimal(mins: i64, step: f64, want_sign: bool) -> String {
+    let hours = round_hours(mins.abs(), step);
+
+    let sign = match (want_sign, mins.signum()) {
+        (true, 1) => "+",
+        (true, -1) => "-",
+        _ => "",
+    };
+    format!("{}{:.*}", sign, step_decimals(step), hours)
+}
+
+/// Languages writing decimals with a comma (`7,50`).
+const COMMA_LANGUAGES: &[&str] = &[
+    "it", "de", "fr", "es", "pt", "nl", "da", "sv", "nb", "nn", "fi", "pl", "cs", "sk", "sl", "hr",
+    "hu", "ro", "ru", "uk", "el", "tr", "ca", "bg", "lt", "lv", "et",
+];
+
+/// Separator used by a locale name such as `it_IT.UTF-8` (`.` when unknown).
+pub fn locale_decimal_separator(locale: &str) -> char {
+    let lang = locale
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if COMMA_LANGUAGES.contains(&lang.as_str()) {
+        ','
+    } else {
+        '.'
+    }
+}
+
+/// `decimal_separator` config: "." / "," as given, "auto" from the first
+/// set variable among `LC_ALL`, `LC_NUMERIC` and `LANG`.
+pub fn decimal_separator(cfg: &Config) -> char {
+    match cfg.decimal_separator.trim() {
+        "," => ',',
+        "auto" => ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|v| std::env::var(v).ok())
+            .find(|v| !v.is_empty())
+            .map(|v| locale_decimal_separator(&v))
+            .unwrap_or('.'),
+        _ => '.',
+    }
+}
+
+/// How durations are rendered: `07h30m` (default) or decimal hours (`--decimal`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DurationStyle {
+    Clock,
+    Decimal { step: f64 },
+}
+
+impl DurationStyle {
+    pub fn new(decimal: bool, step: f64) -> Self {
+        if decimal {
+            DurationStyle::Decimal { step }
+        } else {
+            DurationStyle::Clock
+        }
+    }
+
+    /// Compact rendering: "07h30m" / "+02h04m" or "7.50" / "+2.07".
+    pub fn format(&self, mins: i64, want_sign: bool) -> String {
+        match self {
+            DurationStyle::Clock => mins2readable(mins, want_sign, true),
+            DurationStyle::Decimal { step } => mins2decimal(mins, *step, want_sign),
+        }
+    }
+}
+
+/// `surplus_format` config: how surplus / ΔWORK values are displayed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SurplusFormat {
+    /// `+01h15m`
+    #[default]
+    Hm,
+    /// `+75 min`
+    Minutes,
+    /// `+1.25h` (rounded to `decimal_step`)
+    Decimal,
+}
+
+impl SurplusFormat {
+    pub fn format(self, mins: i64, step: f64) -> String {
+        match self {
+            SurplusFormat::Hm => mins2readable(mins, true, true),
+            SurplusFormat::Minutes => {
+                let sign = if mins > 0 { "+" } else { "" };
+                format!("{}{} min", sign, mins)
+            }
+            SurplusFormat::Decimal => format!("{}h", mins2decimal(mins, step, mins != 0)),
+        }
+    }
+}
+
+/// Surplus / ΔWORK as configured (`surplus_format`); every command and
+/// export goes through here.
+pub fn format_surplus(mins: i64, cfg: &Config) -> String {
+    cfg.surplus_format.format(mins, cfg.decimal_step)
+}
+
 /// Restituisce una descrizione testuale e un colore ANSI per la posizione.
 /// Usata nei test e in eventuali output human-readable.
 pub fn describe_position(code: &str) -> (String, &'static str) {
@@ -95,3 +222,36 @@ pub fn right_pad_prefix(box_width: usize, visible_text: &str) -> String {
 pub fn build_import_source(base: &str, format: &str) -> String {
     format!("{} (from {})", base, format.to_ascii_lowercase())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surplus_formats() {
+        assert_eq!(SurplusFormat::Hm.format(75, 0.01), "+01h15m");
+        assert_eq!(SurplusFormat::Minutes.format(75, 0.01), "+75 min");
+        assert_eq!(SurplusFormat::Minutes.format(-20, 0.01), "-20 min");
+        assert_eq!(SurplusFormat::Decimal.format(75, 0.01), "+1.25h");
+        assert_eq!(SurplusFormat::Decimal.format(-45, 0.25), "-0.75h");
+        assert_eq!(SurplusFormat::Decimal.format(0, 0.01), "0.00h");
+    }
+
+    #[test]
+    fn decimals_follow_the_step() {
+        assert_eq!(mins2decimal(66, 0.1, false), "1.1");
+        assert_eq!(mins2decimal(75, 0.25, false), "1.25");
+        assert_eq!(mins2decimal(450, 0.5, true), "+7.5");
+        assert_eq!(mins2decimal(450, 1.0, false), "8");
+        assert_eq!(mins2decimal(61, 0.001, false), "1.017");
+        assert_eq!(mins2decimal(61, 0.0, false), "1.02");
+    }
+
+    #[test]
+    fn separator_from_locale() {
+        assert_eq!(locale_decimal_separator("it_IT.UTF-8"), ',');
+        assert_eq!(locale_decimal_separator("de-DE"), ',');
+        assert_eq!(locale_decimal_separator("en_US.UTF-8"), '.');
+        assert_eq!(locale_decimal_separator("C"), '.');
+    }
+}
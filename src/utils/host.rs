@@ -0,0 +1,91 @@
+//! Identity of the machine (and optionally the OS user) recording events.
+//! Useful to track down conflicts when the same DB is synced between machines.
+
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Hostname of this machine (resolved once per process).
+pub fn hostname() -> Option<String> {
+    static HOST: OnceLock<Option<String>> = OnceLock::new();
+    HOST.get_or_init(resolve_hostname).clone()
+}
+
+/// Name of the OS user running the process.
+pub fn os_user() -> Option<String> {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .find_map(|k| std::env::var(k).ok())
+        .and_then(non_empty)
+}
+
+/// SSID of the Wi-Fi network this machine is connected to, asked to the
+/// local OS tools (no network traffic). None when offline, wired or unknown.
+pub fn wifi_ssid() -> Option<String> {
+    let run = |cmd: &str, args: &[&str]| {
+        Command::new(cmd)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+    };
+
+    if cfg!(target_os = "windows") {
+        // "    SSID                   : OfficeNet" (skip the BSSID line)
+        return run("netsh", &["wlan", "show", "interfaces"])?
+            .lines()
+            .map(str::trim)
+            .find(|l| l.starts_with("SSID"))
+            .and_then(|l| l.split_once(':'))
+            .and_then(|(_, v)| non_empty(v.to_string()));
+    }
+
+    if cfg!(target_os = "macos") {
+        // "Current Wi-Fi Network: OfficeNet"
+        return run("networksetup", &["-getairportnetwork", "en0"])?
+            .split_once(": ")
+            .and_then(|(_, v)| non_empty(v.to_string()));
+    }
+
+    // Linux: wireless-tools, then NetworkManager ("yes:OfficeNet")
+    run("iwgetid", &["-r"]).and_then(non_empty).or_else(|| {
+        run("nmcli", &["-t", "-f", "active,ssid", "dev", "wifi"])?
+            .lines()
+            .find_map(|l| l.strip_prefix("yes:"))
+            .and_then(|v| non_empty(v.to_string()))
+    })
+}
+
+fn resolve_hostname() -> Option<String> {
+    // Windows (and some shells) export it directly
+    if let Some(h) = ["COMPUTERNAME", "HOSTNAME"]
+        .iter()
+        .find_map(|k| std::env::var(k).ok())
+        .and_then(non_empty)
+    {
+        return Some(h);
+    }
+
+    // Linux
+    if let Ok(h) = std::fs::read_to_string("/proc/sys/kernel/hostname")
+        && let Some(h) = non_empty(h)
+    {
+        return Some(h);
+    }
+
+    // macOS / BSD / fallback
+    Command::new("hostname")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| non_empty(String::from_utf8_lossy(&o.stdout).to_string()))
+}
+
+fn non_empty(s: String) -> Option<String> {
+    let s = s.trim();
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
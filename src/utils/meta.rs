@@ -0,0 +1,159 @@
+//! Structured event metadata (`add --meta key=value`).
+//!
+//! The `events.meta` column historically holds free text (e.g. the name of a
+//! national holiday). Metadata added from the CLI is stored there as a flat
+//! JSON object; anything that is not a JSON object is treated as plain text.
+
+use crate::errors::{AppError, AppResult};
+use serde_json::{Map, Value};
+
+/// Key used to preserve pre-existing free text when merging metadata into it.
+const TEXT_KEY: &str = "text";
+
+/// Parse `key=value` items into a JSON object string (None if no items).
+/// Numbers and booleans are stored with their JSON type, everything else as string.
+/// A number is kept as typed only when it reads back unchanged (`007`, `1e3`
+/// or `1.50` stay strings).
+pub fn parse_meta_pairs(items: &[String]) -> AppResult<Option<String>> {
+    if items.is_empty() {
+        return Ok(None);
+    }
+
+    let mut map = Map::new();
+    for item in items {
+        let (key, value) = item
+            .split_once('=')
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .filter(|(k, _)| !k.is_empty())
+            .ok_or_else(|| {
+                AppError::InvalidArgs(format!(
+                    "Invalid --meta '{}': expected KEY=VALUE (e.g. --meta project=acme).",
+                    item
+                ))
+            })?;
+
+        map.insert(key.to_string(), typed_value(value));
+    }
+
+    Ok(Some(Value::Object(map).to_string()))
+}
+
+fn typed_value(v: &str) -> Value {
+    if let Ok(i) = v.parse::<i64>()
+        && i.to_string() == v
+    {
+        return Value::from(i);
+    }
+    if let Ok(f) = v.parse::<f64>()
+        && f.is_finite()
+        && Value::from(f).to_string() == v
+    {
+        return Value::from(f);
+    }
+    match v {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(v.to_string()),
+    }
+}
+
+/// Parse the stored meta as a JSON object (None for empty or free text).
+pub fn meta_map(meta: Option<&str>) -> Option<Map<String, Value>> {
+    match serde_json::from_str::<Value>(meta?.trim()) {
+        Ok(Value::Object(m)) => Some(m),
+        _ => None,
+    }
+}
+
+/// Merge new `key=value` metadata into the stored value (new keys win).
+/// Free text already present is kept under the `text` key.
+pub fn merge_meta(existing: Option<&str>, new_json: &str) -> String {
+    let mut merged = match meta_map(existing) {
+        Some(m) => m,
+        None => {
+            let mut m = Map::new();
+            if let Some(text) = existing.map(str::trim).filter(|s| !s.is_empty()) {
+                m.insert(TEXT_KEY.to_string(), Value::String(text.to_string()));
+            }
+            m
+        }
+    };
+
+    if let Some(new) = meta_map(Some(new_json)) {
+        merged.extend(new);
+    }
+
+    Value::Object(merged).to_string()
+}
+
+/// Value of a single key, rendered as plain text ("" if missing).
+pub fn meta_value(meta: Option<&str>, key: &str) -> String {
+    meta_map(meta)
+        .and_then(|m| m.get(key).map(value_to_string))
+        .unwrap_or_default()
+}
+
+/// Human readable form: `k=v, k2=v2` for JSON objects, the raw text otherwise.
+pub fn format_meta(meta: Option<&str>) -> String {
+    let Some(raw) = meta.map(str::trim).filter(|s| !s.is_empty()) else {
+        return String::new();
+    };
+
+    match meta_map(Some(raw)) {
+        Some(m) => m
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, value_to_string(v)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => raw.to_string(),
+    }
+}
+
+fn value_to_string(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_pairs() {
+        let json = parse_meta_pairs(&["project=acme".into(), "other=2".into()])
+            .unwrap()
+            .unwrap();
+        assert_eq!(meta_value(Some(&json), "other"), "2");
+        assert_eq!(format_meta(Some(&json)), "other=2, project=acme");
+        assert!(parse_meta_pairs(&["novalue".into()]).is_err());
+    }
+
+    #[test]
+    fn numbers_are_typed_only_when_lossless() {
+        let json = parse_meta_pairs(&[
+            "ticket=007".into(),
+            "code=1e3".into(),
+            "rate=1.50".into(),
+            "hours=7".into(),
+            "ratio=0.25".into(),
+        ])
+        .unwrap()
+        .unwrap();
+        let map = meta_map(Some(&json)).unwrap();
+        assert_eq!(map["ticket"], Value::String("007".into()));
+        assert_eq!(map["code"], Value::String("1e3".into()));
+        assert_eq!(map["rate"], Value::String("1.50".into()));
+        assert_eq!(map["hours"], Value::from(7));
+        assert_eq!(map["ratio"], Value::from(0.25));
+        assert_eq!(meta_value(Some(&json), "ticket"), "007");
+    }
+
+    #[test]
+    fn merge_keeps_free_text() {
+        let merged = merge_meta(Some("Epiphany"), r#"{"ticket":"T-1"}"#);
+        assert_eq!(format_meta(Some(&merged)), "text=Epiphany, ticket=T-1");
+        assert_eq!(format_meta(Some("Epiphany")), "Epiphany");
+    }
+}
@@ -1,7 +1,18 @@
+#[cfg(feature = "cli")]
+pub mod archive;
+#[cfg(feature = "cli")]
+pub mod checksum;
+#[cfg(feature = "cli")]
 pub mod colors;
 pub mod date;
 pub mod formatting;
+#[cfg(feature = "cli")]
+pub mod host;
+pub mod meta;
+#[cfg(feature = "cli")]
 pub mod path;
+pub mod period;
+#[cfg(feature = "cli")]
 pub mod table;
 pub mod time;
 
@@ -1,5 +1,6 @@
 //! Path utilities: resolve config path, expand ~, validate absolute paths, etc.
 
+use chrono::{Datelike, NaiveDate};
 use std::path::PathBuf;
 
 pub fn expand_tilde(path: &str) -> PathBuf {
@@ -14,3 +15,22 @@ pub fn expand_tilde(path: &str) -> PathBuf {
 pub fn is_absolute(path: &str) -> bool {
     PathBuf::from(path).is_absolute()
 }
+
+/// Render a per-month file name template: `{YYYY}` and `{MM}` are replaced
+/// with the year and month of `date` (e.g. `/out/report-{YYYY}-{MM}.csv`).
+pub fn month_template(template: &str, date: NaiveDate) -> PathBuf {
+    expand_tilde(
+        &template
+            .replace("{YYYY}", &format!("{:04}", date.year()))
+            .replace("{MM}", &format!("{:02}", date.month())),
+    )
+}
+
+/// Like [`month_template`], with `{DD}` for the day as well
+/// (e.g. `~/backups/rtimelogger-{YYYY}-{MM}-{DD}.sqlite`).
+pub fn day_template(template: &str, date: NaiveDate) -> PathBuf {
+    month_template(
+        &template.replace("{DD}", &format!("{:02}", date.day())),
+        date,
+    )
+}
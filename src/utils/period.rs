@@ -0,0 +1,220 @@
+//! Period parsing shared by `list --period`, `export --range` and `db`.
+//!
+//! Supported expressions:
+//! - `all`
+//! - `YYYY`, `YYYY-MM`, `YYYY-MM-DD`, `today`
+//! - `START:END` where both sides are any of the above (e.g. `2025-01:2025-03-15`)
+
+use crate::errors::{AppError, AppResult};
+use crate::utils::date::{all_days_of_month, today};
+use chrono::{Datelike, NaiveDate};
+
+/// A parsed period: either every recorded day, or an inclusive date interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Period {
+    All,
+    Range { from: NaiveDate, to: NaiveDate },
+}
+
+impl Period {
+    /// Parse a period expression, returning `InvalidPeriod` with a hint on failure.
+    pub fn parse(p: &str) -> AppResult<Self> {
+        let p = p.trim();
+        if p.eq_ignore_ascii_case("all") {
+            return Ok(Period::All);
+        }
+
+        let invalid = |hint: String| AppError::InvalidPeriod {
+            input: p.to_string(),
+            hint,
+        };
+
+        let tokens: Vec<&str> = p.split(':').map(str::trim).collect();
+        if tokens.len() > 2 {
+            return Err(invalid("a range must contain exactly one ':'".into()));
+        }
+
+        let mut bounds = Vec::new();
+        for t in &tokens {
+            match token_bounds(t) {
+                Some(b) => bounds.push(b),
+                None => return Err(invalid(format!("'{}': {}", t, token_hint(t)))),
+            }
+        }
+
+        let from = bounds[0].0;
+        let to = bounds[bounds.len() - 1].1;
+
+        if from > to {
+            return Err(invalid(format!(
+                "range start ({}) is after range end ({})",
+                tokens[0],
+                tokens[tokens.len() - 1]
+            )));
+        }
+
+        Ok(Period::Range { from, to })
+    }
+
+    /// Parse an optional period; `None` means "all".
+    pub fn parse_opt(p: Option<&str>) -> AppResult<Self> {
+        match p {
+            Some(s) => Self::parse(s),
+            None => Ok(Period::All),
+        }
+    }
+
+    /// The month containing today.
+    pub fn current_month() -> Self {
+        let t = today();
+        let days = all_days_of_month(t.year(), t.month());
+        Period::Range {
+            from: days[0],
+            to: days[days.len() - 1],
+        }
+    }
+
+    /// Inclusive interval between two dates.
+    pub fn between(from: NaiveDate, to: NaiveDate) -> Self {
+        Period::Range { from, to }
+    }
+
+    pub fn is_all(&self) -> bool {
+        matches!(self, Period::All)
+    }
+
+    /// Date bounds, or None for `all`.
+    pub fn bounds(&self) -> Option<(NaiveDate, NaiveDate)> {
+        match self {
+            Period::All => None,
+            Period::Range { from, to } => Some((*from, *to)),
+        }
+    }
+
+    /// Bounds formatted as `YYYY-MM-DD` for `WHERE date BETWEEN ?1 AND ?2`.
+    pub fn to_sql_bounds(&self) -> Option<(String, String)> {
+        self.bounds().map(|(f, t)| {
+            (
+                f.format("%Y-%m-%d").to_string(),
+                t.format("%Y-%m-%d").to_string(),
+            )
+        })
+    }
+
+    /// Every day in the period (empty for `all`: resolve it against the DB first).
+    pub fn iter_dates(&self) -> Vec<NaiveDate> {
+        let mut out = Vec::new();
+        if let Some((from, to)) = self.bounds() {
+            let mut d = from;
+            while d <= to {
+                out.push(d);
+                match d.succ_opt() {
+                    Some(n) => d = n,
+                    None => break,
+                }
+            }
+        }
+        out
+    }
+
+    /// True if the date falls inside the period.
+    pub fn contains(&self, d: NaiveDate) -> bool {
+        match self.bounds() {
+            None => true,
+            Some((from, to)) => from <= d && d <= to,
+        }
+    }
+}
+
+/// Returns the first and last day covered by a single period token
+/// (`YYYY`, `YYYY-MM` or `YYYY-MM-DD`), or None if the token is malformed.
+fn token_bounds(p: &str) -> Option<(NaiveDate, NaiveDate)> {
+    if p.eq_ignore_ascii_case("today") {
+        let t = today();
+        return Some((t, t));
+    }
+    match p.len() {
+        4 if p.chars().all(|c| c.is_ascii_digit()) => {
+            let y: i32 = p.parse().ok()?;
+            Some((
+                NaiveDate::from_ymd_opt(y, 1, 1)?,
+                NaiveDate::from_ymd_opt(y, 12, 31)?,
+            ))
+        }
+        7 if p.as_bytes()[4] == b'-' => {
+            let first = NaiveDate::parse_from_str(&format!("{}-01", p), "%Y-%m-%d").ok()?;
+            let last = all_days_of_month(first.year(), first.month());
+            Some((first, *last.last()?))
+        }
+        10 => {
+            let d = NaiveDate::parse_from_str(p, "%Y-%m-%d").ok()?;
+            Some((d, d))
+        }
+        _ => None,
+    }
+}
+
+/// Suggest a fix for a malformed period token (e.g. "2025-9" → "use 2025-09").
+fn token_hint(p: &str) -> String {
+    let parts: Vec<&str> = p.split('-').collect();
+    let numeric = parts
+        .iter()
+        .all(|s| !s.is_empty() && s.len() <= 4 && s.chars().all(|c| c.is_ascii_digit()));
+
+    if numeric && parts.len() <= 3 && parts[0].len() == 4 {
+        let candidate = parts
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                if i == 0 {
+                    s.to_string()
+                } else {
+                    format!("{:0>2}", s)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("-");
+
+        if candidate != p && token_bounds(&candidate).is_some() {
+            return format!("use {}", candidate);
+        }
+    }
+
+    "expected YYYY, YYYY-MM or YYYY-MM-DD".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn parses_supported_shapes() {
+        assert_eq!(Period::parse("all").unwrap(), Period::All);
+        assert_eq!(
+            Period::parse("2024-02").unwrap().bounds(),
+            Some((d(2024, 2, 1), d(2024, 2, 29)))
+        );
+        assert_eq!(
+            Period::parse("2025-01:2025-03-15").unwrap().bounds(),
+            Some((d(2025, 1, 1), d(2025, 3, 15)))
+        );
+        assert_eq!(Period::parse("2025-09-15").unwrap().iter_dates().len(), 1);
+    }
+
+    #[test]
+    fn suggests_zero_padding() {
+        match Period::parse("2025-9") {
+            Err(AppError::InvalidPeriod { hint, .. }) => assert!(hint.contains("use 2025-09")),
+            other => panic!("unexpected: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_reversed_range() {
+        assert!(Period::parse("2025-03:2025-01").is_err());
+    }
+}
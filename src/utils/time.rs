@@ -1,6 +1,5 @@
 //! Time utilities: parsing HH:MM, duration computations, formatting minutes, etc.
 
-use crate::errors::{AppError, AppResult};
 use chrono::NaiveTime;
 
 pub fn parse_time(t: &str) -> Option<NaiveTime> {
@@ -18,13 +17,35 @@ pub fn format_minutes(mins: i64) -> String {
     format!("{}{:02}:{:02}", sign, m / 60, m % 60)
 }
 
-pub fn parse_optional_time(input: Option<&String>) -> AppResult<Option<NaiveTime>> {
-    if let Some(s) = input {
-        let t = parse_time(s).ok_or_else(|| AppError::InvalidTime(s.to_string()))?;
-        Ok(Some(t))
+/// clap value parser for `HH:MM` arguments.
+pub fn parse_time_arg(s: &str) -> Result<NaiveTime, String> {
+    parse_time(s.trim()).ok_or_else(|| format!("Invalid time '{}': expected HH:MM", s))
+}
+
+/// clap value parser for durations: minutes (`90`) or hours and minutes
+/// (`7h`, `7h30`, `7h30m`, `45m`). Returns minutes.
+pub fn parse_duration_arg(s: &str) -> Result<i64, String> {
+    let err = || format!("Invalid duration '{}': use minutes (90) or 7h30m", s);
+    let t = s.trim().to_lowercase();
+    if t.is_empty() {
+        return Err(err());
+    }
+
+    let (hours, rest) = match t.split_once('h') {
+        Some((h, r)) => (h.trim().parse::<i64>().map_err(|_| err())?, r.trim()),
+        None => (0, t.as_str()),
+    };
+    let rest = rest.strip_suffix('m').unwrap_or(rest).trim();
+    let minutes = if rest.is_empty() {
+        0
     } else {
-        Ok(None)
+        rest.parse::<i64>().map_err(|_| err())?
+    };
+
+    if hours < 0 || minutes < 0 {
+        return Err(err());
     }
+    Ok(hours * 60 + minutes)
 }
 
 pub(crate) fn parse_lunch_window(s: &str) -> Option<(NaiveTime, NaiveTime)> {